@@ -15,7 +15,7 @@ fn benchmark_parsing(c: &mut Criterion) {
             b.iter(|| {
                 let allocator = Allocator::default();
                 let parser = Parser::new(code.clone());
-                black_box(parser.parse(&allocator).unwrap())
+                black_box(parser.parse(&allocator).unwrap().is_success())
             })
         },
     );
@@ -36,7 +36,7 @@ fn benchmark_parsing(c: &mut Criterion) {
             b.iter(|| {
                 let allocator = Allocator::default();
                 let parser = Parser::new(code.clone());
-                black_box(parser.parse(&allocator).unwrap())
+                black_box(parser.parse(&allocator).unwrap().is_success())
             })
         },
     );
@@ -53,7 +53,7 @@ fn benchmark_large_file(c: &mut Criterion) {
             b.iter(|| {
                 let allocator = Allocator::default();
                 let parser = Parser::new(source.clone());
-                black_box(parser.parse(&allocator).unwrap())
+                black_box(parser.parse(&allocator).unwrap().is_success())
             })
         });
     }
@@ -0,0 +1,108 @@
+#!/usr/bin/env python3
+"""Train the bundled byte-level BPE vocabulary used for offline token estimates.
+
+The output is in tiktoken format (`<base64 token> <rank>` per line) with the
+256 single bytes first, so any tiktoken-format vocabulary can replace it via
+`--vocab`. Token pieces are split with the same pattern as `src/tokens/bpe.rs`.
+
+Usage: scripts/train_bpe_vocab.py OUTPUT CORPUS_FILE_OR_DIR... [--size N]
+"""
+
+import argparse
+import base64
+import collections
+import os
+import sys
+
+import regex
+
+PATTERN = regex.compile(r"""'(?:s|t|re|ve|m|ll|d)| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+""")
+EXTENSIONS = (".md", ".txt", ".py", ".rs", ".js", ".ts", ".json")
+MAX_CORPUS_BYTES = 8_000_000
+
+
+def corpus_files(paths):
+    for path in paths:
+        if os.path.isdir(path):
+            for root, _, names in sorted(os.walk(path)):
+                for name in sorted(names):
+                    if name.endswith(EXTENSIONS):
+                        yield os.path.join(root, name)
+        else:
+            yield path
+
+
+def piece_counts(paths):
+    counts = collections.Counter()
+    total = 0
+    for path in corpus_files(paths):
+        try:
+            text = open(path, encoding="utf-8").read()
+        except (UnicodeDecodeError, OSError):
+            continue
+        counts.update(PATTERN.findall(text))
+        total += len(text)
+        if total >= MAX_CORPUS_BYTES:
+            break
+    return counts
+
+
+def train(counts, size):
+    words = [[bytes([b]) for b in piece.encode("utf-8")] for piece in counts]
+    freqs = list(counts.values())
+
+    pairs = collections.Counter()
+    where = collections.defaultdict(set)
+    for i, word in enumerate(words):
+        for pair in zip(word, word[1:]):
+            pairs[pair] += freqs[i]
+            where[pair].add(i)
+
+    merges = []
+    while len(merges) + 256 < size and pairs:
+        best, count = max(pairs.items(), key=lambda kv: (kv[1], kv[0]))
+        if count < 2:
+            break
+        merged = best[0] + best[1]
+        merges.append(merged)
+
+        for i in list(where[best]):
+            word = words[i]
+            for pair in zip(word, word[1:]):
+                pairs[pair] -= freqs[i]
+                if pairs[pair] <= 0:
+                    del pairs[pair]
+            new = []
+            j = 0
+            while j < len(word):
+                if j + 1 < len(word) and (word[j], word[j + 1]) == best:
+                    new.append(merged)
+                    j += 2
+                else:
+                    new.append(word[j])
+                    j += 1
+            words[i] = new
+            for pair in zip(new, new[1:]):
+                pairs[pair] += freqs[i]
+                where[pair].add(i)
+        del where[best]
+
+    return [bytes([b]) for b in range(256)] + merges
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__)
+    parser.add_argument("output")
+    parser.add_argument("corpus", nargs="+")
+    parser.add_argument("--size", type=int, default=8192)
+    args = parser.parse_args()
+
+    vocab = train(piece_counts(args.corpus), args.size)
+    with open(args.output, "w") as out:
+        for rank, token in enumerate(vocab):
+            out.write(f"{base64.b64encode(token).decode()} {rank}\n")
+    print(f"wrote {len(vocab)} tokens to {args.output}", file=sys.stderr)
+
+
+if __name__ == "__main__":
+    main()
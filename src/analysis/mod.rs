@@ -7,7 +7,6 @@ pub mod report;
 
 use crate::Result;
 use oxc_ast::ast::Program;
-use serde::{Deserialize, Serialize};
 
 /// Main analyzer that performs deep code analysis.
 pub struct AdvancedAnalyzer<'a> {
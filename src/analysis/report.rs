@@ -102,7 +102,7 @@ impl AnalysisReport {
             content.push_str("|----------|------------|---------|--------|------------|\n");
 
             let mut sorted = self.complexity.function_complexity.clone();
-            sorted.sort_by(|a, b| b.cyclomatic.cmp(&a.cyclomatic));
+            sorted.sort_by_key(|f| std::cmp::Reverse(f.cyclomatic));
 
             for func in sorted.iter().take(10) {
                 content.push_str(&format!(
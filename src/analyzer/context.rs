@@ -0,0 +1,485 @@
+//! Where an extracted artifact lives: its span, enclosing function and binding.
+//!
+//! [`ContextIndex`] records the span of every function, of every value
+//! assigned to a variable, member or object property, and of every call
+//! argument. Each set nests like the AST it comes from, so the innermost
+//! interval containing an artifact is found by binary search and a walk up
+//! the parent chain. A binding or call only counts when it lies inside the
+//! artifact's innermost function: a string in a callback isn't assigned to
+//! whatever the surrounding call is.
+
+use crate::parser::{LineIndex, SourceSpan};
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+
+/// Location of an artifact in `beautified.js`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BeautifiedLocation {
+    /// 1-based line.
+    pub line: usize,
+
+    /// 1-based column; omitted when variable renaming shifted the line.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub column: Option<usize>,
+}
+
+/// Where an extracted artifact came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Byte span, line and column in the original bundle.
+    pub span: SourceSpan,
+
+    /// Line and column in `beautified.js`, when it was generated.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub beautified: Option<BeautifiedLocation>,
+
+    /// Innermost named function enclosing the artifact.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub function: Option<String>,
+
+    /// Variable, member or property the artifact is assigned to.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub binding: Option<String>,
+}
+
+/// Artifacts that record their provenance.
+pub trait HasProvenance {
+    /// The artifact's provenance, if it was located.
+    fn provenance_mut(&mut self) -> Option<&mut Provenance>;
+
+    /// Nested artifacts with provenance of their own, e.g. subcommands.
+    fn children_mut(&mut self) -> &mut [Self]
+    where
+        Self: Sized,
+    {
+        &mut []
+    }
+}
+
+/// Functions and assigned values, by span.
+#[derive(Debug, Default)]
+pub struct ContextIndex {
+    functions: Intervals,
+    bindings: Intervals,
+    arguments: Intervals,
+}
+
+impl ContextIndex {
+    /// Index every function and binding in a program.
+    pub fn new(program: &Program<'_>) -> Self {
+        let mut collector = ContextCollector {
+            source_text: program.source_text,
+            index: ContextIndex::default(),
+        };
+        collector.visit_statements(&program.body);
+
+        let mut index = collector.index;
+        index.functions.finish();
+        index.bindings.finish();
+        index.arguments.finish();
+        index
+    }
+
+    /// Innermost named function containing a span.
+    pub fn function_at(&self, span: Span) -> Option<&str> {
+        self.functions
+            .containing(span)
+            .find_map(|entry| entry.name.as_deref())
+    }
+
+    /// Innermost binding whose value contains a span within its function.
+    pub fn binding_at(&self, span: Span) -> Option<&str> {
+        let binding = self.bindings.containing(span).next()?;
+        self.in_scope(span, binding)
+    }
+
+    /// Callee of the innermost call whose argument contains a span within its function.
+    pub fn callee_at(&self, span: Span) -> Option<&str> {
+        let argument = self.arguments.containing(span).next()?;
+        self.in_scope(span, argument)
+    }
+
+    /// Name of an interval lying strictly inside the span's innermost function.
+    fn in_scope<'s>(&self, span: Span, entry: &'s Interval) -> Option<&'s str> {
+        if let Some(scope) = self.functions.containing(span).next() {
+            let inside = scope.start <= entry.start && entry.end <= scope.end;
+            if !inside || (scope.start, scope.end) == (entry.start, entry.end) {
+                return None;
+            }
+        }
+        entry.name.as_deref()
+    }
+
+    /// Provenance of a span, without a beautified location.
+    pub fn provenance(&self, line_index: &LineIndex, span: Span) -> Provenance {
+        Provenance {
+            span: SourceSpan::locate(line_index, span),
+            beautified: None,
+            function: self.function_at(span).map(str::to_string),
+            binding: self.binding_at(span).map(str::to_string),
+        }
+    }
+}
+
+/// Properly nested spans, some named.
+#[derive(Debug, Default)]
+struct Intervals {
+    entries: Vec<Interval>,
+}
+
+#[derive(Debug)]
+struct Interval {
+    start: u32,
+    end: u32,
+    name: Option<String>,
+    parent: Option<usize>,
+}
+
+impl Intervals {
+    fn push(&mut self, span: Span, name: Option<String>) {
+        self.entries.push(Interval {
+            start: span.start,
+            end: span.end,
+            name,
+            parent: None,
+        });
+    }
+
+    /// Sort outermost first and link each interval to its container.
+    fn finish(&mut self) {
+        self.entries
+            .sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+        let mut stack: Vec<usize> = Vec::new();
+        for idx in 0..self.entries.len() {
+            let end = self.entries[idx].end;
+            while stack.last().is_some_and(|&top| self.entries[top].end < end) {
+                stack.pop();
+            }
+            self.entries[idx].parent = stack.last().copied();
+            stack.push(idx);
+        }
+    }
+
+    /// Intervals containing a span, innermost first.
+    fn containing(&self, span: Span) -> impl Iterator<Item = &Interval> {
+        let last = self.entries.partition_point(|e| e.start <= span.start);
+        let first = last.checked_sub(1).map(|i| &self.entries[i]);
+        std::iter::successors(first, |entry| entry.parent.map(|i| &self.entries[i]))
+            .filter(move |entry| span.end <= entry.end)
+    }
+}
+
+/// Walks the AST recording functions and bound values.
+struct ContextCollector<'s> {
+    source_text: &'s str,
+    index: ContextIndex,
+}
+
+impl ContextCollector<'_> {
+    fn visit_statements(&mut self, stmts: &[Statement<'_>]) {
+        for stmt in stmts {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement<'_>) {
+        match stmt {
+            Statement::VariableDeclaration(decl) => self.visit_declaration(decl),
+            Statement::ExpressionStatement(expr_stmt) => self.visit_expression(&expr_stmt.expression),
+            Statement::ReturnStatement(ret) => {
+                if let Some(ref arg) = ret.argument {
+                    self.visit_expression(arg);
+                }
+            }
+            Statement::ThrowStatement(throw) => self.visit_expression(&throw.argument),
+            Statement::BlockStatement(block) => self.visit_statements(&block.body),
+            Statement::IfStatement(if_stmt) => {
+                self.visit_expression(&if_stmt.test);
+                self.visit_statement(&if_stmt.consequent);
+                if let Some(ref alt) = if_stmt.alternate {
+                    self.visit_statement(alt);
+                }
+            }
+            Statement::FunctionDeclaration(func) => self.visit_function(func, None),
+            Statement::ClassDeclaration(class) => self.visit_class(class, None),
+            Statement::TryStatement(try_stmt) => {
+                self.visit_statements(&try_stmt.block.body);
+                if let Some(ref handler) = try_stmt.handler {
+                    self.visit_statements(&handler.body.body);
+                }
+                if let Some(ref finalizer) = try_stmt.finalizer {
+                    self.visit_statements(&finalizer.body);
+                }
+            }
+            Statement::SwitchStatement(switch) => {
+                self.visit_expression(&switch.discriminant);
+                for case in &switch.cases {
+                    self.visit_statements(&case.consequent);
+                }
+            }
+            Statement::ForStatement(for_stmt) => {
+                if let Some(ForStatementInit::VariableDeclaration(decl)) = &for_stmt.init {
+                    self.visit_declaration(decl);
+                }
+                self.visit_statement(&for_stmt.body);
+            }
+            Statement::ForInStatement(for_in) => self.visit_statement(&for_in.body),
+            Statement::ForOfStatement(for_of) => {
+                self.visit_expression(&for_of.right);
+                self.visit_statement(&for_of.body);
+            }
+            Statement::WhileStatement(while_stmt) => self.visit_statement(&while_stmt.body),
+            Statement::DoWhileStatement(do_while) => self.visit_statement(&do_while.body),
+            Statement::LabeledStatement(labeled) => self.visit_statement(&labeled.body),
+            Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                Some(Declaration::VariableDeclaration(decl)) => self.visit_declaration(decl),
+                Some(Declaration::FunctionDeclaration(func)) => self.visit_function(func, None),
+                Some(Declaration::ClassDeclaration(class)) => self.visit_class(class, None),
+                _ => {}
+            },
+            Statement::ExportDefaultDeclaration(export) => match &export.declaration {
+                ExportDefaultDeclarationKind::FunctionDeclaration(func) => self.visit_function(func, None),
+                ExportDefaultDeclarationKind::ClassDeclaration(class) => self.visit_class(class, None),
+                other => {
+                    if let Some(expr) = other.as_expression() {
+                        self.visit_expression(expr);
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn visit_declaration(&mut self, decl: &VariableDeclaration<'_>) {
+        for declarator in &decl.declarations {
+            let Some(ref init) = declarator.init else { continue };
+            match declarator.id.get_binding_identifier() {
+                Some(id) => self.visit_bound(id.name.to_string(), init),
+                None => self.visit_expression(init),
+            }
+        }
+    }
+
+    /// Record a value assigned to `name` and visit it.
+    fn visit_bound(&mut self, name: String, expr: &Expression<'_>) {
+        self.index.bindings.push(expr.span(), Some(name.clone()));
+        match expr {
+            Expression::ObjectExpression(obj) => self.visit_object(obj, Some(&name)),
+            Expression::FunctionExpression(func) => self.visit_function(func, Some(name)),
+            Expression::ArrowFunctionExpression(arrow) => {
+                self.index.functions.push(arrow.span, Some(name));
+                self.visit_statements(&arrow.body.statements);
+            }
+            Expression::ClassExpression(class) => self.visit_class(class, Some(name)),
+            Expression::ParenthesizedExpression(paren) => self.visit_bound(name, &paren.expression),
+            _ => self.visit_expression(expr),
+        }
+    }
+
+    /// Visit object properties, naming values `owner.key` when the object is bound.
+    fn visit_object(&mut self, obj: &ObjectExpression<'_>, owner: Option<&str>) {
+        for prop in &obj.properties {
+            match prop {
+                ObjectPropertyKind::ObjectProperty(p) => match p.key.static_name() {
+                    Some(key) => {
+                        let name = match owner {
+                            Some(owner) => format!("{}.{}", owner, key),
+                            None => key.to_string(),
+                        };
+                        self.visit_bound(name, &p.value);
+                    }
+                    None => self.visit_expression(&p.value),
+                },
+                ObjectPropertyKind::SpreadProperty(p) => self.visit_expression(&p.argument),
+            }
+        }
+    }
+
+    fn visit_function(&mut self, func: &Function<'_>, name: Option<String>) {
+        let name = func.id.as_ref().map(|id| id.name.to_string()).or(name);
+        self.index.functions.push(func.span, name);
+        if let Some(ref body) = func.body {
+            self.visit_statements(&body.statements);
+        }
+    }
+
+    fn visit_class(&mut self, class: &Class<'_>, name: Option<String>) {
+        let class_name = class.id.as_ref().map(|id| id.name.to_string()).or(name);
+        if let Some(ref super_class) = class.super_class {
+            self.visit_expression(super_class);
+        }
+        for element in &class.body.body {
+            match element {
+                ClassElement::MethodDefinition(method) => {
+                    let method_name = method.key.static_name().map(|key| match &class_name {
+                        Some(class_name) => format!("{}.{}", class_name, key),
+                        None => key.to_string(),
+                    });
+                    self.visit_function(&method.value, method_name);
+                }
+                ClassElement::PropertyDefinition(prop) => {
+                    if let Some(ref value) = prop.value {
+                        self.visit_expression(value);
+                    }
+                }
+                ClassElement::StaticBlock(block) => self.visit_statements(&block.body),
+                _ => {}
+            }
+        }
+    }
+
+    /// Record each argument under the callee's name and visit it.
+    fn visit_arguments(&mut self, callee: &Expression<'_>, arguments: &[Argument<'_>]) {
+        let name = self.callee_name(callee);
+        for arg in arguments {
+            self.index.arguments.push(arg.span(), name.clone());
+            if let Argument::SpreadElement(spread) = arg {
+                self.visit_expression(&spread.argument);
+            } else if let Some(expr) = arg.as_expression() {
+                self.visit_expression(expr);
+            }
+        }
+    }
+
+    /// Source of an assignment target that names a variable or member chain.
+    fn target_name(&self, target: &AssignmentTarget<'_>) -> Option<String> {
+        match target {
+            AssignmentTarget::AssignmentTargetIdentifier(id) => Some(id.name.to_string()),
+            AssignmentTarget::StaticMemberExpression(member) => {
+                let span = member.span;
+                Some(self.source_text[span.start as usize..span.end as usize].to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Source of a callee that names a function or member chain.
+    fn callee_name(&self, callee: &Expression<'_>) -> Option<String> {
+        match callee.without_parentheses() {
+            Expression::Identifier(id) => Some(id.name.to_string()),
+            Expression::StaticMemberExpression(member) => {
+                let span = member.span;
+                Some(self.source_text[span.start as usize..span.end as usize].to_string())
+            }
+            _ => None,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'_>) {
+        match expr {
+            Expression::CallExpression(call) => {
+                self.visit_expression(&call.callee);
+                self.visit_arguments(&call.callee, &call.arguments);
+            }
+            Expression::NewExpression(new_expr) => {
+                self.visit_expression(&new_expr.callee);
+                self.visit_arguments(&new_expr.callee, &new_expr.arguments);
+            }
+            Expression::StaticMemberExpression(member) => self.visit_expression(&member.object),
+            Expression::ComputedMemberExpression(member) => {
+                self.visit_expression(&member.object);
+                self.visit_expression(&member.expression);
+            }
+            Expression::ChainExpression(chain) => {
+                if let ChainElement::CallExpression(call) = &chain.expression {
+                    self.visit_expression(&call.callee);
+                    self.visit_arguments(&call.callee, &call.arguments);
+                }
+            }
+            Expression::ObjectExpression(obj) => self.visit_object(obj, None),
+            Expression::ArrayExpression(arr) => {
+                for elem in &arr.elements {
+                    if let ArrayExpressionElement::SpreadElement(spread) = elem {
+                        self.visit_expression(&spread.argument);
+                    } else if let Some(expr) = elem.as_expression() {
+                        self.visit_expression(expr);
+                    }
+                }
+            }
+            Expression::TemplateLiteral(tmpl) => {
+                for expr in &tmpl.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::TaggedTemplateExpression(tagged) => {
+                for expr in &tagged.quasi.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::FunctionExpression(func) => self.visit_function(func, None),
+            Expression::ArrowFunctionExpression(arrow) => {
+                self.index.functions.push(arrow.span, None);
+                self.visit_statements(&arrow.body.statements);
+            }
+            Expression::ClassExpression(class) => self.visit_class(class, None),
+            Expression::AssignmentExpression(assign) => match self.target_name(&assign.left) {
+                Some(name) => self.visit_bound(name, &assign.right),
+                None => self.visit_expression(&assign.right),
+            },
+            Expression::BinaryExpression(binary) => {
+                self.visit_expression(&binary.left);
+                self.visit_expression(&binary.right);
+            }
+            Expression::LogicalExpression(logical) => {
+                self.visit_expression(&logical.left);
+                self.visit_expression(&logical.right);
+            }
+            Expression::ConditionalExpression(cond) => {
+                self.visit_expression(&cond.test);
+                self.visit_expression(&cond.consequent);
+                self.visit_expression(&cond.alternate);
+            }
+            Expression::SequenceExpression(seq) => {
+                for expr in &seq.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::UnaryExpression(unary) => self.visit_expression(&unary.argument),
+            Expression::AwaitExpression(await_expr) => self.visit_expression(&await_expr.argument),
+            Expression::ParenthesizedExpression(paren) => self.visit_expression(&paren.expression),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_function_and_binding_context() {
+        let code = "var A = L(() => {\n  B = { name: \"Read\", prompt: \"reads\" };\n});\n\
+                    function buildPrompt(x) { var C = \"hello \" + x; return C; }\n\
+                    class Tool { describe() { return \"desc\"; } }\n\
+                    console.error(\"oops\", { code: \"E1\" });";
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let index = ContextIndex::new(parse_result.program());
+
+        let at = |needle: &str| {
+            let start = code.find(needle).unwrap() as u32;
+            Span::new(start, start + needle.len() as u32)
+        };
+
+        assert_eq!(index.binding_at(at("\"Read\"")), Some("B.name"));
+        assert_eq!(index.binding_at(at("{ name: \"Read\", prompt: \"reads\" }")), Some("B"));
+        assert_eq!(index.binding_at(at("\"hello \"")), Some("C"));
+        assert_eq!(index.binding_at(at("\"desc\"")), None);
+        assert_eq!(index.function_at(at("\"hello \"")), Some("buildPrompt"));
+        assert_eq!(index.function_at(at("\"reads\"")), None);
+        assert_eq!(index.function_at(at("\"desc\"")), Some("Tool.describe"));
+        assert_eq!(index.callee_at(at("\"oops\"")), Some("console.error"));
+        assert_eq!(index.callee_at(at("\"E1\"")), Some("console.error"));
+        assert_eq!(index.callee_at(at("\"reads\"")), None);
+
+        let provenance = index.provenance(parse_result.line_index(), at("\"reads\""));
+        assert_eq!(provenance.span.line, 2);
+        assert_eq!(provenance.binding.as_deref(), Some("B.prompt"));
+    }
+}
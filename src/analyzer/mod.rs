@@ -1,24 +1,64 @@
 //! AST analysis module for understanding code structure.
 
+pub mod context;
 pub mod symbols;
 
+use crate::parser::LineIndex;
 use oxc_ast::ast::*;
+use oxc_span::Span;
+use std::cell::OnceCell;
+
+pub use context::{BeautifiedLocation, ContextIndex, HasProvenance, Provenance};
 pub use symbols::SymbolTable;
 
 /// Analyzes the AST to find interesting patterns and structures.
 pub struct Analyzer<'a> {
     program: &'a Program<'a>,
+    line_index: Option<&'a LineIndex>,
+    owned_line_index: OnceCell<LineIndex>,
+    context: OnceCell<ContextIndex>,
 }
 
 impl<'a> Analyzer<'a> {
     /// Create a new analyzer for the given program.
     pub fn new(program: &'a Program<'a>) -> Self {
-        Self { program }
+        Self {
+            program,
+            line_index: None,
+            owned_line_index: OnceCell::new(),
+            context: OnceCell::new(),
+        }
+    }
+
+    /// Share the parser's line index instead of building another.
+    pub fn with_line_index(mut self, line_index: &'a LineIndex) -> Self {
+        self.line_index = Some(line_index);
+        self
+    }
+
+    /// Line index of the program's source text.
+    pub fn line_index(&self) -> &LineIndex {
+        match self.line_index {
+            Some(index) => index,
+            None => self
+                .owned_line_index
+                .get_or_init(|| LineIndex::new(self.program.source_text)),
+        }
+    }
+
+    /// Enclosing functions and bindings, indexed on first use.
+    pub fn context(&self) -> &ContextIndex {
+        self.context.get_or_init(|| ContextIndex::new(self.program))
+    }
+
+    /// Span, enclosing function and binding of a node.
+    pub fn provenance(&self, span: Span) -> Provenance {
+        self.context().provenance(self.line_index(), span)
     }
 
     /// Find all string literals in the program.
     pub fn find_string_literals(&self) -> Vec<StringLiteralInfo<'a>> {
-        let mut collector = StringLiteralCollector::new();
+        let mut collector = StringLiteralCollector::new(self.line_index());
         collector.visit_program(self.program);
         collector.literals
     }
@@ -30,6 +70,34 @@ impl<'a> Analyzer<'a> {
         collector.objects
     }
 
+    /// Find every expression matching a predicate, in source order.
+    ///
+    /// Unlike the specialised collectors this descends into every statement
+    /// and expression form, including call arguments, classes and templates.
+    pub fn find_expressions<F>(&self, predicate: F) -> Vec<&'a Expression<'a>>
+    where
+        F: FnMut(&Expression<'a>) -> bool,
+    {
+        let mut collector = ExpressionCollector {
+            predicate,
+            found: Vec::new(),
+            functions: Vec::new(),
+        };
+        collector.visit_statements(&self.program.body);
+        collector.found
+    }
+
+    /// Find every function, method and arrow body, outermost first.
+    pub fn find_functions(&self) -> Vec<FunctionScope<'a>> {
+        let mut collector = ExpressionCollector {
+            predicate: |_: &Expression<'a>| false,
+            found: Vec::new(),
+            functions: Vec::new(),
+        };
+        collector.visit_statements(&self.program.body);
+        collector.functions
+    }
+
     /// Get the program reference.
     pub fn program(&self) -> &'a Program<'a> {
         self.program
@@ -42,6 +110,16 @@ pub struct StringLiteralInfo<'a> {
     pub value: &'a str,
     pub length: usize,
     pub line_hint: Option<usize>,
+    pub span: Span,
+}
+
+/// A function body found in the code.
+#[derive(Debug, Clone, Copy)]
+pub struct FunctionScope<'a> {
+    /// Span of the whole function, parameters included.
+    pub span: Span,
+    /// Body statements; an expression-bodied arrow has a single expression statement.
+    pub statements: &'a [Statement<'a>],
 }
 
 /// Information about an object expression found in the code.
@@ -62,17 +140,29 @@ pub struct PropertyInfo<'a> {
 }
 
 /// Visitor that collects all string literals.
-struct StringLiteralCollector<'a> {
+struct StringLiteralCollector<'a, 'i> {
     literals: Vec<StringLiteralInfo<'a>>,
+    line_index: &'i LineIndex,
 }
 
-impl<'a> StringLiteralCollector<'a> {
-    fn new() -> Self {
+impl<'a, 'i> StringLiteralCollector<'a, 'i> {
+    fn new(line_index: &'i LineIndex) -> Self {
         Self {
             literals: Vec::new(),
+            line_index,
         }
     }
 
+    fn push(&mut self, str_lit: &'a StringLiteral<'a>) {
+        let value = str_lit.value.as_str();
+        self.literals.push(StringLiteralInfo {
+            value,
+            length: value.len(),
+            line_hint: Some(self.line_index.locate(str_lit.span.start).0),
+            span: str_lit.span,
+        });
+    }
+
     fn visit_program(&mut self, program: &'a Program<'a>) {
         for stmt in &program.body {
             self.visit_statement(stmt);
@@ -96,6 +186,9 @@ impl<'a> StringLiteralCollector<'a> {
                     self.visit_expression(arg);
                 }
             }
+            Statement::ThrowStatement(throw) => {
+                self.visit_expression(&throw.argument);
+            }
             Statement::BlockStatement(block) => {
                 for stmt in &block.body {
                     self.visit_statement(stmt);
@@ -121,14 +214,7 @@ impl<'a> StringLiteralCollector<'a> {
 
     fn visit_expression(&mut self, expr: &'a Expression<'a>) {
         match expr {
-            Expression::StringLiteral(str_lit) => {
-                let value = str_lit.value.as_str();
-                self.literals.push(StringLiteralInfo {
-                    value,
-                    length: value.len(),
-                    line_hint: None,
-                });
-            }
+            Expression::StringLiteral(str_lit) => self.push(str_lit),
             Expression::ObjectExpression(obj) => {
                 for prop in &obj.properties {
                     match prop {
@@ -153,14 +239,7 @@ impl<'a> StringLiteralCollector<'a> {
                         | ArrayExpressionElement::NumericLiteral(_)
                         | ArrayExpressionElement::BigIntLiteral(_)
                         | ArrayExpressionElement::RegExpLiteral(_) => {}
-                        ArrayExpressionElement::StringLiteral(str_lit) => {
-                            let value = str_lit.value.as_str();
-                            self.literals.push(StringLiteralInfo {
-                                value,
-                                length: value.len(),
-                                line_hint: None,
-                            });
-                        }
+                        ArrayExpressionElement::StringLiteral(str_lit) => self.push(str_lit),
                         _ => {
                             // For other expression elements, we need to visit them
                             // This is a limitation of the current approach
@@ -188,7 +267,19 @@ impl<'a> StringLiteralCollector<'a> {
                                 }
                             }
                         }
-                        _ => {}
+                        // Messages passed to calls, for callee rules
+                        _ => {
+                            if let Some(expr) = arg.as_expression() {
+                                self.visit_expression(expr);
+                            }
+                        }
+                    }
+                }
+            }
+            Expression::NewExpression(new_expr) => {
+                for arg in &new_expr.arguments {
+                    if let Some(expr) = arg.as_expression() {
+                        self.visit_expression(expr);
                     }
                 }
             }
@@ -209,6 +300,10 @@ impl<'a> StringLiteralCollector<'a> {
 }
 
 /// Visitor that collects all object expressions.
+///
+/// Tool and command objects in the bundle usually live inside lazy_init
+/// callbacks or function bodies, so this walks into nested functions, call
+/// arguments and assignments rather than stopping at top-level declarations.
 struct ObjectCollector<'a> {
     objects: Vec<ObjectExpressionInfo<'a>>,
 }
@@ -226,6 +321,12 @@ impl<'a> ObjectCollector<'a> {
         }
     }
 
+    fn visit_statements(&mut self, stmts: &'a [Statement<'a>]) {
+        for stmt in stmts {
+            self.visit_statement(stmt);
+        }
+    }
+
     fn visit_statement(&mut self, stmt: &'a Statement<'a>) {
         match stmt {
             Statement::VariableDeclaration(decl) => {
@@ -244,71 +345,72 @@ impl<'a> ObjectCollector<'a> {
                 }
             }
             Statement::BlockStatement(block) => {
-                for stmt in &block.body {
-                    self.visit_statement(stmt);
+                self.visit_statements(&block.body);
+            }
+            Statement::IfStatement(if_stmt) => {
+                self.visit_expression(&if_stmt.test);
+                self.visit_statement(&if_stmt.consequent);
+                if let Some(ref alt) = if_stmt.alternate {
+                    self.visit_statement(alt);
                 }
             }
             Statement::FunctionDeclaration(func) => {
-                if let Some(ref body) = func.body {
-                    for stmt in &body.statements {
-                        self.visit_statement(stmt);
+                self.visit_function(func);
+            }
+            Statement::TryStatement(try_stmt) => {
+                self.visit_statements(&try_stmt.block.body);
+                if let Some(ref handler) = try_stmt.handler {
+                    self.visit_statements(&handler.body.body);
+                }
+                if let Some(ref finalizer) = try_stmt.finalizer {
+                    self.visit_statements(&finalizer.body);
+                }
+            }
+            Statement::SwitchStatement(switch) => {
+                self.visit_expression(&switch.discriminant);
+                for case in &switch.cases {
+                    self.visit_statements(&case.consequent);
+                }
+            }
+            Statement::ForStatement(for_stmt) => self.visit_statement(&for_stmt.body),
+            Statement::ForInStatement(for_in) => self.visit_statement(&for_in.body),
+            Statement::ForOfStatement(for_of) => {
+                self.visit_expression(&for_of.right);
+                self.visit_statement(&for_of.body);
+            }
+            Statement::WhileStatement(while_stmt) => self.visit_statement(&while_stmt.body),
+            Statement::DoWhileStatement(do_while) => self.visit_statement(&do_while.body),
+            Statement::LabeledStatement(labeled) => self.visit_statement(&labeled.body),
+            Statement::ExportNamedDeclaration(export) => {
+                if let Some(Declaration::VariableDeclaration(decl)) = &export.declaration {
+                    for declarator in &decl.declarations {
+                        if let Some(ref init) = declarator.init {
+                            self.visit_expression(init);
+                        }
                     }
                 }
             }
+            Statement::ExportDefaultDeclaration(export) => {
+                if let Some(expr) = export.declaration.as_expression() {
+                    self.visit_expression(expr);
+                }
+            }
             _ => {}
         }
     }
 
+    fn visit_function(&mut self, func: &'a Function<'a>) {
+        if let Some(ref body) = func.body {
+            self.visit_statements(&body.statements);
+        }
+    }
+
     fn visit_expression(&mut self, expr: &'a Expression<'a>) {
         match expr {
             Expression::ObjectExpression(obj) => {
-                let properties: Vec<PropertyInfo> = obj
-                    .properties
-                    .iter()
-                    .filter_map(|prop| match prop {
-                        ObjectPropertyKind::ObjectProperty(p) => {
-                            let key = match &p.key {
-                                PropertyKey::StaticIdentifier(id) => Some(id.name.as_str()),
-                                PropertyKey::StringLiteral(s) => Some(s.value.as_str()),
-                                _ => None,
-                            };
-
-                            // Extract the actual string value if it's a string literal
-                            let string_value = match &p.value {
-                                Expression::StringLiteral(s) => Some(s.value.as_str()),
-                                _ => None,
-                            };
-
-                            // Also capture identifier references (like: name: UvA)
-                            let identifier_value = match &p.value {
-                                Expression::Identifier(id) => Some(id.name.as_str()),
-                                _ => None,
-                            };
-
-                            // Check if value is a function (method or function expression)
-                            let is_function = p.method || matches!(
-                                &p.value,
-                                Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_)
-                            );
-
-                            Some(PropertyInfo {
-                                key,
-                                is_method: is_function,  // TRUE for both methods and function expressions
-                                string_value,
-                                identifier_value,
-                            })
-                        }
-                        _ => None,
-                    })
-                    .collect();
-
-                self.objects.push(ObjectExpressionInfo {
-                    property_count: properties.len(),
-                    properties,
-                    ast_object: obj,
-                });
+                self.collect_object(obj);
 
-                // Visit nested objects recursively
+                // Visit nested objects recursively (including method bodies)
                 for prop in &obj.properties {
                     match prop {
                         ObjectPropertyKind::ObjectProperty(p) => {
@@ -323,70 +425,377 @@ impl<'a> ObjectCollector<'a> {
             Expression::ArrayExpression(arr) => {
                 // Visit ALL array elements recursively
                 for elem in &arr.elements {
-                    match elem {
-                        ArrayExpressionElement::SpreadElement(spread) => {
-                            self.visit_expression(&spread.argument);
-                        }
-                        // Handle object expressions in arrays (THIS IS KEY!)
-                        ArrayExpressionElement::ObjectExpression(obj_expr) => {
-                            // Collect properties from objects in arrays
-                            let properties: Vec<PropertyInfo> = obj_expr
-                                .properties
-                                .iter()
-                                .filter_map(|prop| match prop {
-                                    ObjectPropertyKind::ObjectProperty(p) => {
-                                        let key = match &p.key {
-                                            PropertyKey::StaticIdentifier(id) => Some(id.name.as_str()),
-                                            PropertyKey::StringLiteral(s) => Some(s.value.as_str()),
-                                            _ => None,
-                                        };
-
-                                        // Extract the actual string value
-                                        let string_value = match &p.value {
-                                            Expression::StringLiteral(s) => Some(s.value.as_str()),
-                                            _ => None,
-                                        };
-
-                                        // Also capture identifier references
-                                        let identifier_value = match &p.value {
-                                            Expression::Identifier(id) => Some(id.name.as_str()),
-                                            _ => None,
-                                        };
-
-                                        // Visit the property value recursively
-                                        self.visit_expression(&p.value);
-                                        Some(PropertyInfo {
-                                            key,
-                                            is_method: p.method,
-                                            string_value,
-                                            identifier_value,
-                                        })
-                                    }
-                                    ObjectPropertyKind::SpreadProperty(p) => {
-                                        self.visit_expression(&p.argument);
-                                        None
-                                    }
-                                })
-                                .collect();
-
-                            self.objects.push(ObjectExpressionInfo {
-                                property_count: properties.len(),
-                                properties,
-                                ast_object: obj_expr,
-                            });
+                    if let ArrayExpressionElement::SpreadElement(spread) = elem {
+                        self.visit_expression(&spread.argument);
+                    } else if let Some(expr) = elem.as_expression() {
+                        self.visit_expression(expr);
+                    }
+                }
+            }
+            Expression::CallExpression(call) => {
+                self.visit_expression(&call.callee);
+                self.visit_arguments(&call.arguments);
+            }
+            Expression::NewExpression(new_expr) => {
+                self.visit_arguments(&new_expr.arguments);
+            }
+            Expression::FunctionExpression(func) => {
+                self.visit_function(func);
+            }
+            Expression::ArrowFunctionExpression(arrow) => {
+                self.visit_statements(&arrow.body.statements);
+            }
+            Expression::AssignmentExpression(assign) => {
+                self.visit_expression(&assign.right);
+            }
+            Expression::SequenceExpression(seq) => {
+                for expr in &seq.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::ConditionalExpression(cond) => {
+                self.visit_expression(&cond.consequent);
+                self.visit_expression(&cond.alternate);
+            }
+            Expression::LogicalExpression(logical) => {
+                self.visit_expression(&logical.left);
+                self.visit_expression(&logical.right);
+            }
+            Expression::ParenthesizedExpression(paren) => {
+                self.visit_expression(&paren.expression);
+            }
+            Expression::AwaitExpression(await_expr) => {
+                self.visit_expression(&await_expr.argument);
+            }
+            Expression::UnaryExpression(unary) => {
+                self.visit_expression(&unary.argument);
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_arguments(&mut self, arguments: &'a [Argument<'a>]) {
+        for arg in arguments {
+            if let Argument::SpreadElement(spread) = arg {
+                self.visit_expression(&spread.argument);
+            } else if let Some(expr) = arg.as_expression() {
+                self.visit_expression(expr);
+            }
+        }
+    }
+
+    /// Record an object expression and its statically-keyed properties.
+    fn collect_object(&mut self, obj: &'a ObjectExpression<'a>) {
+        let properties: Vec<PropertyInfo> = obj
+            .properties
+            .iter()
+            .filter_map(|prop| match prop {
+                ObjectPropertyKind::ObjectProperty(p) => {
+                    let key = match &p.key {
+                        PropertyKey::StaticIdentifier(id) => Some(id.name.as_str()),
+                        PropertyKey::StringLiteral(s) => Some(s.value.as_str()),
+                        _ => None,
+                    };
+
+                    // Extract the actual string value if it's a string literal
+                    let string_value = match &p.value {
+                        Expression::StringLiteral(s) => Some(s.value.as_str()),
+                        _ => None,
+                    };
+
+                    // Also capture identifier references (like: name: UvA)
+                    let identifier_value = match &p.value {
+                        Expression::Identifier(id) => Some(id.name.as_str()),
+                        _ => None,
+                    };
+
+                    // Check if value is a function (method or function expression)
+                    let is_function = p.method || matches!(
+                        &p.value,
+                        Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_)
+                    );
+
+                    Some(PropertyInfo {
+                        key,
+                        is_method: is_function,  // TRUE for both methods and function expressions
+                        string_value,
+                        identifier_value,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        self.objects.push(ObjectExpressionInfo {
+            property_count: properties.len(),
+            properties,
+            ast_object: obj,
+        });
+    }
+}
+
+/// Visitor that collects every expression matching a predicate.
+struct ExpressionCollector<'a, F> {
+    predicate: F,
+    found: Vec<&'a Expression<'a>>,
+    functions: Vec<FunctionScope<'a>>,
+}
+
+impl<'a, F> ExpressionCollector<'a, F>
+where
+    F: FnMut(&Expression<'a>) -> bool,
+{
+    fn visit_statements(&mut self, stmts: &'a [Statement<'a>]) {
+        for stmt in stmts {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &'a Statement<'a>) {
+        match stmt {
+            Statement::VariableDeclaration(decl) => self.visit_declaration(decl),
+            Statement::ExpressionStatement(expr_stmt) => self.visit_expression(&expr_stmt.expression),
+            Statement::ReturnStatement(ret) => {
+                if let Some(ref arg) = ret.argument {
+                    self.visit_expression(arg);
+                }
+            }
+            Statement::ThrowStatement(throw) => self.visit_expression(&throw.argument),
+            Statement::BlockStatement(block) => self.visit_statements(&block.body),
+            Statement::IfStatement(if_stmt) => {
+                self.visit_expression(&if_stmt.test);
+                self.visit_statement(&if_stmt.consequent);
+                if let Some(ref alt) = if_stmt.alternate {
+                    self.visit_statement(alt);
+                }
+            }
+            Statement::FunctionDeclaration(func) => self.visit_function(func),
+            Statement::ClassDeclaration(class) => self.visit_class(class),
+            Statement::TryStatement(try_stmt) => {
+                self.visit_statements(&try_stmt.block.body);
+                if let Some(ref handler) = try_stmt.handler {
+                    self.visit_statements(&handler.body.body);
+                }
+                if let Some(ref finalizer) = try_stmt.finalizer {
+                    self.visit_statements(&finalizer.body);
+                }
+            }
+            Statement::SwitchStatement(switch) => {
+                self.visit_expression(&switch.discriminant);
+                for case in &switch.cases {
+                    if let Some(ref test) = case.test {
+                        self.visit_expression(test);
+                    }
+                    self.visit_statements(&case.consequent);
+                }
+            }
+            Statement::ForStatement(for_stmt) => {
+                match &for_stmt.init {
+                    Some(ForStatementInit::VariableDeclaration(decl)) => self.visit_declaration(decl),
+                    Some(init) => {
+                        if let Some(expr) = init.as_expression() {
+                            self.visit_expression(expr);
                         }
-                        ArrayExpressionElement::ArrayExpression(nested_arr) => {
-                            // Handle nested arrays by recursively processing elements
-                            for nested_elem in &nested_arr.elements {
-                                if let ArrayExpressionElement::SpreadElement(spread) = nested_elem {
-                                    self.visit_expression(&spread.argument);
-                                }
+                    }
+                    None => {}
+                }
+                if let Some(ref test) = for_stmt.test {
+                    self.visit_expression(test);
+                }
+                if let Some(ref update) = for_stmt.update {
+                    self.visit_expression(update);
+                }
+                self.visit_statement(&for_stmt.body);
+            }
+            Statement::ForInStatement(for_in) => {
+                self.visit_expression(&for_in.right);
+                self.visit_statement(&for_in.body);
+            }
+            Statement::ForOfStatement(for_of) => {
+                self.visit_expression(&for_of.right);
+                self.visit_statement(&for_of.body);
+            }
+            Statement::WhileStatement(while_stmt) => {
+                self.visit_expression(&while_stmt.test);
+                self.visit_statement(&while_stmt.body);
+            }
+            Statement::DoWhileStatement(do_while) => {
+                self.visit_statement(&do_while.body);
+                self.visit_expression(&do_while.test);
+            }
+            Statement::LabeledStatement(labeled) => self.visit_statement(&labeled.body),
+            Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                Some(Declaration::VariableDeclaration(decl)) => self.visit_declaration(decl),
+                Some(Declaration::FunctionDeclaration(func)) => self.visit_function(func),
+                Some(Declaration::ClassDeclaration(class)) => self.visit_class(class),
+                _ => {}
+            },
+            Statement::ExportDefaultDeclaration(export) => match &export.declaration {
+                ExportDefaultDeclarationKind::FunctionDeclaration(func) => self.visit_function(func),
+                ExportDefaultDeclarationKind::ClassDeclaration(class) => self.visit_class(class),
+                other => {
+                    if let Some(expr) = other.as_expression() {
+                        self.visit_expression(expr);
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn visit_declaration(&mut self, decl: &'a VariableDeclaration<'a>) {
+        for declarator in &decl.declarations {
+            if let Some(ref init) = declarator.init {
+                self.visit_expression(init);
+            }
+        }
+    }
+
+    fn visit_function(&mut self, func: &'a Function<'a>) {
+        if let Some(ref body) = func.body {
+            self.functions.push(FunctionScope {
+                span: func.span,
+                statements: &body.statements,
+            });
+            self.visit_statements(&body.statements);
+        }
+    }
+
+    fn visit_class(&mut self, class: &'a Class<'a>) {
+        if let Some(ref super_class) = class.super_class {
+            self.visit_expression(super_class);
+        }
+        for element in &class.body.body {
+            match element {
+                ClassElement::MethodDefinition(method) => self.visit_function(&method.value),
+                ClassElement::PropertyDefinition(prop) => {
+                    if let Some(ref value) = prop.value {
+                        self.visit_expression(value);
+                    }
+                }
+                ClassElement::StaticBlock(block) => self.visit_statements(&block.body),
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_arguments(&mut self, arguments: &'a [Argument<'a>]) {
+        for arg in arguments {
+            if let Argument::SpreadElement(spread) = arg {
+                self.visit_expression(&spread.argument);
+            } else if let Some(expr) = arg.as_expression() {
+                self.visit_expression(expr);
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &'a Expression<'a>) {
+        if (self.predicate)(expr) {
+            self.found.push(expr);
+        }
+
+        match expr {
+            Expression::CallExpression(call) => {
+                self.visit_expression(&call.callee);
+                self.visit_arguments(&call.arguments);
+            }
+            Expression::NewExpression(new_expr) => {
+                self.visit_expression(&new_expr.callee);
+                self.visit_arguments(&new_expr.arguments);
+            }
+            Expression::StaticMemberExpression(member) => self.visit_expression(&member.object),
+            Expression::ComputedMemberExpression(member) => {
+                self.visit_expression(&member.object);
+                self.visit_expression(&member.expression);
+            }
+            Expression::ChainExpression(chain) => match &chain.expression {
+                ChainElement::CallExpression(call) => {
+                    self.visit_expression(&call.callee);
+                    self.visit_arguments(&call.arguments);
+                }
+                ChainElement::StaticMemberExpression(member) => self.visit_expression(&member.object),
+                ChainElement::ComputedMemberExpression(member) => {
+                    self.visit_expression(&member.object);
+                    self.visit_expression(&member.expression);
+                }
+                _ => {}
+            },
+            Expression::ObjectExpression(obj) => {
+                for prop in &obj.properties {
+                    match prop {
+                        ObjectPropertyKind::ObjectProperty(p) => {
+                            if let Some(key) = p.key.as_expression() {
+                                self.visit_expression(key);
                             }
+                            self.visit_expression(&p.value);
                         }
-                        _ => {}
+                        ObjectPropertyKind::SpreadProperty(p) => self.visit_expression(&p.argument),
+                    }
+                }
+            }
+            Expression::ArrayExpression(arr) => {
+                for elem in &arr.elements {
+                    if let ArrayExpressionElement::SpreadElement(spread) = elem {
+                        self.visit_expression(&spread.argument);
+                    } else if let Some(expr) = elem.as_expression() {
+                        self.visit_expression(expr);
+                    }
+                }
+            }
+            Expression::TemplateLiteral(tmpl) => {
+                for expr in &tmpl.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::TaggedTemplateExpression(tagged) => {
+                self.visit_expression(&tagged.tag);
+                for expr in &tagged.quasi.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::FunctionExpression(func) => self.visit_function(func),
+            Expression::ArrowFunctionExpression(arrow) => {
+                self.functions.push(FunctionScope {
+                    span: arrow.span,
+                    statements: &arrow.body.statements,
+                });
+                self.visit_statements(&arrow.body.statements);
+            }
+            Expression::ClassExpression(class) => self.visit_class(class),
+            Expression::AssignmentExpression(assign) => {
+                if let Some(target) = assign.left.as_simple_assignment_target() {
+                    if let Some(member) = target.as_member_expression() {
+                        self.visit_expression(member.object());
                     }
                 }
+                self.visit_expression(&assign.right);
+            }
+            Expression::BinaryExpression(binary) => {
+                self.visit_expression(&binary.left);
+                self.visit_expression(&binary.right);
+            }
+            Expression::LogicalExpression(logical) => {
+                self.visit_expression(&logical.left);
+                self.visit_expression(&logical.right);
+            }
+            Expression::ConditionalExpression(cond) => {
+                self.visit_expression(&cond.test);
+                self.visit_expression(&cond.consequent);
+                self.visit_expression(&cond.alternate);
+            }
+            Expression::SequenceExpression(seq) => {
+                for expr in &seq.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::UnaryExpression(unary) => self.visit_expression(&unary.argument),
+            Expression::AwaitExpression(await_expr) => self.visit_expression(&await_expr.argument),
+            Expression::YieldExpression(yield_expr) => {
+                if let Some(ref arg) = yield_expr.argument {
+                    self.visit_expression(arg);
+                }
             }
+            Expression::ParenthesizedExpression(paren) => self.visit_expression(&paren.expression),
             _ => {}
         }
     }
@@ -40,6 +40,8 @@ pub struct SymbolTable<'a> {
     pub symbols: HashMap<String, SymbolValue>,
     /// Lazy init blocks found during parsing.
     pub lazy_blocks: Vec<LazyInitBlock>,
+    /// Initializer expressions for variables and lazy_init assignments.
+    bindings: HashMap<String, &'a Expression<'a>>,
     /// The program AST.
     program: &'a Program<'a>,
 }
@@ -50,6 +52,7 @@ impl<'a> SymbolTable<'a> {
         let mut table = Self {
             symbols: HashMap::new(),
             lazy_blocks: Vec::new(),
+            bindings: HashMap::new(),
             program,
         };
         table.build();
@@ -59,7 +62,8 @@ impl<'a> SymbolTable<'a> {
     /// Build the symbol table by traversing the AST.
     fn build(&mut self) {
         // Pass 1: Extract top-level declarations (variables AND functions)
-        for stmt in &self.program.body {
+        let program = self.program;
+        for stmt in &program.body {
             self.visit_statement(stmt);
         }
 
@@ -76,7 +80,7 @@ impl<'a> SymbolTable<'a> {
     }
 
     /// Visit a statement to collect variable declarations and function declarations.
-    fn visit_statement(&mut self, stmt: &Statement) {
+    fn visit_statement(&mut self, stmt: &'a Statement<'a>) {
         match stmt {
             Statement::VariableDeclaration(decl) => {
                 self.visit_variable_declaration(decl);
@@ -89,7 +93,7 @@ impl<'a> SymbolTable<'a> {
     }
 
     /// Visit function declarations and extract their return values.
-    fn visit_function_declaration(&mut self, func: &Function) {
+    fn visit_function_declaration(&mut self, func: &'a Function<'a>) {
         if let Some(id) = &func.id {
             let func_name = id.name.as_str().to_string();
 
@@ -110,13 +114,14 @@ impl<'a> SymbolTable<'a> {
     }
 
     /// Visit variable declarations and extract values.
-    fn visit_variable_declaration(&mut self, decl: &VariableDeclaration) {
+    fn visit_variable_declaration(&mut self, decl: &'a VariableDeclaration<'a>) {
         for declarator in &decl.declarations {
             // Handle simple identifier patterns
             if let BindingPatternKind::BindingIdentifier(id) = &declarator.id.kind {
                 let var_name = id.name.as_str().to_string();
 
                 if let Some(ref init) = declarator.init {
+                    self.bindings.insert(var_name.clone(), init);
                     let value = self.extract_value(init);
                     trace!("Variable declaration: {} = {:?}", var_name, value);
                     self.symbols.insert(var_name, value);
@@ -126,7 +131,7 @@ impl<'a> SymbolTable<'a> {
     }
 
     /// Extract value from any expression.
-    fn extract_value(&mut self, expr: &Expression) -> SymbolValue {
+    fn extract_value(&mut self, expr: &'a Expression<'a>) -> SymbolValue {
         match expr {
             Expression::StringLiteral(s) => {
                 SymbolValue::String(s.value.as_str().to_string())
@@ -174,24 +179,22 @@ impl<'a> SymbolTable<'a> {
     }
 
     /// Extract assignments from lazy_init block.
-    fn extract_lazy_init_block(&mut self, call: &CallExpression) {
-        if let Some(arg) = call.arguments.first() {
-            if let Argument::ArrowFunctionExpression(arrow) = arg {
-                let assignments = self.extract_assignments_from_function_body(&arrow.body);
-
-                if !assignments.is_empty() {
-                    trace!("Extracted {} assignments from lazy_init block", assignments.len());
-                    self.lazy_blocks.push(LazyInitBlock {
-                        assignments,
-                        span: arrow.span,
-                    });
-                }
+    fn extract_lazy_init_block(&mut self, call: &'a CallExpression<'a>) {
+        if let Some(Argument::ArrowFunctionExpression(arrow)) = call.arguments.first() {
+            let assignments = self.extract_assignments_from_function_body(&arrow.body);
+
+            if !assignments.is_empty() {
+                trace!("Extracted {} assignments from lazy_init block", assignments.len());
+                self.lazy_blocks.push(LazyInitBlock {
+                    assignments,
+                    span: arrow.span,
+                });
             }
         }
     }
 
     /// Extract variable assignments from a function body.
-    fn extract_assignments_from_function_body(&mut self, body: &FunctionBody) -> Vec<(String, SymbolValue)> {
+    fn extract_assignments_from_function_body(&mut self, body: &'a FunctionBody<'a>) -> Vec<(String, SymbolValue)> {
         let mut assignments = Vec::new();
 
         for stmt in &body.statements {
@@ -200,6 +203,7 @@ impl<'a> SymbolTable<'a> {
                     // Extract: variableName = value
                     if let AssignmentTarget::AssignmentTargetIdentifier(id) = &assign.left {
                         let name = id.name.as_str().to_string();
+                        self.bindings.insert(name.clone(), &assign.right);
                         let value = self.extract_value(&assign.right);
                         trace!("Assignment in lazy_init: {} = {:?}", name, value);
                         assignments.push((name, value));
@@ -259,6 +263,11 @@ impl<'a> SymbolTable<'a> {
         self.symbols.get(name)
     }
 
+    /// Get the expression a variable was initialized or lazily assigned with.
+    pub fn get_binding(&self, name: &str) -> Option<&'a Expression<'a>> {
+        self.bindings.get(name).copied()
+    }
+
     /// Get a symbol's string value, resolving references if needed.
     pub fn get_string_value(&self, name: &str) -> Option<String> {
         match self.symbols.get(name)? {
@@ -339,6 +348,13 @@ impl<'a> SymbolTable<'a> {
             Expression::Identifier(id) => {
                 self.get_string_value(id.name.as_str())
             }
+            Expression::CallExpression(call) if call.arguments.is_empty() => {
+                // Zero-arg helper calls resolve to the function's first return value
+                match &call.callee {
+                    Expression::Identifier(id) => self.get_string_value(id.name.as_str()),
+                    _ => None,
+                }
+            }
             Expression::StringLiteral(s) => {
                 Some(s.value.as_str().to_string())
             }
@@ -24,6 +24,18 @@ pub struct Cli {
     #[arg(short, long)]
     pub quiet: bool,
 
+    /// Token estimator for prompt and tool budgets
+    #[arg(long, default_value = "bpe")]
+    pub tokenizer: TokenizerKind,
+
+    /// tiktoken-format vocabulary to use instead of the bundled one
+    #[arg(long, value_name = "FILE")]
+    pub vocab: Option<PathBuf>,
+
+    /// TOML or YAML extraction rules extending the bundled ones
+    #[arg(long, value_name = "FILE")]
+    pub rules: Option<PathBuf>,
+
     /// Subcommand to run
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -83,6 +95,14 @@ pub enum Commands {
         #[arg(long)]
         tools_only: bool,
 
+        /// Also export tools in these formats (comma-separated)
+        #[arg(long = "tool-format", value_enum, value_delimiter = ',')]
+        tool_formats: Vec<ToolFormat>,
+
+        /// Use full prompts instead of short descriptions in exported tools
+        #[arg(long)]
+        full_descriptions: bool,
+
         /// Output format (json)
         #[arg(short, long, default_value = "json")]
         format: OutputFormat,
@@ -103,6 +123,77 @@ pub enum Commands {
         strategy: SplitStrategy,
     },
 
+    /// Generate TypeScript and Rust types from extracted tool schemas
+    Codegen {
+        /// Languages to generate (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',', default_value = "typescript,rust")]
+        languages: Vec<CodegenLanguage>,
+    },
+
+    /// Validate recorded tool calls (JSONL of {tool, input}) against extracted schemas
+    ValidateCalls {
+        /// JSONL file of recorded tool calls
+        #[arg(value_name = "CALLS")]
+        calls: PathBuf,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Render system prompt variants from the traced assembly graph
+    Assemble {
+        /// Assembly function to render (defaults to the largest)
+        #[arg(long)]
+        function: Option<String>,
+
+        /// Fix a condition by id or source, e.g. `--set c0=true` (repeatable)
+        #[arg(long = "set", value_name = "CONDITION=BOOL", value_parser = parse_condition)]
+        conditions: Vec<(String, bool)>,
+
+        /// Maximum number of variants to render
+        #[arg(long, default_value = "64")]
+        max_variants: usize,
+
+        /// List assembly functions and their conditions instead of rendering
+        #[arg(long)]
+        list: bool,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Find where an error message comes from in the error catalog
+    Errors {
+        /// Message as the user saw it, or a fragment; lists every site when omitted
+        #[arg(value_name = "MESSAGE")]
+        query: Option<String>,
+
+        /// Only sites raising this error class
+        #[arg(long)]
+        class: Option<String>,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// List platform-specific code paths and slice beautified.js for one platform
+    Platforms {
+        /// Only regions that run on this platform
+        #[arg(long)]
+        platform: Option<TargetPlatform>,
+
+        /// Write beautified.<PLATFORM>.js with other platforms' code stripped
+        #[arg(long, value_name = "PLATFORM")]
+        slice: Option<TargetPlatform>,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
     /// Analyze code structure (Phase 2+)
     Analyze {
         /// Generate call graph
@@ -130,6 +221,59 @@ pub enum OutputFormat {
     Debug,
 }
 
+/// Tool export format
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ToolFormat {
+    /// Anthropic Messages API `tools` array
+    Api,
+    /// MCP `tools/list` result
+    Mcp,
+}
+
+/// Token estimator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TokenizerKind {
+    /// Byte-level BPE vocabulary (bundled, or `--vocab`)
+    Bpe,
+    /// Fixed characters-per-token ratio
+    CharRatio,
+}
+
+/// Parse a `CONDITION=BOOL` setting.
+fn parse_condition(arg: &str) -> std::result::Result<(String, bool), String> {
+    let (condition, value) = arg
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected CONDITION=BOOL, got '{}'", arg))?;
+    let value = match value.trim() {
+        "true" | "1" => true,
+        "false" | "0" => false,
+        other => return Err(format!("expected true or false, got '{}'", other)),
+    };
+    Ok((condition.trim().to_string(), value))
+}
+
+/// Target language for generated types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CodegenLanguage {
+    /// TypeScript interfaces
+    Typescript,
+    /// Rust serde types
+    Rust,
+}
+
+/// Platform a code path runs on
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum TargetPlatform {
+    /// macOS
+    Darwin,
+    /// Linux outside WSL
+    Linux,
+    /// Windows Subsystem for Linux
+    Wsl,
+    /// Windows
+    Win32,
+}
+
 /// Module splitting strategy
 #[derive(Debug, Clone, clap::ValueEnum)]
 pub enum SplitStrategy {
@@ -162,6 +306,19 @@ impl Cli {
         }
     }
 
+    /// Build the selected token estimator
+    pub fn tokenizer(&self) -> crate::error::Result<Box<dyn crate::tokens::Tokenizer>> {
+        match self.tokenizer {
+            TokenizerKind::Bpe => crate::tokens::load(self.vocab.as_deref()),
+            TokenizerKind::CharRatio => Ok(Box::new(crate::tokens::CharRatioTokenizer::default())),
+        }
+    }
+
+    /// Load the extraction rules
+    pub fn rules(&self) -> crate::error::Result<crate::rules::Rules> {
+        crate::rules::Rules::load(self.rules.as_deref())
+    }
+
     /// Validate the CLI arguments
     pub fn validate(&self) -> crate::error::Result<()> {
         use crate::error::DecypherError;
@@ -197,6 +354,9 @@ mod tests {
             output: PathBuf::from("output"),
             verbose: 0,
             quiet: false,
+            tokenizer: TokenizerKind::Bpe,
+            vocab: None,
+            rules: None,
             command: None,
         };
 
@@ -210,6 +370,9 @@ mod tests {
             output: PathBuf::from("output"),
             verbose: 1,
             quiet: false,
+            tokenizer: TokenizerKind::Bpe,
+            vocab: None,
+            rules: None,
             command: None,
         };
 
@@ -223,6 +386,9 @@ mod tests {
             output: PathBuf::from("output"),
             verbose: 2,
             quiet: false,
+            tokenizer: TokenizerKind::Bpe,
+            vocab: None,
+            rules: None,
             command: None,
         };
 
@@ -236,6 +402,9 @@ mod tests {
             output: PathBuf::from("output"),
             verbose: 0,
             quiet: true,
+            tokenizer: TokenizerKind::Bpe,
+            vocab: None,
+            rules: None,
             command: None,
         };
 
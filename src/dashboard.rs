@@ -1,15 +1,21 @@
 //! Statistics dashboard for comprehensive overview.
 
 use crate::analysis::report::AnalysisReport;
-use crate::extractor::{config::ConfigValue, prompts::SystemPrompt, tools::ToolDefinition};
+use crate::extractor::models::{ModelCatalog, ModelInfo};
+use crate::extractor::plugin::PluginOutputs;
 use crate::output::ExtractionSummary;
 use crate::parser::visitor::AstStats;
+use crate::tokens::TokenBudget;
 use crate::transformer::split::Module;
 use crate::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
+/// Number of tools listed in the token ranking.
+const TOP_TOOLS: usize = 10;
+
 /// Complete statistics dashboard.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dashboard {
@@ -27,6 +33,18 @@ pub struct Dashboard {
 
     /// Overall summary.
     pub summary: OverallSummary,
+
+    /// Models found in the bundle.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<ModelInfo>,
+
+    /// Estimated token budget for prompts and tools.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<TokenBudget>,
+
+    /// Items found by each extractor plugin.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extractors: BTreeMap<String, usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +64,8 @@ pub struct ParsingStats {
 pub struct ExtractionStats {
     pub prompts: usize,
     pub tools: usize,
+    #[serde(default)]
+    pub commands: usize,
     pub configs: usize,
     pub strings: usize,
     pub longest_prompt: usize,
@@ -79,6 +99,7 @@ pub struct OverallSummary {
 
 impl Dashboard {
     /// Create a dashboard from all collected data.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         ast_stats: &AstStats,
         extraction: &ExtractionSummary,
@@ -104,6 +125,7 @@ impl Dashboard {
         let extraction_stats = ExtractionStats {
             prompts: extraction.prompt_count,
             tools: extraction.tool_count,
+            commands: extraction.command_count,
             configs: extraction.config_count,
             strings: extraction.string_count,
             longest_prompt: extraction.longest_prompt,
@@ -155,9 +177,34 @@ impl Dashboard {
             transformation,
             analysis: analysis_stats,
             summary,
+            models: Vec::new(),
+            tokens: None,
+            extractors: BTreeMap::new(),
         }
     }
 
+    /// Include the model catalog.
+    pub fn with_models(mut self, catalog: &ModelCatalog) -> Self {
+        self.models = catalog.models.clone();
+        self
+    }
+
+    /// Include extractor plugin outputs, and the model catalog if present.
+    pub fn with_plugins(mut self, outputs: &PluginOutputs) -> Self {
+        self.extraction.commands = outputs.count("commands");
+        self.extractors = outputs.iter().map(|(name, output)| (name.to_string(), output.count())).collect();
+        match outputs.get_as::<ModelCatalog>("models") {
+            Some(catalog) => self.with_models(catalog),
+            None => self,
+        }
+    }
+
+    /// Include the token budget.
+    pub fn with_tokens(mut self, budget: TokenBudget) -> Self {
+        self.tokens = Some(budget);
+        self
+    }
+
     /// Write dashboard to JSON file.
     pub fn write_json(&self, output_dir: &Path) -> Result<()> {
         let path = output_dir.join("dashboard.json");
@@ -191,6 +238,7 @@ impl Dashboard {
         content.push_str("## Extraction Statistics\n\n");
         content.push_str(&format!("- **System Prompts**: {}\n", self.extraction.prompts));
         content.push_str(&format!("- **Tool Definitions**: {}\n", self.extraction.tools));
+        content.push_str(&format!("- **Slash Commands**: {}\n", self.extraction.commands));
         content.push_str(&format!("- **Configurations**: {}\n", self.extraction.configs));
         content.push_str(&format!("- **Interesting Strings**: {}\n", self.extraction.strings));
         content.push_str(&format!("- **Longest Prompt**: {} chars\n\n", self.extraction.longest_prompt));
@@ -209,6 +257,55 @@ impl Dashboard {
         content.push_str(&format!("- **Classes**: {}\n", self.analysis.classes));
         content.push_str(&format!("- **Total LOC**: {}\n\n", self.analysis.total_loc));
 
+        if !self.models.is_empty() {
+            content.push_str("## Models\n\n");
+            content.push_str("| Model | Name | Context | Max Output | Input $/MTok | Output $/MTok | Betas |\n");
+            content.push_str("|-------|------|---------|------------|--------------|---------------|-------|\n");
+            for model in &self.models {
+                let number = |n: Option<u64>| n.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string());
+                let price = |p: Option<f64>| p.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string());
+                content.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} | {} | {} |\n",
+                    model.id,
+                    model.display_name.as_deref().unwrap_or("-"),
+                    number(model.context_window),
+                    number(model.max_output_tokens),
+                    price(model.pricing.as_ref().map(|p| p.input)),
+                    price(model.pricing.as_ref().map(|p| p.output)),
+                    model.betas.join(", "),
+                ));
+            }
+            content.push('\n');
+        }
+
+        if !self.extractors.is_empty() {
+            content.push_str("## Extractors\n\n");
+            content.push_str("| Extractor | Items |\n");
+            content.push_str("|-----------|-------|\n");
+            for (name, count) in &self.extractors {
+                content.push_str(&format!("| {} | {} |\n", name, count));
+            }
+            content.push('\n');
+        }
+
+        if let Some(tokens) = &self.tokens {
+            content.push_str("## Token Budget\n\n");
+            content.push_str(&format!("Estimated with `{}`.\n\n", tokens.tokenizer));
+            content.push_str(&format!("- **Prompt Tokens**: {}\n", tokens.prompt_tokens));
+            content.push_str(&format!("- **Tool Tokens**: {}\n\n", tokens.tool_tokens));
+            if !tokens.tools.is_empty() {
+                content.push_str("| Tool | Prompt | Schema | Total |\n");
+                content.push_str("|------|--------|--------|-------|\n");
+                for tool in tokens.tools.iter().take(TOP_TOOLS) {
+                    content.push_str(&format!(
+                        "| {} | {} | {} | {} |\n",
+                        tool.name, tool.prompt_tokens, tool.schema_tokens, tool.total
+                    ));
+                }
+                content.push('\n');
+            }
+        }
+
         let path = output_dir.join("DASHBOARD.md");
         fs::write(&path, content)
             .map_err(|e| crate::error::DecypherError::io(&path, e))?;
@@ -236,6 +333,7 @@ impl Dashboard {
         println!("🔍 EXTRACTION");
         println!("  Prompts:       {}", self.extraction.prompts);
         println!("  Tools:         {}", self.extraction.tools);
+        println!("  Commands:      {}", self.extraction.commands);
         println!("  Configs:       {}", self.extraction.configs);
         println!("  Strings:       {}\n", self.extraction.strings);
 
@@ -252,6 +350,33 @@ impl Dashboard {
         println!("  Classes:       {}", self.analysis.classes);
         println!("  Total LOC:     {}\n", self.analysis.total_loc);
 
+        if !self.models.is_empty() {
+            println!("🤖 MODELS");
+            for model in &self.models {
+                let context = model.context_window.map(|n| format!("{} ctx", n)).unwrap_or_default();
+                println!("  {:<40} {}", model.id, context);
+            }
+            println!();
+        }
+
+        if !self.extractors.is_empty() {
+            println!("🧩 EXTRACTORS");
+            for (name, count) in &self.extractors {
+                println!("  {:<20} {}", name, count);
+            }
+            println!();
+        }
+
+        if let Some(tokens) = &self.tokens {
+            println!("🔢 TOKENS ({})", tokens.tokenizer);
+            println!("  Prompts:       {}", tokens.prompt_tokens);
+            println!("  Tools:         {}", tokens.tool_tokens);
+            for tool in tokens.tools.iter().take(5) {
+                println!("  {:<20} {}", tool.name, tool.total);
+            }
+            println!();
+        }
+
         println!("✅ All phases complete!");
     }
 }
@@ -33,6 +33,18 @@ pub enum DecypherError {
     #[error("Failed to generate output: {0}")]
     CodegenError(String),
 
+    /// Recorded data failed validation.
+    #[error("Validation failed: {0}")]
+    ValidationError(String),
+
+    /// Tokenizer vocabulary could not be loaded.
+    #[error("Failed to load tokenizer: {0}")]
+    TokenizerError(String),
+
+    /// Extraction rules could not be loaded.
+    #[error("Invalid extraction rules: {0}")]
+    RulesError(String),
+
     /// Invalid CLI arguments.
     #[error("Invalid arguments: {0}")]
     InvalidArguments(String),
@@ -0,0 +1,437 @@
+//! Messages API request extraction.
+//!
+//! Requests are object literals passed to `client.messages.create(…)`,
+//! `client.beta.messages.stream(…)` and similar SDK calls. For each call site
+//! we record which request parameters are set and where their values come
+//! from, plus the per-request options (`headers`, `signal`, …). Beta headers
+//! and custom HTTP headers are inventoried across the whole bundle.
+
+use crate::analyzer::{Analyzer, SymbolTable};
+use crate::extractor::members::{callee_name, property_key};
+use crate::extractor::models::is_beta_header;
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{debug, trace};
+
+pub use crate::parser::SourceSpan;
+
+/// SDK methods that send a Messages request.
+const REQUEST_METHODS: &[&str] = &["create", "stream"];
+
+/// Request parameters reported as unset when a call site omits them.
+const TRACKED_PARAMETERS: &[&str] = &[
+    "model",
+    "max_tokens",
+    "system",
+    "messages",
+    "tools",
+    "tool_choice",
+    "thinking",
+    "metadata",
+    "temperature",
+    "betas",
+];
+
+/// Members holding HTTP headers.
+const HEADER_MEMBERS: &[&str] = &["headers", "defaultHeaders"];
+
+/// Header carrying beta flags.
+const BETA_HEADER: &str = "anthropic-beta";
+
+/// Maximum length of a recorded value expression.
+const MAX_ORIGIN_LEN: usize = 120;
+
+/// Where a parameter's value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueOrigin {
+    /// A literal written at the call site.
+    Literal,
+    /// A variable, possibly a resolved constant.
+    Variable,
+    /// The result of a function call.
+    Call,
+    /// A property of another object, e.g. `options.model`.
+    Member,
+    /// Anything else: conditionals, arrays, object literals, …
+    Expression,
+}
+
+/// A request parameter set at a call site.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestParameter {
+    /// Parameter name, e.g. `max_tokens`.
+    pub name: String,
+
+    /// Kind of expression supplying the value.
+    pub origin: ValueOrigin,
+
+    /// Source of the value expression, truncated.
+    pub source: String,
+
+    /// Constant value, when it resolves to a string or number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// Variable the parameter was spread in from, e.g. `...base`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via_spread: Option<String>,
+}
+
+/// A Messages API call site.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiCallSite {
+    /// Callee as written, e.g. `this.client.beta.messages.stream`.
+    pub callee: String,
+
+    /// SDK method: `create` or `stream`.
+    pub method: String,
+
+    /// Whether the call goes through the `beta` namespace.
+    pub beta: bool,
+
+    /// Location of the call.
+    pub span: SourceSpan,
+
+    /// Parameters set in the request body.
+    pub parameters: Vec<RequestParameter>,
+
+    /// Spreads that couldn't be resolved to an object literal.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unresolved_spreads: Vec<String>,
+
+    /// Tracked parameters the call site doesn't set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unset: Vec<String>,
+
+    /// Per-request options (second argument), e.g. `headers` or `signal`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<RequestParameter>,
+}
+
+/// A header set somewhere in the bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeaderEntry {
+    /// Header name, e.g. `x-app`.
+    pub name: String,
+
+    /// Constant value or source of the value expression.
+    pub value: String,
+
+    /// Location of the header entry.
+    pub span: SourceSpan,
+}
+
+/// Everything known about how the bundle talks to the Messages API.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiInventory {
+    /// Request call sites in source order.
+    pub call_sites: Vec<ApiCallSite>,
+
+    /// Beta flag values and where each appears.
+    pub betas: BTreeMap<String, Vec<SourceSpan>>,
+
+    /// HTTP headers set in `headers`/`defaultHeaders` objects.
+    pub headers: Vec<HeaderEntry>,
+}
+
+/// Extractor for Messages API requests.
+pub struct ApiRequestExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+    source_text: &'a str,
+}
+
+impl<'a> ApiRequestExtractor<'a> {
+    /// Create a new API request extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        Self {
+            analyzer,
+            symbol_table,
+            source_text: analyzer.program().source_text,
+        }
+    }
+
+    /// Extract call sites, beta flags and headers.
+    pub fn extract(&self) -> Result<ApiInventory> {
+        debug!("Extracting Messages API requests");
+
+        let exprs = self.analyzer.find_expressions(|expr| {
+            matches!(
+                expr,
+                Expression::CallExpression(_) | Expression::ObjectExpression(_) | Expression::StringLiteral(_)
+            )
+        });
+
+        let mut inventory = ApiInventory::default();
+        for expr in exprs {
+            match expr {
+                Expression::CallExpression(call) => {
+                    if let Some(site) = self.call_site(call) {
+                        trace!("API call site: {} at line {}", site.callee, site.span.line);
+                        inventory.call_sites.push(site);
+                    }
+                }
+                Expression::ObjectExpression(obj) => self.collect_headers(obj, &mut inventory),
+                Expression::StringLiteral(s) => {
+                    for flag in s.value.split(',').map(str::trim).filter(|f| is_beta_header(f)) {
+                        inventory
+                            .betas
+                            .entry(flag.to_string())
+                            .or_default()
+                            .push(SourceSpan::locate(self.analyzer.line_index(), s.span));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        debug!(
+            "Found {} API call sites, {} beta flags, {} headers",
+            inventory.call_sites.len(),
+            inventory.betas.len(),
+            inventory.headers.len()
+        );
+        Ok(inventory)
+    }
+
+    /// Build a call site from `….messages.create(body, options)`.
+    fn call_site(&self, call: &'a CallExpression<'a>) -> Option<ApiCallSite> {
+        let Expression::StaticMemberExpression(member) = &call.callee else {
+            return None;
+        };
+        let method = member.property.name.as_str();
+        if !REQUEST_METHODS.contains(&method) {
+            return None;
+        }
+        let Expression::StaticMemberExpression(namespace) = &member.object else {
+            return None;
+        };
+        if namespace.property.name != "messages" {
+            return None;
+        }
+        let beta = matches!(&namespace.object, Expression::StaticMemberExpression(m) if m.property.name == "beta");
+
+        let body = self.object_argument(call.arguments.first()?.as_expression()?)?;
+        let mut parameters = Vec::new();
+        let mut unresolved_spreads = Vec::new();
+        self.collect_parameters(body, None, &mut parameters, &mut unresolved_spreads);
+
+        let options = match call.arguments.get(1).and_then(|a| a.as_expression()) {
+            Some(expr) => match self.object_argument(expr) {
+                Some(obj) => {
+                    let mut options = Vec::new();
+                    self.collect_parameters(obj, None, &mut options, &mut Vec::new());
+                    options
+                }
+                None => Vec::new(),
+            },
+            None => Vec::new(),
+        };
+
+        let unset = TRACKED_PARAMETERS
+            .iter()
+            .filter(|p| !parameters.iter().any(|param| param.name == **p))
+            .map(|p| p.to_string())
+            .collect();
+
+        Some(ApiCallSite {
+            callee: callee_name(&call.callee).unwrap_or_else(|| self.source_of(call.callee.span())),
+            method: method.to_string(),
+            beta,
+            span: SourceSpan::locate(self.analyzer.line_index(), call.span),
+            parameters,
+            unresolved_spreads,
+            unset,
+            options,
+        })
+    }
+
+    /// Resolve an argument to an object literal, directly or through a variable.
+    fn object_argument(&self, expr: &'a Expression<'a>) -> Option<&'a ObjectExpression<'a>> {
+        match expr {
+            Expression::ObjectExpression(obj) => Some(obj),
+            Expression::Identifier(id) => match self.symbol_table.get_binding(id.name.as_str())? {
+                Expression::ObjectExpression(obj) => Some(obj),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Record each member, flattening spreads of known object literals once.
+    fn collect_parameters(
+        &self,
+        obj: &'a ObjectExpression<'a>,
+        via_spread: Option<&str>,
+        parameters: &mut Vec<RequestParameter>,
+        unresolved: &mut Vec<String>,
+    ) {
+        for prop in &obj.properties {
+            match prop {
+                ObjectPropertyKind::ObjectProperty(p) => {
+                    let Some(name) = property_key(&p.key) else {
+                        continue;
+                    };
+                    let parameter = RequestParameter {
+                        name: name.to_string(),
+                        origin: origin_of(&p.value),
+                        source: self.source_of(p.value.span()),
+                        value: self.constant(&p.value),
+                        via_spread: via_spread.map(str::to_string),
+                    };
+                    match parameters.iter_mut().find(|existing| existing.name == name) {
+                        Some(existing) => *existing = parameter,
+                        None => parameters.push(parameter),
+                    }
+                }
+                ObjectPropertyKind::SpreadProperty(spread) => {
+                    let source = self.source_of(spread.argument.span());
+                    match (via_spread, self.object_argument(&spread.argument)) {
+                        (None, Some(base)) => self.collect_parameters(base, Some(&source), parameters, unresolved),
+                        _ => unresolved.push(source),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Record members of `headers: {…}` objects and any `anthropic-beta` values.
+    fn collect_headers(&self, obj: &'a ObjectExpression<'a>, inventory: &mut ApiInventory) {
+        for prop in &obj.properties {
+            let ObjectPropertyKind::ObjectProperty(p) = prop else {
+                continue;
+            };
+            if !property_key(&p.key).is_some_and(|k| HEADER_MEMBERS.contains(&k)) {
+                continue;
+            }
+            let Some(headers) = self.object_argument(&p.value) else {
+                continue;
+            };
+
+            for header in &headers.properties {
+                let ObjectPropertyKind::ObjectProperty(h) = header else {
+                    continue;
+                };
+                let Some(name) = property_key(&h.key) else {
+                    continue;
+                };
+                let value = self
+                    .constant(&h.value)
+                    .unwrap_or_else(|| self.source_of(h.value.span()));
+                let span = SourceSpan::locate(self.analyzer.line_index(), h.span);
+
+                // Beta flags held in variables don't appear as literals in place
+                if name.eq_ignore_ascii_case(BETA_HEADER) {
+                    for flag in value.split(',').map(str::trim).filter(|f| is_beta_header(f)) {
+                        let spans = inventory.betas.entry(flag.to_string()).or_default();
+                        if !spans.iter().any(|s| s.start >= span.start && s.end <= span.end) {
+                            spans.push(span);
+                        }
+                    }
+                }
+
+                inventory.headers.push(HeaderEntry {
+                    name: name.to_string(),
+                    value,
+                    span,
+                });
+            }
+        }
+    }
+
+    /// Resolve a value to a constant string or number.
+    fn constant(&self, expr: &Expression) -> Option<String> {
+        match expr {
+            Expression::NumericLiteral(n) => Some(n.value.to_string()),
+            Expression::BooleanLiteral(b) => Some(b.value.to_string()),
+            expr => self.symbol_table.resolve_template_expr(expr),
+        }
+    }
+
+    /// Source text of a span, truncated for readability.
+    fn source_of(&self, span: Span) -> String {
+        let text = &self.source_text[span.start as usize..span.end as usize];
+        if text.len() <= MAX_ORIGIN_LEN {
+            return text.to_string();
+        }
+        let cut = (0..=MAX_ORIGIN_LEN).rev().find(|i| text.is_char_boundary(*i)).unwrap_or(0);
+        format!("{}…", &text[..cut])
+    }
+}
+
+/// Classify a value expression.
+fn origin_of(expr: &Expression) -> ValueOrigin {
+    match expr {
+        Expression::StringLiteral(_)
+        | Expression::NumericLiteral(_)
+        | Expression::BooleanLiteral(_)
+        | Expression::NullLiteral(_) => ValueOrigin::Literal,
+        Expression::TemplateLiteral(t) if t.expressions.is_empty() => ValueOrigin::Literal,
+        Expression::UnaryExpression(u) if matches!(u.argument, Expression::NumericLiteral(_)) => ValueOrigin::Literal,
+        Expression::Identifier(_) => ValueOrigin::Variable,
+        Expression::CallExpression(_) | Expression::AwaitExpression(_) => ValueOrigin::Call,
+        Expression::StaticMemberExpression(_) | Expression::ComputedMemberExpression(_) => ValueOrigin::Member,
+        _ => ValueOrigin::Expression,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_extracts_api_requests() {
+        let code = r#"
+            var MODEL = "claude-sonnet-4-20250514";
+            var BETA = "interleaved-thinking-2025-05-14";
+            var base = { model: MODEL, max_tokens: 32000 };
+            async function query(client, A) {
+                return client.beta.messages.stream({
+                    ...base,
+                    system: buildSystem(A),
+                    messages: A.messages,
+                    betas: [BETA, "context-1m-2025-08-07"],
+                    thinking: A.budget > 0 ? { type: "enabled", budget_tokens: A.budget } : void 0
+                }, { signal: A.signal, headers: { "x-app": "cli", "anthropic-beta": BETA } });
+            }
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let inventory = ApiRequestExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+        assert_eq!(inventory.call_sites.len(), 1);
+
+        let site = &inventory.call_sites[0];
+        assert_eq!(site.callee, "client.beta.messages.stream");
+        assert!(site.beta);
+        assert_eq!(site.span.line, 6);
+
+        let names: Vec<_> = site.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["model", "max_tokens", "system", "messages", "betas", "thinking"]);
+        let model = &site.parameters[0];
+        assert_eq!(model.origin, ValueOrigin::Variable);
+        assert_eq!(model.value.as_deref(), Some("claude-sonnet-4-20250514"));
+        assert_eq!(model.via_spread.as_deref(), Some("base"));
+        assert_eq!(site.parameters[2].origin, ValueOrigin::Call);
+        assert!(site.unset.contains(&"tools".to_string()));
+        assert_eq!(site.options.len(), 2);
+
+        let flags: Vec<_> = inventory.betas.keys().map(String::as_str).collect();
+        assert_eq!(flags, ["context-1m-2025-08-07", "interleaved-thinking-2025-05-14"]);
+        let headers: Vec<_> = inventory.headers.iter().map(|h| (h.name.as_str(), h.value.as_str())).collect();
+        assert_eq!(
+            headers,
+            [("x-app", "cli"), ("anthropic-beta", "interleaved-thinking-2025-05-14")]
+        );
+    }
+}
@@ -0,0 +1,986 @@
+//! System prompt assembly graphs.
+//!
+//! The main system prompt is built at runtime: functions return arrays of
+//! sections, join fragments with `"\n"`, concatenate constants and pick text
+//! with ternaries on model, platform and feature flags. This module traces
+//! those functions through returns, local arrays (including `push` under
+//! `if`), `join`, `+`, template literals and conditionals into a
+//! [`PromptNode`] tree, and renders the distinct variants for a set of
+//! condition values. Every rendered segment keeps the source span it came
+//! from.
+
+use crate::analyzer::{Analyzer, SymbolTable};
+use crate::extractor::api_requests::SourceSpan;
+use crate::extractor::members::{first_return, unwind_chain};
+use crate::parser::LineIndex;
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tracing::{debug, trace};
+
+/// Maximum nesting of traced functions and bindings.
+const MAX_TRACE_DEPTH: usize = 16;
+
+/// Minimum static text for a function to count as a prompt assembly.
+const MIN_ASSEMBLY_TEXT: usize = 400;
+
+/// Separator between sections of a returned array.
+const SECTION_SEPARATOR: &str = "\n\n";
+
+/// Maximum length of a condition or dynamic-part label.
+const MAX_LABEL_LEN: usize = 80;
+
+/// A node of the assembly graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PromptNode {
+    /// Static text.
+    Text { text: String, span: SourceSpan },
+
+    /// A runtime value, rendered as `${name}`.
+    Dynamic { name: String, span: SourceSpan },
+
+    /// Parts rendered back to back.
+    Concat { parts: Vec<PromptNode> },
+
+    /// Parts joined with a separator; empty parts are dropped when `skip_empty`.
+    Join {
+        parts: Vec<PromptNode>,
+        separator: String,
+        skip_empty: bool,
+        span: SourceSpan,
+    },
+
+    /// A branch on a condition.
+    Choice {
+        condition: String,
+        then: Box<PromptNode>,
+        otherwise: Box<PromptNode>,
+    },
+
+    /// A named binding or function inlined into the graph.
+    Fragment { name: String, node: Box<PromptNode> },
+
+    /// Nothing.
+    Empty,
+}
+
+impl PromptNode {
+    /// Total length of static text in the graph.
+    pub fn static_len(&self) -> usize {
+        match self {
+            Self::Text { text, .. } => text.len(),
+            Self::Dynamic { .. } | Self::Empty => 0,
+            Self::Concat { parts } | Self::Join { parts, .. } => parts.iter().map(Self::static_len).sum(),
+            Self::Choice { then, otherwise, .. } => then.static_len() + otherwise.static_len(),
+            Self::Fragment { node, .. } => node.static_len(),
+        }
+    }
+
+    /// Whether the graph composes text rather than being a single literal.
+    fn is_composed(&self) -> bool {
+        match self {
+            Self::Text { .. } | Self::Dynamic { .. } | Self::Empty => false,
+            Self::Concat { parts } => parts.iter().any(Self::is_composed),
+            Self::Join { .. } | Self::Choice { .. } | Self::Fragment { .. } => true,
+        }
+    }
+}
+
+/// A condition the assembly branches on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptCondition {
+    /// Short id, e.g. `c0`.
+    pub id: String,
+
+    /// Source of the tested expression, without leading negation.
+    pub source: String,
+
+    /// Location of the first test.
+    pub span: SourceSpan,
+}
+
+/// A function that assembles a prompt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptAssembly {
+    /// Function name.
+    pub name: String,
+
+    /// Location of the function.
+    pub span: SourceSpan,
+
+    /// Conditions, in order of first appearance.
+    pub conditions: Vec<PromptCondition>,
+
+    /// Total static text across all branches.
+    pub static_chars: usize,
+
+    /// The assembly graph.
+    pub root: PromptNode,
+}
+
+/// What produced a segment of rendered text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    Text,
+    Dynamic,
+    Separator,
+}
+
+/// A byte range of rendered text and where it came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: usize,
+    pub end: usize,
+    pub kind: SegmentKind,
+
+    /// Source of the text; separators carry the span of their `join` or array.
+    pub source: SourceSpan,
+
+    /// Innermost inlined binding or function the segment belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fragment: Option<String>,
+}
+
+/// One rendering of an assembly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptVariant {
+    /// Values of the conditions this variant depends on, by id.
+    pub conditions: BTreeMap<String, bool>,
+
+    /// Rendered text.
+    pub text: String,
+
+    /// Provenance of every part of `text`.
+    pub segments: Vec<Segment>,
+
+    /// Estimated token count of `text`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<usize>,
+}
+
+impl PromptAssembly {
+    /// Render every distinct variant consistent with `fixed`, up to `max`.
+    ///
+    /// Conditions not in `fixed` are enumerated as they are reached, so only
+    /// conditions that affect the output appear in a variant.
+    pub fn variants(&self, fixed: &HashMap<String, bool>, max: usize) -> Vec<PromptVariant> {
+        let initial = PromptVariant {
+            conditions: BTreeMap::new(),
+            text: String::new(),
+            segments: Vec::new(),
+            tokens: None,
+        };
+        let renderer = Renderer { fixed, max: max.max(1) };
+        let mut variants = renderer.render(&self.root, vec![initial]);
+
+        let mut seen = HashSet::new();
+        variants.retain(|v| seen.insert(v.text.clone()));
+        variants
+    }
+
+    /// Resolve `id=bool` or `source=bool` settings to condition ids.
+    pub fn resolve_conditions(&self, settings: &[(String, bool)]) -> Result<HashMap<String, bool>> {
+        settings
+            .iter()
+            .map(|(key, value)| {
+                self.conditions
+                    .iter()
+                    .find(|c| c.id == *key || c.source == *key)
+                    .map(|c| (c.id.clone(), *value))
+                    .ok_or_else(|| {
+                        crate::error::DecypherError::InvalidArguments(format!(
+                            "unknown condition '{}' in {}",
+                            key, self.name
+                        ))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Renders nodes into variants, branching on unset conditions.
+struct Renderer<'f> {
+    fixed: &'f HashMap<String, bool>,
+    max: usize,
+}
+
+impl Renderer<'_> {
+    fn render(&self, node: &PromptNode, states: Vec<PromptVariant>) -> Vec<PromptVariant> {
+        match node {
+            PromptNode::Empty => states,
+            PromptNode::Text { text, span } => self.append(states, text, SegmentKind::Text, *span),
+            PromptNode::Dynamic { name, span } => {
+                self.append(states, &format!("${{{}}}", name), SegmentKind::Dynamic, *span)
+            }
+            PromptNode::Concat { parts } => parts.iter().fold(states, |states, part| self.render(part, states)),
+            PromptNode::Join {
+                parts,
+                separator,
+                skip_empty,
+                span,
+            } => {
+                let mut joined: Vec<(PromptVariant, bool)> = states.into_iter().map(|s| (s, false)).collect();
+                for part in parts {
+                    let mut next = Vec::new();
+                    for (state, wrote) in joined {
+                        let scratch = PromptVariant {
+                            conditions: state.conditions.clone(),
+                            text: String::new(),
+                            segments: Vec::new(),
+                            tokens: None,
+                        };
+                        for rendered in self.render(part, vec![scratch]) {
+                            let mut merged = state.clone();
+                            merged.conditions = rendered.conditions;
+                            if *skip_empty && rendered.text.is_empty() {
+                                next.push((merged, wrote));
+                                continue;
+                            }
+                            if wrote {
+                                push_segment(&mut merged, separator, SegmentKind::Separator, *span);
+                            }
+                            let offset = merged.text.len();
+                            merged.text.push_str(&rendered.text);
+                            merged.segments.extend(rendered.segments.into_iter().map(|mut s| {
+                                s.start += offset;
+                                s.end += offset;
+                                s
+                            }));
+                            next.push((merged, true));
+                        }
+                    }
+                    next.truncate(self.max);
+                    joined = next;
+                }
+                joined.into_iter().map(|(s, _)| s).collect()
+            }
+            PromptNode::Choice {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let mut out = Vec::new();
+                for mut state in states {
+                    let value = state
+                        .conditions
+                        .get(condition)
+                        .or_else(|| self.fixed.get(condition))
+                        .copied();
+                    match value {
+                        Some(value) => {
+                            state.conditions.insert(condition.clone(), value);
+                            out.extend(self.render(if value { then } else { otherwise }, vec![state]));
+                        }
+                        None => {
+                            let mut taken = state.clone();
+                            taken.conditions.insert(condition.clone(), true);
+                            state.conditions.insert(condition.clone(), false);
+                            out.extend(self.render(then, vec![taken]));
+                            out.extend(self.render(otherwise, vec![state]));
+                        }
+                    }
+                }
+                out.truncate(self.max);
+                out
+            }
+            PromptNode::Fragment { name, node } => {
+                let mut out = Vec::new();
+                for state in states {
+                    let mark = state.segments.len();
+                    for mut rendered in self.render(node, vec![state]) {
+                        for segment in rendered.segments.iter_mut().skip(mark) {
+                            segment.fragment.get_or_insert_with(|| name.clone());
+                        }
+                        out.push(rendered);
+                    }
+                }
+                out.truncate(self.max);
+                out
+            }
+        }
+    }
+
+    fn append(
+        &self,
+        mut states: Vec<PromptVariant>,
+        text: &str,
+        kind: SegmentKind,
+        source: SourceSpan,
+    ) -> Vec<PromptVariant> {
+        for state in &mut states {
+            push_segment(state, text, kind, source);
+        }
+        states
+    }
+}
+
+fn push_segment(state: &mut PromptVariant, text: &str, kind: SegmentKind, source: SourceSpan) {
+    if text.is_empty() {
+        return;
+    }
+    let start = state.text.len();
+    state.text.push_str(text);
+    state.segments.push(Segment {
+        start,
+        end: state.text.len(),
+        kind,
+        source,
+        fragment: None,
+    });
+}
+
+/// Body of a traced function.
+#[derive(Clone, Copy)]
+enum FunctionBodyKind<'a> {
+    Statements(&'a [Statement<'a>]),
+    Expression(&'a Expression<'a>),
+}
+
+/// A value bound inside the traced function body.
+#[derive(Clone)]
+enum Local<'a> {
+    Expr(&'a Expression<'a>),
+    Array(Vec<PromptNode>),
+    /// A parameter, traced in the caller's scope.
+    Node(PromptNode),
+}
+
+/// Traces expressions into assembly graphs.
+struct Tracer<'s, 'a> {
+    symbol_table: &'s SymbolTable<'a>,
+    functions: &'s HashMap<&'a str, &'a Function<'a>>,
+    source_text: &'a str,
+    line_index: &'s LineIndex,
+    conditions: Vec<PromptCondition>,
+    stack: Vec<String>,
+    locals: Vec<HashMap<&'a str, Local<'a>>>,
+}
+
+impl<'s, 'a> Tracer<'s, 'a> {
+    fn new(
+        symbol_table: &'s SymbolTable<'a>,
+        functions: &'s HashMap<&'a str, &'a Function<'a>>,
+        source_text: &'a str,
+        line_index: &'s LineIndex,
+    ) -> Self {
+        Self {
+            symbol_table,
+            functions,
+            source_text,
+            line_index,
+            conditions: Vec::new(),
+            stack: Vec::new(),
+            locals: Vec::new(),
+        }
+    }
+
+    fn span(&self, span: Span) -> SourceSpan {
+        SourceSpan::locate(self.line_index, span)
+    }
+
+    fn dynamic(&self, expr: &Expression) -> PromptNode {
+        PromptNode::Dynamic {
+            name: label(&self.source_text[expr.span().start as usize..expr.span().end as usize]),
+            span: self.span(expr.span()),
+        }
+    }
+
+    /// Intern a condition, returning its id and whether the test was negated.
+    fn condition(&mut self, test: &Expression) -> (String, bool) {
+        let mut test = test;
+        let mut negated = false;
+        loop {
+            match test {
+                Expression::UnaryExpression(u) if u.operator == UnaryOperator::LogicalNot => {
+                    negated = !negated;
+                    test = &u.argument;
+                }
+                Expression::ParenthesizedExpression(p) => test = &p.expression,
+                _ => break,
+            }
+        }
+
+        let source = label(&self.source_text[test.span().start as usize..test.span().end as usize]);
+        if let Some(existing) = self.conditions.iter().find(|c| c.source == source) {
+            return (existing.id.clone(), negated);
+        }
+        let id = format!("c{}", self.conditions.len());
+        self.conditions.push(PromptCondition {
+            id: id.clone(),
+            source,
+            span: self.span(test.span()),
+        });
+        (id, negated)
+    }
+
+    fn choice(&mut self, test: &Expression, then: PromptNode, otherwise: PromptNode) -> PromptNode {
+        let (condition, negated) = self.condition(test);
+        let (then, otherwise) = if negated { (otherwise, then) } else { (then, otherwise) };
+        PromptNode::Choice {
+            condition,
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+        }
+    }
+
+    fn trace(&mut self, expr: &'a Expression<'a>, depth: usize) -> PromptNode {
+        if depth > MAX_TRACE_DEPTH {
+            return self.dynamic(expr);
+        }
+
+        match expr {
+            Expression::StringLiteral(s) => PromptNode::Text {
+                text: s.value.to_string(),
+                span: self.span(s.span),
+            },
+            Expression::NumericLiteral(n) => PromptNode::Text {
+                text: n.value.to_string(),
+                span: self.span(n.span),
+            },
+            Expression::NullLiteral(_) => PromptNode::Empty,
+            Expression::BooleanLiteral(b) if !b.value => PromptNode::Empty,
+            Expression::Identifier(id) if id.name == "undefined" => PromptNode::Empty,
+            Expression::TemplateLiteral(tmpl) => {
+                let mut parts = Vec::new();
+                for (i, quasi) in tmpl.quasis.iter().enumerate() {
+                    let text = quasi.value.cooked.as_ref().unwrap_or(&quasi.value.raw);
+                    if !text.is_empty() {
+                        parts.push(PromptNode::Text {
+                            text: text.to_string(),
+                            span: self.span(quasi.span),
+                        });
+                    }
+                    if let Some(expr) = tmpl.expressions.get(i) {
+                        parts.push(self.trace(expr, depth + 1));
+                    }
+                }
+                concat(parts)
+            }
+            Expression::BinaryExpression(binary) if binary.operator == BinaryOperator::Addition => {
+                let left = self.trace(&binary.left, depth + 1);
+                let right = self.trace(&binary.right, depth + 1);
+                concat(vec![left, right])
+            }
+            Expression::ConditionalExpression(cond) => {
+                let then = self.trace(&cond.consequent, depth + 1);
+                let otherwise = self.trace(&cond.alternate, depth + 1);
+                self.choice(&cond.test, then, otherwise)
+            }
+            Expression::LogicalExpression(logical) => {
+                let right = self.trace(&logical.right, depth + 1);
+                match logical.operator {
+                    LogicalOperator::And => self.choice(&logical.left, right, PromptNode::Empty),
+                    _ => {
+                        let left = self.trace(&logical.left, depth + 1);
+                        self.choice(&logical.left, left, right)
+                    }
+                }
+            }
+            Expression::ParenthesizedExpression(paren) => self.trace(&paren.expression, depth),
+            Expression::AwaitExpression(await_expr) => self.trace(&await_expr.argument, depth),
+            Expression::SequenceExpression(seq) => match seq.expressions.last() {
+                Some(last) => self.trace(last, depth),
+                None => PromptNode::Empty,
+            },
+            Expression::ArrayExpression(arr) => PromptNode::Join {
+                parts: self.elements(arr, depth),
+                separator: SECTION_SEPARATOR.to_string(),
+                skip_empty: true,
+                span: self.span(arr.span),
+            },
+            Expression::Identifier(id) => self.identifier(expr, id.name.as_str(), depth),
+            Expression::CallExpression(call) => self.call(expr, call, depth),
+            _ => self.dynamic(expr),
+        }
+    }
+
+    fn elements(&mut self, arr: &'a ArrayExpression<'a>, depth: usize) -> Vec<PromptNode> {
+        arr.elements
+            .iter()
+            .filter_map(|element| match element {
+                ArrayExpressionElement::SpreadElement(spread) => match self.array_parts(&spread.argument, depth) {
+                    Some((parts, _)) => Some(concat(parts)),
+                    None => Some(self.dynamic(&spread.argument)),
+                },
+                element => element.as_expression().map(|e| self.trace(e, depth + 1)),
+            })
+            .collect()
+    }
+
+    fn identifier(&mut self, expr: &'a Expression<'a>, name: &'a str, depth: usize) -> PromptNode {
+        if let Some(local) = self.lookup_local(name) {
+            return match local {
+                Local::Expr(bound) => self.trace(bound, depth + 1),
+                Local::Node(node) => node,
+                Local::Array(parts) => PromptNode::Join {
+                    parts,
+                    separator: SECTION_SEPARATOR.to_string(),
+                    skip_empty: true,
+                    span: self.span(expr.span()),
+                },
+            };
+        }
+
+        match self.symbol_table.get_binding(name) {
+            Some(bound) if !self.stack.iter().any(|s| s == name) => {
+                self.stack.push(name.to_string());
+                let node = self.trace(bound, depth + 1);
+                self.stack.pop();
+                fragment(name, node)
+            }
+            _ => self.dynamic(expr),
+        }
+    }
+
+    fn lookup_local(&self, name: &str) -> Option<Local<'a>> {
+        self.locals.last()?.get(name).cloned()
+    }
+
+    fn call(&mut self, expr: &'a Expression<'a>, call: &'a CallExpression<'a>, depth: usize) -> PromptNode {
+        match &call.callee {
+            Expression::Identifier(id) => {
+                let name = id.name.as_str();
+                if self.stack.iter().any(|s| s == name) {
+                    return self.dynamic(expr);
+                }
+                let Some((params, body)) = self.function(name) else {
+                    return self.dynamic(expr);
+                };
+
+                // Arguments are traced in the caller's scope and bound to parameters
+                let mut scope = HashMap::new();
+                for (param, arg) in params.items.iter().zip(&call.arguments) {
+                    if let (BindingPatternKind::BindingIdentifier(id), Some(arg)) =
+                        (&param.pattern.kind, arg.as_expression())
+                    {
+                        scope.insert(id.name.as_str(), Local::Node(self.trace(arg, depth + 1)));
+                    }
+                }
+
+                self.stack.push(name.to_string());
+                self.locals.push(scope);
+                let node = self.trace_function(body, depth + 1);
+                self.locals.pop();
+                self.stack.pop();
+                match node {
+                    PromptNode::Empty => self.dynamic(expr),
+                    node => fragment(name, node),
+                }
+            }
+            Expression::StaticMemberExpression(member) => match member.property.name.as_str() {
+                "join" => {
+                    let separator = match call.arguments.first().and_then(|a| a.as_expression()) {
+                        Some(Expression::StringLiteral(s)) => s.value.to_string(),
+                        Some(_) => return self.dynamic(expr),
+                        None => ",".to_string(),
+                    };
+                    match self.array_parts(&member.object, depth) {
+                        Some((parts, skip_empty)) => PromptNode::Join {
+                            parts,
+                            separator,
+                            skip_empty,
+                            span: self.span(call.span),
+                        },
+                        None => self.dynamic(expr),
+                    }
+                }
+                "trim" | "trimEnd" | "trimStart" => self.trace(&member.object, depth),
+                "concat" => {
+                    let mut parts = vec![self.trace(&member.object, depth + 1)];
+                    for arg in &call.arguments {
+                        if let Some(arg) = arg.as_expression() {
+                            parts.push(self.trace(arg, depth + 1));
+                        }
+                    }
+                    concat(parts)
+                }
+                _ => self.dynamic(expr),
+            },
+            _ => self.dynamic(expr),
+        }
+    }
+
+    /// Resolve the receiver of `join` to its parts, peeling `.filter(…)`.
+    fn array_parts(&mut self, expr: &'a Expression<'a>, depth: usize) -> Option<(Vec<PromptNode>, bool)> {
+        let (base, calls) = unwind_chain(expr);
+        if calls.iter().any(|(method, _)| *method != "filter") {
+            return None;
+        }
+        let filtered = !calls.is_empty();
+
+        match base {
+            Expression::ArrayExpression(arr) => Some((self.elements(arr, depth), filtered)),
+            Expression::Identifier(id) => match self.lookup_local(id.name.as_str()) {
+                Some(Local::Array(parts)) => Some((parts, true)),
+                Some(Local::Expr(Expression::ArrayExpression(arr))) => Some((self.elements(arr, depth), filtered)),
+                Some(Local::Expr(_) | Local::Node(_)) => None,
+                None => match self.symbol_table.get_binding(id.name.as_str())? {
+                    Expression::ArrayExpression(arr) => Some((self.elements(arr, depth), filtered)),
+                    _ => None,
+                },
+            },
+            _ => None,
+        }
+    }
+
+    /// Parameters and body of a named function; expression-bodied arrows
+    /// get their expression wrapped as the return value.
+    fn function(&self, name: &str) -> Option<(&'a FormalParameters<'a>, FunctionBodyKind<'a>)> {
+        let func = match self.functions.get(name) {
+            Some(func) => *func,
+            None => match self.symbol_table.get_binding(name)? {
+                Expression::FunctionExpression(func) => func,
+                Expression::ArrowFunctionExpression(arrow) => {
+                    let body = match (arrow.expression, arrow.body.statements.first()) {
+                        (true, Some(Statement::ExpressionStatement(stmt))) => FunctionBodyKind::Expression(&stmt.expression),
+                        _ => FunctionBodyKind::Statements(arrow.body.statements.as_slice()),
+                    };
+                    return Some((&arrow.params, body));
+                }
+                _ => return None,
+            },
+        };
+        Some((&func.params, FunctionBodyKind::Statements(func.body.as_ref()?.statements.as_slice())))
+    }
+
+    fn trace_function(&mut self, body: FunctionBodyKind<'a>, depth: usize) -> PromptNode {
+        match body {
+            FunctionBodyKind::Expression(expr) => self.trace(expr, depth),
+            FunctionBodyKind::Statements(stmts) => self.trace_body(stmts, depth),
+        }
+    }
+
+    /// Trace a function body: locals, pushes, guarded returns and the final return.
+    fn trace_body(&mut self, stmts: &'a [Statement<'a>], depth: usize) -> PromptNode {
+        for (i, stmt) in stmts.iter().enumerate() {
+            match stmt {
+                Statement::VariableDeclaration(decl) => {
+                    for declarator in &decl.declarations {
+                        let (BindingPatternKind::BindingIdentifier(id), Some(init)) =
+                            (&declarator.id.kind, &declarator.init)
+                        else {
+                            continue;
+                        };
+                        let local = match init {
+                            Expression::ArrayExpression(arr) => Local::Array(self.elements(arr, depth)),
+                            init => Local::Expr(init),
+                        };
+                        if let Some(scope) = self.locals.last_mut() {
+                            scope.insert(id.name.as_str(), local);
+                        }
+                    }
+                }
+                Statement::ExpressionStatement(stmt) => {
+                    if let Some((name, parts)) = self.push(&stmt.expression, depth) {
+                        self.extend_local(name, parts);
+                    }
+                }
+                Statement::IfStatement(if_stmt) => {
+                    if let Some(ret) = returned(&if_stmt.consequent) {
+                        let then = self.trace(ret, depth + 1);
+                        let otherwise = match &if_stmt.alternate {
+                            Some(alt) => match returned(alt) {
+                                Some(ret) => self.trace(ret, depth + 1),
+                                None => self.trace_body(&stmts[i + 1..], depth),
+                            },
+                            None => self.trace_body(&stmts[i + 1..], depth),
+                        };
+                        return self.choice(&if_stmt.test, then, otherwise);
+                    }
+
+                    // `if (c) parts.push(…)` adds optional sections
+                    let pushed = match &if_stmt.consequent {
+                        Statement::ExpressionStatement(stmt) => self.push(&stmt.expression, depth),
+                        Statement::BlockStatement(block) => match block.body.as_slice() {
+                            [Statement::ExpressionStatement(stmt)] => self.push(&stmt.expression, depth),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    if let Some((name, parts)) = pushed {
+                        let guarded = self.choice(&if_stmt.test, concat(parts), PromptNode::Empty);
+                        self.extend_local(name, vec![guarded]);
+                    }
+                }
+                Statement::BlockStatement(block) => {
+                    let node = self.trace_body(&block.body, depth);
+                    if node != PromptNode::Empty {
+                        return node;
+                    }
+                }
+                Statement::ReturnStatement(ret) => {
+                    return match &ret.argument {
+                        Some(arg) => self.trace(arg, depth + 1),
+                        None => PromptNode::Empty,
+                    };
+                }
+                _ => {}
+            }
+        }
+        PromptNode::Empty
+    }
+
+    /// Parse `name.push(a, b)` on a local array.
+    fn push(&mut self, expr: &'a Expression<'a>, depth: usize) -> Option<(&'a str, Vec<PromptNode>)> {
+        let Expression::CallExpression(call) = expr else {
+            return None;
+        };
+        let Expression::StaticMemberExpression(member) = &call.callee else {
+            return None;
+        };
+        let Expression::Identifier(id) = &member.object else {
+            return None;
+        };
+        if member.property.name != "push" || !matches!(self.lookup_local(id.name.as_str()), Some(Local::Array(_))) {
+            return None;
+        }
+
+        let parts = call
+            .arguments
+            .iter()
+            .filter_map(|arg| match arg {
+                Argument::SpreadElement(spread) => Some(self.dynamic(&spread.argument)),
+                arg => arg.as_expression().map(|e| self.trace(e, depth + 1)),
+            })
+            .collect();
+        Some((id.name.as_str(), parts))
+    }
+
+    fn extend_local(&mut self, name: &'a str, parts: Vec<PromptNode>) {
+        if let Some(Local::Array(existing)) = self.locals.last_mut().and_then(|scope| scope.get_mut(name)) {
+            existing.extend(parts);
+        }
+    }
+}
+
+/// The returned expression of `return x` or `{ return x }`.
+fn returned<'a>(stmt: &'a Statement<'a>) -> Option<&'a Expression<'a>> {
+    match stmt {
+        Statement::ReturnStatement(ret) => ret.argument.as_ref(),
+        Statement::BlockStatement(block) => match block.body.as_slice() {
+            [.., Statement::ReturnStatement(_)] => first_return(&block.body),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Concatenate parts, flattening trivial cases.
+fn concat(mut parts: Vec<PromptNode>) -> PromptNode {
+    parts.retain(|p| *p != PromptNode::Empty);
+    match parts.len() {
+        0 => PromptNode::Empty,
+        1 => parts.pop().unwrap_or(PromptNode::Empty),
+        _ => PromptNode::Concat { parts },
+    }
+}
+
+/// Wrap a traced binding, keeping single literals unwrapped.
+fn fragment(name: &str, node: PromptNode) -> PromptNode {
+    match node {
+        PromptNode::Empty => PromptNode::Empty,
+        node => PromptNode::Fragment {
+            name: name.to_string(),
+            node: Box::new(node),
+        },
+    }
+}
+
+/// Collapse whitespace and truncate source for use as a label.
+fn label(source: &str) -> String {
+    let label = source.split_whitespace().collect::<Vec<_>>().join(" ");
+    if label.chars().count() <= MAX_LABEL_LEN {
+        return label;
+    }
+    let truncated: String = label.chars().take(MAX_LABEL_LEN).collect();
+    format!("{}…", truncated)
+}
+
+/// Extractor for prompt assembly graphs.
+pub struct AssemblyExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+}
+
+impl<'a> AssemblyExtractor<'a> {
+    /// Create a new assembly extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        Self {
+            analyzer,
+            symbol_table,
+        }
+    }
+
+    /// Trace every named function and keep those that assemble prompt text,
+    /// largest first.
+    pub fn extract(&self) -> Result<Vec<PromptAssembly>> {
+        debug!("Tracing prompt assembly functions");
+
+        let program = self.analyzer.program();
+        let functions: HashMap<&'a str, &'a Function<'a>> = program
+            .body
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Statement::FunctionDeclaration(func) => Some((func.id.as_ref()?.name.as_str(), &**func)),
+                _ => None,
+            })
+            .collect();
+
+        // Top-level `function f() {}` plus `var f = function/arrow`
+        let mut candidates: Vec<(String, Span)> =
+            functions.iter().map(|(name, func)| (name.to_string(), func.span)).collect();
+        for name in self.symbol_table.symbols.keys() {
+            if let Some(binding @ (Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_))) =
+                self.symbol_table.get_binding(name)
+            {
+                candidates.push((name.clone(), binding.span()));
+            }
+        }
+        candidates.sort_by_key(|(_, span)| span.start);
+
+        let mut assemblies = Vec::new();
+        for (name, span) in candidates {
+            let mut tracer = Tracer::new(self.symbol_table, &functions, program.source_text, self.analyzer.line_index());
+            let Some((_, body)) = tracer.function(&name) else {
+                continue;
+            };
+            tracer.stack.push(name.clone());
+            tracer.locals.push(HashMap::new());
+            let root = tracer.trace_function(body, 0);
+
+            let static_chars = root.static_len();
+            if static_chars < MIN_ASSEMBLY_TEXT || !root.is_composed() {
+                continue;
+            }
+            trace!("Prompt assembly {} ({} chars, {} conditions)", name, static_chars, tracer.conditions.len());
+            assemblies.push(PromptAssembly {
+                name,
+                span: SourceSpan::locate(self.analyzer.line_index(), span),
+                conditions: tracer.conditions,
+                static_chars,
+                root,
+            });
+        }
+
+        assemblies.sort_by(|a, b| b.static_chars.cmp(&a.static_chars).then(a.span.start.cmp(&b.span.start)));
+        debug!("Found {} prompt assembly functions", assemblies.len());
+        Ok(assemblies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    const CODE: &str = r#"
+        var INTRO = "You are an interactive CLI tool that helps users with software engineering tasks. Use the instructions below and the tools available to you to assist the user. ";
+        var TONE = "You should be concise, direct, and to the point. Answer the user's question directly, without elaboration, explanation, or details unless asked. ";
+        function env(A) {
+            return `Working directory: ${A}
+Platform: ${process.platform}`;
+        }
+        function buildSystem(A, B) {
+            let Q = [INTRO, TONE];
+            if (B.todo) Q.push("Use the TodoWrite tool to plan and track tasks. This helps you keep track of complex tasks and gives the user visibility into your progress.");
+            Q.push(isOpus(A) ? "Think hard about the problem before answering." : "Keep answers short.");
+            return [Q.join("\n"), env(cwd())];
+        }
+    "#;
+
+    #[test]
+    fn test_traces_assembly_graph() {
+        let allocator = Allocator::default();
+        let parser = Parser::new(CODE.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let assemblies = AssemblyExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+        assert_eq!(assemblies.len(), 1);
+        let assembly = &assemblies[0];
+        assert_eq!(assembly.name, "buildSystem");
+
+        let sources: Vec<_> = assembly.conditions.iter().map(|c| c.source.as_str()).collect();
+        assert_eq!(sources, ["B.todo", "isOpus(A)"]);
+
+        let variants = assembly.variants(&HashMap::new(), 16);
+        assert_eq!(variants.len(), 4);
+
+        let fixed = assembly
+            .resolve_conditions(&[("B.todo".to_string(), false), ("c1".to_string(), true)])
+            .unwrap();
+        let variants = assembly.variants(&fixed, 16);
+        assert_eq!(variants.len(), 1);
+        let variant = &variants[0];
+        assert!(variant.text.starts_with("You are an interactive CLI tool"));
+        assert!(variant.text.contains("to the point. Answer the user's question directly, without elaboration, explanation, or details unless asked. \nThink hard"));
+        assert!(!variant.text.contains("TodoWrite"));
+        assert!(variant.text.ends_with("Working directory: ${cwd()}\nPlatform: ${process.platform}"));
+
+        // Every byte of the variant is covered by a segment with provenance
+        let covered: usize = variant.segments.iter().map(|s| s.end - s.start).sum();
+        assert_eq!(covered, variant.text.len());
+        let intro = &variant.segments[0];
+        assert_eq!(intro.fragment.as_deref(), Some("INTRO"));
+        assert_eq!(intro.source.line, 2);
+        let env = variant.segments.iter().find(|s| s.kind == SegmentKind::Dynamic).unwrap();
+        assert_eq!(env.fragment.as_deref(), Some("env"));
+
+        assert!(assembly.resolve_conditions(&[("nope".to_string(), true)]).is_err());
+    }
+
+    fn extract(code: &str) -> Vec<PromptAssembly> {
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+        AssemblyExtractor::new(&analyzer, &symbol_table).extract().unwrap()
+    }
+
+    #[test]
+    fn test_min_assembly_text() {
+        let half = "a".repeat(MIN_ASSEMBLY_TEXT / 2);
+        let code = format!(
+            r#"
+            function short(A) {{ return ["{half}", A ? "{half}" : "b".slice(1)]; }}
+            function enough(A) {{ return ["{half}", A ? "{half}" : ""]; }}
+            function literal() {{ return "{half}{half}{half}"; }}
+            "#,
+            half = &half[1..]
+        ) + &format!(r#"function exact(A) {{ return ["{half}", A ? "{half}" : ""]; }}"#);
+
+        let names: Vec<_> = extract(&code).into_iter().map(|a| a.name).collect();
+        // `short` and `enough` fall 2 chars short; `literal` isn't composed
+        assert_eq!(names, ["exact"]);
+    }
+
+    #[test]
+    fn test_max_trace_depth() {
+        let intro = "i".repeat(MIN_ASSEMBLY_TEXT);
+        let mut code = format!("function build() {{ return [\"{}\", f0()]; }}\n", intro);
+        for i in 0..MAX_TRACE_DEPTH {
+            code.push_str(&format!("function f{}() {{ return [\"part {}\", f{}()]; }}\n", i, i, i + 1));
+        }
+        code.push_str(&format!("function f{}() {{ return \"end\"; }}\n", MAX_TRACE_DEPTH));
+        code.push_str("function loop() { return [\"again\", loop()]; }\n");
+
+        let assemblies = extract(&code);
+        assert_eq!(assemblies.len(), 1);
+        let variants = assemblies[0].variants(&HashMap::new(), 4);
+        assert_eq!(variants.len(), 1);
+
+        // Tracing stops partway down the chain and leaves the rest dynamic
+        let text = &variants[0].text;
+        assert!(text.contains("part 0"));
+        assert!(!text.contains("end"));
+        assert_eq!(variants[0].segments.last().map(|s| s.kind), Some(SegmentKind::Dynamic));
+        assert!(text.ends_with("()}"));
+    }
+}
@@ -5,15 +5,19 @@
 
 use crate::analyzer::SymbolTable;
 use crate::extractor::tools::{ToolDefinition, ToolProperties};
+use crate::rules::Rules;
 use crate::Result;
-use oxc_ast::ast::Program;
 use regex::Regex;
 use tracing::debug;
 
 /// Extract tools from beautified JavaScript code.
 pub struct BeautifiedToolExtractor<'a> {
     beautified_code: &'a str,
-    symbol_table: Option<SymbolTable<'a>>,
+    symbol_table: Option<&'a SymbolTable<'a>>,
+    rules: &'a Rules,
+    /// Tool names discovered structurally; when non-empty these replace the
+    /// name heuristic.
+    tool_names: Vec<String>,
 }
 
 impl<'a> BeautifiedToolExtractor<'a> {
@@ -22,18 +26,33 @@ impl<'a> BeautifiedToolExtractor<'a> {
         Self {
             beautified_code,
             symbol_table: None,
+            rules: Rules::bundled(),
+            tool_names: Vec::new(),
         }
     }
 
-    /// Create with AST for symbol resolution.
-    pub fn with_ast(beautified_code: &'a str, program: &'a Program<'a>) -> Self {
-        let symbol_table = SymbolTable::new(program);
+    /// Create with a symbol table to resolve identifiers through.
+    pub fn with_symbol_table(beautified_code: &'a str, symbol_table: &'a SymbolTable<'a>) -> Self {
         Self {
             beautified_code,
             symbol_table: Some(symbol_table),
+            rules: Rules::bundled(),
+            tool_names: Vec::new(),
         }
     }
 
+    /// Use these extraction rules instead of the bundled ones.
+    pub fn with_rules(mut self, rules: &'a Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Restrict extraction to tool names already discovered from the AST.
+    pub fn with_tool_names(mut self, tool_names: Vec<String>) -> Self {
+        self.tool_names = tool_names;
+        self
+    }
+
     /// Extract all tools from the beautified code.
     pub fn extract(&self) -> Result<Vec<ToolDefinition>> {
         debug!("Extracting tools from beautified code");
@@ -50,18 +69,16 @@ impl<'a> BeautifiedToolExtractor<'a> {
             let tool_name = &cap[2];
 
             // Filter to likely tool names (not error codes, signals, etc.)
-            if Self::is_likely_tool_name(tool_name) {
+            if self.is_tool_name(tool_name) {
                 // Try to find the description for this tool
                 let description = self.find_tool_description(tool_name, var_name);
 
                 // Try to find if this has an inputSchema
                 let has_schema = self.has_input_schema(var_name);
 
-                let has_desc = description.is_some();
-
                 // Resolve template variables in description if we have a symbol table
                 let final_description = if let Some(desc) = description {
-                    let resolved = if let Some(ref table) = self.symbol_table {
+                    let resolved = if let Some(table) = self.symbol_table {
                         table.resolve_template(&desc)
                     } else {
                         desc
@@ -84,13 +101,7 @@ impl<'a> BeautifiedToolExtractor<'a> {
                     full_prompt: final_description.clone(),
                     input_schema: None,
                     output_schema: None,
-                    properties: ToolProperties {
-                        is_strict: false,
-                        is_enabled: true,
-                        is_read_only: false,
-                        is_concurrency_safe: false,
-                        user_facing_name: None,
-                    },
+                    properties: ToolProperties::default(),
                     confidence: if final_description.starts_with("Tool:") {
                         0.3 // Low confidence for placeholder
                     } else if has_schema {
@@ -98,6 +109,8 @@ impl<'a> BeautifiedToolExtractor<'a> {
                     } else {
                         0.6
                     },
+                    tokens: None,
+                    provenance: None,
                 };
 
                 debug!("Found tool from beautified code: {} (confidence: {:.2})",
@@ -146,38 +159,20 @@ impl<'a> BeautifiedToolExtractor<'a> {
         (has_sentences || has_prose_words) && has_capitalized_start
     }
 
-    /// Check if a name is likely a tool name.
-    fn is_likely_tool_name(name: &str) -> bool {
-        // Known tool names (expanded list)
-        let known_tools = [
-            "Bash", "Read", "Write", "Edit", "Grep", "Glob", "Task",
-            "TodoWrite", "NotebookEdit", "WebFetch", "WebSearch",
-            "Skill", "SlashCommand", "AskUserQuestion", "ExitPlanMode",
-            "BashOutput", "KillShell", "ListMcpResources", "ReadMcpResource",
-        ];
-
-        if known_tools.contains(&name) {
-            return true;
-        }
-
-        // Heuristics for tool names
-        // - Capitalized
-        // - Reasonable length (3-20 chars)
-        // - Not error codes or constants
-        if name.len() < 3 || name.len() > 20 {
-            return false;
-        }
-
-        // Exclude common non-tool patterns
-        let excluded_prefixes = ["SIG", "SYSRES", "ERROR", "HTTP", "CONST"];
-        for prefix in excluded_prefixes {
-            if name.starts_with(prefix) {
-                return false;
-            }
+    /// Check a name against the discovered tool names, falling back to the
+    /// heuristic when none were supplied.
+    fn is_tool_name(&self, name: &str) -> bool {
+        if self.tool_names.is_empty() {
+            self.is_likely_tool_name(name)
+        } else {
+            self.tool_names.iter().any(|t| t == name)
         }
+    }
 
-        // Must start with capital letter
-        name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false)
+    /// Check if a name is likely a tool name.
+    fn is_likely_tool_name(&self, name: &str) -> bool {
+        // Capitalized, reasonably short, and not an error code or constant
+        self.rules.tools.is_tool_name(name)
     }
 
     /// Find the description for a tool.
@@ -287,10 +282,11 @@ mod tests {
 
     #[test]
     fn test_is_likely_tool_name() {
-        assert!(BeautifiedToolExtractor::is_likely_tool_name("Bash"));
-        assert!(BeautifiedToolExtractor::is_likely_tool_name("TodoWrite"));
-        assert!(!BeautifiedToolExtractor::is_likely_tool_name("SIGHUP"));
-        assert!(!BeautifiedToolExtractor::is_likely_tool_name("ERROR"));
-        assert!(!BeautifiedToolExtractor::is_likely_tool_name("A")); // Too short
+        let extractor = BeautifiedToolExtractor::new("");
+        assert!(extractor.is_likely_tool_name("Bash"));
+        assert!(extractor.is_likely_tool_name("TodoWrite"));
+        assert!(!extractor.is_likely_tool_name("SIGHUP"));
+        assert!(!extractor.is_likely_tool_name("ERROR"));
+        assert!(!extractor.is_likely_tool_name("A")); // Too short
     }
 }
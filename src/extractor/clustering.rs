@@ -0,0 +1,315 @@
+//! Near-duplicate text clustering with MinHash and LSH.
+//!
+//! Texts are shingled into overlapping word triples. MinHash signatures split
+//! into LSH bands propose candidate pairs, which are confirmed on the exact
+//! shingle sets: either by Jaccard similarity (copies with small edits) or by
+//! containment of the smaller text in the larger (fragments). A fragment is
+//! only linked to its single best container so it can't chain two unrelated
+//! texts together.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// Words per shingle.
+const SHINGLE_WORDS: usize = 3;
+
+/// LSH bands; `BANDS * ROWS` hashes per signature.
+const BANDS: usize = 16;
+
+/// Hashes per LSH band.
+const ROWS: usize = 4;
+
+/// Minimum Jaccard similarity for two texts to be near-duplicates.
+pub const JACCARD_THRESHOLD: f64 = 0.6;
+
+/// Minimum share of a smaller text's shingles found in a larger one.
+pub const CONTAINMENT_THRESHOLD: f64 = 0.9;
+
+/// Largest middle section (in words squared) diffed word by word.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// A replaced span between a canonical text and a variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextChange {
+    /// Byte offset of the change in the canonical text.
+    pub offset: usize,
+
+    /// Text of the canonical that the variant lacks.
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub removed: String,
+
+    /// Text the variant has instead.
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub added: String,
+}
+
+/// Group texts into clusters of near-duplicates.
+///
+/// Returns clusters of indices into `texts`, each in ascending order, with
+/// singletons omitted.
+pub fn cluster_texts(texts: &[&str]) -> Vec<Vec<usize>> {
+    let shingles: Vec<HashSet<u64>> = texts.iter().map(|t| shingle(t)).collect();
+    let signatures: Vec<Vec<u64>> = shingles.iter().map(minhash).collect();
+
+    // Candidate pairs share at least one identical band
+    let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+    for (idx, signature) in signatures.iter().enumerate() {
+        if shingles[idx].is_empty() {
+            continue;
+        }
+        for (band, rows) in signature.chunks(ROWS).enumerate() {
+            buckets.entry((band, hash_one(rows))).or_default().push(idx);
+        }
+    }
+    let mut candidates: HashSet<(usize, usize)> = HashSet::new();
+    for members in buckets.values().filter(|m| m.len() > 1) {
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                candidates.insert((a.min(b), a.max(b)));
+            }
+        }
+    }
+
+    let mut parent: Vec<usize> = (0..texts.len()).collect();
+    // Best container per fragment: (containment, container size, container)
+    let mut containers: HashMap<usize, (f64, usize, usize)> = HashMap::new();
+    let mut candidates: Vec<_> = candidates.into_iter().collect();
+    candidates.sort_unstable();
+
+    for (a, b) in candidates {
+        let (sa, sb) = (&shingles[a], &shingles[b]);
+        let shared = sa.intersection(sb).count();
+        let jaccard = shared as f64 / (sa.len() + sb.len() - shared) as f64;
+        if jaccard >= JACCARD_THRESHOLD {
+            union(&mut parent, a, b);
+            continue;
+        }
+
+        let (small, large) = if sa.len() <= sb.len() { (a, b) } else { (b, a) };
+        let containment = shared as f64 / shingles[small].len() as f64;
+        if containment >= CONTAINMENT_THRESHOLD {
+            let entry = (containment, shingles[large].len(), large);
+            let best = containers.entry(small).or_insert(entry);
+            if (entry.0, entry.1) > (best.0, best.1) {
+                *best = entry;
+            }
+        }
+    }
+    for (small, (_, _, large)) in containers {
+        union(&mut parent, small, large);
+    }
+
+    let mut clusters: HashMap<usize, Vec<usize>> = HashMap::new();
+    for idx in 0..texts.len() {
+        let root = find(&mut parent, idx);
+        clusters.entry(root).or_default().push(idx);
+    }
+    let mut clusters: Vec<Vec<usize>> = clusters.into_values().filter(|c| c.len() > 1).collect();
+    clusters.sort();
+    clusters
+}
+
+/// Exact Jaccard similarity of two texts' shingle sets.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    jaccard(&shingle(a), &shingle(b))
+}
+
+/// A text's set of hashed word shingles, for repeated comparisons with [`jaccard`].
+pub fn shingles(text: &str) -> HashSet<u64> {
+    shingle(text)
+}
+
+/// Jaccard similarity of two shingle sets.
+pub fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let shared = a.intersection(b).count();
+    let union = a.len() + b.len() - shared;
+    if union == 0 {
+        return 1.0;
+    }
+    shared as f64 / union as f64
+}
+
+/// Word-level changes turning `canonical` into `variant`.
+pub fn diff_words(canonical: &str, variant: &str) -> Vec<TextChange> {
+    let a = words(canonical);
+    let b = words(variant);
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x.1 == y.1).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x.1 == y.1)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    let end_offset = a.get(a.len() - suffix).map(|w| w.0).unwrap_or(canonical.len());
+
+    if a_mid.is_empty() && b_mid.is_empty() {
+        return Vec::new();
+    }
+    if a_mid.len() * b_mid.len() > MAX_DIFF_CELLS {
+        return vec![TextChange {
+            offset: a_mid.first().map(|w| w.0).unwrap_or(end_offset),
+            removed: a_mid.iter().map(|w| w.1).collect(),
+            added: b_mid.iter().map(|w| w.1).collect(),
+        }];
+    }
+
+    // Longest common subsequence table over the middle section
+    let (n, m) = (a_mid.len(), b_mid.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a_mid[i].1 == b_mid[j].1 {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let mut current: Option<TextChange> = None;
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a_mid[i].1 == b_mid[j].1 {
+            changes.extend(current.take());
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let offset = a_mid.get(i).map(|w| w.0).unwrap_or(end_offset);
+        let change = current.get_or_insert_with(|| TextChange {
+            offset,
+            removed: String::new(),
+            added: String::new(),
+        });
+        if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            change.added.push_str(b_mid[j].1);
+            j += 1;
+        } else {
+            change.removed.push_str(a_mid[i].1);
+            i += 1;
+        }
+    }
+    changes.extend(current);
+    changes
+}
+
+/// Words with their trailing whitespace, and their byte offsets.
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut out = Vec::new();
+    let mut start = None;
+    let mut in_space = false;
+    for (idx, c) in text.char_indices() {
+        if c.is_whitespace() {
+            in_space = true;
+        } else if in_space || start.is_none() {
+            if let Some(s) = start {
+                out.push((s, &text[s..idx]));
+            }
+            start = Some(idx);
+            in_space = false;
+        }
+    }
+    match start {
+        Some(s) => out.push((s, &text[s..])),
+        None if !text.is_empty() => out.push((0, text)),
+        None => {}
+    }
+    out
+}
+
+/// Hashes of overlapping lowercase word triples.
+fn shingle(text: &str) -> HashSet<u64> {
+    let words: Vec<String> = text.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return HashSet::new();
+    }
+    words
+        .windows(SHINGLE_WORDS.min(words.len()))
+        .map(hash_one)
+        .collect()
+}
+
+fn minhash(shingles: &HashSet<u64>) -> Vec<u64> {
+    (0..BANDS * ROWS)
+        .map(|seed| {
+            shingles
+                .iter()
+                .map(|&s| splitmix64(s ^ (seed as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)))
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+fn hash_one<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn find(parent: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut node = idx;
+    while parent[node] != root {
+        let next = parent[node];
+        parent[node] = root;
+        node = next;
+    }
+    root
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let (ra, rb) = (find(parent, a), find(parent, b));
+    if ra != rb {
+        parent[ra.max(rb)] = ra.min(rb);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const READ: &str = "Reads a file from the local filesystem. You can access any file directly by using this tool. \
+        Assume this tool is able to read all files on the machine. The file_path parameter must be an absolute path, \
+        not a relative path. By default, it reads up to 2000 lines starting from the beginning of the file.";
+
+    #[test]
+    fn test_clusters_edits_and_fragments() {
+        let edited = READ.replace("2000 lines", "3000 lines");
+        let fragment = READ.split(" By default").next().unwrap();
+        let unrelated = "Executes a given bash command in a persistent shell session with optional timeout, \
+            ensuring proper handling and security measures for every command that is run.";
+
+        let texts = [READ, unrelated, edited.as_str(), fragment];
+        assert_eq!(cluster_texts(&texts), vec![vec![0, 2, 3]]);
+    }
+
+    #[test]
+    fn test_diff_words() {
+        let edited = READ.replace("2000 lines", "3000 lines").replace("directly ", "");
+        let changes = diff_words(READ, &edited);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].removed, "directly ");
+        assert!(changes[0].added.is_empty());
+        assert_eq!(changes[1].removed, "2000 ");
+        assert_eq!(changes[1].added, "3000 ");
+        assert_eq!(&READ[changes[1].offset..changes[1].offset + 4], "2000");
+
+        assert!(diff_words(READ, READ).is_empty());
+    }
+}
@@ -0,0 +1,1090 @@
+//! Reconstruction of the bundle's own command-line interface.
+//!
+//! The CLI is built with commander: a `new Command()` followed by chains of
+//! `.command()`, `.option()`, `.argument()` and `.action()` calls, often split
+//! across local variables. Chains are replayed in source order onto a tree of
+//! commands, which can then be rendered as `--help` text.
+//!
+//! The class is renamed in minified bundles, so roots are found structurally:
+//! any `new X()` (or free identifier) whose chains add commander-style options.
+
+use crate::analyzer::{Analyzer, HasProvenance, Provenance, SymbolTable};
+use crate::extractor::members::{property_key, unwind_chain};
+use crate::extractor::schemas::literal_to_json;
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::Span;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use tracing::debug;
+
+/// Builder methods that return the command they were called on.
+const RETURNS_SELF: &[&str] = &[
+    "name",
+    "description",
+    "summary",
+    "alias",
+    "aliases",
+    "usage",
+    "argument",
+    "arguments",
+    "addArgument",
+    "option",
+    "requiredOption",
+    "addOption",
+    "addCommand",
+    "action",
+    "version",
+    "helpOption",
+    "helpCommand",
+    "addHelpCommand",
+    "addHelpText",
+    "hook",
+    "allowUnknownOption",
+    "allowExcessArguments",
+    "enablePositionalOptions",
+    "passThroughOptions",
+    "configureHelp",
+    "configureOutput",
+    "showHelpAfterError",
+    "showSuggestionAfterError",
+    "exitOverride",
+    "copyInheritedSettings",
+];
+
+/// Maximum number of identifier hops followed when resolving a receiver.
+const MAX_RESOLVE_DEPTH: usize = 8;
+
+/// Placeholder in help output for a root command the bundle never names.
+const UNNAMED_PROGRAM: &str = "<program>";
+
+/// A command or subcommand of the reconstructed CLI.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CliCommand {
+    /// Command name (the program name for the root); empty for a root the
+    /// bundle never names, which commander names after the running script.
+    pub name: String,
+
+    /// Alternative names.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// Description shown in help.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Custom usage string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<String>,
+
+    /// Program version, from `.version()`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Positional arguments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<CliArgument>,
+
+    /// Options, in declaration order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<CliOption>,
+
+    /// Subcommands, in declaration order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subcommands: Vec<CliCommand>,
+
+    /// Hidden from help output.
+    pub hidden: bool,
+
+    /// Whether an `.action()` handler is attached.
+    pub has_action: bool,
+
+    /// Where the command was created: `new Command()`, the root's first
+    /// use, or the `.command()` call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for CliCommand {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+
+    fn children_mut(&mut self) -> &mut [Self] {
+        &mut self.subcommands
+    }
+}
+
+/// A positional argument.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CliArgument {
+    /// Argument name.
+    pub name: String,
+
+    /// `<name>` rather than `[name]`.
+    pub required: bool,
+
+    /// Accepts multiple values (`name...`).
+    pub variadic: bool,
+
+    /// Description shown in help.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Default value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<JsonValue>,
+
+    /// Allowed values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+}
+
+/// A command-line option.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CliOption {
+    /// Flags as declared, e.g. `-d, --debug [filter]`.
+    pub flags: String,
+
+    /// Short flag, e.g. `-d`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short: Option<String>,
+
+    /// Long flag, e.g. `--debug`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long: Option<String>,
+
+    /// Value placeholder, e.g. `<model>` or `[filter]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// Description shown in help.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Default value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<JsonValue>,
+
+    /// Allowed values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+
+    /// Environment variable that supplies the value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+
+    /// Must be given (`requiredOption`, `makeOptionMandatory`).
+    pub required: bool,
+
+    /// Hidden from help output (`hideHelp`).
+    pub hidden: bool,
+}
+
+/// Extractor for the commander-built CLI.
+pub struct CommanderExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+}
+
+impl<'a> CommanderExtractor<'a> {
+    /// Create a new commander extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        Self {
+            analyzer,
+            symbol_table,
+        }
+    }
+
+    /// Extract every command tree, largest first.
+    pub fn extract(&self) -> Result<Vec<CliCommand>> {
+        debug!("Reconstructing commander CLI");
+        let program = self.analyzer.program();
+
+        // Pass 1 finds which receivers are commands; pass 2 replays their chains
+        let mut discover = ChainVisitor::new(self.symbol_table, None);
+        discover.visit_statements(&program.body);
+
+        let mut build = ChainVisitor::new(self.symbol_table, Some(discover.roots));
+        build.visit_statements(&program.body);
+
+        let mut roots = build.into_trees(&|span| self.analyzer.provenance(span));
+        roots.sort_by_key(|root| std::cmp::Reverse(size(root)));
+
+        debug!("Reconstructed {} CLI command trees", roots.len());
+        Ok(roots)
+    }
+}
+
+/// Identity of a root command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RootKey<'a> {
+    /// A `new X(...)` expression, by span start.
+    New(u32),
+    /// An identifier with no visible binding (e.g. an imported `program`).
+    Free(&'a str),
+}
+
+struct Node {
+    command: CliCommand,
+    span: Span,
+    children: Vec<usize>,
+    has_parent: bool,
+}
+
+/// Walks the program, tracking local bindings and replaying builder chains.
+///
+/// Without `roots` it only discovers command receivers; with them it builds.
+struct ChainVisitor<'s, 'a> {
+    symbol_table: &'s SymbolTable<'a>,
+    locals: HashMap<&'a str, &'a Expression<'a>>,
+    building: bool,
+    roots: HashSet<RootKey<'a>>,
+    root_nodes: HashMap<RootKey<'a>, usize>,
+    subcommands: HashMap<u32, usize>,
+    nodes: Vec<Node>,
+}
+
+impl<'s, 'a> ChainVisitor<'s, 'a> {
+    fn new(symbol_table: &'s SymbolTable<'a>, roots: Option<HashSet<RootKey<'a>>>) -> Self {
+        Self {
+            symbol_table,
+            locals: HashMap::new(),
+            building: roots.is_some(),
+            roots: roots.unwrap_or_default(),
+            root_nodes: HashMap::new(),
+            subcommands: HashMap::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+    fn visit_statements(&mut self, stmts: &'a [Statement<'a>]) {
+        for stmt in stmts {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &'a Statement<'a>) {
+        match stmt {
+            Statement::VariableDeclaration(decl) => self.visit_declaration(decl),
+            Statement::ExpressionStatement(expr_stmt) => {
+                self.visit_expression(&expr_stmt.expression);
+            }
+            Statement::ReturnStatement(ret) => {
+                if let Some(ref arg) = ret.argument {
+                    self.visit_expression(arg);
+                }
+            }
+            Statement::BlockStatement(block) => self.visit_statements(&block.body),
+            Statement::IfStatement(if_stmt) => {
+                self.visit_expression(&if_stmt.test);
+                self.visit_statement(&if_stmt.consequent);
+                if let Some(ref alt) = if_stmt.alternate {
+                    self.visit_statement(alt);
+                }
+            }
+            Statement::FunctionDeclaration(func) => self.visit_function(func),
+            Statement::TryStatement(try_stmt) => {
+                self.visit_statements(&try_stmt.block.body);
+                if let Some(ref handler) = try_stmt.handler {
+                    self.visit_statements(&handler.body.body);
+                }
+                if let Some(ref finalizer) = try_stmt.finalizer {
+                    self.visit_statements(&finalizer.body);
+                }
+            }
+            Statement::SwitchStatement(switch) => {
+                for case in &switch.cases {
+                    self.visit_statements(&case.consequent);
+                }
+            }
+            Statement::ForStatement(for_stmt) => self.visit_statement(&for_stmt.body),
+            Statement::ForInStatement(for_in) => self.visit_statement(&for_in.body),
+            Statement::ForOfStatement(for_of) => self.visit_statement(&for_of.body),
+            Statement::WhileStatement(while_stmt) => self.visit_statement(&while_stmt.body),
+            Statement::DoWhileStatement(do_while) => self.visit_statement(&do_while.body),
+            Statement::LabeledStatement(labeled) => self.visit_statement(&labeled.body),
+            Statement::ExportNamedDeclaration(export) => {
+                if let Some(Declaration::VariableDeclaration(decl)) = &export.declaration {
+                    self.visit_declaration(decl);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_declaration(&mut self, decl: &'a VariableDeclaration<'a>) {
+        for declarator in &decl.declarations {
+            if let Some(ref init) = declarator.init {
+                self.visit_expression(init);
+                if let BindingPatternKind::BindingIdentifier(id) = &declarator.id.kind {
+                    self.locals.insert(id.name.as_str(), init);
+                }
+            }
+        }
+    }
+
+    fn visit_function(&mut self, func: &'a Function<'a>) {
+        if let Some(ref body) = func.body {
+            self.visit_statements(&body.statements);
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &'a Expression<'a>) {
+        match expr {
+            Expression::CallExpression(call) => match &call.callee {
+                Expression::StaticMemberExpression(_) => self.visit_chain(expr),
+                callee => {
+                    self.visit_expression(callee);
+                    self.visit_arguments(&call.arguments);
+                }
+            },
+            Expression::NewExpression(new_expr) => self.visit_arguments(&new_expr.arguments),
+            Expression::FunctionExpression(func) => self.visit_function(func),
+            Expression::ArrowFunctionExpression(arrow) => {
+                self.visit_statements(&arrow.body.statements);
+            }
+            Expression::AssignmentExpression(assign) => {
+                self.visit_expression(&assign.right);
+                if let AssignmentTarget::AssignmentTargetIdentifier(id) = &assign.left {
+                    self.locals.insert(id.name.as_str(), &assign.right);
+                }
+            }
+            Expression::SequenceExpression(seq) => {
+                for expr in &seq.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::ConditionalExpression(cond) => {
+                self.visit_expression(&cond.consequent);
+                self.visit_expression(&cond.alternate);
+            }
+            Expression::LogicalExpression(logical) => {
+                self.visit_expression(&logical.left);
+                self.visit_expression(&logical.right);
+            }
+            Expression::ParenthesizedExpression(paren) => self.visit_expression(&paren.expression),
+            Expression::AwaitExpression(await_expr) => self.visit_expression(&await_expr.argument),
+            Expression::UnaryExpression(unary) => self.visit_expression(&unary.argument),
+            Expression::StaticMemberExpression(member) => self.visit_expression(&member.object),
+            Expression::ObjectExpression(obj) => {
+                for prop in &obj.properties {
+                    if let ObjectPropertyKind::ObjectProperty(p) = prop {
+                        self.visit_expression(&p.value);
+                    }
+                }
+            }
+            Expression::ArrayExpression(arr) => {
+                for elem in &arr.elements {
+                    if let Some(expr) = elem.as_expression() {
+                        self.visit_expression(expr);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_arguments(&mut self, arguments: &'a [Argument<'a>]) {
+        for arg in arguments {
+            if let Some(expr) = arg.as_expression() {
+                self.visit_expression(expr);
+            }
+        }
+    }
+
+    /// Process a whole `a.b().c()` chain, then everything nested inside it.
+    fn visit_chain(&mut self, head: &'a Expression<'a>) {
+        let (base, calls) = unwind_chain(head);
+
+        if self.building {
+            self.replay(base, &calls);
+        } else if calls.iter().any(|(method, call)| is_distinctive(method, call)) {
+            if let Some(key) = self.root_key(base, 0) {
+                self.roots.insert(key);
+            }
+        }
+
+        for (_, call) in &calls {
+            self.visit_arguments(&call.arguments);
+        }
+        self.visit_expression(base);
+    }
+
+    /// Look up what an identifier is bound to, locally first.
+    fn binding(&self, name: &str) -> Option<&'a Expression<'a>> {
+        self.locals
+            .get(name)
+            .copied()
+            .or_else(|| self.symbol_table.get_binding(name))
+    }
+
+    /// Find the root receiver a chain ultimately hangs off.
+    fn root_key(&self, expr: &'a Expression<'a>, depth: usize) -> Option<RootKey<'a>> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return None;
+        }
+        match expr {
+            Expression::NewExpression(new_expr) => Some(RootKey::New(new_expr.span.start)),
+            Expression::Identifier(id) => match self.binding(id.name.as_str()) {
+                Some(bound) => self.root_key(bound, depth + 1),
+                None => Some(RootKey::Free(id.name.as_str())),
+            },
+            Expression::ParenthesizedExpression(paren) => self.root_key(&paren.expression, depth),
+            Expression::CallExpression(call) => match &call.callee {
+                Expression::StaticMemberExpression(member) => self.root_key(&member.object, depth),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Resolve an expression to the command node it evaluates to.
+    fn resolve_node(&mut self, expr: &'a Expression<'a>, depth: usize) -> Option<usize> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return None;
+        }
+        match expr {
+            Expression::NewExpression(new_expr) => {
+                let name = new_expr
+                    .arguments
+                    .first()
+                    .and_then(|a| a.as_expression())
+                    .and_then(|e| self.string_of(e));
+                self.root_node(RootKey::New(new_expr.span.start), name, new_expr.span)
+            }
+            Expression::Identifier(id) => match self.binding(id.name.as_str()) {
+                Some(bound) => self.resolve_node(bound, depth + 1),
+                None => self.root_node(RootKey::Free(id.name.as_str()), None, id.span),
+            },
+            Expression::ParenthesizedExpression(paren) => self.resolve_node(&paren.expression, depth),
+            Expression::CallExpression(call) => {
+                let Expression::StaticMemberExpression(member) = &call.callee else {
+                    return None;
+                };
+                match member.property.name.as_str() {
+                    "command" if !is_executable(call) => self.subcommands.get(&call.span.start).copied(),
+                    "command" => self.resolve_node(&member.object, depth),
+                    method if RETURNS_SELF.contains(&method) => self.resolve_node(&member.object, depth),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Get or create the node for a discovered root.
+    fn root_node(&mut self, key: RootKey<'a>, name: Option<String>, span: Span) -> Option<usize> {
+        if !self.roots.contains(&key) {
+            return None;
+        }
+        if let Some(&idx) = self.root_nodes.get(&key) {
+            return Some(idx);
+        }
+        let idx = self.add_node(
+            CliCommand {
+                name: name.unwrap_or_default(),
+                ..CliCommand::default()
+            },
+            span,
+        );
+        self.root_nodes.insert(key, idx);
+        Some(idx)
+    }
+
+    fn add_node(&mut self, command: CliCommand, span: Span) -> usize {
+        self.nodes.push(Node {
+            command,
+            span,
+            children: Vec::new(),
+            has_parent: false,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn add_child(&mut self, parent: usize, child: usize) {
+        if parent != child && !self.nodes[child].has_parent {
+            self.nodes[child].has_parent = true;
+            self.nodes[parent].children.push(child);
+        }
+    }
+
+    /// Apply a chain's calls in order, following `.command()` into subcommands.
+    fn replay(&mut self, base: &'a Expression<'a>, calls: &[(&'a str, &'a CallExpression<'a>)]) {
+        let Some(mut node) = self.resolve_node(base, 0) else {
+            return;
+        };
+        for (method, call) in calls {
+            match self.apply(node, method, call) {
+                Some(next) => node = next,
+                None => return,
+            }
+        }
+    }
+
+    /// Apply one builder call to a node, returning the node the call evaluates to.
+    fn apply(&mut self, node: usize, method: &str, call: &'a CallExpression<'a>) -> Option<usize> {
+        let args: Vec<&'a Expression<'a>> =
+            call.arguments.iter().filter_map(|a| a.as_expression()).collect();
+        let strings: Vec<Option<String>> = args.iter().map(|e| self.string_of(e)).collect();
+        let arg_string = |i: usize| strings.get(i).cloned().flatten();
+
+        match method {
+            "command" => return Some(self.apply_command(node, call, &args)),
+            "name" => {
+                if let Some(name) = arg_string(0) {
+                    self.nodes[node].command.name = name;
+                }
+            }
+            "description" | "summary" => {
+                let description = arg_string(0);
+                let command = &mut self.nodes[node].command;
+                if method == "description" || command.description.is_none() {
+                    command.description = description.or(command.description.take());
+                }
+            }
+            "usage" => self.nodes[node].command.usage = arg_string(0),
+            "alias" => self.nodes[node].command.aliases.extend(arg_string(0)),
+            "aliases" => {
+                let aliases = args.first().map(|e| self.strings_of(e)).unwrap_or_default();
+                self.nodes[node].command.aliases.extend(aliases);
+            }
+            "version" => {
+                let version = arg_string(0);
+                let flags = arg_string(1).unwrap_or_else(|| "-V, --version".to_string());
+                let description = arg_string(2).or_else(|| Some("output the version number".to_string()));
+                let command = &mut self.nodes[node].command;
+                command.version = version;
+                command.options.push(CliOption {
+                    description,
+                    ..parse_flags(&flags)
+                });
+            }
+            "argument" => {
+                if let Some(mut argument) = arg_string(0).as_deref().and_then(parse_argument_spec) {
+                    argument.description = arg_string(1);
+                    argument.default = args.get(2).and_then(|e| self.value_of(e));
+                    self.nodes[node].command.arguments.push(argument);
+                }
+            }
+            "arguments" => {
+                if let Some(spec) = arg_string(0) {
+                    let arguments = spec.split_whitespace().filter_map(parse_argument_spec);
+                    self.nodes[node].command.arguments.extend(arguments);
+                }
+            }
+            "addArgument" => {
+                if let Some(argument) = args.first().and_then(|e| self.argument_object(e)) {
+                    self.nodes[node].command.arguments.push(argument);
+                }
+            }
+            "option" | "requiredOption" => {
+                if let Some(flags) = arg_string(0) {
+                    // `option(flags, description, parser, default)` or `option(flags, description, default)`
+                    let default = match args.get(2) {
+                        Some(e) if is_function(e) => args.get(3).and_then(|e| self.value_of(e)),
+                        Some(e) => self.value_of(e),
+                        None => None,
+                    };
+                    self.nodes[node].command.options.push(CliOption {
+                        description: arg_string(1),
+                        default,
+                        required: method == "requiredOption",
+                        ..parse_flags(&flags)
+                    });
+                }
+            }
+            "addOption" => {
+                if let Some(option) = args.first().and_then(|e| self.option_object(e)) {
+                    self.nodes[node].command.options.push(option);
+                }
+            }
+            "addCommand" => {
+                if let Some(child) = args.first().and_then(|e| self.resolve_node(e, 0)) {
+                    if args.get(1).is_some_and(|opts| is_hidden_opts(opts)) {
+                        self.nodes[child].command.hidden = true;
+                    }
+                    self.add_child(node, child);
+                }
+            }
+            "action" => self.nodes[node].command.has_action = true,
+            m if RETURNS_SELF.contains(&m) => {}
+            _ => return None,
+        }
+        Some(node)
+    }
+
+    /// Handle `.command(spec, [description], [opts])`.
+    fn apply_command(&mut self, parent: usize, call: &'a CallExpression<'a>, args: &[&'a Expression<'a>]) -> usize {
+        let child = match self.subcommands.get(&call.span.start) {
+            Some(&idx) => idx,
+            None => {
+                let spec = args.first().and_then(|e| self.string_of(e)).unwrap_or_default();
+                let mut tokens = spec.split_whitespace();
+                let name = tokens.next().unwrap_or_default().to_string();
+                let description = if is_executable(call) {
+                    args.get(1).and_then(|e| self.string_of(e))
+                } else {
+                    None
+                };
+                let hidden = args[1..].iter().any(|opts| is_hidden_opts(opts));
+
+                let idx = self.add_node(
+                    CliCommand {
+                        name,
+                        description,
+                        arguments: tokens.filter_map(parse_argument_spec).collect(),
+                        hidden,
+                        ..CliCommand::default()
+                    },
+                    call.span,
+                );
+                self.subcommands.insert(call.span.start, idx);
+                self.add_child(parent, idx);
+                idx
+            }
+        };
+
+        // Executable subcommands (with a description) return the parent
+        if is_executable(call) { parent } else { child }
+    }
+
+    /// Read a `new Option(flags, description).default(..).hideHelp()` chain.
+    fn option_object(&self, expr: &'a Expression<'a>) -> Option<CliOption> {
+        let (base, calls) = unwind_chain(expr);
+        let Expression::NewExpression(new_expr) = base else {
+            return None;
+        };
+        let args: Vec<&Expression> = new_expr.arguments.iter().filter_map(|a| a.as_expression()).collect();
+        let flags = self.string_of(args.first()?)?;
+
+        let mut option = CliOption {
+            description: args.get(1).and_then(|e| self.string_of(e)),
+            ..parse_flags(&flags)
+        };
+        for (method, call) in calls {
+            let first = call.arguments.first().and_then(|a| a.as_expression());
+            match method {
+                "default" => option.default = first.and_then(|e| self.value_of(e)),
+                "hideHelp" => option.hidden = first.is_none_or(|e| truthy(e)),
+                "makeOptionMandatory" => option.required = first.is_none_or(|e| truthy(e)),
+                "choices" => option.choices = first.map(|e| self.strings_of(e)).unwrap_or_default(),
+                "env" => option.env = first.and_then(|e| self.string_of(e)),
+                _ => {}
+            }
+        }
+        Some(option)
+    }
+
+    /// Read a `new Argument(name, description).default(..)` chain.
+    fn argument_object(&self, expr: &'a Expression<'a>) -> Option<CliArgument> {
+        let (base, calls) = unwind_chain(expr);
+        let Expression::NewExpression(new_expr) = base else {
+            return None;
+        };
+        let args: Vec<&Expression> = new_expr.arguments.iter().filter_map(|a| a.as_expression()).collect();
+        let mut argument = parse_argument_spec(&self.string_of(args.first()?)?)?;
+        argument.description = args.get(1).and_then(|e| self.string_of(e));
+
+        for (method, call) in calls {
+            let first = call.arguments.first().and_then(|a| a.as_expression());
+            match method {
+                "default" => argument.default = first.and_then(|e| self.value_of(e)),
+                "choices" => argument.choices = first.map(|e| self.strings_of(e)).unwrap_or_default(),
+                "argOptional" => argument.required = false,
+                "argRequired" => argument.required = true,
+                _ => {}
+            }
+        }
+        Some(argument)
+    }
+
+    /// Resolve an expression to a string, through local and global bindings
+    /// and zero-argument helpers such as `.name(getBinName())`.
+    fn string_of(&self, expr: &Expression) -> Option<String> {
+        match expr {
+            Expression::Identifier(id) => {
+                if let Some(bound) = self.locals.get(id.name.as_str()) {
+                    return self.symbol_table.resolve_template_expr(bound);
+                }
+            }
+            Expression::CallExpression(call) if call.arguments.is_empty() => {
+                if let Expression::Identifier(callee) = &call.callee {
+                    return self.symbol_table.get_string_value(callee.name.as_str());
+                }
+            }
+            _ => {}
+        }
+        self.symbol_table.resolve_template_expr(expr)
+    }
+
+    /// Resolve an array of strings.
+    fn strings_of(&self, expr: &Expression) -> Vec<String> {
+        match expr {
+            Expression::ArrayExpression(arr) => arr
+                .elements
+                .iter()
+                .filter_map(|e| e.as_expression())
+                .filter_map(|e| self.string_of(e))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Resolve a default value.
+    fn value_of(&self, expr: &Expression) -> Option<JsonValue> {
+        literal_to_json(expr).or_else(|| self.string_of(expr).map(JsonValue::String))
+    }
+
+    /// Assemble the node arena into trees.
+    fn into_trees(self, locate: &dyn Fn(Span) -> Provenance) -> Vec<CliCommand> {
+        (0..self.nodes.len())
+            .filter(|&idx| !self.nodes[idx].has_parent)
+            .map(|idx| build_tree(&self.nodes, idx, 0, locate))
+            .filter(|root| !root.options.is_empty() || !root.subcommands.is_empty())
+            .collect()
+    }
+}
+
+fn build_tree(nodes: &[Node], idx: usize, depth: usize, locate: &dyn Fn(Span) -> Provenance) -> CliCommand {
+    let mut command = nodes[idx].command.clone();
+    command.provenance = Some(locate(nodes[idx].span));
+    if depth < MAX_RESOLVE_DEPTH {
+        command.subcommands = nodes[idx]
+            .children
+            .iter()
+            .map(|&child| build_tree(nodes, child, depth + 1, locate))
+            .collect();
+    }
+    command
+}
+
+/// Number of commands and options in a tree.
+fn size(command: &CliCommand) -> usize {
+    1 + command.options.len() + command.subcommands.iter().map(size).sum::<usize>()
+}
+
+/// Whether a call only makes sense on a commander command.
+fn is_distinctive(method: &str, call: &CallExpression) -> bool {
+    let first_string = match call.arguments.first() {
+        Some(Argument::StringLiteral(s)) => Some(s.value.as_str()),
+        _ => None,
+    };
+    match method {
+        "option" | "requiredOption" => first_string.is_some_and(|f| f.starts_with('-')),
+        "addOption" | "helpOption" | "addHelpCommand" => true,
+        _ => false,
+    }
+}
+
+/// `.command("name", "description")` declares an executable subcommand and returns the parent.
+fn is_executable(call: &CallExpression) -> bool {
+    matches!(
+        call.arguments.get(1),
+        Some(Argument::StringLiteral(_) | Argument::TemplateLiteral(_))
+    )
+}
+
+fn is_function(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_) | Expression::Identifier(_)
+    ) && literal_to_json(expr).is_none()
+}
+
+fn truthy(expr: &Expression) -> bool {
+    literal_to_json(expr).is_none_or(|v| v != JsonValue::Bool(false))
+}
+
+/// Whether `{ hidden: true }` or `{ noHelp: true }` is passed.
+fn is_hidden_opts(expr: &Expression) -> bool {
+    let Expression::ObjectExpression(obj) = expr else {
+        return false;
+    };
+    obj.properties.iter().any(|prop| match prop {
+        ObjectPropertyKind::ObjectProperty(p) => {
+            matches!(property_key(&p.key), Some("hidden" | "noHelp")) && truthy(&p.value)
+        }
+        _ => false,
+    })
+}
+
+/// Parse `<name>`, `[name]` or `<name...>`.
+fn parse_argument_spec(spec: &str) -> Option<CliArgument> {
+    let (required, inner) = if let Some(inner) = spec.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+        (true, inner)
+    } else if let Some(inner) = spec.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        (false, inner)
+    } else {
+        return None;
+    };
+    let (name, variadic) = match inner.strip_suffix("...") {
+        Some(name) => (name, true),
+        None => (inner, false),
+    };
+    Some(CliArgument {
+        name: name.to_string(),
+        required,
+        variadic,
+        description: None,
+        default: None,
+        choices: Vec::new(),
+    })
+}
+
+/// Parse option flags such as `-m, --model <model>` or `--no-color`.
+fn parse_flags(flags: &str) -> CliOption {
+    let mut option = CliOption {
+        flags: flags.to_string(),
+        ..CliOption::default()
+    };
+    for token in flags.split([' ', ',', '|']).filter(|t| !t.is_empty()) {
+        if token.starts_with('<') || token.starts_with('[') {
+            option.value.get_or_insert_with(|| token.to_string());
+        } else if token.starts_with("--") {
+            option.long.get_or_insert_with(|| token.to_string());
+        } else if token.starts_with('-') {
+            option.short.get_or_insert_with(|| token.to_string());
+        }
+    }
+    option
+}
+
+/// Render `--help` output for every command in the trees.
+pub fn render_help(roots: &[CliCommand]) -> String {
+    let mut out = String::new();
+    for root in roots {
+        render_command(&mut out, root, &[]);
+    }
+    out
+}
+
+fn render_command(out: &mut String, command: &CliCommand, parents: &[&str]) {
+    let mut path: Vec<&str> = parents.to_vec();
+    path.push(if command.name.is_empty() { UNNAMED_PROGRAM } else { &command.name });
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    let _ = writeln!(out, "$ {} --help", path.join(" "));
+
+    let usage = command.usage.clone().unwrap_or_else(|| {
+        let mut parts = vec!["[options]".to_string()];
+        if !command.subcommands.is_empty() {
+            parts.push("[command]".to_string());
+        }
+        parts.extend(command.arguments.iter().map(argument_term));
+        parts.join(" ")
+    });
+    let _ = writeln!(out, "Usage: {} {}", path.join(" "), usage);
+    if let Some(description) = &command.description {
+        let _ = writeln!(out, "\n{}", description);
+    }
+
+    let arguments: Vec<(String, String)> = command
+        .arguments
+        .iter()
+        .filter(|a| a.description.is_some())
+        .map(|a| {
+            let mut extras = Vec::new();
+            if !a.choices.is_empty() {
+                extras.push(format!("choices: {}", quoted(&a.choices)));
+            }
+            if let Some(default) = &a.default {
+                extras.push(format!("default: {}", default));
+            }
+            (a.name.clone(), with_extras(a.description.clone().unwrap_or_default(), &extras))
+        })
+        .collect();
+
+    let mut options: Vec<(String, String)> = command
+        .options
+        .iter()
+        .map(|o| {
+            let mut extras = Vec::new();
+            if !o.choices.is_empty() {
+                extras.push(format!("choices: {}", quoted(&o.choices)));
+            }
+            if let Some(default) = &o.default {
+                extras.push(format!("default: {}", default));
+            }
+            if let Some(env) = &o.env {
+                extras.push(format!("env: {}", env));
+            }
+            if o.hidden {
+                extras.push("hidden".to_string());
+            }
+            (o.flags.clone(), with_extras(o.description.clone().unwrap_or_default(), &extras))
+        })
+        .collect();
+    options.push(("-h, --help".to_string(), "Display help for command".to_string()));
+
+    let commands: Vec<(String, String)> = command
+        .subcommands
+        .iter()
+        .map(|sub| {
+            let mut term = sub.name.clone();
+            if let Some(alias) = sub.aliases.first() {
+                term = format!("{}|{}", term, alias);
+            }
+            if !sub.options.is_empty() {
+                term.push_str(" [options]");
+            }
+            for argument in &sub.arguments {
+                term = format!("{} {}", term, argument_term(argument));
+            }
+            let extras = if sub.hidden { vec!["hidden".to_string()] } else { Vec::new() };
+            (term, with_extras(sub.description.clone().unwrap_or_default(), &extras))
+        })
+        .collect();
+
+    let width = arguments
+        .iter()
+        .chain(&options)
+        .chain(&commands)
+        .map(|(term, _)| term.len())
+        .max()
+        .unwrap_or(0);
+
+    for (title, items) in [("Arguments", &arguments), ("Options", &options), ("Commands", &commands)] {
+        if items.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "\n{}:", title);
+        for (term, description) in items {
+            let line = format!("  {:<width$}  {}", term, description, width = width);
+            let _ = writeln!(out, "{}", line.trim_end());
+        }
+    }
+
+    for sub in &command.subcommands {
+        render_command(out, sub, &path);
+    }
+}
+
+fn argument_term(argument: &CliArgument) -> String {
+    let dots = if argument.variadic { "..." } else { "" };
+    if argument.required {
+        format!("<{}{}>", argument.name, dots)
+    } else {
+        format!("[{}{}]", argument.name, dots)
+    }
+}
+
+fn quoted(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|v| format!("{:?}", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn with_extras(description: String, extras: &[String]) -> String {
+    if extras.is_empty() {
+        description
+    } else {
+        format!("{} ({})", description, extras.join(", ")).trim_start().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    const CLI: &str = r#"
+        async function main() {
+            let A = new Qx();
+            A.name("claude").description("Claude Code").argument("[prompt]", "Your prompt")
+                .option("-d, --debug [filter]", "Enable debug mode")
+                .option("--model <model>", "Model for the session", "sonnet")
+                .addOption(new Zk("--max-turns <turns>", "Maximum turns").argParser(Number).hideHelp())
+                .action(async (P, O) => {});
+            let B = A.command("mcp").description("Configure MCP servers");
+            B.command("add <name> <commandOrUrl> [args...]").alias("a")
+                .description("Add a server")
+                .addOption(new Zk("-s, --scope <scope>", "Scope").choices(["local", "user"]).default("local"))
+                .action(async () => {});
+            A.command("migrate-installer", { hidden: !0 }).action(() => {});
+            await A.parseAsync(process.argv);
+        }
+    "#;
+
+    fn extract(code: &str) -> Vec<CliCommand> {
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+        CommanderExtractor::new(&analyzer, &symbol_table).extract().unwrap()
+    }
+
+    #[test]
+    fn test_reconstructs_command_tree() {
+        let roots = extract(CLI);
+        assert_eq!(roots.len(), 1);
+
+        let claude = &roots[0];
+        assert_eq!(claude.name, "claude");
+        assert!(claude.has_action);
+        assert_eq!(claude.arguments[0].name, "prompt");
+        assert!(!claude.arguments[0].required);
+
+        let model = claude.options.iter().find(|o| o.long.as_deref() == Some("--model")).unwrap();
+        assert_eq!(model.value.as_deref(), Some("<model>"));
+        assert_eq!(model.default, Some(JsonValue::from("sonnet")));
+        let max_turns = claude.options.iter().find(|o| o.long.as_deref() == Some("--max-turns")).unwrap();
+        assert!(max_turns.hidden);
+
+        let names: Vec<_> = claude.subcommands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["mcp", "migrate-installer"]);
+        assert!(claude.subcommands[1].hidden);
+
+        let add = &claude.subcommands[0].subcommands[0];
+        assert_eq!(add.name, "add");
+        assert_eq!(add.aliases, ["a"]);
+        assert_eq!(add.arguments.len(), 3);
+        assert!(add.arguments[2].variadic);
+        assert_eq!(add.options[0].choices, ["local", "user"]);
+
+        let line_of = |needle: &str| CLI[..CLI.find(needle).unwrap()].matches('\n').count() + 1;
+        let line = |command: &CliCommand| command.provenance.as_ref().unwrap().span.line;
+        assert_eq!(line(claude), line_of("new Qx()"));
+        assert_eq!(line(add), line_of("B.command(\"add"));
+    }
+
+    #[test]
+    fn test_render_help() {
+        let help = render_help(&extract(CLI));
+
+        assert!(help.starts_with("$ claude --help\nUsage: claude [options] [command] [prompt]\n"));
+        assert!(help.contains("  --model <model>       Model for the session (default: \"sonnet\")\n"));
+        assert!(help.contains("(hidden)"));
+        assert!(help.contains("\n$ claude mcp add --help\nUsage: claude mcp add [options] <name> <commandOrUrl> [args...]\n"));
+        assert!(help.contains("  add|a [options] <name> <commandOrUrl> [args...]  Add a server\n"));
+    }
+
+    #[test]
+    fn test_root_name_resolution() {
+        let code = r#"
+            var BIN = "claude-dev";
+            var A = new Qx(BIN);
+            A.option("-p, --print", "Print response and exit");
+            program.name(BIN_NAME()).option("--verbose", "Verbose output");
+            program.command("doctor").description("Check the installation");
+            function BIN_NAME() { return "cc"; }
+            var C = new Qx();
+            C.option("-c, --continue", "Continue the most recent conversation");
+        "#;
+        let roots = extract(code);
+
+        let names: Vec<_> = roots.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["cc", "claude-dev", ""]);
+
+        let help = render_help(&roots[2..]);
+        assert!(help.starts_with("$ <program> --help\nUsage: <program> [options]\n"));
+    }
+}
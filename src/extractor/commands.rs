@@ -0,0 +1,272 @@
+//! Slash command extraction.
+//!
+//! Built-in commands such as `/compact` or `/review` are object literals with
+//! a name, description and a `type` of `local`, `local-jsx` or `prompt`.
+//! Prompt commands build their prompt in `getPromptForCommand`.
+
+use crate::analyzer::{Analyzer, HasProvenance, ObjectExpressionInfo, Provenance, SymbolTable};
+use crate::extractor::members::{property_key, ObjectMembers};
+use crate::extractor::tools::{ToolFlag, INPUT_SCHEMA_MEMBERS};
+use crate::Result;
+use oxc_ast::ast::*;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, trace};
+
+/// Values of a command's `type` member.
+pub(crate) const COMMAND_TYPES: &[&str] = &["local", "local-jsx", "prompt"];
+
+/// Members that characterise a command object in the bundle.
+const COMMAND_MEMBERS: &[&str] = &[
+    "aliases",
+    "argumentHint",
+    "getPromptForCommand",
+    "progressMessage",
+    "isHidden",
+    "userFacingName",
+];
+
+/// A built-in slash command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlashCommand {
+    /// Command name, without the leading slash.
+    pub name: String,
+
+    /// One-line description shown in the command picker.
+    pub description: String,
+
+    /// Alternative names.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// Command kind: `local`, `local-jsx` or `prompt`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub command_type: Option<String>,
+
+    /// Hint for the command's arguments, e.g. `[instructions]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+
+    /// Whether the command is hidden from the picker.
+    pub is_hidden: ToolFlag,
+
+    /// Whether the command is enabled.
+    pub is_enabled: ToolFlag,
+
+    /// Status text shown while a prompt command runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress_message: Option<String>,
+
+    /// Prompt text for prompt-based commands.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+
+    /// Where the command object was found in the bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for SlashCommand {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+}
+
+/// Extractor for slash command definitions.
+pub struct CommandExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+}
+
+impl<'a> CommandExtractor<'a> {
+    /// Create a new command extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        Self {
+            analyzer,
+            symbol_table,
+        }
+    }
+
+    /// Extract all slash commands, sorted by name.
+    pub fn extract(&self) -> Result<Vec<SlashCommand>> {
+        debug!("Extracting slash commands");
+
+        let mut commands: Vec<SlashCommand> = Vec::new();
+        for obj in &self.analyzer.find_object_expressions() {
+            let Some(command) = self.extract_command_from_object(obj) else {
+                continue;
+            };
+
+            // Prefer the definition that carries a prompt
+            match commands.iter_mut().find(|c| c.name == command.name) {
+                Some(existing) if existing.prompt.is_none() && command.prompt.is_some() => {
+                    *existing = command
+                }
+                Some(_) => {}
+                None => commands.push(command),
+            }
+        }
+
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        debug!("Extracted {} slash commands", commands.len());
+        Ok(commands)
+    }
+
+    /// Check if a set of members matches the command definition pattern.
+    ///
+    /// A command needs a `name` and `description` plus either a known `type`
+    /// or at least two characteristic members. Tools are excluded.
+    fn is_command_object(&self, members: &ObjectMembers<'_, 'a>) -> bool {
+        if !members.contains("name") || !members.contains("description") {
+            return false;
+        }
+        if INPUT_SCHEMA_MEMBERS.iter().any(|k| members.contains(k)) {
+            return false;
+        }
+
+        let has_type = members
+            .string("type")
+            .is_some_and(|t| COMMAND_TYPES.contains(&t.as_str()));
+        let characteristic = COMMAND_MEMBERS.iter().filter(|k| members.contains(k)).count();
+
+        has_type || characteristic >= 2
+    }
+
+    /// Extract a command definition from an object.
+    fn extract_command_from_object(&self, obj: &ObjectExpressionInfo<'a>) -> Option<SlashCommand> {
+        let has_spread = obj
+            .ast_object
+            .properties
+            .iter()
+            .any(|p| matches!(p, ObjectPropertyKind::SpreadProperty(_)));
+        if !has_spread && !obj.properties.iter().any(|p| p.key == Some("description")) {
+            return None;
+        }
+
+        let source_text = self.analyzer.program().source_text;
+        let members = ObjectMembers::collect(self.symbol_table, source_text, obj.ast_object);
+        if !self.is_command_object(&members) {
+            return None;
+        }
+
+        let name = members.string("name")?;
+        if !is_plausible_command_name(&name) {
+            trace!("Rejecting command candidate with implausible name: {:?}", name);
+            return None;
+        }
+
+        trace!("Extracting command: {}", name);
+
+        Some(SlashCommand {
+            description: members.string("description").unwrap_or_default(),
+            aliases: members.strings("aliases"),
+            command_type: members.string("type"),
+            argument_hint: members.string("argumentHint"),
+            is_hidden: members.flag("isHidden", false),
+            is_enabled: members.flag("isEnabled", true),
+            progress_message: members.string("progressMessage"),
+            prompt: self.prompt_text(&members),
+            provenance: Some(self.analyzer.provenance(obj.ast_object.span)),
+            name,
+        })
+    }
+
+    /// Recover the prompt a command sends, from `getPromptForCommand`'s return
+    /// value: either a string or an array of `{ type: "text", text }` blocks.
+    fn prompt_text(&self, members: &ObjectMembers<'_, 'a>) -> Option<String> {
+        let value = members.value("getPromptForCommand")?;
+        let value = match value {
+            Expression::AwaitExpression(await_expr) => &await_expr.argument,
+            value => value,
+        };
+
+        let text = match value {
+            Expression::ArrayExpression(arr) => arr
+                .elements
+                .iter()
+                .filter_map(|e| match e.as_expression()? {
+                    Expression::ObjectExpression(block) => self.text_block(block, members),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            value => members.resolve_string(value)?,
+        };
+
+        (!text.trim().is_empty()).then_some(text)
+    }
+
+    /// Get the `text` of a content block.
+    fn text_block(&self, block: &ObjectExpression, members: &ObjectMembers<'_, 'a>) -> Option<String> {
+        block.properties.iter().find_map(|prop| match prop {
+            ObjectPropertyKind::ObjectProperty(p) if property_key(&p.key) == Some("text") => {
+                members.resolve_string(&p.value)
+            }
+            _ => None,
+        })
+    }
+}
+
+/// Command names look like `compact`, `pr-comments` or `mcp:server`.
+fn is_plausible_command_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_extracts_commands() {
+        let code = r#"
+            var REVIEW = "You are an expert code reviewer.";
+            var review = {
+                type: "prompt",
+                name: "review",
+                description: "Review a pull request",
+                progressMessage: "reviewing pull request",
+                isEnabled: () => !0,
+                isHidden: !1,
+                async getPromptForCommand(A) {
+                    return [{ type: "text", text: REVIEW }, { type: "text", text: `PR: ${A}` }];
+                },
+                userFacingName() { return "review" }
+            };
+            var clear = {
+                type: "local",
+                name: "clear",
+                description: "Clear conversation history",
+                aliases: ["reset", "new"],
+                argumentHint: "",
+                async call() {}
+            };
+            var tool = { name: "Bash", description: "Run", inputSchema: k.object({}) };
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let commands = CommandExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+        let names: Vec<_> = commands.iter().map(|c| c.name.as_str()).collect();
+
+        assert_eq!(names, ["clear", "review"]);
+        assert_eq!(commands[0].aliases, ["reset", "new"]);
+        let review = &commands[1];
+        assert_eq!(review.command_type.as_deref(), Some("prompt"));
+        assert_eq!(review.is_hidden, ToolFlag::Constant(false));
+        let provenance = review.provenance.as_ref().unwrap();
+        assert_eq!((provenance.span.line, provenance.binding.as_deref()), (3, Some("review")));
+        assert_eq!(
+            review.prompt.as_deref(),
+            Some("You are an expert code reviewer.\n\nPR: ${...}")
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Configuration value extraction.
 
-use crate::analyzer::{Analyzer, StringLiteralInfo};
+use crate::analyzer::{Analyzer, HasProvenance, Provenance, StringLiteralInfo};
+use crate::rules::{Rules, Subject};
 use crate::Result;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
@@ -19,6 +20,16 @@ pub struct ConfigValue {
 
     /// Category.
     pub category: ConfigCategory,
+
+    /// Where the value was found in the bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for ConfigValue {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
 }
 
 /// Type of configuration value.
@@ -46,12 +57,22 @@ pub enum ConfigCategory {
 /// Extractor for configuration values.
 pub struct ConfigExtractor<'a> {
     analyzer: &'a Analyzer<'a>,
+    rules: &'a Rules,
 }
 
 impl<'a> ConfigExtractor<'a> {
     /// Create a new config extractor.
     pub fn new(analyzer: &'a Analyzer<'a>) -> Self {
-        Self { analyzer }
+        Self {
+            analyzer,
+            rules: Rules::bundled(),
+        }
+    }
+
+    /// Use these extraction rules instead of the bundled ones.
+    pub fn with_rules(mut self, rules: &'a Rules) -> Self {
+        self.rules = rules;
+        self
     }
 
     /// Extract all configuration values.
@@ -69,6 +90,7 @@ impl<'a> ConfigExtractor<'a> {
                     value: literal.value.to_string(),
                     value_type: ConfigType::String,
                     category,
+                    provenance: Some(self.analyzer.provenance(literal.span)),
                 };
                 configs.push(config);
             }
@@ -79,50 +101,13 @@ impl<'a> ConfigExtractor<'a> {
     }
 
     /// Check if a string literal is likely a config value.
-    fn is_likely_config(&self, literal: &StringLiteralInfo) -> bool {
-        let value = literal.value;
-
-        // Configuration indicators
-        let config_patterns = [
-            "claude-sonnet",
-            "claude-opus",
-            "anthropic",
-            ".com/",
-            "http://",
-            "https://",
-            "/api/",
-            "VERSION",
-            "CLAUDE_",
-            "API_KEY",
-        ];
-
-        // Should be reasonably short (not a prompt)
-        if literal.length > 200 {
-            return false;
-        }
-
-        config_patterns.iter().any(|&pattern| value.contains(pattern))
+    fn is_likely_config(&self, literal: &StringLiteralInfo<'a>) -> bool {
+        self.rules.config.is_config(&Subject::literal(self.analyzer, literal))
     }
 
     /// Categorize the configuration value.
-    fn categorize_config(&self, literal: &StringLiteralInfo) -> ConfigCategory {
-        let value = literal.value;
-
-        if value.contains("sonnet") || value.contains("opus") || value.contains("haiku") {
-            ConfigCategory::Model
-        } else if value.contains("/api/") || value.contains("anthropic.com") {
-            ConfigCategory::API
-        } else if value.contains("telemetry") || value.contains("metric") {
-            ConfigCategory::Telemetry
-        } else if value.contains("/") || value.contains("\\") {
-            ConfigCategory::Path
-        } else if value.contains("timeout") || value.contains("ms") {
-            ConfigCategory::Timeout
-        } else if value.contains("feature") || value.contains("flag") {
-            ConfigCategory::Feature
-        } else {
-            ConfigCategory::Other
-        }
+    fn categorize_config(&self, literal: &StringLiteralInfo<'a>) -> ConfigCategory {
+        self.rules.config.categorize(&Subject::literal(self.analyzer, literal))
     }
 }
 
@@ -0,0 +1,693 @@
+//! Error message catalog.
+//!
+//! Every `throw`, `Promise.reject(…)` and error-reporting call such as
+//! `console.error(…)` or `logError(…)` is recorded with the error class, the
+//! message as a template (constants resolved, everything else left as
+//! `${…}`), the enclosing function and the `if`, ternary, `&&`/`||`, `case`
+//! and `catch` tests guarding it within that function. Rethrows of a caught
+//! value are skipped. [`ErrorCatalog::search`] finds the sites that could
+//! have produced a message a user saw.
+
+use crate::analyzer::{Analyzer, SymbolTable};
+use crate::extractor::api_requests::SourceSpan;
+use crate::extractor::members::callee_name;
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{debug, trace};
+
+/// Maximum length of a condition or placeholder label.
+const MAX_LABEL_LEN: usize = 80;
+
+/// Minimum static text for a template to match a message on its own.
+const MIN_MATCH_TEXT: usize = 4;
+
+/// Calls that write to stderr without "error" in their name.
+const REPORT_CALLEES: &[&str] = &["console.warn", "process.stderr.write"];
+
+/// How an error leaves its site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorSiteKind {
+    /// `throw …`
+    Throw,
+    /// `Promise.reject(…)`
+    Reject,
+    /// A call that formats or logs an error.
+    Report,
+}
+
+/// A place where an error is raised or reported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorSite {
+    /// How the error leaves the site.
+    pub kind: ErrorSiteKind,
+
+    /// Error class, e.g. `Error` or `AbortError`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+
+    /// Message template with unresolved parts as `${…}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Reporting function, for [`ErrorSiteKind::Report`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callee: Option<String>,
+
+    /// Innermost named function around the site.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+
+    /// Tests that must hold to reach the site, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<String>,
+
+    /// Location of the throw or call.
+    pub span: SourceSpan,
+}
+
+impl ErrorSite {
+    /// Whether a message could have come from this site: the template
+    /// matches somewhere in `text`, or `text` occurs in the message, class or
+    /// function (case-insensitive).
+    pub fn matches(&self, text: &str) -> bool {
+        let needle = text.to_lowercase();
+        let contains = |field: &Option<String>| field.as_ref().is_some_and(|f| f.to_lowercase().contains(&needle));
+        if contains(&self.message) || contains(&self.class) || contains(&self.function) {
+            return true;
+        }
+        self.message
+            .as_deref()
+            .and_then(template_regex)
+            .is_some_and(|re| re.is_match(text))
+    }
+}
+
+/// Error sites in source order, with a count per class.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorCatalog {
+    /// Every site found.
+    pub sites: Vec<ErrorSite>,
+
+    /// Sites per error class.
+    pub classes: BTreeMap<String, usize>,
+}
+
+impl ErrorCatalog {
+    /// Sites that could have produced `text`.
+    pub fn search(&self, text: &str) -> Vec<&ErrorSite> {
+        self.sites.iter().filter(|site| site.matches(text)).collect()
+    }
+}
+
+/// Compile a message template into a regex with `.*?` for each placeholder.
+fn template_regex(template: &str) -> Option<Regex> {
+    let mut pattern = String::from("(?is)");
+    let mut static_len = 0;
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        pattern.push_str(&regex::escape(&rest[..start]));
+        static_len += rest[..start].trim().len();
+        pattern.push_str(".*?");
+
+        let mut depth = 0;
+        let end = rest[start + 2..].char_indices().find_map(|(i, c)| match c {
+            '{' => {
+                depth += 1;
+                None
+            }
+            '}' if depth == 0 => Some(start + 2 + i + 1),
+            '}' => {
+                depth -= 1;
+                None
+            }
+            _ => None,
+        });
+        rest = &rest[end.unwrap_or(rest.len())..];
+    }
+    pattern.push_str(&regex::escape(rest));
+    static_len += rest.trim().len();
+
+    if static_len < MIN_MATCH_TEXT {
+        return None;
+    }
+    Regex::new(&pattern).ok()
+}
+
+/// Render the catalog as Markdown, one table per error class.
+pub fn render_markdown(catalog: &ErrorCatalog) -> String {
+    let mut out = String::from("# Error Catalog\n");
+
+    let mut groups: BTreeMap<&str, Vec<&ErrorSite>> = BTreeMap::new();
+    for site in &catalog.sites {
+        groups.entry(site.class.as_deref().unwrap_or("(none)")).or_default().push(site);
+    }
+
+    let cell = |s: &str| s.replace('|', "\\|").replace('\n', "\\n");
+    for (class, sites) in groups {
+        out.push_str(&format!("\n## {} ({})\n\n", class, sites.len()));
+        out.push_str("| Line | Kind | Message | Function | Conditions |\n");
+        out.push_str("|------|------|---------|----------|------------|\n");
+        for site in sites {
+            out.push_str(&format!(
+                "| {} | {:?} | {} | {} | {} |\n",
+                site.span.line,
+                site.kind,
+                site.message.as_deref().map(|m| format!("`{}`", cell(m))).unwrap_or_default(),
+                site.function.as_deref().map(|f| format!("`{}`", f)).unwrap_or_default(),
+                site.conditions.iter().map(|c| format!("`{}`", cell(c))).collect::<Vec<_>>().join(", "),
+            ));
+        }
+    }
+
+    out
+}
+
+/// Extractor for the error catalog.
+pub struct ErrorExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+    source_text: &'a str,
+}
+
+impl<'a> ErrorExtractor<'a> {
+    /// Create a new error extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        Self {
+            analyzer,
+            symbol_table,
+            source_text: analyzer.program().source_text,
+        }
+    }
+
+    /// Collect every error site in source order.
+    pub fn extract(&self) -> Result<ErrorCatalog> {
+        debug!("Extracting error sites");
+
+        let mut collector = SiteCollector {
+            extractor: self,
+            guards: Vec::new(),
+            sites: Vec::new(),
+        };
+        collector.visit_statements(&self.analyzer.program().body);
+
+        let mut catalog = ErrorCatalog {
+            sites: collector.sites,
+            classes: BTreeMap::new(),
+        };
+        for class in catalog.sites.iter().filter_map(|s| s.class.as_ref()) {
+            *catalog.classes.entry(class.clone()).or_default() += 1;
+        }
+
+        debug!("Found {} error sites in {} classes", catalog.sites.len(), catalog.classes.len());
+        Ok(catalog)
+    }
+
+    /// Class and message of a thrown or rejected value; `None` for rethrows.
+    fn thrown(&self, expr: &Expression) -> Option<(Option<String>, Option<String>)> {
+        match expr.without_parentheses() {
+            Expression::NewExpression(new_expr) => Some((
+                callee_name(&new_expr.callee),
+                new_expr.arguments.first().and_then(|a| a.as_expression()).map(|m| self.message(m)),
+            )),
+            Expression::CallExpression(call) => {
+                let name = callee_name(&call.callee);
+                let first = call.arguments.first().and_then(|a| a.as_expression());
+                // `Object.assign(new Error(…), { code })` keeps the inner error
+                if name.as_deref() == Some("Object.assign") {
+                    return first.and_then(|e| self.thrown(e));
+                }
+                let class = name.filter(|n| is_class_name(n));
+                Some((class, first.map(|m| self.message(m))))
+            }
+            Expression::Identifier(_)
+            | Expression::StaticMemberExpression(_)
+            | Expression::ComputedMemberExpression(_) => None,
+            Expression::SequenceExpression(seq) => self.thrown(seq.expressions.last()?),
+            other => Some((None, Some(self.message(other)))),
+        }
+    }
+
+    /// Render a message expression as a template.
+    fn message(&self, expr: &Expression) -> String {
+        match expr.without_parentheses() {
+            Expression::StringLiteral(s) => s.value.to_string(),
+            Expression::TemplateLiteral(tmpl) => {
+                let mut out = String::new();
+                for (i, quasi) in tmpl.quasis.iter().enumerate() {
+                    out.push_str(quasi.value.raw.as_str());
+                    if let Some(expr) = tmpl.expressions.get(i) {
+                        out.push_str(&self.message(expr));
+                    }
+                }
+                out
+            }
+            Expression::BinaryExpression(binary) if binary.operator == BinaryOperator::Addition => {
+                format!("{}{}", self.message(&binary.left), self.message(&binary.right))
+            }
+            Expression::NumericLiteral(n) => n.value.to_string(),
+            Expression::Identifier(id) => match self.symbol_table.get_string_value(id.name.as_str()) {
+                Some(value) if !value.contains("${...}") => value,
+                _ => self.placeholder(expr.span()),
+            },
+            other => self.placeholder(other.span()),
+        }
+    }
+
+    fn placeholder(&self, span: Span) -> String {
+        format!("${{{}}}", label(&self.source_text[span.start as usize..span.end as usize]))
+    }
+
+    fn label(&self, span: Span) -> String {
+        label(&self.source_text[span.start as usize..span.end as usize])
+    }
+
+    /// Label for the negation of a test.
+    fn negated(&self, test: &Expression) -> String {
+        match test.without_parentheses() {
+            Expression::UnaryExpression(u) if u.operator == UnaryOperator::LogicalNot => self.label(u.argument.span()),
+            Expression::Identifier(_)
+            | Expression::StaticMemberExpression(_)
+            | Expression::CallExpression(_) => format!("!{}", self.label(test.span())),
+            _ => format!("!({})", self.label(test.span())),
+        }
+    }
+
+    fn site(&self, kind: ErrorSiteKind, span: Span, class: Option<String>, message: Option<String>) -> ErrorSite {
+        ErrorSite {
+            kind,
+            class,
+            message,
+            callee: None,
+            function: self.analyzer.context().function_at(span).map(str::to_string),
+            conditions: Vec::new(),
+            span: SourceSpan::locate(self.analyzer.line_index(), span),
+        }
+    }
+}
+
+/// Constructor-like names: `Error`, `errors.AbortError`.
+fn is_class_name(name: &str) -> bool {
+    name.rsplit('.').next().is_some_and(|last| last.starts_with(|c: char| c.is_ascii_uppercase()))
+}
+
+/// Calls that format or log an error: `console.error`, `logError`, ….
+fn is_report_callee(name: &str) -> bool {
+    if REPORT_CALLEES.contains(&name) {
+        return true;
+    }
+    let last = name.rsplit('.').next().unwrap_or(name);
+    !is_class_name(last) && last.to_ascii_lowercase().contains("error")
+}
+
+/// Collapse whitespace and truncate source for use as a label.
+fn label(source: &str) -> String {
+    let label = source.split_whitespace().collect::<Vec<_>>().join(" ");
+    if label.chars().count() <= MAX_LABEL_LEN {
+        return label;
+    }
+    let truncated: String = label.chars().take(MAX_LABEL_LEN).collect();
+    format!("{}…", truncated)
+}
+
+/// Walks the AST keeping the tests that guard the current position.
+struct SiteCollector<'e, 'a> {
+    extractor: &'e ErrorExtractor<'a>,
+    guards: Vec<String>,
+    sites: Vec<ErrorSite>,
+}
+
+impl SiteCollector<'_, '_> {
+    fn push(&mut self, mut site: ErrorSite) {
+        site.conditions = self.guards.clone();
+        trace!("{:?} site at line {}: {:?}", site.kind, site.span.line, site.message);
+        self.sites.push(site);
+    }
+
+    /// Visit with an extra guard in effect.
+    fn guarded(&mut self, guard: String, visit: impl FnOnce(&mut Self)) {
+        self.guards.push(guard);
+        visit(self);
+        self.guards.pop();
+    }
+
+    fn visit_statements(&mut self, stmts: &[Statement<'_>]) {
+        for stmt in stmts {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement<'_>) {
+        match stmt {
+            Statement::VariableDeclaration(decl) => self.visit_declaration(decl),
+            Statement::ExpressionStatement(expr_stmt) => self.visit_expression(&expr_stmt.expression),
+            Statement::ReturnStatement(ret) => {
+                if let Some(ref arg) = ret.argument {
+                    self.visit_expression(arg);
+                }
+            }
+            Statement::ThrowStatement(throw) => {
+                if let Some((class, message)) = self.extractor.thrown(&throw.argument) {
+                    self.push(self.extractor.site(ErrorSiteKind::Throw, throw.span, class, message));
+                }
+                self.visit_expression(&throw.argument);
+            }
+            Statement::BlockStatement(block) => self.visit_statements(&block.body),
+            Statement::IfStatement(if_stmt) => {
+                self.visit_expression(&if_stmt.test);
+                let test = self.extractor.label(if_stmt.test.span());
+                self.guarded(test, |this| this.visit_statement(&if_stmt.consequent));
+                if let Some(ref alt) = if_stmt.alternate {
+                    let negated = self.extractor.negated(&if_stmt.test);
+                    self.guarded(negated, |this| this.visit_statement(alt));
+                }
+            }
+            Statement::FunctionDeclaration(func) => self.visit_function(func),
+            Statement::ClassDeclaration(class) => self.visit_class(class),
+            Statement::TryStatement(try_stmt) => {
+                self.visit_statements(&try_stmt.block.body);
+                if let Some(ref handler) = try_stmt.handler {
+                    let guard = match &handler.param {
+                        Some(param) => format!("catch ({})", self.extractor.label(param.pattern.span())),
+                        None => "catch".to_string(),
+                    };
+                    self.guarded(guard, |this| this.visit_statements(&handler.body.body));
+                }
+                if let Some(ref finalizer) = try_stmt.finalizer {
+                    self.visit_statements(&finalizer.body);
+                }
+            }
+            Statement::SwitchStatement(switch) => {
+                self.visit_expression(&switch.discriminant);
+                let discriminant = self.extractor.label(switch.discriminant.span());
+                for case in &switch.cases {
+                    let guard = match &case.test {
+                        Some(test) => format!("{} === {}", discriminant, self.extractor.label(test.span())),
+                        None => format!("{} (default)", discriminant),
+                    };
+                    self.guarded(guard, |this| this.visit_statements(&case.consequent));
+                }
+            }
+            Statement::ForStatement(for_stmt) => {
+                if let Some(ForStatementInit::VariableDeclaration(decl)) = &for_stmt.init {
+                    self.visit_declaration(decl);
+                }
+                self.visit_statement(&for_stmt.body);
+            }
+            Statement::ForInStatement(for_in) => self.visit_statement(&for_in.body),
+            Statement::ForOfStatement(for_of) => {
+                self.visit_expression(&for_of.right);
+                self.visit_statement(&for_of.body);
+            }
+            Statement::WhileStatement(while_stmt) => self.visit_statement(&while_stmt.body),
+            Statement::DoWhileStatement(do_while) => self.visit_statement(&do_while.body),
+            Statement::LabeledStatement(labeled) => self.visit_statement(&labeled.body),
+            Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                Some(Declaration::VariableDeclaration(decl)) => self.visit_declaration(decl),
+                Some(Declaration::FunctionDeclaration(func)) => self.visit_function(func),
+                Some(Declaration::ClassDeclaration(class)) => self.visit_class(class),
+                _ => {}
+            },
+            Statement::ExportDefaultDeclaration(export) => match &export.declaration {
+                ExportDefaultDeclarationKind::FunctionDeclaration(func) => self.visit_function(func),
+                ExportDefaultDeclarationKind::ClassDeclaration(class) => self.visit_class(class),
+                other => {
+                    if let Some(expr) = other.as_expression() {
+                        self.visit_expression(expr);
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn visit_declaration(&mut self, decl: &VariableDeclaration<'_>) {
+        for declarator in &decl.declarations {
+            if let Some(ref init) = declarator.init {
+                self.visit_expression(init);
+            }
+        }
+    }
+
+    /// Guards don't carry across function boundaries.
+    fn visit_body(&mut self, stmts: &[Statement<'_>]) {
+        let outer = std::mem::take(&mut self.guards);
+        self.visit_statements(stmts);
+        self.guards = outer;
+    }
+
+    fn visit_function(&mut self, func: &Function<'_>) {
+        if let Some(ref body) = func.body {
+            self.visit_body(&body.statements);
+        }
+    }
+
+    fn visit_class(&mut self, class: &Class<'_>) {
+        if let Some(ref super_class) = class.super_class {
+            self.visit_expression(super_class);
+        }
+        for element in &class.body.body {
+            match element {
+                ClassElement::MethodDefinition(method) => self.visit_function(&method.value),
+                ClassElement::PropertyDefinition(prop) => {
+                    if let Some(ref value) = prop.value {
+                        self.visit_expression(value);
+                    }
+                }
+                ClassElement::StaticBlock(block) => self.visit_body(&block.body),
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_arguments(&mut self, arguments: &[Argument<'_>]) {
+        for arg in arguments {
+            if let Argument::SpreadElement(spread) = arg {
+                self.visit_expression(&spread.argument);
+            } else if let Some(expr) = arg.as_expression() {
+                self.visit_expression(expr);
+            }
+        }
+    }
+
+    /// Record `Promise.reject(…)` and reporting calls.
+    fn visit_call(&mut self, call: &CallExpression<'_>) {
+        let Some(name) = callee_name(&call.callee) else {
+            return;
+        };
+        let Some(first) = call.arguments.first().and_then(|a| a.as_expression()) else {
+            return;
+        };
+        let extractor = self.extractor;
+
+        if name == "Promise.reject" {
+            if let Some((class, message)) = extractor.thrown(first) {
+                self.push(extractor.site(ErrorSiteKind::Reject, call.span, class, message));
+            }
+        } else if is_report_callee(&name) {
+            let (class, message) = match first.without_parentheses() {
+                Expression::NewExpression(_) => extractor.thrown(first).unwrap_or_default(),
+                other => (None, Some(extractor.message(other))),
+            };
+            let mut site = extractor.site(ErrorSiteKind::Report, call.span, class, message);
+            site.callee = Some(name);
+            self.push(site);
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'_>) {
+        match expr {
+            Expression::CallExpression(call) => {
+                self.visit_call(call);
+                self.visit_expression(&call.callee);
+                self.visit_arguments(&call.arguments);
+            }
+            Expression::NewExpression(new_expr) => {
+                self.visit_expression(&new_expr.callee);
+                self.visit_arguments(&new_expr.arguments);
+            }
+            Expression::StaticMemberExpression(member) => self.visit_expression(&member.object),
+            Expression::ComputedMemberExpression(member) => {
+                self.visit_expression(&member.object);
+                self.visit_expression(&member.expression);
+            }
+            Expression::ChainExpression(chain) => {
+                if let ChainElement::CallExpression(call) = &chain.expression {
+                    self.visit_call(call);
+                    self.visit_expression(&call.callee);
+                    self.visit_arguments(&call.arguments);
+                }
+            }
+            Expression::ObjectExpression(obj) => {
+                for prop in &obj.properties {
+                    match prop {
+                        ObjectPropertyKind::ObjectProperty(p) => self.visit_expression(&p.value),
+                        ObjectPropertyKind::SpreadProperty(p) => self.visit_expression(&p.argument),
+                    }
+                }
+            }
+            Expression::ArrayExpression(arr) => {
+                for elem in &arr.elements {
+                    if let ArrayExpressionElement::SpreadElement(spread) = elem {
+                        self.visit_expression(&spread.argument);
+                    } else if let Some(expr) = elem.as_expression() {
+                        self.visit_expression(expr);
+                    }
+                }
+            }
+            Expression::TemplateLiteral(tmpl) => {
+                for expr in &tmpl.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::FunctionExpression(func) => self.visit_function(func),
+            Expression::ArrowFunctionExpression(arrow) => self.visit_body(&arrow.body.statements),
+            Expression::ClassExpression(class) => self.visit_class(class),
+            Expression::AssignmentExpression(assign) => self.visit_expression(&assign.right),
+            Expression::BinaryExpression(binary) => {
+                self.visit_expression(&binary.left);
+                self.visit_expression(&binary.right);
+            }
+            Expression::LogicalExpression(logical) => {
+                self.visit_expression(&logical.left);
+                let guard = match logical.operator {
+                    LogicalOperator::And => self.extractor.label(logical.left.span()),
+                    LogicalOperator::Or => self.extractor.negated(&logical.left),
+                    LogicalOperator::Coalesce => format!("{} == null", self.extractor.label(logical.left.span())),
+                };
+                self.guarded(guard, |this| this.visit_expression(&logical.right));
+            }
+            Expression::ConditionalExpression(cond) => {
+                self.visit_expression(&cond.test);
+                let test = self.extractor.label(cond.test.span());
+                self.guarded(test, |this| this.visit_expression(&cond.consequent));
+                let negated = self.extractor.negated(&cond.test);
+                self.guarded(negated, |this| this.visit_expression(&cond.alternate));
+            }
+            Expression::SequenceExpression(seq) => {
+                for expr in &seq.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::UnaryExpression(unary) => self.visit_expression(&unary.argument),
+            Expression::AwaitExpression(await_expr) => self.visit_expression(&await_expr.argument),
+            Expression::ParenthesizedExpression(paren) => self.visit_expression(&paren.expression),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_extracts_error_sites() {
+        let code = r#"
+            var PREFIX = "Invalid model";
+            class AbortError extends Error {}
+            function validateModel(A, Q) {
+                if (!A.model) {
+                    throw new Error(`${PREFIX}: ${A.model}. Run /model to pick one.`);
+                } else if (Q.signal.aborted) {
+                    throw new AbortError("Request was aborted");
+                }
+                try {
+                    connect(A);
+                } catch (e) {
+                    console.error("Failed to connect: " + e.message);
+                    throw e;
+                }
+                return A.retry ? Promise.reject(Object.assign(new Error("Retry limit reached"), { code: 429 })) : A;
+            }
+        "#;
+
+        let catalog = extract(code);
+        let sites: Vec<_> = catalog
+            .sites
+            .iter()
+            .map(|s| (s.kind, s.class.as_deref(), s.message.as_deref()))
+            .collect();
+        assert_eq!(
+            sites,
+            [
+                (ErrorSiteKind::Throw, Some("Error"), Some("Invalid model: ${A.model}. Run /model to pick one.")),
+                (ErrorSiteKind::Throw, Some("AbortError"), Some("Request was aborted")),
+                (ErrorSiteKind::Report, None, Some("Failed to connect: ${e.message}")),
+                (ErrorSiteKind::Reject, Some("Error"), Some("Retry limit reached")),
+            ]
+        );
+
+        let first = &catalog.sites[0];
+        assert_eq!(first.function.as_deref(), Some("validateModel"));
+        assert_eq!(first.conditions, ["!A.model"]);
+        assert_eq!(catalog.sites[1].conditions, ["A.model", "Q.signal.aborted"]);
+        assert_eq!(catalog.sites[2].conditions, ["catch (e)"]);
+        assert_eq!(catalog.sites[2].callee.as_deref(), Some("console.error"));
+        assert_eq!(catalog.sites[3].conditions, ["A.retry"]);
+        assert_eq!(catalog.classes["Error"], 2);
+
+        let found = catalog.search("Invalid model: claude-foo. Run /model to pick one.");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].span.line, 6);
+        assert_eq!(catalog.search("aborted").len(), 1);
+    }
+
+    fn extract(code: &str) -> ErrorCatalog {
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+        ErrorExtractor::new(&analyzer, &symbol_table).extract().unwrap()
+    }
+
+    #[test]
+    fn test_unguarded_throw_has_no_conditions() {
+        let catalog = extract(
+            r#"
+            throw new TypeError("Top-level failure");
+            function fail() {
+                throw new Error("Always fails");
+            }
+        "#,
+        );
+        let sites: Vec<_> = catalog
+            .sites
+            .iter()
+            .map(|s| (s.class.as_deref(), s.function.as_deref(), s.conditions.len()))
+            .collect();
+        assert_eq!(sites, [(Some("TypeError"), None, 0), (Some("Error"), Some("fail"), 0)]);
+    }
+
+    #[test]
+    fn test_skips_rethrown_errors() {
+        let catalog = extract(
+            r#"
+            function load(path) {
+                try {
+                    return read(path);
+                } catch (e) {
+                    if (e.code === "ENOENT") throw e;
+                    if (e.cause) throw e.cause;
+                    throw new Error("Failed to load " + path, { cause: e });
+                }
+            }
+            function retry(err) {
+                return Promise.reject(err);
+            }
+        "#,
+        );
+        // Only the wrapping error is a new message
+        assert_eq!(catalog.sites.len(), 1);
+        let site = &catalog.sites[0];
+        assert_eq!(site.message.as_deref(), Some("Failed to load ${path}"));
+        assert_eq!(site.conditions, ["catch (e)"]);
+    }
+}
@@ -0,0 +1,350 @@
+//! Example dataset extraction from prompts.
+//!
+//! Tool prompts illustrate usage with `<example>`, `<good-example>` and
+//! `<bad-example>` blocks holding `user:`/`assistant:` turns, often followed
+//! by `<reasoning>` or `<commentary>` explaining them. Each block becomes one
+//! dataset record linked to the prompt it came from.
+
+use crate::analyzer::{HasProvenance, Provenance};
+use crate::extractor::prompts::SystemPrompt;
+use crate::extractor::tools::ToolDefinition;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Roles recognised at the start of a turn line.
+const TURN_ROLES: &[&str] = &["user", "assistant", "human", "claude"];
+
+/// Annotation tags kept out of the turns.
+const ANNOTATION_TAGS: &[&str] = &["reasoning", "commentary"];
+
+/// Kind of example block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExampleKind {
+    /// `<example>`
+    Example,
+    /// `<good-example>`
+    GoodExample,
+    /// `<bad-example>`
+    BadExample,
+}
+
+impl ExampleKind {
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Example => "example",
+            Self::GoodExample => "good-example",
+            Self::BadExample => "bad-example",
+        }
+    }
+}
+
+/// One speaker turn of an example.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExampleTurn {
+    /// Lowercased speaker label, e.g. `user` or `assistant`.
+    pub role: String,
+
+    /// Text of the turn.
+    pub text: String,
+}
+
+/// A dataset record for one example block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExampleRecord {
+    /// Id of the prompt containing the example, or `tool:<name>` for tool prompts.
+    pub prompt_id: String,
+
+    /// Tool the prompt documents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+
+    /// Kind of example block.
+    pub kind: ExampleKind,
+
+    /// Position of the block within its prompt.
+    pub index: usize,
+
+    /// Text before the first turn.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+
+    /// Speaker turns in order.
+    pub turns: Vec<ExampleTurn>,
+
+    /// `<reasoning>` text inside or right after the block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+
+    /// `<commentary>` text inside or right after the block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commentary: Option<String>,
+
+    /// Block content as written.
+    pub raw: String,
+
+    /// Where the containing prompt is defined.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for ExampleRecord {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+}
+
+/// Collect example records from prompts and tool prompts.
+///
+/// Prompts are linked to the tool whose prompt contains them (or vice versa);
+/// tool prompts not covered by any prompt are read as `tool:<name>`.
+pub fn collect_examples(prompts: &[SystemPrompt], tools: &[ToolDefinition]) -> Vec<ExampleRecord> {
+    let mut records = Vec::new();
+    let mut seen = HashSet::new();
+    let mut covered_tools = HashSet::new();
+
+    for prompt in prompts {
+        let tool = tools.iter().find(|t| {
+            !t.full_prompt.is_empty()
+                && (t.full_prompt.contains(prompt.content.as_str()) || prompt.content.contains(t.full_prompt.as_str()))
+        });
+        if let Some(tool) = tool {
+            covered_tools.insert(tool.name.as_str());
+        }
+        let tool_name = tool.map(|t| t.name.as_str());
+        let examples = parse_examples(&prompt.id, tool_name, &prompt.content);
+        push_unique(&mut records, &mut seen, examples, prompt.provenance.as_ref());
+    }
+
+    for tool in tools.iter().filter(|t| !covered_tools.contains(t.name.as_str())) {
+        let prompt_id = format!("tool:{}", tool.name);
+        let examples = parse_examples(&prompt_id, Some(&tool.name), &tool.full_prompt);
+        push_unique(&mut records, &mut seen, examples, tool.provenance.as_ref());
+    }
+
+    records
+}
+
+/// Parse every example block in a prompt.
+pub fn parse_examples(prompt_id: &str, tool: Option<&str>, text: &str) -> Vec<ExampleRecord> {
+    let mut records = Vec::new();
+    let mut rest = text;
+
+    while let Some((kind, open_start)) = next_block(rest) {
+        let tag = kind.tag();
+        let after_open = &rest[open_start..];
+        let Some(open_end) = after_open.find('>') else { break };
+        let body_start = open_start + open_end + 1;
+        let close = format!("</{}>", tag);
+        let Some(body_len) = rest[body_start..].find(&close) else { break };
+
+        let raw = rest[body_start..body_start + body_len].trim();
+        rest = &rest[body_start + body_len + close.len()..];
+
+        let (body, mut reasoning, mut commentary) = split_annotations(raw);
+        // Annotations directly after the block belong to it
+        while let Some((tag, inner, consumed)) = leading_annotation(rest) {
+            let slot = if tag == "reasoning" { &mut reasoning } else { &mut commentary };
+            append(slot, inner);
+            rest = &rest[consumed..];
+        }
+
+        let (context, turns) = parse_turns(&body);
+        records.push(ExampleRecord {
+            prompt_id: prompt_id.to_string(),
+            tool: tool.map(str::to_string),
+            kind,
+            index: records.len(),
+            context,
+            turns,
+            reasoning,
+            commentary,
+            raw: raw.to_string(),
+            provenance: None,
+        });
+    }
+
+    records
+}
+
+/// Add records not seen before, located at their prompt's definition.
+fn push_unique(
+    records: &mut Vec<ExampleRecord>,
+    seen: &mut HashSet<(Option<String>, String)>,
+    new: Vec<ExampleRecord>,
+    provenance: Option<&Provenance>,
+) {
+    for mut record in new {
+        if seen.insert((record.tool.clone(), record.raw.clone())) {
+            record.provenance = provenance.cloned();
+            records.push(record);
+        }
+    }
+}
+
+/// Earliest example opening tag: its kind and byte offset.
+fn next_block(text: &str) -> Option<(ExampleKind, usize)> {
+    [ExampleKind::Example, ExampleKind::GoodExample, ExampleKind::BadExample]
+        .into_iter()
+        .filter_map(|kind| {
+            let open = format!("<{}", kind.tag());
+            text.match_indices(&open)
+                .find(|(i, _)| matches!(text[i + open.len()..].chars().next(), Some('>' | ' ')))
+                .map(|(i, _)| (kind, i))
+        })
+        .min_by_key(|(_, i)| *i)
+}
+
+/// Remove annotation tags from a block, returning the remaining text.
+fn split_annotations(raw: &str) -> (String, Option<String>, Option<String>) {
+    let mut body = raw.to_string();
+    let mut found = [None, None];
+
+    for (slot, tag) in found.iter_mut().zip(ANNOTATION_TAGS) {
+        let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+        while let Some(start) = body.find(&open) {
+            let Some(len) = body[start..].find(&close) else { break };
+            let inner = body[start + open.len()..start + len].trim().to_string();
+            append(slot, &inner);
+            body.replace_range(start..start + len + close.len(), "");
+        }
+    }
+
+    let [reasoning, commentary] = found;
+    (body, reasoning, commentary)
+}
+
+/// An annotation tag at the start of `text`, after whitespace.
+fn leading_annotation(text: &str) -> Option<(&'static str, &str, usize)> {
+    let skipped = text.len() - text.trim_start().len();
+    let rest = &text[skipped..];
+    ANNOTATION_TAGS.iter().find_map(|tag| {
+        let open = format!("<{}>", tag);
+        let close = format!("</{}>", tag);
+        let inner = rest.strip_prefix(&open)?;
+        let len = inner.find(&close)?;
+        Some((*tag, inner[..len].trim(), skipped + open.len() + len + close.len()))
+    })
+}
+
+fn append(slot: &mut Option<String>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match slot {
+        Some(existing) => {
+            existing.push_str("\n\n");
+            existing.push_str(text);
+        }
+        None => *slot = Some(text.to_string()),
+    }
+}
+
+/// Split a block into leading context and speaker turns.
+fn parse_turns(body: &str) -> (Option<String>, Vec<ExampleTurn>) {
+    let mut context = Vec::new();
+    let mut turns: Vec<ExampleTurn> = Vec::new();
+
+    for line in body.lines() {
+        match turn_start(line) {
+            Some((role, text)) => turns.push(ExampleTurn {
+                role,
+                text: text.to_string(),
+            }),
+            None => match turns.last_mut() {
+                Some(turn) => {
+                    turn.text.push('\n');
+                    turn.text.push_str(line);
+                }
+                None => context.push(line),
+            },
+        }
+    }
+
+    for turn in &mut turns {
+        turn.text = turn.text.trim().to_string();
+    }
+    let context = context.join("\n").trim().to_string();
+    ((!context.is_empty()).then_some(context), turns)
+}
+
+/// `role: text` where role is a known speaker label.
+fn turn_start(line: &str) -> Option<(String, &str)> {
+    let (label, text) = line.trim_start().split_once(':')?;
+    let role = label.trim().to_lowercase();
+    TURN_ROLES.contains(&role.as_str()).then(|| (role, text.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::prompts::PromptCategory;
+    use crate::parser::SourceSpan;
+    use oxc_span::Span;
+
+    #[test]
+    fn test_parses_examples_with_annotations() {
+        let text = "Use this tool to run commands.
+
+<example>
+Context: the user is in a git repository.
+user: What changed?
+assistant: [runs git diff]
+The diff shows two edited files.
+<commentary>Summarize before acting.</commentary>
+</example>
+
+<good-example>
+pytest /foo/bar/tests
+</good-example>
+<bad-example>
+cd /foo/bar && pytest tests
+</bad-example>
+<reasoning>
+The assistant should avoid cd.
+</reasoning>";
+
+        let records = parse_examples("prompt_lit_3", Some("Bash"), text);
+        assert_eq!(records.len(), 3);
+
+        let first = &records[0];
+        assert_eq!(first.kind, ExampleKind::Example);
+        assert_eq!(first.tool.as_deref(), Some("Bash"));
+        assert_eq!(first.context.as_deref(), Some("Context: the user is in a git repository."));
+        assert_eq!(first.turns.len(), 2);
+        assert_eq!(first.turns[1].role, "assistant");
+        assert_eq!(first.turns[1].text, "[runs git diff]\nThe diff shows two edited files.");
+        assert_eq!(first.commentary.as_deref(), Some("Summarize before acting."));
+
+        assert_eq!(records[1].kind, ExampleKind::GoodExample);
+        assert!(records[1].turns.is_empty());
+        assert!(records[1].reasoning.is_none());
+
+        assert_eq!(records[2].kind, ExampleKind::BadExample);
+        assert_eq!(records[2].index, 2);
+        assert_eq!(records[2].reasoning.as_deref(), Some("The assistant should avoid cd."));
+    }
+
+    #[test]
+    fn test_records_prompt_provenance() {
+        let content = "<example>\nuser: hi\nassistant: hello\n</example>".to_string();
+        let provenance = Provenance {
+            span: SourceSpan::new("var p = `x`;", Span::new(8, 11)),
+            beautified: None,
+            function: None,
+            binding: Some("p".to_string()),
+        };
+        let prompt = SystemPrompt {
+            id: "prompt_lit_0".to_string(),
+            length: content.len(),
+            content,
+            category: PromptCategory::Example,
+            tokens: None,
+            provenance: Some(provenance.clone()),
+        };
+
+        let records = collect_examples(&[prompt], &[]);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].provenance, Some(provenance));
+    }
+}
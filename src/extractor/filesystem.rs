@@ -0,0 +1,466 @@
+//! Filesystem access map.
+//!
+//! Calls into `fs` and `fs/promises` are found through `require`/`import`
+//! aliases (and, for minified bundles, any `…Sync` method named like an `fs`
+//! function) and classified as read, write, delete, watch or mkdir. Path
+//! arguments are rebuilt as templates by following `path.join`/`resolve`,
+//! concatenation, template literals, variables and helper functions that
+//! return a path; `os.homedir()` becomes `~`, `process.cwd()` `<cwd>`,
+//! `os.tmpdir()` `<tmp>` and `process.env.X` `$X`. For `env ?? default`
+//! the default is followed. Accesses are grouped by the root of their path.
+
+use crate::analyzer::{Analyzer, SymbolTable};
+use crate::extractor::api_requests::SourceSpan;
+use crate::extractor::members::{callee_name, find_function, top_level_functions};
+use crate::extractor::modules::ModuleAliases;
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tracing::{debug, trace};
+
+/// Maximum depth when following variables and helpers into a path.
+const MAX_RESOLVE_DEPTH: usize = 8;
+
+/// Maximum length of a dynamic part label.
+const MAX_LABEL_LEN: usize = 60;
+
+/// Root for paths that start with a runtime value.
+const DYNAMIC_ROOT: &str = "(dynamic)";
+
+/// What a call does to a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsOperation {
+    /// Reads contents, metadata or existence.
+    Read,
+    /// Creates or modifies a file.
+    Write,
+    /// Removes or moves away a path.
+    Delete,
+    /// Watches for changes.
+    Watch,
+    /// Creates a directory.
+    Mkdir,
+}
+
+/// A filesystem call on one path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FsAccess {
+    /// What the call does to the path.
+    pub operation: FsOperation,
+
+    /// Callee as written, e.g. `fs.writeFileSync`.
+    pub callee: String,
+
+    /// Path template with unresolved parts as `${…}`.
+    pub path: String,
+
+    /// Path root the access is grouped under, e.g. `~/.claude`.
+    pub root: String,
+
+    /// Innermost named function around the call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+
+    /// Location of the call.
+    pub span: SourceSpan,
+}
+
+/// Every filesystem access, and the operations seen under each root.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilesystemMap {
+    /// Accesses in source order.
+    pub accesses: Vec<FsAccess>,
+
+    /// Operation counts per path root.
+    pub roots: BTreeMap<String, BTreeMap<FsOperation, usize>>,
+}
+
+/// Render the map as Markdown, one table per path root.
+pub fn render_markdown(map: &FilesystemMap) -> String {
+    let mut out = String::from("# Filesystem Access\n");
+
+    let mut groups: BTreeMap<&str, Vec<&FsAccess>> = BTreeMap::new();
+    for access in &map.accesses {
+        groups.entry(access.root.as_str()).or_default().push(access);
+    }
+
+    for (root, accesses) in groups {
+        out.push_str(&format!("\n## {} ({})\n\n", root, accesses.len()));
+        out.push_str("| Line | Operation | Path | Call | Function |\n");
+        out.push_str("|------|-----------|------|------|----------|\n");
+        for access in accesses {
+            out.push_str(&format!(
+                "| {} | {:?} | `{}` | `{}` | {} |\n",
+                access.span.line,
+                access.operation,
+                access.path.replace('|', "\\|"),
+                access.callee,
+                access.function.as_deref().map(|f| format!("`{}`", f)).unwrap_or_default(),
+            ));
+        }
+    }
+
+    out
+}
+
+/// Operations on each path argument of an `fs` method, without its `Sync` suffix.
+fn operations(method: &str) -> Option<&'static [FsOperation]> {
+    use FsOperation::*;
+    let method = method.strip_suffix("Sync").unwrap_or(method);
+    Some(match method {
+        "readFile" | "createReadStream" | "readdir" | "opendir" | "stat" | "lstat" | "exists" | "access"
+        | "realpath" | "readlink" | "open" => &[Read],
+        "writeFile" | "appendFile" | "createWriteStream" | "chmod" | "chown" | "truncate" | "utimes" => &[Write],
+        "copyFile" | "cp" | "symlink" | "link" => &[Read, Write],
+        "rename" => &[Delete, Write],
+        "unlink" | "rm" | "rmdir" => &[Delete],
+        "watch" | "watchFile" => &[Watch],
+        "mkdir" | "mkdtemp" => &[Mkdir],
+        _ => return None,
+    })
+}
+
+/// Extractor for filesystem accesses.
+pub struct FilesystemExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+    functions: HashMap<&'a str, &'a Function<'a>>,
+    modules: ModuleAliases,
+    source_text: &'a str,
+}
+
+impl<'a> FilesystemExtractor<'a> {
+    /// Create a new filesystem extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        let program = analyzer.program();
+        Self {
+            analyzer,
+            modules: ModuleAliases::collect(program, symbol_table),
+            symbol_table,
+            functions: top_level_functions(program),
+            source_text: program.source_text,
+        }
+    }
+
+    /// Collect every filesystem access in source order.
+    pub fn extract(&self) -> Result<FilesystemMap> {
+        debug!("Extracting filesystem accesses");
+
+        let calls = self.analyzer.find_expressions(|expr| matches!(expr, Expression::CallExpression(_)));
+
+        let mut map = FilesystemMap::default();
+        for expr in calls {
+            let Expression::CallExpression(call) = expr else {
+                continue;
+            };
+            let Some(ops) = self.fs_method(&call.callee).and_then(|m| operations(&m)) else {
+                continue;
+            };
+            let callee = callee_name(&call.callee).unwrap_or_else(|| self.label(call.callee.span()));
+            for (op, arg) in ops.iter().zip(&call.arguments) {
+                let Some(arg) = arg.as_expression() else {
+                    continue;
+                };
+                let path = self.path(arg, &HashMap::new(), 0);
+                let access = FsAccess {
+                    operation: *op,
+                    callee: callee.clone(),
+                    root: root_of(&path),
+                    path,
+                    function: self.analyzer.context().function_at(call.span).map(str::to_string),
+                    span: SourceSpan::locate(self.analyzer.line_index(), call.span),
+                };
+                trace!("{:?} {} at line {}", access.operation, access.path, access.span.line);
+                *map.roots.entry(access.root.clone()).or_default().entry(access.operation).or_default() += 1;
+                map.accesses.push(access);
+            }
+        }
+
+        debug!("Found {} filesystem accesses under {} roots", map.accesses.len(), map.roots.len());
+        Ok(map)
+    }
+
+    /// Module and method of a callee, treating unknown objects with `…Sync` methods as `fs`.
+    fn module_method(&self, callee: &'a Expression<'a>) -> Option<(String, String)> {
+        if let Some(found) = self.modules.module_method(self.symbol_table, callee) {
+            return Some(found);
+        }
+        match callee.without_parentheses() {
+            // Minified bundles hide the module behind a short name
+            Expression::StaticMemberExpression(member) if member.property.name.ends_with("Sync") => {
+                Some(("fs".to_string(), member.property.name.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// The `fs` method a callee invokes.
+    fn fs_method(&self, callee: &'a Expression<'a>) -> Option<String> {
+        let (module, method) = self.module_method(callee)?;
+        (module == "fs" || module == "fs/promises").then_some(method)
+    }
+
+    /// Rebuild a path as a template.
+    fn path(&self, expr: &'a Expression<'a>, scope: &HashMap<&str, String>, depth: usize) -> String {
+        if depth > MAX_RESOLVE_DEPTH {
+            return self.placeholder(expr.span());
+        }
+        match expr.without_parentheses() {
+            Expression::StringLiteral(s) => s.value.to_string(),
+            Expression::TemplateLiteral(tmpl) => {
+                let mut out = String::new();
+                for (i, quasi) in tmpl.quasis.iter().enumerate() {
+                    out.push_str(quasi.value.raw.as_str());
+                    if let Some(expr) = tmpl.expressions.get(i) {
+                        out.push_str(&self.path(expr, scope, depth + 1));
+                    }
+                }
+                out
+            }
+            Expression::BinaryExpression(binary) if binary.operator == BinaryOperator::Addition => {
+                format!("{}{}", self.path(&binary.left, scope, depth + 1), self.path(&binary.right, scope, depth + 1))
+            }
+            Expression::LogicalExpression(logical) if logical.operator != LogicalOperator::And => {
+                self.path(&logical.right, scope, depth + 1)
+            }
+            Expression::Identifier(id) => {
+                let name = id.name.as_str();
+                if let Some(value) = scope.get(name) {
+                    return value.clone();
+                }
+                if name == "__dirname" || name == "__filename" {
+                    return "<install>".to_string();
+                }
+                match self.symbol_table.get_binding(name) {
+                    Some(binding) if !matches!(binding, Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_)) => {
+                        self.path(binding, &HashMap::new(), depth + 1)
+                    }
+                    _ => self.placeholder(expr.span()),
+                }
+            }
+            Expression::StaticMemberExpression(member) => match &member.object {
+                Expression::StaticMemberExpression(env)
+                    if env.property.name == "env" && matches!(&env.object, Expression::Identifier(p) if p.name == "process") =>
+                {
+                    match member.property.name.as_str() {
+                        "HOME" | "USERPROFILE" => "~".to_string(),
+                        name => format!("${}", name),
+                    }
+                }
+                _ => self.placeholder(expr.span()),
+            },
+            Expression::CallExpression(call) => self.call_path(call, scope, depth),
+            other => self.placeholder(other.span()),
+        }
+    }
+
+    /// Paths produced by `path.join`, `os.homedir()`, `process.cwd()` and helpers.
+    fn call_path(&self, call: &'a CallExpression<'a>, scope: &HashMap<&str, String>, depth: usize) -> String {
+        let args: Vec<&'a Expression<'a>> = call.arguments.iter().filter_map(|a| a.as_expression()).collect();
+        let resolved = |i: usize| args.get(i).map(|a| self.path(a, scope, depth + 1));
+
+        if callee_name(&call.callee).as_deref() == Some("process.cwd") {
+            return "<cwd>".to_string();
+        }
+        if let Some((module, method)) = self.module_method(&call.callee) {
+            match (module.as_str(), method.as_str()) {
+                ("os", "homedir") => return "~".to_string(),
+                ("os", "tmpdir") => return "<tmp>".to_string(),
+                ("path", "join" | "resolve" | "normalize") => {
+                    let parts: Vec<String> = (0..args.len()).filter_map(resolved).collect();
+                    return join_paths(&parts, method == "resolve");
+                }
+                ("path", "dirname") => {
+                    if let Some(path) = resolved(0) {
+                        return match path.rsplit_once('/') {
+                            Some((parent, _)) if !parent.is_empty() => parent.to_string(),
+                            _ => format!("{}/..", path),
+                        };
+                    }
+                }
+                _ => {}
+            }
+            return self.placeholder(call.span);
+        }
+
+        // Helpers returning a path, with arguments bound to parameters
+        if let Expression::Identifier(id) = &call.callee {
+            if let Some(function) = find_function(&self.functions, self.symbol_table, id.name.as_str()) {
+                if let Some(returned) = function.returned() {
+                    let inner: HashMap<&str, String> = function
+                        .param_names()
+                        .into_iter()
+                        .enumerate()
+                        .filter_map(|(i, name)| Some((name?, resolved(i)?)))
+                        .collect();
+                    return self.path(returned, &inner, depth + 1);
+                }
+            }
+        }
+        self.placeholder(call.span)
+    }
+
+    fn placeholder(&self, span: Span) -> String {
+        format!("${{{}}}", self.label(span))
+    }
+
+    /// Collapse whitespace and truncate source for use as a label.
+    fn label(&self, span: Span) -> String {
+        let source = &self.source_text[span.start as usize..span.end as usize];
+        let label = source.split_whitespace().collect::<Vec<_>>().join(" ");
+        if label.chars().count() <= MAX_LABEL_LEN {
+            return label;
+        }
+        let truncated: String = label.chars().take(MAX_LABEL_LEN).collect();
+        format!("{}…", truncated)
+    }
+}
+
+/// Join path templates; with `resolve`, an absolute part discards what came before.
+fn join_paths(parts: &[String], resolve: bool) -> String {
+    let mut out = String::new();
+    for part in parts {
+        let absolute = part.starts_with('/') || part.starts_with('~') || part.starts_with('<');
+        if out.is_empty() || (resolve && absolute) {
+            out = part.clone();
+        } else if !part.is_empty() {
+            out = format!("{}/{}", out.trim_end_matches('/'), part.trim_start_matches("./").trim_start_matches('/'));
+        }
+    }
+    out
+}
+
+/// Root a path is grouped under: `~/.claude`, `$XDG_CONFIG_HOME/claude`, `<cwd>/.claude`, `<tmp>`, `/etc`, ….
+fn root_of(path: &str) -> String {
+    if path.starts_with("${") {
+        return DYNAMIC_ROOT.to_string();
+    }
+    let (base, rest) = path.split_once('/').unwrap_or((path, ""));
+    match base {
+        "<tmp>" => base.to_string(),
+        // `$XDG_CONFIG_HOME` and other environment roots are absolute
+        _ if base == "~" || base.starts_with('$') || matches!(base, "<cwd>" | "<install>" | "") => {
+            // Keep the first directory: a segment with more below it, or a dot directory
+            let (first, below) = rest.split_once('/').unwrap_or((rest, ""));
+            let directory = !below.is_empty() || (first.starts_with('.') && !first[1..].contains('.'));
+            if directory && !first.contains("${") {
+                format!("{}/{}", base, first)
+            } else if base.is_empty() {
+                "/".to_string()
+            } else {
+                base.to_string()
+            }
+        }
+        // Relative paths resolve against the working directory
+        _ => root_of(&format!("<cwd>/{}", path.trim_start_matches("./"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    fn extract(code: &str) -> FilesystemMap {
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+        FilesystemExtractor::new(&analyzer, &symbol_table).extract().unwrap()
+    }
+
+    /// Operation, path and root of every access.
+    fn accesses(map: &FilesystemMap) -> Vec<(FsOperation, &str, &str)> {
+        map.accesses.iter().map(|a| (a.operation, a.path.as_str(), a.root.as_str())).collect()
+    }
+
+    #[test]
+    fn test_extracts_filesystem_accesses() {
+        let code = r#"
+            import * as fs from "node:fs";
+            import { homedir as Hq } from "os";
+            import { join } from "path";
+            var P1 = require("fs/promises");
+            function configDir() {
+                return process.env.CLAUDE_CONFIG_DIR ?? join(Hq(), ".claude");
+            }
+            function settingsPath(A) {
+                return join(A, ".claude", "settings.local.json");
+            }
+            function save(Q) {
+                fs.mkdirSync(configDir(), { recursive: true });
+                fs.writeFileSync(join(configDir(), "settings.json"), JSON.stringify(Q));
+                if (fs.existsSync(settingsPath(process.cwd()))) P1.unlink(settingsPath(process.cwd()));
+                fs.renameSync(`${configDir()}/.tmp`, "/etc/claude-code/managed-settings.json");
+            }
+            var cache = new Map();
+            cache.get("x");
+        "#;
+
+        let map = extract(code);
+        assert_eq!(
+            accesses(&map),
+            [
+                (FsOperation::Mkdir, "~/.claude", "~/.claude"),
+                (FsOperation::Write, "~/.claude/settings.json", "~/.claude"),
+                (FsOperation::Read, "<cwd>/.claude/settings.local.json", "<cwd>/.claude"),
+                (FsOperation::Delete, "<cwd>/.claude/settings.local.json", "<cwd>/.claude"),
+                (FsOperation::Delete, "~/.claude/.tmp", "~/.claude"),
+                (FsOperation::Write, "/etc/claude-code/managed-settings.json", "/etc"),
+            ]
+        );
+        assert_eq!(map.accesses[0].function.as_deref(), Some("save"));
+        assert_eq!(map.accesses[3].callee, "P1.unlink");
+        assert_eq!(map.roots["~/.claude"][&FsOperation::Delete], 1);
+    }
+
+    #[test]
+    fn test_home_and_env_roots() {
+        let map = extract(
+            r#"
+            const fs = require("fs");
+            fs.readFileSync("~/.claude.json");
+            fs.readFileSync(process.env.HOME + "/.claude/credentials.json");
+            fs.writeFileSync(`${process.env.XDG_CONFIG_HOME}/claude/config.json`, "");
+            fs.rmSync(process.env.TMPDIR + "/claude-1234");
+        "#,
+        );
+        assert_eq!(
+            accesses(&map),
+            [
+                (FsOperation::Read, "~/.claude.json", "~"),
+                (FsOperation::Read, "~/.claude/credentials.json", "~/.claude"),
+                (FsOperation::Write, "$XDG_CONFIG_HOME/claude/config.json", "$XDG_CONFIG_HOME/claude"),
+                (FsOperation::Delete, "$TMPDIR/claude-1234", "$TMPDIR"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_joins_paths_from_variables() {
+        let map = extract(
+            r#"
+            const fs = require("fs");
+            const path = require("path");
+            const os = require("os");
+            var cacheDir = path.join(os.homedir(), ".cache", "claude");
+            function load(name, dir) {
+                fs.readFileSync(path.join(cacheDir, name + ".json"));
+                fs.readFileSync(path.join(dir, "config.json"));
+            }
+        "#,
+        );
+        assert_eq!(
+            accesses(&map),
+            [
+                (FsOperation::Read, "~/.cache/claude/${name}.json", "~/.cache"),
+                (FsOperation::Read, "${dir}/config.json", "(dynamic)"),
+            ]
+        );
+    }
+}
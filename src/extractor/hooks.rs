@@ -0,0 +1,381 @@
+//! Hook event extraction.
+//!
+//! User hooks receive a JSON payload built as an object literal carrying
+//! `hook_event_name`, usually spread over a shared base such as
+//! `{ session_id, transcript_path, cwd }`. Their stdout is validated against a
+//! zod schema with common decision fields (`decision`, `continue`, ...) and
+//! per-event `hookSpecificOutput` variants keyed by `hookEventName`.
+
+use crate::analyzer::{Analyzer, HasProvenance, Provenance, SymbolTable};
+use crate::extractor::members::{first_return, property_key};
+use crate::extractor::schemas::SchemaExtractor;
+use crate::Result;
+use oxc_ast::ast::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{debug, trace};
+
+/// Payload member naming the event.
+const EVENT_NAME_KEY: &str = "hook_event_name";
+
+/// Output member naming the event in `hookSpecificOutput` variants.
+const OUTPUT_EVENT_KEY: &str = "hookEventName";
+
+/// Members that, next to `decision`, identify the hook output schema.
+const DECISION_MEMBERS: &[&str] = &["continue", "suppressOutput", "stopReason"];
+
+/// Maximum depth of `...spread` chains followed.
+const MAX_SPREAD_DEPTH: usize = 4;
+
+/// A hook event with its payload and event-specific decision fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookEvent {
+    /// Event name, e.g. `PreToolUse`.
+    pub name: String,
+
+    /// Fields of the JSON payload sent to the hook process.
+    pub payload: Vec<HookField>,
+
+    /// Schema of the event's `hookSpecificOutput`, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decision_schema: Option<JsonValue>,
+
+    /// Where the first payload (or, without one, the event list) was found.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for HookEvent {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+}
+
+/// A payload field and the kind of value it carries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookField {
+    /// Field name.
+    pub name: String,
+
+    /// Value kind: `string`, `number`, `boolean`, `array`, `object` or `unknown`.
+    pub kind: String,
+
+    /// Constant value, when the field is a literal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// All hook events and the decision fields read back from hook output.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HookCatalog {
+    /// Hook events, sorted by name.
+    pub events: Vec<HookEvent>,
+
+    /// Schema of the decision fields common to every event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<JsonValue>,
+}
+
+/// Extractor for hook events and payloads.
+pub struct HookExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+    functions: HashMap<&'a str, &'a Function<'a>>,
+}
+
+impl<'a> HookExtractor<'a> {
+    /// Create a new hook extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        let functions = analyzer
+            .program()
+            .body
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Statement::FunctionDeclaration(func) => Some((func.id.as_ref()?.name.as_str(), &**func)),
+                _ => None,
+            })
+            .collect();
+        Self {
+            analyzer,
+            symbol_table,
+            functions,
+        }
+    }
+
+    /// Extract the hook catalog.
+    pub fn extract(&self) -> Result<HookCatalog> {
+        debug!("Extracting hook events");
+
+        let objects = self.analyzer.find_expressions(|expr| {
+            matches!(expr, Expression::ObjectExpression(_) | Expression::ArrayExpression(_))
+        });
+
+        let mut events: BTreeMap<String, HookEvent> = BTreeMap::new();
+        for expr in &objects {
+            let Expression::ObjectExpression(obj) = expr else {
+                continue;
+            };
+            if let Some((name, payload)) = self.payload(obj) {
+                trace!("Hook payload for {}: {} fields", name, payload.len());
+                let event = events.entry(name.clone()).or_insert_with(|| HookEvent {
+                    name,
+                    payload: Vec::new(),
+                    decision_schema: None,
+                    provenance: Some(self.analyzer.provenance(obj.span)),
+                });
+                for field in payload {
+                    if !event.payload.iter().any(|f| f.name == field.name) {
+                        event.payload.push(field);
+                    }
+                }
+            }
+        }
+
+        // Event lists such as the matcher configuration name events with no payload site
+        for expr in &objects {
+            if let Expression::ArrayExpression(arr) = expr {
+                for name in self.event_list(arr, &events) {
+                    events.entry(name.clone()).or_insert_with(|| HookEvent {
+                        name,
+                        payload: Vec::new(),
+                        decision_schema: None,
+                        provenance: Some(self.analyzer.provenance(arr.span)),
+                    });
+                }
+            }
+        }
+
+        let schemas = SchemaExtractor::new(self.symbol_table);
+        let mut output_schema: Option<JsonValue> = None;
+        for expr in &objects {
+            let Expression::ObjectExpression(obj) = expr else {
+                continue;
+            };
+            let keys: Vec<&str> = obj
+                .properties
+                .iter()
+                .filter_map(|p| match p {
+                    ObjectPropertyKind::ObjectProperty(p) => property_key(&p.key),
+                    _ => None,
+                })
+                .collect();
+
+            if keys.contains(&OUTPUT_EVENT_KEY) {
+                let mut schema = schemas.parse_object_shape(obj);
+                let name = schema["properties"][OUTPUT_EVENT_KEY]["const"].as_str().map(str::to_string);
+                if let (Some(name), Some(event)) = (name.as_ref(), name.as_ref().and_then(|n| events.get_mut(n))) {
+                    trace!("Decision schema for {}", name);
+                    strip_property(&mut schema, OUTPUT_EVENT_KEY);
+                    event.decision_schema = Some(schema);
+                }
+            } else if keys.contains(&"decision") && DECISION_MEMBERS.iter().any(|m| keys.contains(m)) {
+                let schema = schemas.parse_object_shape(obj);
+                let size = |s: &JsonValue| s["properties"].as_object().map_or(0, |p| p.len());
+                if output_schema.as_ref().is_none_or(|current| size(&schema) > size(current)) {
+                    output_schema = Some(schema);
+                }
+            }
+        }
+
+        let catalog = HookCatalog {
+            events: events.into_values().collect(),
+            output_schema,
+        };
+        debug!("Extracted {} hook events", catalog.events.len());
+        Ok(catalog)
+    }
+
+    /// The event name and fields of a payload literal.
+    fn payload(&self, obj: &'a ObjectExpression<'a>) -> Option<(String, Vec<HookField>)> {
+        let event_prop = obj.properties.iter().find_map(|p| match p {
+            ObjectPropertyKind::ObjectProperty(p) if property_key(&p.key) == Some(EVENT_NAME_KEY) => Some(p),
+            _ => None,
+        })?;
+        let name = self.symbol_table.resolve_template_expr(&event_prop.value)?;
+        if !is_event_name(&name) {
+            return None;
+        }
+
+        let mut fields = Vec::new();
+        self.collect_fields(obj, 0, &mut fields);
+        Some((name, fields))
+    }
+
+    /// Collect fields in source order, flattening spreads; later fields override.
+    fn collect_fields(&self, obj: &'a ObjectExpression<'a>, depth: usize, fields: &mut Vec<HookField>) {
+        for prop in &obj.properties {
+            match prop {
+                ObjectPropertyKind::ObjectProperty(p) => {
+                    let Some(key) = property_key(&p.key) else {
+                        continue;
+                    };
+                    let field = self.field(key, &p.value);
+                    match fields.iter_mut().find(|f| f.name == key) {
+                        Some(existing) => *existing = field,
+                        None => fields.push(field),
+                    }
+                }
+                ObjectPropertyKind::SpreadProperty(spread) if depth < MAX_SPREAD_DEPTH => {
+                    if let Some(base) = self.spread_base(&spread.argument) {
+                        self.collect_fields(base, depth + 1, fields);
+                    }
+                }
+                ObjectPropertyKind::SpreadProperty(_) => {}
+            }
+        }
+    }
+
+    /// Resolve `...base` or `...makeBase(x)` to the object literal it spreads.
+    fn spread_base(&self, expr: &'a Expression<'a>) -> Option<&'a ObjectExpression<'a>> {
+        let returned = match expr {
+            Expression::ObjectExpression(obj) => return Some(obj),
+            Expression::Identifier(id) => self.symbol_table.get_binding(id.name.as_str())?,
+            Expression::CallExpression(call) => match &call.callee {
+                Expression::Identifier(id) => self.returned_value(id.name.as_str())?,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match returned {
+            Expression::ObjectExpression(obj) => Some(obj),
+            _ => None,
+        }
+    }
+
+    /// The first returned expression of a named function.
+    fn returned_value(&self, name: &str) -> Option<&'a Expression<'a>> {
+        if let Some(func) = self.functions.get(name) {
+            return first_return(&func.body.as_ref()?.statements);
+        }
+        match self.symbol_table.get_binding(name)? {
+            Expression::FunctionExpression(func) => first_return(&func.body.as_ref()?.statements),
+            Expression::ArrowFunctionExpression(arrow) => match arrow.body.statements.first()? {
+                Statement::ExpressionStatement(stmt) if arrow.expression => Some(&stmt.expression),
+                _ => first_return(&arrow.body.statements),
+            },
+            _ => None,
+        }
+    }
+
+    /// Describe a payload field from its value expression.
+    fn field(&self, name: &str, value: &Expression) -> HookField {
+        let (kind, constant) = match value {
+            Expression::StringLiteral(s) => ("string", Some(s.value.to_string())),
+            Expression::TemplateLiteral(_) => ("string", self.symbol_table.resolve_template_expr(value)),
+            Expression::NumericLiteral(n) => ("number", Some(n.value.to_string())),
+            Expression::BooleanLiteral(b) => ("boolean", Some(b.value.to_string())),
+            Expression::UnaryExpression(u) if u.operator == UnaryOperator::LogicalNot => ("boolean", None),
+            Expression::ArrayExpression(_) => ("array", None),
+            Expression::ObjectExpression(_) => ("object", None),
+            Expression::Identifier(id) => match self.symbol_table.get_string_value(id.name.as_str()) {
+                Some(s) => ("string", Some(s)),
+                None => ("unknown", None),
+            },
+            _ => ("unknown", None),
+        };
+        HookField {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            value: constant,
+        }
+    }
+
+    /// Names in an all-string array that lists at least two known events.
+    fn event_list(&self, arr: &ArrayExpression, known: &BTreeMap<String, HookEvent>) -> Vec<String> {
+        let names: Option<Vec<String>> = arr
+            .elements
+            .iter()
+            .map(|e| self.symbol_table.resolve_template_expr(e.as_expression()?))
+            .collect();
+        let Some(names) = names else {
+            return Vec::new();
+        };
+        if names.iter().filter(|n| known.contains_key(*n)).count() < 2 {
+            return Vec::new();
+        }
+        names.into_iter().filter(|n| is_event_name(n)).collect()
+    }
+}
+
+/// Event names are PascalCase identifiers such as `PreToolUse`.
+fn is_event_name(name: &str) -> bool {
+    name.len() <= 64
+        && name.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Remove a property from an object schema and its `required` list.
+fn strip_property(schema: &mut JsonValue, key: &str) {
+    if let Some(properties) = schema["properties"].as_object_mut() {
+        properties.remove(key);
+    }
+    if let Some(required) = schema["required"].as_array_mut() {
+        required.retain(|r| r != key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_extracts_hook_events() {
+        let code = r#"
+            var PRE = "PreToolUse";
+            var EVENTS = ["PreToolUse", "PostToolUse", "Stop", "SessionStart"];
+            function base(A) {
+                return { session_id: A.id, transcript_path: A.path, cwd: process.cwd() };
+            }
+            function pre(A, B) {
+                return { ...base(A), hook_event_name: PRE, tool_name: B.name, tool_input: B.input };
+            }
+            function post(A, B, C) {
+                return { ...base(A), hook_event_name: "PostToolUse", tool_name: B.name, tool_response: C };
+            }
+            var out = k.object({
+                continue: k.boolean().optional(),
+                decision: k.enum(["approve", "block"]).optional(),
+                reason: k.string().optional(),
+                hookSpecificOutput: k.union([
+                    k.object({
+                        hookEventName: k.literal("PreToolUse"),
+                        permissionDecision: k.enum(["allow", "deny", "ask"]).optional()
+                    })
+                ]).optional()
+            });
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let catalog = HookExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+        let names: Vec<_> = catalog.events.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["PostToolUse", "PreToolUse", "SessionStart", "Stop"]);
+        let sites: Vec<_> = catalog
+            .events
+            .iter()
+            .map(|e| e.provenance.as_ref().map(|p| (p.span.line, p.function.as_deref())))
+            .collect();
+        assert_eq!(sites, [Some((11, Some("post"))), Some((8, Some("pre"))), Some((3, None)), Some((3, None))]);
+
+        let pre = &catalog.events[1];
+        let fields: Vec<_> = pre.payload.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["session_id", "transcript_path", "cwd", "hook_event_name", "tool_name", "tool_input"]
+        );
+        assert_eq!(pre.payload[3].value.as_deref(), Some("PreToolUse"));
+        let decision = pre.decision_schema.as_ref().unwrap();
+        assert!(decision["properties"]["permissionDecision"].is_object());
+        assert!(decision["properties"].get(OUTPUT_EVENT_KEY).is_none());
+
+        let output = catalog.output_schema.unwrap();
+        assert_eq!(output["properties"]["decision"]["enum"], serde_json::json!(["approve", "block"]));
+    }
+}
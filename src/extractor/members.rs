@@ -0,0 +1,521 @@
+//! Member resolution for object literals found in the bundle.
+//!
+//! Tools, slash commands and similar definitions are plain objects whose
+//! members may be literals, identifiers, methods or `...spread` bases. This
+//! module flattens those members and evaluates them through the symbol table.
+
+use crate::analyzer::symbols::SymbolValue;
+use crate::analyzer::SymbolTable;
+use crate::extractor::tools::ToolFlag;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use std::collections::HashMap;
+
+/// Maximum depth of `...spread` chains followed.
+const MAX_SPREAD_DEPTH: usize = 4;
+
+/// What a member evaluates to.
+pub enum MemberBody<'a> {
+    /// A plain value, or a function whose body is a single return.
+    Value(&'a Expression<'a>),
+    /// A function with control flow; carries the span of its body.
+    Complex(Span),
+}
+
+/// Members of an object literal, with `...spread` bases flattened in.
+///
+/// Later members override earlier ones, mirroring JavaScript semantics.
+pub struct ObjectMembers<'s, 'a> {
+    members: HashMap<&'a str, &'a ObjectProperty<'a>>,
+    symbol_table: &'s SymbolTable<'a>,
+    source_text: &'a str,
+}
+
+impl<'s, 'a> ObjectMembers<'s, 'a> {
+    /// Collect an object's members, resolving spread bases through the symbol table.
+    pub fn collect(
+        symbol_table: &'s SymbolTable<'a>,
+        source_text: &'a str,
+        obj: &'a ObjectExpression<'a>,
+    ) -> Self {
+        let mut members = Self {
+            members: HashMap::new(),
+            symbol_table,
+            source_text,
+        };
+        members.add_object(obj, 0);
+        members
+    }
+
+    fn add_object(&mut self, obj: &'a ObjectExpression<'a>, depth: usize) {
+        for prop in &obj.properties {
+            match prop {
+                ObjectPropertyKind::ObjectProperty(p) => {
+                    if let Some(key) = property_key(&p.key) {
+                        self.members.insert(key, p);
+                    }
+                }
+                ObjectPropertyKind::SpreadProperty(spread) if depth < MAX_SPREAD_DEPTH => {
+                    if let Some(base) = self.resolve_object(&spread.argument) {
+                        self.add_object(base, depth + 1);
+                    }
+                }
+                ObjectPropertyKind::SpreadProperty(_) => {}
+            }
+        }
+    }
+
+    /// Resolve an expression to the object literal it denotes, if any.
+    fn resolve_object(&self, expr: &'a Expression<'a>) -> Option<&'a ObjectExpression<'a>> {
+        match expr {
+            Expression::ObjectExpression(obj) => Some(obj),
+            Expression::Identifier(id) => match self.symbol_table.get_binding(id.name.as_str())? {
+                Expression::ObjectExpression(obj) => Some(obj),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether the member exists.
+    pub fn contains(&self, key: &str) -> bool {
+        self.members.contains_key(key)
+    }
+
+    /// The property node for a member.
+    pub fn get(&self, key: &str) -> Option<&'a ObjectProperty<'a>> {
+        self.members.get(key).copied()
+    }
+
+    /// Whether the member is a method or function-valued property.
+    pub fn is_function(&self, key: &str) -> bool {
+        self.get(key)
+            .is_some_and(|p| p.method || is_function_expr(&p.value))
+    }
+
+    /// Get the effective value of a member: the first returned expression for
+    /// methods and functions, the value itself otherwise.
+    pub fn value(&self, key: &str) -> Option<&'a Expression<'a>> {
+        let prop = self.get(key)?;
+        match &prop.value {
+            Expression::FunctionExpression(func) => first_return(&func.body.as_ref()?.statements),
+            Expression::ArrowFunctionExpression(arrow) => {
+                if arrow.expression {
+                    match arrow.body.statements.first()? {
+                        Statement::ExpressionStatement(stmt) => Some(&stmt.expression),
+                        _ => None,
+                    }
+                } else {
+                    first_return(&arrow.body.statements)
+                }
+            }
+            value => Some(value),
+        }
+    }
+
+    /// Classify a member as a single value or a body with control flow.
+    pub fn body(&self, key: &str) -> Option<MemberBody<'a>> {
+        let prop = self.get(key)?;
+        let body = match &prop.value {
+            Expression::FunctionExpression(func) => func.body.as_ref()?,
+            Expression::ArrowFunctionExpression(arrow) => &arrow.body,
+            value => return Some(MemberBody::Value(value)),
+        };
+
+        match body.statements.as_slice() {
+            [Statement::ReturnStatement(ret)] => match &ret.argument {
+                Some(arg) => Some(MemberBody::Value(arg)),
+                None => Some(MemberBody::Complex(body.span)),
+            },
+            [Statement::ExpressionStatement(stmt)] if matches!(&prop.value, Expression::ArrowFunctionExpression(a) if a.expression) => {
+                Some(MemberBody::Value(&stmt.expression))
+            }
+            _ => Some(MemberBody::Complex(body.span)),
+        }
+    }
+
+    /// Resolve a member to a string through the symbol table.
+    pub fn string(&self, key: &str) -> Option<String> {
+        self.value(key)
+            .and_then(|v| self.symbol_table.resolve_template_expr(v))
+    }
+
+    /// Resolve an array member to its string elements.
+    pub fn strings(&self, key: &str) -> Vec<String> {
+        match self.value(key) {
+            Some(Expression::ArrayExpression(arr)) => arr
+                .elements
+                .iter()
+                .filter_map(|e| e.as_expression())
+                .filter_map(|e| self.symbol_table.resolve_template_expr(e))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Evaluate a boolean member, falling back to `default` when absent.
+    pub fn flag(&self, key: &str, default: bool) -> ToolFlag {
+        match self.body(key) {
+            None => ToolFlag::Constant(default),
+            Some(MemberBody::Value(expr)) => match self.constant_bool(expr) {
+                Some(b) => ToolFlag::Constant(b),
+                None => ToolFlag::Conditional {
+                    expression: self.source_of(expr.span()),
+                },
+            },
+            Some(MemberBody::Complex(span)) => ToolFlag::Conditional {
+                expression: self.source_of(span),
+            },
+        }
+    }
+
+    /// Evaluate a non-negative numeric member such as `maxResultSizeChars: 1e5`.
+    pub fn number(&self, key: &str) -> Option<u64> {
+        let value = match self.body(key)? {
+            MemberBody::Value(Expression::NumericLiteral(n)) => n.value,
+            MemberBody::Value(Expression::Identifier(id)) => {
+                match self.symbol_table.get_value(id.name.as_str())? {
+                    SymbolValue::Number(n) => *n,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        (value.is_finite() && value >= 0.0).then_some(value as u64)
+    }
+
+    /// Evaluate an expression to a boolean when it is statically known.
+    ///
+    /// Understands minified forms (`!0`, `!1`) and constants in the symbol table.
+    pub fn constant_bool(&self, expr: &Expression) -> Option<bool> {
+        match expr {
+            Expression::BooleanLiteral(b) => Some(b.value),
+            Expression::NumericLiteral(n) => Some(n.value != 0.0),
+            Expression::ParenthesizedExpression(paren) => self.constant_bool(&paren.expression),
+            Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::LogicalNot => {
+                self.constant_bool(&unary.argument).map(|b| !b)
+            }
+            Expression::Identifier(id) => match self.symbol_table.get_value(id.name.as_str())? {
+                SymbolValue::Boolean(b) => Some(*b),
+                SymbolValue::Number(n) => Some(*n != 0.0),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Resolve an expression to a string through the symbol table.
+    pub fn resolve_string(&self, expr: &Expression) -> Option<String> {
+        self.symbol_table.resolve_template_expr(expr)
+    }
+
+    /// Get the source text covered by a span.
+    pub fn source_of(&self, span: Span) -> String {
+        self.source_text[span.start as usize..span.end as usize].to_string()
+    }
+}
+
+/// Split `a.b(..).c(..)` into its base `a` and the calls `[b, c]`, innermost first.
+pub fn unwind_chain<'r, 'p>(
+    mut expr: &'r Expression<'p>,
+) -> (&'r Expression<'p>, Vec<(&'r str, &'r CallExpression<'p>)>) {
+    let mut calls = Vec::new();
+    while let Expression::CallExpression(call) = expr {
+        let Expression::StaticMemberExpression(member) = &call.callee else {
+            break;
+        };
+        calls.push((member.property.name.as_str(), &**call));
+        expr = &member.object;
+    }
+    calls.reverse();
+    (expr, calls)
+}
+
+/// Get the static name of a property key.
+pub fn property_key<'a>(key: &'a PropertyKey<'a>) -> Option<&'a str> {
+    match key {
+        PropertyKey::StaticIdentifier(id) => Some(id.name.as_str()),
+        PropertyKey::StringLiteral(s) => Some(s.value.as_str()),
+        _ => None,
+    }
+}
+
+/// Name of a called function: `fn` for `fn()`, `obj.fn` for `obj.fn()`.
+pub fn callee_name(callee: &Expression) -> Option<String> {
+    match callee {
+        Expression::Identifier(id) => Some(id.name.as_str().to_string()),
+        Expression::StaticMemberExpression(member) => {
+            let object = callee_name(&member.object).unwrap_or_else(|| "this".to_string());
+            Some(format!("{}.{}", object, member.property.name.as_str()))
+        }
+        _ => None,
+    }
+}
+
+/// Check if an expression is a function or arrow function.
+pub fn is_function_expr(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_)
+    )
+}
+
+/// A named function's parameters and body.
+pub struct NamedFunction<'a> {
+    /// Formal parameters.
+    pub params: &'a FormalParameters<'a>,
+    /// Body statements.
+    pub statements: &'a [Statement<'a>],
+    /// Body of an expression-bodied arrow.
+    pub expression: Option<&'a Expression<'a>>,
+}
+
+impl<'a> NamedFunction<'a> {
+    /// The function or arrow an expression denotes.
+    pub fn from_expression(expr: &'a Expression<'a>) -> Option<Self> {
+        match expr {
+            Expression::FunctionExpression(func) => Some(Self {
+                params: &func.params,
+                statements: func.body.as_ref()?.statements.as_slice(),
+                expression: None,
+            }),
+            Expression::ArrowFunctionExpression(arrow) => {
+                let expression = match (arrow.expression, arrow.body.statements.first()) {
+                    (true, Some(Statement::ExpressionStatement(stmt))) => Some(&stmt.expression),
+                    _ => None,
+                };
+                Some(Self {
+                    params: &arrow.params,
+                    statements: arrow.body.statements.as_slice(),
+                    expression,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The returned expression: an arrow's expression or the first top-level `return`.
+    pub fn returned(&self) -> Option<&'a Expression<'a>> {
+        self.expression.or_else(|| first_return(self.statements))
+    }
+
+    /// Every returned expression, including returns nested in blocks, in source order.
+    pub fn returns(&self) -> Vec<&'a Expression<'a>> {
+        if let Some(expression) = self.expression {
+            return vec![expression];
+        }
+        let mut returns = Vec::new();
+        walk_statements(self.statements, &mut Vec::new(), &mut |stmts, _| {
+            returns.extend(stmts.iter().filter_map(|stmt| match stmt {
+                Statement::ReturnStatement(ret) => ret.argument.as_ref(),
+                _ => None,
+            }));
+        });
+        returns.sort_by_key(|expr| expr.span().start);
+        returns
+    }
+
+    /// Names of simple identifier parameters, `None` for patterns.
+    pub fn param_names(&self) -> Vec<Option<&'a str>> {
+        self.params
+            .items
+            .iter()
+            .map(|param| match &param.pattern.kind {
+                BindingPatternKind::BindingIdentifier(id) => Some(id.name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Top-level `function` declarations by name.
+pub fn top_level_functions<'a>(program: &'a Program<'a>) -> HashMap<&'a str, &'a Function<'a>> {
+    program
+        .body
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::FunctionDeclaration(func) => Some((func.id.as_ref()?.name.as_str(), &**func)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Look up a function declared at the top level or bound to a variable.
+pub fn find_function<'a>(
+    functions: &HashMap<&'a str, &'a Function<'a>>,
+    symbol_table: &SymbolTable<'a>,
+    name: &str,
+) -> Option<NamedFunction<'a>> {
+    match functions.get(name) {
+        Some(func) => Some(NamedFunction {
+            params: &func.params,
+            statements: func.body.as_ref()?.statements.as_slice(),
+            expression: None,
+        }),
+        None => NamedFunction::from_expression(symbol_table.get_binding(name)?),
+    }
+}
+
+/// Find the argument of the first top-level `return` in a function body.
+pub fn first_return<'a>(statements: &'a [Statement<'a>]) -> Option<&'a Expression<'a>> {
+    statements.iter().find_map(|stmt| match stmt {
+        Statement::ReturnStatement(ret) => ret.argument.as_ref(),
+        _ => None,
+    })
+}
+
+/// Visit a statement list and every list nested in it, without entering functions.
+///
+/// The visitor also receives the spans of the conditional and loop statements around each list.
+pub fn walk_statements<'a>(
+    stmts: &'a [Statement<'a>],
+    guards: &mut Vec<Span>,
+    visit: &mut impl FnMut(&'a [Statement<'a>], &[Span]),
+) {
+    visit(stmts, guards);
+    for stmt in stmts {
+        let (guard, nested): (Option<Span>, Vec<&'a [Statement<'a>]>) = match stmt {
+            Statement::BlockStatement(block) => (None, vec![&block.body]),
+            Statement::IfStatement(if_stmt) => {
+                let mut nested = vec![std::slice::from_ref(&if_stmt.consequent)];
+                if let Some(ref alt) = if_stmt.alternate {
+                    nested.push(std::slice::from_ref(alt));
+                }
+                (Some(if_stmt.span), nested)
+            }
+            Statement::TryStatement(try_stmt) => {
+                walk_statements(&try_stmt.block.body, guards, visit);
+                if let Some(ref finalizer) = try_stmt.finalizer {
+                    walk_statements(&finalizer.body, guards, visit);
+                }
+                let handler = try_stmt.handler.as_ref().map(|h| &h.body.body[..]);
+                (Some(try_stmt.span), handler.into_iter().collect())
+            }
+            Statement::SwitchStatement(switch) => {
+                (Some(switch.span), switch.cases.iter().map(|c| &c.consequent[..]).collect())
+            }
+            Statement::ForStatement(s) => (Some(s.span), vec![std::slice::from_ref(&s.body)]),
+            Statement::ForInStatement(s) => (Some(s.span), vec![std::slice::from_ref(&s.body)]),
+            Statement::ForOfStatement(s) => (Some(s.span), vec![std::slice::from_ref(&s.body)]),
+            Statement::WhileStatement(s) => (Some(s.span), vec![std::slice::from_ref(&s.body)]),
+            Statement::DoWhileStatement(s) => (Some(s.span), vec![std::slice::from_ref(&s.body)]),
+            Statement::LabeledStatement(s) => (None, vec![std::slice::from_ref(&s.body)]),
+            _ => (None, Vec::new()),
+        };
+        if let Some(guard) = guard {
+            guards.push(guard);
+        }
+        for stmts in nested {
+            walk_statements(stmts, guards, visit);
+        }
+        if guard.is_some() {
+            guards.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    /// Run `check` on the members of the object bound to `name`.
+    fn with_members(code: &str, name: &str, check: impl FnOnce(&ObjectMembers)) {
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let program = parse_result.program();
+        let symbol_table = SymbolTable::new(program);
+        let Some(Expression::ObjectExpression(obj)) = symbol_table.get_binding(name) else {
+            panic!("{} is not bound to an object", name);
+        };
+        check(&ObjectMembers::collect(&symbol_table, program.source_text, obj));
+    }
+
+    #[test]
+    fn test_spread_flattening() {
+        let code = r#"
+            var base = { name: "base", limit: 10, isHidden: !1 };
+            var mid = { ...base, isHidden: !0 };
+            var own = { ...mid, name: "own", ...{ extra: 2 }, ...makeBase() };
+            var shadowed = { name: "own", ...base };
+            var s0 = { deep: 1 }, s1 = { ...s0 }, s2 = { ...s1 }, s3 = { ...s2 }, s4 = { ...s3 };
+            var deep = { ...s4 };
+        "#;
+
+        with_members(code, "own", |members| {
+            assert_eq!(members.string("name").as_deref(), Some("own"));
+            assert_eq!(members.number("limit"), Some(10));
+            assert_eq!(members.number("extra"), Some(2));
+            assert_eq!(members.flag("isHidden", false), ToolFlag::Constant(true));
+        });
+        with_members(code, "shadowed", |members| {
+            assert_eq!(members.string("name").as_deref(), Some("base"));
+        });
+        // Spreads nest at most MAX_SPREAD_DEPTH levels
+        with_members(code, "deep", |members| assert!(!members.contains("deep")));
+    }
+
+    #[test]
+    fn test_conditional_flags() {
+        let code = r#"
+            var DISABLED = false;
+            var tool = {
+                isReadOnly() { return !0 },
+                isEnabled: () => DISABLED,
+                isConcurrencySafe(A) { return A.safe },
+                isDestructive(A) { if (A.force) return !0; return !1 },
+                strict: 1
+            };
+        "#;
+
+        with_members(code, "tool", |members| {
+            assert!(members.is_function("isReadOnly"));
+            assert_eq!(members.flag("isReadOnly", false), ToolFlag::Constant(true));
+            assert_eq!(members.flag("isEnabled", true), ToolFlag::Constant(false));
+            assert_eq!(members.flag("strict", false), ToolFlag::Constant(true));
+            assert_eq!(members.flag("isHidden", true), ToolFlag::Constant(true));
+            assert_eq!(
+                members.flag("isConcurrencySafe", false),
+                ToolFlag::Conditional { expression: "A.safe".to_string() }
+            );
+            let ToolFlag::Conditional { expression } = members.flag("isDestructive", false) else {
+                panic!("expected a conditional flag");
+            };
+            assert!(expression.starts_with('{') && expression.contains("A.force"));
+        });
+    }
+
+    #[test]
+    fn test_function_returns_and_guards() {
+        let code = r#"
+            var pick = function (A, { B }) {
+                if (A) return "a";
+                try { return "b" } catch { return "c" }
+                return (() => "nested")();
+            };
+        "#;
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let func = find_function(&HashMap::new(), &symbol_table, "pick").unwrap();
+        assert_eq!(func.param_names(), [Some("A"), None]);
+        assert!(func.returned().is_some_and(|e| matches!(e, Expression::CallExpression(_))));
+
+        let returns: Vec<_> = func
+            .returns()
+            .into_iter()
+            .filter_map(|e| symbol_table.resolve_template_expr(e))
+            .collect();
+        assert_eq!(returns, ["a", "b", "c"]);
+
+        let mut guard_depths = Vec::new();
+        walk_statements(func.statements, &mut Vec::new(), &mut |_, guards| guard_depths.push(guards.len()));
+        // Body, `if` consequent, `try` block (always runs), `catch` handler
+        assert_eq!(guard_depths, [0, 1, 0, 1]);
+    }
+}
@@ -1,283 +1,247 @@
 //! Extraction module for pulling structured data from JavaScript AST.
 
+pub mod api_requests;
+pub mod assembly;
 pub mod beautified_tools;
+pub mod clustering;
+pub mod commander;
+pub mod commands;
 pub mod config;
+pub mod errors;
+pub mod examples;
+pub mod filesystem;
+pub mod hooks;
+pub mod members;
+pub mod models;
+pub mod modules;
+pub mod network;
+pub mod platforms;
+pub mod plugin;
+pub mod processes;
 pub mod prompts;
 pub mod prompts_enhanced;
 pub mod schemas;
+pub mod sections;
+pub mod settings;
 pub mod strings;
+pub mod templates;
+pub mod tool_export;
 pub mod tools;
 
 use crate::analyzer::{Analyzer, SymbolTable};
+use crate::rules::Rules;
 use crate::Result;
+use std::cell::OnceCell;
+use std::collections::HashMap;
 use tracing::{debug, trace};
 
 /// Main extractor that coordinates all extraction operations.
 pub struct Extractor<'a> {
     analyzer: Analyzer<'a>,
+    rules: &'a Rules,
+    symbol_table: OnceCell<SymbolTable<'a>>,
+    tools: OnceCell<Vec<tools::ToolDefinition>>,
 }
 
 impl<'a> Extractor<'a> {
     /// Create a new extractor.
     pub fn new(analyzer: Analyzer<'a>) -> Self {
-        Self { analyzer }
+        Self {
+            analyzer,
+            rules: Rules::bundled(),
+            symbol_table: OnceCell::new(),
+            tools: OnceCell::new(),
+        }
+    }
+
+    /// Use these extraction rules instead of the bundled ones.
+    pub fn with_rules(mut self, rules: &'a Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// The analyzer shared by every extraction.
+    pub fn analyzer(&self) -> &Analyzer<'a> {
+        &self.analyzer
+    }
+
+    /// Symbol table shared by every extraction, built on first use.
+    pub fn symbol_table(&self) -> &SymbolTable<'a> {
+        self.symbol_table.get_or_init(|| SymbolTable::new(self.analyzer.program()))
+    }
+
+    /// The extraction rules in use.
+    pub fn rules(&self) -> &'a Rules {
+        self.rules
     }
 
     /// Extract system prompts (legacy method).
     pub fn extract_prompts(&self) -> Result<Vec<prompts::SystemPrompt>> {
-        prompts::PromptExtractor::new(&self.analyzer).extract()
+        prompts::PromptExtractor::new(&self.analyzer).with_rules(self.rules).extract()
     }
 
     /// Extract enhanced system prompts with fragment merging and tool association.
     pub fn extract_prompts_enhanced(&self) -> Result<Vec<prompts_enhanced::EnhancedSystemPrompt>> {
-        let symbol_table = SymbolTable::new(self.analyzer.program());
-        prompts_enhanced::EnhancedPromptExtractor::new(&self.analyzer, &symbol_table).extract()
+        prompts_enhanced::EnhancedPromptExtractor::new(&self.analyzer, self.symbol_table())
+            .with_rules(self.rules)
+            .with_tool_names(self.tool_names()?)
+            .extract()
     }
 
     /// Extract tool definitions from AST.
     pub fn extract_tools(&self) -> Result<Vec<tools::ToolDefinition>> {
-        tools::ToolExtractor::new(&self.analyzer).extract()
+        Ok(self.tools()?.to_vec())
     }
 
-    /// Extract tools from beautified code (more effective for minified bundles).
-    pub fn extract_tools_from_beautified(&self, beautified_code: &str) -> Result<Vec<tools::ToolDefinition>> {
-        beautified_tools::BeautifiedToolExtractor::with_ast(beautified_code, self.analyzer.program()).extract()
+    /// Tool definitions found structurally, extracted on first use.
+    pub fn tools(&self) -> Result<&[tools::ToolDefinition]> {
+        if let Some(tools) = self.tools.get() {
+            return Ok(tools);
+        }
+        let tools = tools::ToolExtractor::new(&self.analyzer, self.symbol_table()).extract()?;
+        Ok(self.tools.get_or_init(|| tools))
     }
 
-    /// Extract tools from system prompts (best approach!).
-    pub fn extract_tools_from_prompts(&self, prompts: &[prompts::SystemPrompt]) -> Result<Vec<tools::ToolDefinition>> {
-        tools::ToolExtractor::extract_from_prompts(prompts)
+    /// Extract built-in slash commands.
+    pub fn extract_commands(&self) -> Result<Vec<commands::SlashCommand>> {
+        commands::CommandExtractor::new(&self.analyzer, self.symbol_table()).extract()
     }
 
-    /// Extract tools with enhanced descriptions from enhanced prompts.
-    pub fn extract_tools_with_enhanced_prompts(
-        &self,
-        enhanced_prompts: &[prompts_enhanced::EnhancedSystemPrompt],
-    ) -> Result<Vec<tools::ToolDefinition>> {
-        debug!("Extracting tools using hybrid approach (regex + symbol table)");
-
-        // CONCRETE SOLUTION: Use beautified_tools regex extractor BUT with
-        // the enhanced symbol table that has lazy_init and function returns resolved
-        let beautified_code = self.generate_beautified_code()?;
-        debug!("Generated {} bytes of beautified code", beautified_code.len());
+    /// Reconstruct the bundle's own commander CLI.
+    pub fn extract_cli(&self) -> Result<Vec<commander::CliCommand>> {
+        commander::CommanderExtractor::new(&self.analyzer, self.symbol_table()).extract()
+    }
 
-        // Use beautified_tools extractor with our fully-resolved symbol table
-        let beautified_extractor = beautified_tools::BeautifiedToolExtractor::with_ast(
-            &beautified_code,
-            self.analyzer.program()
-        );
+    /// Extract hook events, their payloads and decision fields.
+    pub fn extract_hooks(&self) -> Result<hooks::HookCatalog> {
+        hooks::HookExtractor::new(&self.analyzer, self.symbol_table()).extract()
+    }
 
-        let mut tools = beautified_extractor.extract()?;
-        debug!("Extracted {} base tools from regex patterns", tools.len());
+    /// Extract Messages API call sites, beta flags and headers.
+    pub fn extract_api_requests(&self) -> Result<api_requests::ApiInventory> {
+        api_requests::ApiRequestExtractor::new(&self.analyzer, self.symbol_table()).extract()
+    }
 
-        // Enrich with prompts from enhanced extraction (fixes remaining wrong matches)
-        for tool in &mut tools {
-            if let Some(prompt) = Self::find_best_prompt_for_tool(enhanced_prompts, &tool.name) {
-                // Replace if:
-                // 1. New prompt is significantly longer (2x), OR
-                // 2. Current has low confidence (<0.8), OR
-                // 3. Current is placeholder/generic
-                let should_replace = prompt.length > tool.full_prompt.len() * 2
-                    || tool.confidence < 0.8
-                    || tool.full_prompt.starts_with("Tool:")
-                    || tool.full_prompt.starts_with("A powerful search tool"); // Wrong match
+    /// Catalog throw sites, rejections and error reports.
+    pub fn extract_errors(&self) -> Result<errors::ErrorCatalog> {
+        errors::ErrorExtractor::new(&self.analyzer, self.symbol_table()).extract()
+    }
 
-                if should_replace {
-                    tool.full_prompt = prompt.content.clone();
-                    tool.short_description = prompt.content.chars().take(200).collect();
-                    tool.confidence = 1.0;
-                    debug!("Enhanced tool {} with {}-char prompt (was {} chars, conf {})",
-                           tool.name, prompt.length, tool.full_prompt.len(), tool.confidence);
-                }
-            }
-        }
+    /// Inventory HTTP and WebSocket endpoints.
+    pub fn extract_network(&self) -> Result<network::NetworkInventory> {
+        network::NetworkExtractor::new(&self.analyzer, self.symbol_table()).extract()
+    }
 
-        Ok(tools)
+    /// Map filesystem reads, writes and deletes to path templates.
+    pub fn extract_filesystem(&self) -> Result<filesystem::FilesystemMap> {
+        filesystem::FilesystemExtractor::new(&self.analyzer, self.symbol_table()).extract()
     }
 
-    /// Find the best matching prompt for a tool (static method).
-    fn find_best_prompt_for_tool<'b>(
-        prompts: &'b [prompts_enhanced::EnhancedSystemPrompt],
-        tool_name: &str,
-    ) -> Option<&'b prompts_enhanced::EnhancedSystemPrompt> {
-        // Use tool-specific patterns - prefer longer, more complete documentation
-        let candidates: Vec<_> = prompts.iter().filter(|p| {
-            let content = &p.content;
-            let first_100 = content.chars().take(100).collect::<String>();
-
-            match tool_name {
-                "Read" => content.starts_with("Reads a file from the local filesystem"),
-                "Write" => content.starts_with("Writes a file to the local filesystem"),
-                "Edit" => content.starts_with("Performs exact string replacements"),
-                "Bash" => content.starts_with("Executes a given bash command"),
-                "Grep" => first_100.contains("powerful search tool") &&
-                         first_100.contains("ripgrep"),
-                "Glob" => first_100.contains("Fast file pattern matching") ||
-                         (first_100.contains("glob patterns") && first_100.contains("**/*.js")),
-                "Task" => content.starts_with("Launch a new agent to handle complex") ||
-                         first_100.contains("launches specialized agents"),
-                "TodoWrite" => content.starts_with("Use this tool to create and manage a structured task list"),
-                "WebFetch" => first_100.contains("Fetches content from a specified URL"),
-                "WebSearch" => first_100.contains("Allows Claude to search the web"),
-                "NotebookEdit" => content.starts_with("Completely replaces the contents of a specific cell") ||
-                                 (first_100.contains("Jupyter notebook") && first_100.contains("cell") && first_100.contains("ipynb")),
-                "AskUserQuestion" => content.starts_with("Use this tool when you need to ask the user"),
-                "Skill" => first_100.contains("Execute a skill within the main conversation"),
-                "SlashCommand" => first_100.contains("Execute a slash command"),
-                "ExitPlanMode" => first_100.contains("plan mode") && first_100.contains("ready to code"),
-                "BashOutput" => first_100.contains("Retrieves output from a running") ||
-                               first_100.contains("Retrieves output from a running or completed background"),
-                "KillShell" => first_100.contains("Kills a running background bash") ||
-                              first_100.contains("Kills a running background bash shell"),
-                "LSP" => content.starts_with("Interact with Language Server Protocol"),
-                "Skill" => first_100.contains("Execute a skill within"),
-                "LocalVariables" | "Anr" | "SENT" => false, // Skip these for now
-                _ => false,
-            }
-        }).collect();
+    /// Resolve spawn and exec call sites to binaries and argument templates.
+    pub fn extract_processes(&self) -> Result<processes::ProcessInventory> {
+        processes::ProcessExtractor::new(&self.analyzer, self.symbol_table()).extract()
+    }
 
-        // Return the longest matching prompt (most complete documentation)
-        candidates.into_iter().max_by_key(|p| p.length)
+    /// Find code regions guarded by platform, WSL and environment checks.
+    pub fn extract_platforms(&self) -> Result<platforms::PlatformInventory> {
+        platforms::PlatformExtractor::new(&self.analyzer, self.symbol_table()).extract()
     }
 
-    /// Validate that a description is actual documentation, not code.
-    fn is_valid_tool_description(&self, content: &str) -> bool {
-        // Reject if it looks like code
-        let code_indicators = [
-            "function(",
-            "async function",
-            "() =>",
-            "} catch {",
-            "throw Error(",
-            "stdio:",
-            ".forEach(",
-            "\t}",
-            "});",
-        ];
-
-        let code_count = code_indicators.iter().filter(|&&ind| content.contains(ind)).count();
-        if code_count >= 2 {
-            return false;
-        }
+    /// Reconstruct the model catalog: ids, limits, pricing and betas.
+    pub fn extract_models(&self) -> Result<models::ModelCatalog> {
+        models::ModelExtractor::new(&self.analyzer, self.symbol_table()).extract()
+    }
 
-        // Reject if starts with code syntax
-        let trimmed = content.trim();
-        if trimmed.starts_with(",") || trimmed.starts_with("}") || trimmed.starts_with(")") {
-            return false;
+    /// Extract prompt templates with named placeholders.
+    pub fn extract_prompt_templates(
+        &self,
+        rename_map: Option<&HashMap<String, String>>,
+    ) -> Result<Vec<templates::PromptTemplate>> {
+        let mut extractor = templates::TemplateExtractor::new(&self.analyzer, self.symbol_table())
+            .with_tool_names(self.tool_names()?);
+        if let Some(rename_map) = rename_map {
+            extractor = extractor.with_rename_map(rename_map.clone());
         }
+        extractor.extract()
+    }
 
-        // Must have some prose characteristics
-        let has_sentences = content.contains(". ") || content.contains(".\n");
-        let has_prose_words = content.contains("the ") || content.contains("to ") || content.contains("this ");
-
-        has_sentences || has_prose_words
+    /// Trace the functions that assemble system prompts.
+    pub fn extract_prompt_assemblies(&self) -> Result<Vec<assembly::PromptAssembly>> {
+        assembly::AssemblyExtractor::new(&self.analyzer, self.symbol_table()).extract()
     }
 
-    /// Find the best prompt for a given tool.
-    fn find_tool_prompt<'b>(
-        &self,
-        prompts: &'b [prompts_enhanced::EnhancedSystemPrompt],
-        tool_name: &str,
-    ) -> Option<&'b prompts_enhanced::EnhancedSystemPrompt> {
-        // Strategy 1: Exact match via associated_tool
-        let exact_match = prompts
-            .iter()
-            .find(|p| p.associated_tool.as_deref() == Some(tool_name));
-
-        if exact_match.is_some() {
-            debug!("Strategy 1 (exact) matched for {}", tool_name);
-            return exact_match;
-        }
+    /// Extract the settings schema and permission rule grammar.
+    pub fn extract_settings_schema(&self) -> Result<Option<settings::SettingsSchema>> {
+        settings::SettingsExtractor::new(&self.analyzer, self.symbol_table())
+            .with_tool_names(self.tool_names()?)
+            .extract()
+    }
 
-        // Strategy 2: Tool-specific patterns (high confidence)
-        debug!("Strategy 1 failed for {}, trying tool-specific patterns", tool_name);
-        let specific_match = self.find_tool_specific_prompt(prompts, tool_name);
-        if specific_match.is_some() {
-            debug!("Strategy 2 (tool-specific) matched for {}", tool_name);
-            return specific_match;
-        }
+    /// Names of the tools discovered structurally in the AST.
+    fn tool_names(&self) -> Result<Vec<String>> {
+        Ok(self.tools()?.iter().map(|t| t.name.clone()).collect())
+    }
 
-        // Strategy 3: Fallback - Best match via content analysis
-        debug!("Strategy 2 failed for {}, trying fallback", tool_name);
-        let fallback = prompts
-            .iter()
-            .filter(|p| p.category == prompts::PromptCategory::Tool)
-            .filter(|p| {
-                // Tool name appears in first 200 chars AND content is valid
-                let first_200 = p.content.chars().take(200).collect::<String>();
-                first_200.contains(tool_name) && self.is_valid_tool_description(&p.content)
-            })
-            .max_by_key(|p| p.length);
-
-        if fallback.is_some() {
-            debug!("Strategy 3 (fallback) matched for {}", tool_name);
-        } else {
-            debug!("No match found for {}", tool_name);
-        }
+    /// Extract tools from beautified code (more effective for minified bundles).
+    pub fn extract_tools_from_beautified(&self, beautified_code: &str) -> Result<Vec<tools::ToolDefinition>> {
+        beautified_tools::BeautifiedToolExtractor::with_symbol_table(beautified_code, self.symbol_table())
+            .with_rules(self.rules)
+            .with_tool_names(self.tool_names()?)
+            .extract()
+    }
 
-        fallback
+    /// Extract tools from system prompts (best approach!).
+    pub fn extract_tools_from_prompts(&self, prompts: &[prompts::SystemPrompt]) -> Result<Vec<tools::ToolDefinition>> {
+        tools::ToolExtractor::extract_from_prompts(prompts, &self.tool_names()?)
     }
 
-    /// Find tool-specific prompts using tailored patterns.
-    fn find_tool_specific_prompt<'b>(
+    /// Extract tools with enhanced descriptions from enhanced prompts.
+    pub fn extract_tools_with_enhanced_prompts(
         &self,
-        prompts: &'b [prompts_enhanced::EnhancedSystemPrompt],
-        tool_name: &str,
-    ) -> Option<&'b prompts_enhanced::EnhancedSystemPrompt> {
-        if tool_name == "Read" {
-            debug!("find_tool_specific_prompt for Read: have {} prompts to check", prompts.len());
+        enhanced_prompts: &[prompts_enhanced::EnhancedSystemPrompt],
+    ) -> Result<Vec<tools::ToolDefinition>> {
+        debug!("Extracting tools using structural detection + enhanced prompts");
+
+        let mut tools = self.tools()?.to_vec();
+        debug!("Extracted {} tools structurally", tools.len());
+
+        // Fall back to regex over beautified code when the AST yields nothing
+        if tools.is_empty() {
+            let beautified_code = self.generate_beautified_code()?;
+            debug!("Generated {} bytes of beautified code", beautified_code.len());
+            tools = self.extract_tools_from_beautified(&beautified_code)?;
+            debug!("Extracted {} base tools from regex patterns", tools.len());
         }
 
-        let result = prompts.iter().find(|p| {
-            let content = &p.content;
-            let first_100 = content.chars().take(100).collect::<String>();
+        // Enrich with prompts associated to each tool during enhanced extraction
+        for tool in &mut tools {
+            let best = enhanced_prompts
+                .iter()
+                .filter(|p| p.associated_tool.as_deref() == Some(tool.name.as_str()))
+                .max_by_key(|p| p.length);
+
+            if let Some(prompt) = best {
+                // Replace if the prompt is significantly longer (2x) or the
+                // current documentation is low-confidence
+                let should_replace = prompt.length > tool.full_prompt.len() * 2
+                    || tool.confidence < 0.8;
 
-            let matches = match tool_name {
-                "Read" => {
-                    let check = content.starts_with("Reads a file from the local filesystem");
-                    if p.content.contains("Reads a file") {
-                        debug!("Read prompt {}: starts_with={}, first_40='{}'", p.id, check, &content.chars().take(40).collect::<String>());
+                if should_replace {
+                    trace!("Enhancing tool {} with {}-char prompt (was {} chars, conf {})",
+                           tool.name, prompt.length, tool.full_prompt.len(), tool.confidence);
+                    tool.full_prompt = prompt.content.clone();
+                    if tool.short_description.is_empty() {
+                        tool.short_description = prompt.content.chars().take(200).collect();
+                    }
+                    tool.confidence = tool.confidence.max(0.8);
+                    if tool.provenance.is_none() {
+                        tool.provenance = prompt.provenance.clone();
                     }
-                    check
-                },
-                "Write" => content.starts_with("Writes a file to the local filesystem"),
-                "Edit" => content.starts_with("Performs exact string replacements") ||
-                         first_100.contains("exact string replacements in files"),
-                "Bash" => content.starts_with("Executes a given bash command") ||
-                         first_100.contains("Executes a given bash command"),
-                "Grep" => first_100.contains("powerful search tool") &&
-                         first_100.contains("ripgrep") &&
-                         !first_100.contains("glob patterns like"),
-                "Glob" => first_100.contains("Fast file pattern matching") ||
-                         (first_100.contains("glob patterns") && first_100.contains("**/*.js")),
-                "Task" => first_100.contains("launches specialized agents") ||
-                         first_100.contains("Launch a new agent"),
-                "TodoWrite" => content.starts_with("Use this tool to create and manage a structured task list"),
-                "WebFetch" => first_100.contains("Fetches content from a specified URL"),
-                "WebSearch" => first_100.contains("search the web"),
-                "NotebookEdit" => first_100.contains("Jupyter notebook") && first_100.contains("cell"),
-                "AskUserQuestion" => content.starts_with("Use this tool when you need to ask the user"),
-                "Skill" => first_100.contains("Execute a skill within the main conversation"),
-                "SlashCommand" => first_100.contains("Execute a slash command"),
-                "ExitPlanMode" => first_100.contains("plan mode") && first_100.contains("ready to code"),
-                "BashOutput" => first_100.contains("Retrieves output from a running"),
-                "KillShell" => first_100.contains("Kills a running background bash"),
-                "LSP" => content.starts_with("Interact with Language Server Protocol"),
-                _ => false,
-            };
-
-            if matches {
-                trace!("Tool-specific match for {}: {} ({})", tool_name, p.id, p.length);
+                }
             }
-
-            matches
-        });
-
-        if result.is_some() {
-            debug!("Found tool-specific prompt for {}", tool_name);
         }
 
-        result
+        Ok(tools)
     }
 
     /// Generate beautified code for extraction.
@@ -291,11 +255,11 @@ impl<'a> Extractor<'a> {
 
     /// Extract configuration values.
     pub fn extract_configs(&self) -> Result<Vec<config::ConfigValue>> {
-        config::ConfigExtractor::new(&self.analyzer).extract()
+        config::ConfigExtractor::new(&self.analyzer).with_rules(self.rules).extract()
     }
 
     /// Extract interesting string literals.
     pub fn extract_strings(&self) -> Result<Vec<strings::InterestingString>> {
-        strings::StringExtractor::new(&self.analyzer).extract()
+        strings::StringExtractor::new(&self.analyzer).with_rules(self.rules).extract()
     }
 }
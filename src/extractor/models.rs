@@ -0,0 +1,799 @@
+//! Model catalog extraction.
+//!
+//! Model metadata is spread over several shapes in the bundle:
+//!
+//! - provider configs: `{ firstParty: "claude-…", bedrock: "…", vertex: "…" }`
+//! - pricing objects: `{ inputTokens: 3, outputTokens: 15, … }` per million
+//!   tokens, keyed by model id in a lookup table
+//! - limit functions: `if (A.includes("opus-4")) return 32000; … return 8192`,
+//!   including `else if` chains and `A.includes("…") ? 1e6 : 2e5` returns
+//! - display name functions of the same shape returning strings
+//! - beta headers pushed under `if (A.includes("…"))` guards
+//!
+//! Each is recovered independently and joined on the model id.
+
+use crate::analyzer::symbols::SymbolValue;
+use crate::analyzer::{Analyzer, HasProvenance, Provenance, SymbolTable};
+use crate::extractor::members::{property_key, ObjectMembers};
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{debug, trace};
+
+/// Model family names used as shorthand aliases.
+const MODEL_FAMILIES: &[&str] = &["opus", "sonnet", "haiku"];
+
+/// Provider config member holding the first-party model id.
+const FIRST_PARTY_KEY: &str = "firstParty";
+
+/// Smallest value treated as a token limit.
+const MIN_TOKEN_LIMIT: u64 = 1024;
+
+/// Limit functions whose smallest value reaches this return context windows.
+const MIN_CONTEXT_WINDOW: u64 = 100_000;
+
+/// A model and everything known about it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// First-party model id, e.g. `claude-sonnet-4-20250514`.
+    pub id: String,
+
+    /// Human-readable name, e.g. `Sonnet 4`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    /// Ids used by other providers, keyed by provider (`bedrock`, `vertex`, …).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub provider_ids: BTreeMap<String, String>,
+
+    /// Context window in tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u64>,
+
+    /// Maximum output tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+
+    /// Price per million tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+
+    /// `anthropic-beta` headers enabled for this model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub betas: Vec<String>,
+
+    /// Where the model was first defined: its id object, table entry or literal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for ModelInfo {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+}
+
+/// Price per million tokens, in USD.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+}
+
+/// Which limit a rule sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitKind {
+    ContextWindow,
+    MaxOutputTokens,
+}
+
+/// One branch of a limit function: models containing `pattern` get `value`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LimitRule {
+    pub limit: LimitKind,
+
+    /// Substring tested against the model id; `None` for the fallback.
+    pub pattern: Option<String>,
+
+    pub value: u64,
+
+    /// Where the value is returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for LimitRule {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+}
+
+/// The reconstructed model tables.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelCatalog {
+    /// Models sorted by id.
+    pub models: Vec<ModelInfo>,
+
+    /// Shorthand aliases such as `sonnet` or `opusplan`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// Limit rules in source order, as applied to each model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub limit_rules: Vec<LimitRule>,
+}
+
+/// A guarded return: `if (A.includes(pattern…)) return value`.
+struct Branch<'a> {
+    patterns: Vec<String>,
+    value: &'a Expression<'a>,
+}
+
+/// Extractor for the model catalog.
+pub struct ModelExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+}
+
+impl<'a> ModelExtractor<'a> {
+    /// Create a new model extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        Self {
+            analyzer,
+            symbol_table,
+        }
+    }
+
+    /// Extract the model catalog.
+    pub fn extract(&self) -> Result<ModelCatalog> {
+        debug!("Extracting model catalog");
+
+        let source_text = self.analyzer.program().source_text;
+        let exprs = self.analyzer.find_expressions(|expr| {
+            matches!(
+                expr,
+                Expression::ObjectExpression(_)
+                    | Expression::ArrayExpression(_)
+                    | Expression::StringLiteral(_)
+                    | Expression::Identifier(_)
+                    | Expression::FunctionExpression(_)
+                    | Expression::ArrowFunctionExpression(_)
+            )
+        });
+
+        let mut models: BTreeMap<String, ModelInfo> = BTreeMap::new();
+        let mut pricing_tables = Vec::new();
+        let mut aliases = BTreeSet::new();
+        let mut betas_by_span: Vec<(Span, String)> = Vec::new();
+
+        for expr in exprs.iter().copied() {
+            match expr {
+                Expression::ObjectExpression(obj) => {
+                    let members = ObjectMembers::collect(self.symbol_table, source_text, obj);
+                    if let Some(id) = members.string(FIRST_PARTY_KEY).filter(|id| is_model_id(id)) {
+                        let model = models.entry(id.clone()).or_insert_with(|| ModelInfo::new(id, self.analyzer.provenance(obj.span)));
+                        for prop in &obj.properties {
+                            let ObjectPropertyKind::ObjectProperty(p) = prop else {
+                                continue;
+                            };
+                            let Some(provider) = property_key(&p.key).filter(|k| *k != FIRST_PARTY_KEY) else {
+                                continue;
+                            };
+                            if let Some(provider_id) = members.string(provider) {
+                                model.provider_ids.insert(provider.to_string(), provider_id);
+                            }
+                        }
+                    } else {
+                        pricing_tables.push(&**obj);
+                    }
+                }
+                Expression::ArrayExpression(arr) => {
+                    if let Some(list) = self.alias_list(arr) {
+                        aliases.extend(list);
+                    }
+                }
+                Expression::StringLiteral(s) if is_beta_header(&s.value) => {
+                    betas_by_span.push((s.span, s.value.to_string()));
+                }
+                Expression::Identifier(id) => {
+                    if let Some(value) = self.symbol_table.get_string_value(id.name.as_str()) {
+                        if is_beta_header(&value) {
+                            betas_by_span.push((id.span, value));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Lookup tables keyed by model id: pricing and display names
+        for obj in pricing_tables {
+            for prop in &obj.properties {
+                let ObjectPropertyKind::ObjectProperty(p) = prop else {
+                    continue;
+                };
+                let Some(id) = self.model_key(p) else {
+                    continue;
+                };
+                if let Some(pricing) = self.pricing(&p.value) {
+                    trace!("Pricing for {}", id);
+                    let entry = models.entry(id.clone());
+                    entry.or_insert_with(|| ModelInfo::new(id, self.analyzer.provenance(p.span))).pricing = Some(pricing);
+                } else if let Some(name) = self.symbol_table.resolve_template_expr(&p.value) {
+                    if is_display_name(&name) {
+                        let entry = models.entry(id.clone());
+                        entry.or_insert_with(|| ModelInfo::new(id, self.analyzer.provenance(p.span))).display_name = Some(name);
+                    }
+                }
+            }
+        }
+
+        // Functions branching on the model id
+        let known: Vec<&str> = models.keys().chain(&aliases).map(String::as_str).collect();
+        let mut limit_rules = Vec::new();
+        let mut display_rules: Vec<(String, String)> = Vec::new();
+        let mut beta_rules: Vec<(String, String)> = Vec::new();
+        for body in self.function_bodies(&exprs) {
+            let (branches, fallback) = self.branches(body, &betas_by_span, &mut beta_rules);
+            if branches.is_empty() {
+                continue;
+            }
+            if let Some(rules) = self.limit_rules(&branches, fallback, &known) {
+                limit_rules.extend(rules);
+            } else if let Some(rules) = self.display_rules(&branches, &known) {
+                display_rules.extend(rules);
+            }
+        }
+
+        // Stand-alone dated ids not seen in any table
+        for expr in &exprs {
+            if let Expression::StringLiteral(s) = expr {
+                if is_dated_model_id(&s.value) && !is_provider_id(&s.value, &models) {
+                    let id = s.value.to_string();
+                    models.entry(id.clone()).or_insert_with(|| ModelInfo::new(id, self.analyzer.provenance(s.span)));
+                }
+            }
+        }
+
+        for model in models.values_mut() {
+            model.apply_rules(&limit_rules, &display_rules, &beta_rules);
+        }
+
+        let catalog = ModelCatalog {
+            models: models.into_values().collect(),
+            aliases: aliases.into_iter().collect(),
+            limit_rules,
+        };
+        debug!("Extracted {} models", catalog.models.len());
+        Ok(catalog)
+    }
+
+    /// Resolve a table key to a model id: a literal, or `[config.firstParty]`.
+    fn model_key(&self, prop: &ObjectProperty<'a>) -> Option<String> {
+        let id = match property_key(&prop.key) {
+            Some(key) => key.to_string(),
+            None => self.model_id_expr(prop.key.as_expression()?)?,
+        };
+        is_model_id(&id).then_some(id)
+    }
+
+    /// Resolve `X.firstParty`, `fn(X.firstParty)` or a constant to a model id.
+    fn model_id_expr(&self, expr: &Expression<'a>) -> Option<String> {
+        match expr {
+            Expression::StaticMemberExpression(member) if member.property.name == FIRST_PARTY_KEY => {
+                let Expression::Identifier(id) = &member.object else {
+                    return None;
+                };
+                let Expression::ObjectExpression(config) = self.symbol_table.get_binding(id.name.as_str())? else {
+                    return None;
+                };
+                let source_text = self.analyzer.program().source_text;
+                ObjectMembers::collect(self.symbol_table, source_text, config).string(FIRST_PARTY_KEY)
+            }
+            Expression::CallExpression(call) => self.model_id_expr(call.arguments.first()?.as_expression()?),
+            expr => self.symbol_table.resolve_template_expr(expr),
+        }
+    }
+
+    /// Read a pricing object, directly or through an identifier.
+    fn pricing(&self, expr: &'a Expression<'a>) -> Option<ModelPricing> {
+        let obj = match expr {
+            Expression::ObjectExpression(obj) => obj,
+            Expression::Identifier(id) => match self.symbol_table.get_binding(id.name.as_str())? {
+                Expression::ObjectExpression(obj) => obj,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let number = |key: &str| {
+            obj.properties.iter().find_map(|p| match p {
+                ObjectPropertyKind::ObjectProperty(p) if property_key(&p.key) == Some(key) => self.number(&p.value),
+                _ => None,
+            })
+        };
+
+        Some(ModelPricing {
+            input: number("inputTokens")?,
+            output: number("outputTokens")?,
+            cache_write: number("promptCacheWriteTokens"),
+            cache_read: number("promptCacheReadTokens"),
+        })
+    }
+
+    /// Evaluate a numeric literal or constant.
+    fn number(&self, expr: &Expression) -> Option<f64> {
+        match expr {
+            Expression::NumericLiteral(n) => Some(n.value),
+            Expression::Identifier(id) => match self.symbol_table.get_value(id.name.as_str())? {
+                SymbolValue::Number(n) => Some(*n),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// An all-string array of at least two family aliases.
+    fn alias_list(&self, arr: &ArrayExpression) -> Option<Vec<String>> {
+        let values: Vec<String> = arr
+            .elements
+            .iter()
+            .map(|e| self.symbol_table.resolve_template_expr(e.as_expression()?))
+            .collect::<Option<_>>()?;
+        let is_alias = |v: &String| MODEL_FAMILIES.iter().any(|f| v.starts_with(f)) && !v.contains(' ');
+        (values.len() >= 2 && values.iter().all(is_alias)).then_some(values)
+    }
+
+    /// Bodies of top-level function declarations and of every function expression.
+    fn function_bodies(&self, exprs: &[&'a Expression<'a>]) -> Vec<&'a [Statement<'a>]> {
+        let declarations = self.analyzer.program().body.iter().filter_map(|stmt| match stmt {
+            Statement::FunctionDeclaration(func) => Some(func.body.as_ref()?.statements.as_slice()),
+            _ => None,
+        });
+        let expressions = exprs.iter().filter_map(|expr| match expr {
+            Expression::FunctionExpression(func) => Some(func.body.as_ref()?.statements.as_slice()),
+            Expression::ArrowFunctionExpression(arrow) => Some(arrow.body.statements.as_slice()),
+            _ => None,
+        });
+        declarations.chain(expressions).collect()
+    }
+
+    /// Collect `if (id.includes(…)) return v` branches (following `else if`
+    /// chains and `?:` returns) and the final `return v`.
+    ///
+    /// Beta headers referenced inside any model-guarded branch are recorded
+    /// against each of its patterns.
+    fn branches(
+        &self,
+        body: &'a [Statement<'a>],
+        betas: &[(Span, String)],
+        beta_rules: &mut Vec<(String, String)>,
+    ) -> (Vec<Branch<'a>>, Option<&'a Expression<'a>>) {
+        let mut branches = Vec::new();
+        let mut fallback = None;
+
+        for stmt in body {
+            match stmt {
+                Statement::IfStatement(if_stmt) => {
+                    fallback = self.if_branches(if_stmt, betas, beta_rules, &mut branches);
+                    if fallback.is_some() {
+                        break;
+                    }
+                }
+                Statement::ReturnStatement(ret) => {
+                    fallback = ret.argument.as_ref().map(|value| self.ternary_branches(value, &mut branches));
+                    break;
+                }
+                // The body of an expression arrow
+                Statement::ExpressionStatement(stmt) if body.len() == 1 => {
+                    fallback = Some(self.ternary_branches(&stmt.expression, &mut branches));
+                }
+                _ => {}
+            }
+        }
+
+        (branches, fallback)
+    }
+
+    /// Add the branches of a model-guarded `if`/`else if` chain, returning the
+    /// final `else` return if the chain has one.
+    fn if_branches(
+        &self,
+        if_stmt: &'a IfStatement<'a>,
+        betas: &[(Span, String)],
+        beta_rules: &mut Vec<(String, String)>,
+        branches: &mut Vec<Branch<'a>>,
+    ) -> Option<&'a Expression<'a>> {
+        let mut patterns = Vec::new();
+        collect_patterns(&if_stmt.test, &mut patterns);
+        self.resolve_patterns(&if_stmt.test, &mut patterns);
+        if patterns.is_empty() {
+            return None;
+        }
+
+        let span = if_stmt.consequent.span();
+        for (_, beta) in betas.iter().filter(|(s, _)| s.start >= span.start && s.end <= span.end) {
+            for pattern in &patterns {
+                beta_rules.push((pattern.clone(), beta.clone()));
+            }
+        }
+        if let Some(value) = single_return(&if_stmt.consequent) {
+            branches.push(Branch { patterns, value });
+        }
+
+        match if_stmt.alternate.as_ref()? {
+            Statement::IfStatement(alternate) => self.if_branches(alternate, betas, beta_rules, branches),
+            alternate => single_return(alternate),
+        }
+    }
+
+    /// Add the arms of `id.includes(…) ? v : …` returns, returning the last `else` value.
+    fn ternary_branches(&self, expr: &'a Expression<'a>, branches: &mut Vec<Branch<'a>>) -> &'a Expression<'a> {
+        let Expression::ConditionalExpression(cond) = expr.without_parentheses() else {
+            return expr;
+        };
+        let mut patterns = Vec::new();
+        collect_patterns(&cond.test, &mut patterns);
+        self.resolve_patterns(&cond.test, &mut patterns);
+        if patterns.is_empty() {
+            return expr;
+        }
+        branches.push(Branch {
+            patterns,
+            value: &cond.consequent,
+        });
+        self.ternary_branches(&cond.alternate, branches)
+    }
+
+    /// Resolve identifier arguments of `includes`/`startsWith` tests.
+    fn resolve_patterns(&self, test: &Expression, patterns: &mut Vec<String>) {
+        match test {
+            Expression::LogicalExpression(logical) => {
+                self.resolve_patterns(&logical.left, patterns);
+                self.resolve_patterns(&logical.right, patterns);
+            }
+            Expression::ParenthesizedExpression(paren) => self.resolve_patterns(&paren.expression, patterns),
+            Expression::CallExpression(call) if is_match_call(call) => {
+                if let Some(Expression::Identifier(id)) = call.arguments.first().and_then(|a| a.as_expression()) {
+                    if let Some(value) = self.symbol_table.get_string_value(id.name.as_str()) {
+                        patterns.push(value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Interpret branches returning token counts as limit rules.
+    fn limit_rules(
+        &self,
+        branches: &[Branch<'a>],
+        fallback: Option<&'a Expression<'a>>,
+        known: &[&str],
+    ) -> Option<Vec<LimitRule>> {
+        let mut values: Vec<(Option<String>, u64, Span)> = Vec::new();
+        for branch in branches {
+            let value = self.number(branch.value)? as u64;
+            for pattern in &branch.patterns {
+                values.push((Some(pattern.clone()), value, branch.value.span()));
+            }
+        }
+        if let Some(fallback) = fallback {
+            if let Some(value) = self.number(fallback) {
+                values.push((None, value as u64, fallback.span()));
+            }
+        }
+
+        if values.iter().any(|(_, v, _)| *v < MIN_TOKEN_LIMIT)
+            || !values.iter().flat_map(|(p, _, _)| p).any(|p| is_model_pattern(p, known))
+        {
+            return None;
+        }
+
+        let min = values.iter().map(|(_, v, _)| *v).min()?;
+        let limit = if min >= MIN_CONTEXT_WINDOW {
+            LimitKind::ContextWindow
+        } else {
+            LimitKind::MaxOutputTokens
+        };
+        Some(
+            values
+                .into_iter()
+                .map(|(pattern, value, span)| LimitRule {
+                    limit,
+                    pattern,
+                    value,
+                    provenance: Some(self.analyzer.provenance(span)),
+                })
+                .collect(),
+        )
+    }
+
+    /// Interpret branches returning display names as name rules.
+    fn display_rules(&self, branches: &[Branch<'a>], known: &[&str]) -> Option<Vec<(String, String)>> {
+        let mut rules = Vec::new();
+        for branch in branches {
+            let name = self.symbol_table.resolve_template_expr(branch.value)?;
+            if !is_display_name(&name) {
+                return None;
+            }
+            for pattern in &branch.patterns {
+                rules.push((pattern.clone(), name.clone()));
+            }
+        }
+        (rules.len() >= 2 && rules.iter().all(|(p, _)| is_model_pattern(p, known))).then_some(rules)
+    }
+}
+
+impl ModelInfo {
+    fn new(id: String, provenance: Provenance) -> Self {
+        Self {
+            id,
+            provenance: Some(provenance),
+            display_name: None,
+            provider_ids: BTreeMap::new(),
+            context_window: None,
+            max_output_tokens: None,
+            pricing: None,
+            betas: Vec::new(),
+        }
+    }
+
+    /// Apply branch rules: the first matching pattern of each limit function
+    /// wins, falling back to its default.
+    fn apply_rules(&mut self, limits: &[LimitRule], names: &[(String, String)], betas: &[(String, String)]) {
+        for kind in [LimitKind::ContextWindow, LimitKind::MaxOutputTokens] {
+            let rules: Vec<_> = limits.iter().filter(|r| r.limit == kind).collect();
+            let value = rules
+                .iter()
+                .find(|r| r.pattern.as_ref().is_some_and(|p| self.id.contains(p.as_str())))
+                .or_else(|| rules.iter().find(|r| r.pattern.is_none()))
+                .map(|r| r.value);
+            match kind {
+                LimitKind::ContextWindow => self.context_window = value,
+                LimitKind::MaxOutputTokens => self.max_output_tokens = value,
+            }
+        }
+
+        if self.display_name.is_none() {
+            self.display_name = names
+                .iter()
+                .find(|(pattern, _)| self.id.contains(pattern.as_str()))
+                .map(|(_, name)| name.clone());
+        }
+
+        let mut enabled: BTreeSet<String> = self.betas.drain(..).collect();
+        enabled.extend(
+            betas
+                .iter()
+                .filter(|(pattern, _)| self.id.contains(pattern.as_str()))
+                .map(|(_, beta)| beta.clone()),
+        );
+        self.betas = enabled.into_iter().collect();
+    }
+}
+
+/// Literal arguments of `x.includes("…")` / `x.startsWith("…")` in a test.
+fn collect_patterns(test: &Expression, patterns: &mut Vec<String>) {
+    match test {
+        Expression::LogicalExpression(logical) => {
+            collect_patterns(&logical.left, patterns);
+            collect_patterns(&logical.right, patterns);
+        }
+        Expression::ParenthesizedExpression(paren) => collect_patterns(&paren.expression, patterns),
+        Expression::CallExpression(call) if is_match_call(call) => {
+            if let Some(Expression::StringLiteral(s)) = call.arguments.first().and_then(|a| a.as_expression()) {
+                if !s.value.is_empty() {
+                    patterns.push(s.value.to_string());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_match_call(call: &CallExpression) -> bool {
+    matches!(
+        &call.callee,
+        Expression::StaticMemberExpression(m) if matches!(m.property.name.as_str(), "includes" | "startsWith")
+    )
+}
+
+/// The returned value of `return v` or `{ return v }`.
+fn single_return<'a>(stmt: &'a Statement<'a>) -> Option<&'a Expression<'a>> {
+    match stmt {
+        Statement::ReturnStatement(ret) => ret.argument.as_ref(),
+        Statement::BlockStatement(block) => match block.body.as_slice() {
+            [Statement::ReturnStatement(ret)] => ret.argument.as_ref(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// First-party ids look like `claude-3-5-haiku-20241022` or `claude-opus-4-1`.
+fn is_model_id(value: &str) -> bool {
+    value.starts_with("claude-")
+        && value.len() <= 64
+        && value[7..].chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '.'))
+        && MODEL_FAMILIES.iter().any(|f| value.contains(f))
+}
+
+/// A model id ending in an 8-digit release date.
+fn is_dated_model_id(value: &str) -> bool {
+    is_model_id(value)
+        && value
+            .rsplit('-')
+            .next()
+            .is_some_and(|date| date.len() == 8 && date.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Whether a value is already recorded as some model's provider id.
+fn is_provider_id(value: &str, models: &BTreeMap<String, ModelInfo>) -> bool {
+    models.values().any(|m| m.provider_ids.values().any(|p| p == value))
+}
+
+/// A substring test against model ids, e.g. `claude-3-5`, `opus-4`, or a
+/// fragment of a known id or alias such as `[1m]`.
+fn is_model_pattern(pattern: &str, known: &[&str]) -> bool {
+    pattern.len() >= 3
+        && (pattern.contains("claude")
+            || MODEL_FAMILIES.iter().any(|f| pattern.contains(f))
+            || known.iter().any(|k| k.contains(pattern)))
+}
+
+/// Display names look like `Sonnet 4` or `Claude 3.5 Haiku`.
+fn is_display_name(name: &str) -> bool {
+    name.len() <= 40
+        && name.contains(' ')
+        && name.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+        && name.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Beta headers are dated feature names, e.g. `context-1m-2025-08-07`.
+pub fn is_beta_header(value: &str) -> bool {
+    let parts: Vec<&str> = value.rsplitn(4, '-').collect();
+    let [day, month, year, feature] = parts.as_slice() else {
+        return false;
+    };
+    year.len() == 4
+        && year.starts_with("20")
+        && month.len() == 2
+        && day.len() == 2
+        && [year, month, day].iter().all(|p| p.chars().all(|c| c.is_ascii_digit()))
+        && !feature.is_empty()
+        && feature.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_extracts_model_catalog() {
+        let code = r#"
+            var SONNET = { firstParty: "claude-sonnet-4-20250514", bedrock: "us.anthropic.claude-sonnet-4-20250514-v1:0", vertex: "claude-sonnet-4@20250514" };
+            var HAIKU = { firstParty: "claude-3-5-haiku-20241022", bedrock: "us.anthropic.claude-3-5-haiku-20241022-v1:0" };
+            var P1 = { inputTokens: 3, outputTokens: 15, promptCacheWriteTokens: 3.75, promptCacheReadTokens: 0.3 };
+            var P2 = { inputTokens: 0.8, outputTokens: 4 };
+            var PRICES = { [norm(SONNET.firstParty)]: P1, "claude-3-5-haiku-20241022": P2 };
+            var ALIASES = ["sonnet", "opus", "haiku", "sonnet[1m]"];
+            var CTX_BETA = "context-1m-2025-08-07";
+            function maxOutput(A) {
+                if (A.includes("3-5")) return 8192;
+                if (A.includes("claude-sonnet-4")) return 64000;
+                return 4096;
+            }
+            function contextWindow(A) {
+                if (A.includes("[1m]")) return 1e6;
+                return 200000;
+            }
+            function displayName(A) {
+                if (A.includes("claude-sonnet-4")) return "Sonnet 4";
+                if (A.includes("claude-3-5-haiku")) return "Haiku 3.5";
+                return null;
+            }
+            function betas(A) {
+                let B = [];
+                if (A.includes("claude-sonnet-4")) B.push(CTX_BETA);
+                return B;
+            }
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let catalog = ModelExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+        let ids: Vec<_> = catalog.models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["claude-3-5-haiku-20241022", "claude-sonnet-4-20250514"]);
+        assert_eq!(catalog.aliases, ["haiku", "opus", "sonnet", "sonnet[1m]"]);
+
+        let haiku = &catalog.models[0];
+        assert_eq!(haiku.display_name.as_deref(), Some("Haiku 3.5"));
+        assert_eq!(haiku.max_output_tokens, Some(8192));
+        assert_eq!(haiku.context_window, Some(200000));
+        assert_eq!(haiku.pricing.as_ref().unwrap().input, 0.8);
+        assert!(haiku.betas.is_empty());
+
+        let sonnet = &catalog.models[1];
+        assert_eq!(sonnet.provider_ids["vertex"], "claude-sonnet-4@20250514");
+        assert_eq!(sonnet.max_output_tokens, Some(64000));
+        assert_eq!(sonnet.pricing.as_ref().unwrap().cache_read, Some(0.3));
+        assert_eq!(sonnet.betas, ["context-1m-2025-08-07"]);
+    }
+
+    #[test]
+    fn test_limits_from_functions() {
+        let code = r#"
+            var OPUS = { firstParty: "claude-opus-4-1-20250805" };
+            var SONNET = { firstParty: "claude-sonnet-4-20250514" };
+            var ALIASES = ["sonnet", "opus", "sonnet[1m]"];
+            var OPUS_MAX = 32000;
+            var SONNET_PRICE = { inputTokens: 3, outputTokens: 15 };
+            var PRICES = { [SONNET.firstParty]: SONNET_PRICE };
+            var CTX_BETA = "context-1m-2025-08-07";
+            function maxOutput(A) {
+                let B = A.toLowerCase();
+                if (B.includes("opus-4")) return OPUS_MAX;
+                else if (B.includes("sonnet-4")) { return 64000; }
+                else return 8192;
+            }
+            var contextWindow = (A) => A.includes("[1m]") ? 1e6 : 200000;
+            function betas(A) {
+                let B = [];
+                if (A.includes("opus-4")) { B.push("interleaved-thinking-2025-05-14"); }
+                else if (A.includes("sonnet-4")) B.push(CTX_BETA);
+                return B;
+            }
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let catalog = ModelExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+        let ids: Vec<_> = catalog.models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["claude-opus-4-1-20250805", "claude-sonnet-4-20250514"]);
+
+        let opus = &catalog.models[0];
+        assert_eq!(opus.max_output_tokens, Some(32000));
+        assert_eq!(opus.context_window, Some(200000));
+        assert_eq!(opus.betas, ["interleaved-thinking-2025-05-14"]);
+        assert!(opus.pricing.is_none());
+
+        let sonnet = &catalog.models[1];
+        assert_eq!(sonnet.max_output_tokens, Some(64000));
+        assert_eq!(sonnet.context_window, Some(200000));
+        assert_eq!(sonnet.betas, ["context-1m-2025-08-07"]);
+        assert_eq!(sonnet.pricing.as_ref().map(|p| p.output), Some(15.0));
+
+        let fallback = catalog
+            .limit_rules
+            .iter()
+            .find(|r| r.limit == LimitKind::MaxOutputTokens && r.pattern.is_none());
+        assert_eq!(fallback.map(|r| r.value), Some(8192));
+        let line = |rule: &LimitRule| rule.provenance.as_ref().unwrap().span.line;
+        assert_eq!(fallback.map(line), Some(13));
+        assert_eq!(opus.provenance.as_ref().map(|p| p.binding.as_deref()), Some(Some("OPUS")));
+    }
+
+    #[test]
+    fn test_is_beta_header() {
+        assert!(is_beta_header("interleaved-thinking-2025-05-14"));
+        assert!(!is_beta_header("claude-sonnet-4-20250514"));
+        assert!(!is_beta_header("2025-05-14"));
+    }
+}
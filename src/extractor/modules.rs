@@ -0,0 +1,214 @@
+//! Node module aliases.
+//!
+//! Bundles reach built-in modules through `import` specifiers, destructured
+//! `require` calls and esbuild's `__toESM(require(…))` wrapper. This module
+//! records those local names so a callee like `Q.spawn` or an imported
+//! `readFileSync` can be traced back to the module that provides it.
+
+use crate::analyzer::SymbolTable;
+use oxc_ast::ast::*;
+use std::collections::HashMap;
+
+/// Conventional local names for modules, used when no alias is recorded.
+const CONVENTIONAL_NAMES: &[(&str, &str)] = &[
+    ("fs", "fs"),
+    ("fsp", "fs/promises"),
+    ("fsPromises", "fs/promises"),
+    ("path", "path"),
+    ("os", "os"),
+    ("child_process", "child_process"),
+    ("childProcess", "child_process"),
+];
+
+/// Node module a `require`/`import` specifier names, without `node:`.
+pub fn module_name(specifier: &str) -> &str {
+    specifier.strip_prefix("node:").unwrap_or(specifier)
+}
+
+/// Local names bound to modules (`fs`) or module members (`fs.readFileSync`).
+#[derive(Debug, Default)]
+pub struct ModuleAliases {
+    aliases: HashMap<String, String>,
+}
+
+impl ModuleAliases {
+    /// Record `import … from "fs"` and `const { … } = require("fs")` names.
+    pub fn collect<'a>(program: &'a Program<'a>, symbol_table: &SymbolTable<'a>) -> Self {
+        let mut modules = Self::default();
+        for stmt in &program.body {
+            match stmt {
+                Statement::ImportDeclaration(decl) => {
+                    let module = module_name(decl.source.value.as_str());
+                    for specifier in decl.specifiers.iter().flatten() {
+                        let (local, target) = match specifier {
+                            ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                                (s.local.name.as_str(), format!("{}.{}", module, s.imported.name()))
+                            }
+                            ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => (s.local.name.as_str(), module.to_string()),
+                            ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+                                (s.local.name.as_str(), module.to_string())
+                            }
+                        };
+                        modules.aliases.insert(local.to_string(), target);
+                    }
+                }
+                Statement::VariableDeclaration(decl) => {
+                    for declarator in &decl.declarations {
+                        let (BindingPatternKind::ObjectPattern(pattern), Some(init)) =
+                            (&declarator.id.kind, &declarator.init)
+                        else {
+                            continue;
+                        };
+                        let Some(module) = modules.required(symbol_table, init) else {
+                            continue;
+                        };
+                        for prop in &pattern.properties {
+                            let (Some(key), Some(local)) = (prop.key.static_name(), prop.value.get_binding_identifier())
+                            else {
+                                continue;
+                            };
+                            modules.aliases.insert(local.name.to_string(), format!("{}.{}", module, key));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        modules
+    }
+
+    /// Module loaded by `require("fs")`, `__toESM(require("fs"))` or `require("fs").promises`.
+    pub fn required(&self, symbol_table: &SymbolTable, expr: &Expression) -> Option<String> {
+        match expr.without_parentheses() {
+            Expression::CallExpression(call) => {
+                let arg = call.arguments.first()?.as_expression()?;
+                match (&call.callee, arg) {
+                    (Expression::Identifier(id), Expression::StringLiteral(s)) if id.name == "require" => {
+                        Some(module_name(s.value.as_str()).to_string())
+                    }
+                    _ if call.arguments.len() <= 2 => self.required(symbol_table, arg),
+                    _ => None,
+                }
+            }
+            Expression::StaticMemberExpression(member) if member.property.name == "promises" => {
+                (self.module_of(symbol_table, &member.object)? == "fs").then(|| "fs/promises".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Module an object expression refers to.
+    pub fn module_of(&self, symbol_table: &SymbolTable, expr: &Expression) -> Option<String> {
+        match expr.without_parentheses() {
+            Expression::Identifier(id) => {
+                let name = id.name.as_str();
+                if let Some(module) = self.aliases.get(name) {
+                    return Some(module.clone());
+                }
+                if let Some(module) = symbol_table.get_binding(name).and_then(|b| self.required(symbol_table, b)) {
+                    return Some(module);
+                }
+                CONVENTIONAL_NAMES
+                    .iter()
+                    .find(|(local, _)| *local == name)
+                    .map(|(_, module)| module.to_string())
+            }
+            other => self.required(symbol_table, other),
+        }
+    }
+
+    /// Module and method of a callee: `fs.readFile`, an imported `readFileSync`, ….
+    ///
+    /// A module called directly, such as a default-imported `execa`, has the method `default`.
+    pub fn module_method(&self, symbol_table: &SymbolTable, callee: &Expression) -> Option<(String, String)> {
+        match callee.without_parentheses() {
+            Expression::Identifier(id) => {
+                let target = self.aliases.get(id.name.as_str())?;
+                match target.split_once('.') {
+                    Some((module, method)) => Some((module.to_string(), method.to_string())),
+                    None => Some((target.clone(), "default".to_string())),
+                }
+            }
+            Expression::StaticMemberExpression(member) => {
+                let module = self.module_of(symbol_table, &member.object)?;
+                Some((module, member.property.name.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    /// Module and method of every top-level call statement's callee.
+    fn callee_modules(code: &str) -> Vec<Option<(String, String)>> {
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let program = parse_result.program();
+        let symbol_table = SymbolTable::new(program);
+        let modules = ModuleAliases::collect(program, &symbol_table);
+
+        program
+            .body
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Statement::ExpressionStatement(stmt) => match &stmt.expression {
+                    Expression::CallExpression(call) => Some(modules.module_method(&symbol_table, &call.callee)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn pair(module: &str, method: &str) -> Option<(String, String)> {
+        Some((module.to_string(), method.to_string()))
+    }
+
+    #[test]
+    fn test_import_and_require_aliases() {
+        let code = r#"
+            import execa from "execa";
+            import * as nodeFs from "node:fs";
+            import { readFile as rf } from "fs/promises";
+            const { spawn: sp, execFileSync } = require("node:child_process");
+            var Q = __toESM(require("child_process"), 1);
+            var P = require("fs").promises;
+            execa("git");
+            nodeFs.readFileSync("a");
+            rf("b");
+            sp("rg");
+            execFileSync("git");
+            Q.exec("ls");
+            P.writeFile("c", "");
+            fs.statSync("d");
+            unknown.spawn("e");
+        "#;
+
+        assert_eq!(
+            callee_modules(code),
+            [
+                pair("execa", "default"),
+                pair("fs", "readFileSync"),
+                pair("fs/promises", "readFile"),
+                pair("child_process", "spawn"),
+                pair("child_process", "execFileSync"),
+                pair("child_process", "exec"),
+                pair("fs/promises", "writeFile"),
+                pair("fs", "statSync"),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_module_name() {
+        assert_eq!(module_name("node:os"), "os");
+        assert_eq!(module_name("os"), "os");
+    }
+}
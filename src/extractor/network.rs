@@ -0,0 +1,668 @@
+//! Network endpoint inventory.
+//!
+//! `StringCategory::Url` only sees URL-looking literals. Here every HTTP call
+//! site is reconstructed: `fetch(…)`, axios-like clients (`client.post(…)`,
+//! `axios({ url, method })`), `http(s).request`, `new WebSocket(…)`,
+//! `new EventSource(…)` and SDK clients constructed with a `baseURL`. URLs
+//! are rebuilt as templates by following string concatenation, template
+//! literals, `new URL(path, base)` and variables through the
+//! [`SymbolTable`]; whatever stays dynamic is left as `${…}`. Each endpoint
+//! records its method, headers and a summary of the request body, and the
+//! inventory groups endpoints by host for an egress review.
+
+use crate::analyzer::{Analyzer, SymbolTable};
+use crate::extractor::api_requests::SourceSpan;
+use crate::extractor::members::{callee_name, property_key};
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{debug, trace};
+
+/// Maximum depth when following variables into URL parts.
+const MAX_RESOLVE_DEPTH: usize = 8;
+
+/// Maximum length of a dynamic part label.
+const MAX_LABEL_LEN: usize = 60;
+
+/// Client methods named after the HTTP method they send.
+const HTTP_METHODS: &[&str] = &["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Objects whose methods send HTTP requests whatever the URL looks like.
+const CLIENT_OBJECTS: &[&str] = &["axios", "http", "https", "got", "ky", "superagent", "undici"];
+
+/// URL schemes recognised at the start of a template.
+const SCHEMES: &[&str] = &["https://", "http://", "wss://", "ws://"];
+
+/// Host label for URLs whose host isn't static.
+const DYNAMIC_HOST: &str = "(dynamic)";
+
+/// Which API sends the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointClient {
+    /// `fetch(url, init)`
+    Fetch,
+    /// An axios-like or Node `http(s)` client.
+    HttpClient,
+    /// `new WebSocket(url)`
+    WebSocket,
+    /// `new EventSource(url)`
+    EventSource,
+    /// An SDK client constructed with `baseURL`.
+    Sdk,
+}
+
+/// How a request body is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyEncoding {
+    /// `JSON.stringify(…)` or an object handed to a client.
+    Json,
+    /// `URLSearchParams` or `FormData`.
+    Form,
+    /// A string or template literal.
+    Text,
+    /// Anything else.
+    Unknown,
+}
+
+/// Summary of a request body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BodyShape {
+    /// Encoding of the body.
+    pub encoding: BodyEncoding,
+
+    /// Top-level fields, when the body is built from an object literal.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+
+    /// Source of the body expression when its fields aren't known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// An HTTP or WebSocket call site.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Endpoint {
+    /// API sending the request.
+    pub client: EndpointClient,
+
+    /// Callee as written, e.g. `fetch` or `this.http.post`.
+    pub callee: String,
+
+    /// HTTP method; `WS` for WebSockets and `*` for SDK base URLs.
+    pub method: String,
+
+    /// URL template with unresolved parts as `${…}`.
+    pub url: String,
+
+    /// Host, or `(dynamic)` when it isn't static.
+    pub host: String,
+
+    /// Header names and constant values (or the value source).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+
+    /// Request body summary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<BodyShape>,
+
+    /// Innermost named function around the call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+
+    /// Location of the call.
+    pub span: SourceSpan,
+}
+
+/// Every endpoint in the bundle, and how many go to each host.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkInventory {
+    /// Endpoints in source order.
+    pub endpoints: Vec<Endpoint>,
+
+    /// Endpoint count per host.
+    pub hosts: BTreeMap<String, usize>,
+}
+
+/// Render the inventory as Markdown, one table per host.
+pub fn render_markdown(inventory: &NetworkInventory) -> String {
+    let mut out = String::from("# Network Endpoints\n");
+
+    let mut groups: BTreeMap<&str, Vec<&Endpoint>> = BTreeMap::new();
+    for endpoint in &inventory.endpoints {
+        groups.entry(endpoint.host.as_str()).or_default().push(endpoint);
+    }
+
+    let cell = |s: &str| s.replace('|', "\\|");
+    for (host, endpoints) in groups {
+        out.push_str(&format!("\n## {} ({})\n\n", host, endpoints.len()));
+        out.push_str("| Line | Method | URL | Headers | Body | Function |\n");
+        out.push_str("|------|--------|-----|---------|------|----------|\n");
+        for endpoint in endpoints {
+            let body = endpoint.body.as_ref().map(|b| match b.fields.is_empty() {
+                true => format!("{:?}", b.encoding),
+                false => format!("{:?} {{{}}}", b.encoding, b.fields.join(", ")),
+            });
+            out.push_str(&format!(
+                "| {} | {} | `{}` | {} | {} | {} |\n",
+                endpoint.span.line,
+                endpoint.method,
+                cell(&endpoint.url),
+                cell(&endpoint.headers.keys().cloned().collect::<Vec<_>>().join(", ")),
+                cell(&body.unwrap_or_default()),
+                endpoint.function.as_deref().map(|f| format!("`{}`", f)).unwrap_or_default(),
+            ));
+        }
+    }
+
+    out
+}
+
+/// Extractor for network endpoints.
+pub struct NetworkExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+    source_text: &'a str,
+}
+
+impl<'a> NetworkExtractor<'a> {
+    /// Create a new network extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        Self {
+            analyzer,
+            symbol_table,
+            source_text: analyzer.program().source_text,
+        }
+    }
+
+    /// Collect every endpoint in source order.
+    pub fn extract(&self) -> Result<NetworkInventory> {
+        debug!("Extracting network endpoints");
+
+        let exprs = self
+            .analyzer
+            .find_expressions(|expr| matches!(expr, Expression::CallExpression(_) | Expression::NewExpression(_)));
+
+        let mut inventory = NetworkInventory::default();
+        for expr in exprs {
+            let endpoint = match expr {
+                Expression::CallExpression(call) => self.call(call),
+                Expression::NewExpression(new_expr) => self.construct(new_expr),
+                _ => None,
+            };
+            // `file:`, `data:` and the like aren't network egress
+            if let Some(endpoint) = endpoint.filter(|e| !has_other_scheme(&e.url)) {
+                trace!("{} {} at line {}", endpoint.method, endpoint.url, endpoint.span.line);
+                *inventory.hosts.entry(endpoint.host.clone()).or_default() += 1;
+                inventory.endpoints.push(endpoint);
+            }
+        }
+
+        debug!(
+            "Found {} endpoints across {} hosts",
+            inventory.endpoints.len(),
+            inventory.hosts.len()
+        );
+        Ok(inventory)
+    }
+
+    /// `fetch`, client method calls and `axios(config)`.
+    fn call(&self, call: &'a CallExpression<'a>) -> Option<Endpoint> {
+        let callee = callee_name(&call.callee)?;
+        let (object, method) = match callee.rsplit_once('.') {
+            Some((object, method)) => (Some(object), method),
+            None => (None, callee.as_str()),
+        };
+        let args: Vec<&'a Expression<'a>> = call.arguments.iter().filter_map(|a| a.as_expression()).collect();
+        let first = *args.first()?;
+
+        // fetch(url, { method, headers, body })
+        if method == "fetch" && object.is_none_or(|o| o == "globalThis" || o == "window") {
+            let init = args.get(1).and_then(|e| self.object(e));
+            let url = self.url(first, 0);
+            let method = init
+                .and_then(|o| self.member(o, "method"))
+                .and_then(|m| self.constant(m))
+                .map_or_else(|| "GET".to_string(), |m| m.to_ascii_uppercase());
+            let headers = init.map(|o| self.headers(o)).unwrap_or_default();
+            let body = init.and_then(|o| self.member(o, "body")).map(|b| self.body(b));
+            return Some(self.endpoint(EndpointClient::Fetch, callee, method, url, headers, body, call.span));
+        }
+
+        let client_object = object.is_some_and(is_client_object);
+
+        // axios(config), axios.request(config), https.request(options)
+        if (object.is_none() && is_client_object(method)) || (client_object && method == "request") {
+            let config = self.object(first)?;
+            let url = match self.member(config, "url") {
+                Some(url) => self.url(url, 0),
+                None => self.host_url(config)?,
+            };
+            let method = self
+                .member(config, "method")
+                .and_then(|m| self.constant(m))
+                .map_or_else(|| "GET".to_string(), |m| m.to_ascii_uppercase());
+            let headers = self.headers(config);
+            let body = self.member(config, "data").or_else(|| self.member(config, "body")).map(|b| self.body(b));
+            return Some(self.endpoint(EndpointClient::HttpClient, callee, method, url, headers, body, call.span));
+        }
+
+        // client.post(url, data, config)
+        if !HTTP_METHODS.contains(&method) || object.is_none() {
+            return None;
+        }
+        let url = self.url(first, 0);
+        if !(has_scheme(&url) || (client_object && (url.starts_with('/') || url.starts_with("${")))) {
+            return None;
+        }
+        let sends_body = matches!(method, "post" | "put" | "patch");
+        let body = sends_body.then(|| args.get(1).map(|b| self.body(b))).flatten();
+        let config = args.get(if sends_body { 2 } else { 1 }).and_then(|e| self.object(e));
+        let headers = config.map(|o| self.headers(o)).unwrap_or_default();
+        Some(self.endpoint(
+            EndpointClient::HttpClient,
+            callee.clone(),
+            method.to_ascii_uppercase(),
+            url,
+            headers,
+            body,
+            call.span,
+        ))
+    }
+
+    /// `new WebSocket(url)`, `new EventSource(url)` and SDK clients with a `baseURL`.
+    fn construct(&self, new_expr: &'a NewExpression<'a>) -> Option<Endpoint> {
+        let callee = callee_name(&new_expr.callee)?;
+        let first = new_expr.arguments.first()?.as_expression()?;
+        let class = callee.rsplit('.').next().unwrap_or(&callee);
+
+        let (client, method) = match class {
+            "WebSocket" => (EndpointClient::WebSocket, "WS"),
+            "EventSource" => (EndpointClient::EventSource, "GET"),
+            _ => {
+                let options = self.object(first)?;
+                let base = self.member(options, "baseURL").or_else(|| self.member(options, "baseUrl"))?;
+                let url = self.url(base, 0);
+                let mut headers = self.headers(options);
+                if let Some(defaults) = self.member(options, "defaultHeaders").and_then(|h| self.object(h)) {
+                    headers.extend(self.header_entries(defaults));
+                }
+                return Some(self.endpoint(EndpointClient::Sdk, callee, "*".to_string(), url, headers, None, new_expr.span));
+            }
+        };
+
+        let url = self.url(first, 0);
+        Some(self.endpoint(client, callee, method.to_string(), url, BTreeMap::new(), None, new_expr.span))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn endpoint(
+        &self,
+        client: EndpointClient,
+        callee: String,
+        method: String,
+        url: String,
+        headers: BTreeMap<String, String>,
+        body: Option<BodyShape>,
+        span: Span,
+    ) -> Endpoint {
+        Endpoint {
+            client,
+            callee,
+            method,
+            host: host_of(&url),
+            url,
+            headers,
+            body,
+            function: self.analyzer.context().function_at(span).map(str::to_string),
+            span: SourceSpan::locate(self.analyzer.line_index(), span),
+        }
+    }
+
+    /// Rebuild a URL as a template.
+    fn url(&self, expr: &Expression, depth: usize) -> String {
+        if depth > MAX_RESOLVE_DEPTH {
+            return self.placeholder(expr.span());
+        }
+        match expr.without_parentheses() {
+            Expression::StringLiteral(s) => s.value.to_string(),
+            Expression::TemplateLiteral(tmpl) => {
+                let mut out = String::new();
+                for (i, quasi) in tmpl.quasis.iter().enumerate() {
+                    out.push_str(quasi.value.raw.as_str());
+                    if let Some(expr) = tmpl.expressions.get(i) {
+                        out.push_str(&self.url(expr, depth + 1));
+                    }
+                }
+                out
+            }
+            Expression::BinaryExpression(binary) if binary.operator == BinaryOperator::Addition => {
+                format!("{}{}", self.url(&binary.left, depth + 1), self.url(&binary.right, depth + 1))
+            }
+            Expression::Identifier(id) => {
+                let name = id.name.as_str();
+                match self.symbol_table.get_binding(name) {
+                    Some(binding @ (Expression::TemplateLiteral(_)
+                    | Expression::BinaryExpression(_)
+                    | Expression::NewExpression(_)
+                    | Expression::StringLiteral(_))) => self.url(binding, depth + 1),
+                    _ => self
+                        .symbol_table
+                        .get_string_value(name)
+                        .filter(|v| !v.contains("${...}"))
+                        .unwrap_or_else(|| self.placeholder(expr.span())),
+                }
+            }
+            // new URL(path, base)
+            Expression::NewExpression(new_expr) if callee_name(&new_expr.callee).as_deref() == Some("URL") => {
+                let mut args = new_expr.arguments.iter().filter_map(|a| a.as_expression());
+                let Some(path) = args.next() else {
+                    return self.placeholder(expr.span());
+                };
+                let path = self.url(path, depth + 1);
+                match args.next() {
+                    Some(base) if !has_scheme(&path) => join_url(&self.url(base, depth + 1), &path),
+                    _ => path,
+                }
+            }
+            // url.toString()
+            Expression::CallExpression(call) if call.arguments.is_empty() => match &call.callee {
+                Expression::StaticMemberExpression(member) if member.property.name == "toString" => {
+                    self.url(&member.object, depth + 1)
+                }
+                _ => self.placeholder(expr.span()),
+            },
+            // new URL(…).href
+            Expression::StaticMemberExpression(member)
+                if member.property.name == "href" && matches!(member.object, Expression::NewExpression(_)) =>
+            {
+                self.url(&member.object, depth + 1)
+            }
+            other => self.placeholder(other.span()),
+        }
+    }
+
+    /// `protocol//hostname:port/path` from Node request options.
+    fn host_url(&self, options: &'a ObjectExpression<'a>) -> Option<String> {
+        let host = self.member(options, "hostname").or_else(|| self.member(options, "host"))?;
+        let protocol = self
+            .member(options, "protocol")
+            .and_then(|p| self.constant(p))
+            .unwrap_or_else(|| "https:".to_string());
+        let port = self.member(options, "port").map(|p| format!(":{}", self.url(p, 0))).unwrap_or_default();
+        let path = self.member(options, "path").map(|p| self.url(p, 0)).unwrap_or_default();
+        Some(format!("{}//{}{}{}", protocol, self.url(host, 0), port, path))
+    }
+
+    /// Headers set in `headers: {…}`.
+    fn headers(&self, options: &'a ObjectExpression<'a>) -> BTreeMap<String, String> {
+        match self.member(options, "headers").and_then(|h| self.object(h)) {
+            Some(headers) => self.header_entries(headers),
+            None => BTreeMap::new(),
+        }
+    }
+
+    fn header_entries(&self, headers: &'a ObjectExpression<'a>) -> BTreeMap<String, String> {
+        headers
+            .properties
+            .iter()
+            .filter_map(|prop| match prop {
+                ObjectPropertyKind::ObjectProperty(p) => {
+                    let name = property_key(&p.key)?;
+                    let value = self.constant(&p.value).unwrap_or_else(|| self.label(p.value.span()));
+                    Some((name.to_string(), value))
+                }
+                ObjectPropertyKind::SpreadProperty(_) => None,
+            })
+            .collect()
+    }
+
+    /// Summarise a request body.
+    fn body(&self, expr: &'a Expression<'a>) -> BodyShape {
+        let shape = |encoding, fields: Vec<String>, source: Option<String>| BodyShape {
+            encoding,
+            fields,
+            source,
+        };
+        match expr.without_parentheses() {
+            Expression::CallExpression(call) if callee_name(&call.callee).as_deref() == Some("JSON.stringify") => {
+                let value = call.arguments.first().and_then(|a| a.as_expression());
+                match value.and_then(|v| self.object(v)) {
+                    Some(obj) => shape(BodyEncoding::Json, fields(obj), None),
+                    None => shape(BodyEncoding::Json, Vec::new(), value.map(|v| self.label(v.span()))),
+                }
+            }
+            Expression::NewExpression(new_expr)
+                if matches!(callee_name(&new_expr.callee).as_deref(), Some("URLSearchParams" | "FormData")) =>
+            {
+                let obj = new_expr.arguments.first().and_then(|a| a.as_expression()).and_then(|v| self.object(v));
+                shape(BodyEncoding::Form, obj.map(fields).unwrap_or_default(), None)
+            }
+            Expression::StringLiteral(_) | Expression::TemplateLiteral(_) => shape(BodyEncoding::Text, Vec::new(), None),
+            other => match self.object(other) {
+                Some(obj) => shape(BodyEncoding::Json, fields(obj), None),
+                None => shape(BodyEncoding::Unknown, Vec::new(), Some(self.label(other.span()))),
+            },
+        }
+    }
+
+    /// Resolve an expression to an object literal, directly or through a variable.
+    fn object(&self, expr: &'a Expression<'a>) -> Option<&'a ObjectExpression<'a>> {
+        match expr.without_parentheses() {
+            Expression::ObjectExpression(obj) => Some(obj),
+            Expression::Identifier(id) => match self.symbol_table.get_binding(id.name.as_str())? {
+                Expression::ObjectExpression(obj) => Some(obj),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Value of a named member of an object literal.
+    fn member(&self, obj: &'a ObjectExpression<'a>, key: &str) -> Option<&'a Expression<'a>> {
+        obj.properties.iter().find_map(|prop| match prop {
+            ObjectPropertyKind::ObjectProperty(p) if property_key(&p.key) == Some(key) => Some(&p.value),
+            _ => None,
+        })
+    }
+
+    /// Resolve an expression to a constant string or number.
+    fn constant(&self, expr: &Expression) -> Option<String> {
+        match expr {
+            Expression::NumericLiteral(n) => Some(n.value.to_string()),
+            expr => self.symbol_table.resolve_template_expr(expr).filter(|v| !v.contains("${...}")),
+        }
+    }
+
+    fn placeholder(&self, span: Span) -> String {
+        format!("${{{}}}", self.label(span))
+    }
+
+    /// Collapse whitespace and truncate source for use as a label.
+    fn label(&self, span: Span) -> String {
+        let source = &self.source_text[span.start as usize..span.end as usize];
+        let label = source.split_whitespace().collect::<Vec<_>>().join(" ");
+        if label.chars().count() <= MAX_LABEL_LEN {
+            return label;
+        }
+        let truncated: String = label.chars().take(MAX_LABEL_LEN).collect();
+        format!("{}…", truncated)
+    }
+}
+
+/// Top-level field names of an object literal; spreads as `...source`.
+fn fields(obj: &ObjectExpression) -> Vec<String> {
+    obj.properties
+        .iter()
+        .filter_map(|prop| match prop {
+            ObjectPropertyKind::ObjectProperty(p) => property_key(&p.key).map(str::to_string),
+            ObjectPropertyKind::SpreadProperty(s) => match &s.argument {
+                Expression::Identifier(id) => Some(format!("...{}", id.name)),
+                _ => Some("...".to_string()),
+            },
+        })
+        .collect()
+}
+
+/// Whether an object name sends HTTP requests: `axios`, `this.http`, ….
+fn is_client_object(name: &str) -> bool {
+    let last = name.rsplit('.').next().unwrap_or(name).to_ascii_lowercase();
+    CLIENT_OBJECTS.contains(&last.as_str()) || last.contains("axios")
+}
+
+fn has_scheme(url: &str) -> bool {
+    SCHEMES.iter().any(|s| url.starts_with(s))
+}
+
+/// Whether a URL template starts with a scheme other than HTTP or WebSocket.
+fn has_other_scheme(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return false;
+    };
+    let is_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    // `localhost:8080` is a host and port
+    is_scheme && !has_scheme(url) && !rest.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Static host of a URL template.
+fn host_of(url: &str) -> String {
+    let Some(rest) = SCHEMES.iter().find_map(|s| url.strip_prefix(s)) else {
+        return DYNAMIC_HOST.to_string();
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    if host.is_empty() || host.contains("${") {
+        return DYNAMIC_HOST.to_string();
+    }
+    host.to_string()
+}
+
+/// Resolve a relative path against a base like `new URL(path, base)`.
+fn join_url(base: &str, path: &str) -> String {
+    match path.strip_prefix('/') {
+        // Absolute paths replace the base path
+        Some(path) => match SCHEMES.iter().find_map(|s| base.strip_prefix(s).map(|rest| (s, rest))) {
+            Some((scheme, rest)) => {
+                let host = rest.split('/').next().unwrap_or(rest);
+                format!("{}{}/{}", scheme, host, path)
+            }
+            None => format!("{}/{}", base.trim_end_matches('/'), path),
+        },
+        None => format!("{}/{}", base.trim_end_matches('/'), path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_extracts_endpoints() {
+        let code = r#"
+            var BASE = "https://api.anthropic.com";
+            var OAUTH = `${BASE}/v1/oauth`;
+            async function refreshToken(A) {
+                return fetch(`${OAUTH}/token`, {
+                    method: "post",
+                    headers: { "Content-Type": "application/json", Authorization: A.auth },
+                    body: JSON.stringify({ grant_type: "refresh_token", refresh_token: A.token })
+                });
+            }
+            function report(Q) {
+                return this.axios.post("/api/event_logging/batch", { events: Q }, { headers: { "x-service-name": "cli" } });
+            }
+            var ws = new WebSocket(new URL("/v1/sessions/" + id, "wss://bridge.example.com/ignored"));
+            var client = new Anthropic({ apiKey: key, baseURL: process.env.ANTHROPIC_BASE_URL });
+            var cache = new Map();
+            cache.get("key");
+        "#;
+
+        let inventory = extract(code);
+        let endpoints: Vec<_> = inventory
+            .endpoints
+            .iter()
+            .map(|e| (e.client, e.method.as_str(), e.url.as_str(), e.host.as_str()))
+            .collect();
+        assert_eq!(
+            endpoints,
+            [
+                (EndpointClient::Fetch, "POST", "https://api.anthropic.com/v1/oauth/token", "api.anthropic.com"),
+                (EndpointClient::HttpClient, "POST", "/api/event_logging/batch", "(dynamic)"),
+                (EndpointClient::WebSocket, "WS", "wss://bridge.example.com/v1/sessions/${id}", "bridge.example.com"),
+                (EndpointClient::Sdk, "*", "${process.env.ANTHROPIC_BASE_URL}", "(dynamic)"),
+            ]
+        );
+
+        let fetch = &inventory.endpoints[0];
+        assert_eq!(fetch.function.as_deref(), Some("refreshToken"));
+        assert_eq!(fetch.headers["Content-Type"], "application/json");
+        assert_eq!(fetch.headers["Authorization"], "A.auth");
+        let body = fetch.body.as_ref().unwrap();
+        assert_eq!(body.encoding, BodyEncoding::Json);
+        assert_eq!(body.fields, ["grant_type", "refresh_token"]);
+
+        let post = &inventory.endpoints[1];
+        assert_eq!(post.body.as_ref().unwrap().fields, ["events"]);
+        assert_eq!(post.headers["x-service-name"], "cli");
+        assert_eq!(inventory.hosts["(dynamic)"], 2);
+    }
+
+    fn extract(code: &str) -> NetworkInventory {
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+        NetworkExtractor::new(&analyzer, &symbol_table).extract().unwrap()
+    }
+
+    /// URL and host of every endpoint.
+    fn urls(inventory: &NetworkInventory) -> Vec<(&str, &str)> {
+        inventory.endpoints.iter().map(|e| (e.url.as_str(), e.host.as_str())).collect()
+    }
+
+    #[test]
+    fn test_builds_url_templates() {
+        let inventory = extract(
+            r#"
+            var API = "https://api.example.com";
+            function members(org, n) {
+                return fetch(`${API}/v1/orgs/${org.id}/members?limit=${n}`);
+            }
+            function info(p) {
+                return fetch(API + "/v1/" + p + "/info");
+            }
+            function status(region) {
+                return fetch(`https://${region}.example.com/status`);
+            }
+        "#,
+        );
+        assert_eq!(
+            urls(&inventory),
+            [
+                ("https://api.example.com/v1/orgs/${org.id}/members?limit=${n}", "api.example.com"),
+                ("https://api.example.com/v1/${p}/info", "api.example.com"),
+                ("https://${region}.example.com/status", "(dynamic)"),
+            ]
+        );
+        assert_eq!(inventory.hosts["api.example.com"], 2);
+    }
+
+    #[test]
+    fn test_skips_non_http_schemes() {
+        let inventory = extract(
+            r#"
+            fetch("file:///etc/hosts");
+            fetch("data:text/plain,hello");
+            this.axios.get("ftp://mirror.example.com/pub");
+            fetch("http://localhost:8080/health");
+        "#,
+        );
+        assert_eq!(urls(&inventory), [("http://localhost:8080/health", "localhost:8080")]);
+    }
+}
@@ -0,0 +1,824 @@
+//! Platform-specific code paths.
+//!
+//! Tests on `process.platform`, `os.platform()`/`os.type()`, helpers that
+//! return them (or names like `"macos"`), WSL detection (`isWsl`-style names,
+//! `/proc/version` checks for "microsoft", `WSL_DISTRO_NAME`) and Windows
+//! environment variables are evaluated to the set of platforms they hold on.
+//! Every region such a test guards (an `if` or `else` branch, the rest of a
+//! block after an early return, a ternary arm, a `switch` case or the right
+//! side of a `cond && call()` statement) is reported with the platforms it
+//! runs on, narrowed by the guards around it. [`slice`] strips the regions
+//! that don't run on one platform.
+
+use crate::analyzer::{Analyzer, SymbolTable};
+use crate::extractor::api_requests::SourceSpan;
+use crate::extractor::members::{
+    callee_name, find_function, is_function_expr, top_level_functions, walk_statements, NamedFunction,
+};
+use crate::extractor::modules::ModuleAliases;
+use crate::parser::Parser;
+use crate::Result;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use tracing::{debug, trace};
+
+/// Maximum depth when following bindings and helpers into a test.
+const MAX_RESOLVE_DEPTH: usize = 8;
+
+/// Maximum length of a condition label.
+const MAX_LABEL_LEN: usize = 60;
+
+/// Maximum number of callees listed per region.
+const MAX_CALLS: usize = 10;
+
+/// Environment variables whose presence indicates a platform.
+const PLATFORM_ENV: &[(&str, Platform)] = &[
+    ("WSL_DISTRO_NAME", Platform::Wsl),
+    ("WSL_INTEROP", Platform::Wsl),
+    ("WINDIR", Platform::Win32),
+    ("COMSPEC", Platform::Win32),
+    ("MSYSTEM", Platform::Win32),
+    ("LOCALAPPDATA", Platform::Win32),
+    ("APPDATA", Platform::Win32),
+];
+
+/// Values helpers return to name a platform, besides `process.platform` values.
+const PLATFORM_NAMES: &[&str] = &["macos", "darwin", "windows", "win32", "wsl", "linux"];
+
+/// A platform code can be specific to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    /// macOS.
+    Darwin,
+    /// Linux outside WSL.
+    Linux,
+    /// Windows Subsystem for Linux, which `process.platform` reports as `linux`.
+    Wsl,
+    /// Windows.
+    Win32,
+    /// Any other `process.platform` value, such as `freebsd`.
+    Other,
+}
+
+impl Platform {
+    /// Every platform, in order.
+    pub const ALL: [Platform; 5] = [Platform::Darwin, Platform::Linux, Platform::Wsl, Platform::Win32, Platform::Other];
+
+    /// Name as used in `process.platform` and output file names.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Platform::Darwin => "darwin",
+            Platform::Linux => "linux",
+            Platform::Wsl => "wsl",
+            Platform::Win32 => "win32",
+            Platform::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Set of platforms as a bit mask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PlatformSet(u8);
+
+impl PlatformSet {
+    const NONE: Self = Self(0);
+    const ALL: Self = Self(0b11111);
+
+    fn of(platforms: &[Platform]) -> Self {
+        Self(platforms.iter().fold(0, |bits, p| bits | 1 << *p as u8))
+    }
+
+    fn complement(self) -> Self {
+        Self(!self.0 & Self::ALL.0)
+    }
+
+    fn platforms(self) -> Vec<Platform> {
+        Platform::ALL.into_iter().filter(|p| self.0 & 1 << *p as u8 != 0).collect()
+    }
+}
+
+/// Platforms where both tests hold; a test that isn't platform-specific doesn't narrow.
+fn meet(a: Option<PlatformSet>, b: Option<PlatformSet>) -> Option<PlatformSet> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(PlatformSet(a.0 & b.0)),
+        (a, None) | (None, a) => a,
+    }
+}
+
+/// Platforms where either test holds; unknown when either isn't platform-specific.
+fn join(a: Option<PlatformSet>, b: Option<PlatformSet>) -> Option<PlatformSet> {
+    Some(PlatformSet(a?.0 | b?.0))
+}
+
+/// Platforms a compared value names. `linux` includes WSL when it comes
+/// straight from `process.platform` and excludes it from helpers that tell them apart.
+fn value_set(value: &str, raw: bool) -> PlatformSet {
+    match value.to_ascii_lowercase().as_str() {
+        "darwin" | "macos" | "mac" => PlatformSet::of(&[Platform::Darwin]),
+        "win32" | "windows" | "windows_nt" => PlatformSet::of(&[Platform::Win32]),
+        "wsl" => PlatformSet::of(&[Platform::Wsl]),
+        "linux" if raw => PlatformSet::of(&[Platform::Linux, Platform::Wsl]),
+        "linux" => PlatformSet::of(&[Platform::Linux]),
+        _ => PlatformSet::of(&[Platform::Other]),
+    }
+}
+
+/// Names like `isWsl` or `IS_WSL`.
+fn is_wsl_name(name: &str) -> bool {
+    name.rsplit('.').next().unwrap_or(name).to_ascii_lowercase().contains("wsl")
+}
+
+/// Static value of a string literal or a template without placeholders.
+fn string_value<'a>(expr: &'a Expression<'a>) -> Option<&'a str> {
+    match expr.without_parentheses() {
+        Expression::StringLiteral(s) => Some(s.value.as_str()),
+        Expression::TemplateLiteral(tmpl) if tmpl.expressions.is_empty() => {
+            tmpl.quasis.first().map(|q| q.value.raw.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// Whether `expr` is `process.env`.
+fn is_process_env(expr: &Expression) -> bool {
+    matches!(expr.without_parentheses(), Expression::StaticMemberExpression(env)
+        if env.property.name == "env" && matches!(&env.object, Expression::Identifier(p) if p.name == "process"))
+}
+
+/// Whether a statement always leaves its block.
+fn exits(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::ReturnStatement(_)
+        | Statement::ThrowStatement(_)
+        | Statement::BreakStatement(_)
+        | Statement::ContinueStatement(_) => true,
+        Statement::BlockStatement(block) => block.body.last().is_some_and(exits),
+        Statement::IfStatement(if_stmt) => {
+            exits(&if_stmt.consequent) && if_stmt.alternate.as_ref().is_some_and(exits)
+        }
+        _ => false,
+    }
+}
+
+/// What kind of region a guard controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardKind {
+    /// The consequent of an `if`.
+    If,
+    /// The `else` branch of an `if`.
+    Else,
+    /// The statements after `if (…) return`.
+    EarlyReturn,
+    /// An arm of a `?:` ternary.
+    Ternary,
+    /// The right side of a `cond && expr` or `cond || expr` statement.
+    Logical,
+    /// A `switch` case body.
+    Switch,
+}
+
+/// A region of code that only runs on some platforms.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlatformGuard {
+    /// What kind of region the test controls.
+    pub kind: GuardKind,
+
+    /// Test as written, e.g. `process.platform === "darwin"`.
+    pub condition: String,
+
+    /// Platforms the region runs on, narrowed by the guards around it.
+    pub platforms: Vec<Platform>,
+
+    /// The guarded code.
+    pub region: SourceSpan,
+
+    /// Last line of the region.
+    pub end_line: usize,
+
+    /// Innermost named function around the region.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+
+    /// First distinct named callees in the region.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<String>,
+}
+
+/// Every platform-specific region, and the number of regions per platform.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlatformInventory {
+    /// Regions in source order.
+    pub guards: Vec<PlatformGuard>,
+
+    /// Regions that run on each platform.
+    pub platforms: BTreeMap<Platform, usize>,
+}
+
+impl PlatformInventory {
+    /// Regions that run on a platform.
+    pub fn on(&self, platform: Platform) -> impl Iterator<Item = &PlatformGuard> {
+        self.guards.iter().filter(move |g| g.platforms.contains(&platform))
+    }
+}
+
+/// Comma-separated platform names.
+fn names(platforms: &[Platform]) -> String {
+    platforms.iter().map(|p| p.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+/// Render the inventory as Markdown, one table per platform.
+pub fn render_markdown(inventory: &PlatformInventory) -> String {
+    let mut out = String::from("# Platform-Specific Code\n");
+
+    for platform in Platform::ALL {
+        let guards: Vec<_> = inventory.on(platform).collect();
+        if guards.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n## {} ({})\n\n", platform, guards.len()));
+        out.push_str("| Lines | Kind | Condition | Platforms | Function | Calls |\n");
+        out.push_str("|-------|------|-----------|-----------|----------|-------|\n");
+        for guard in guards {
+            out.push_str(&format!(
+                "| {}-{} | {:?} | `{}` | {} | {} | {} |\n",
+                guard.region.line,
+                guard.end_line,
+                guard.kind,
+                guard.condition.replace('|', "\\|"),
+                names(&guard.platforms),
+                guard.function.as_deref().map(|f| format!("`{}`", f)).unwrap_or_default(),
+                guard.calls.join(", "),
+            ));
+        }
+    }
+
+    out
+}
+
+/// Strip the regions that don't run on `platform` from the source they were found in.
+///
+/// Branches become empty blocks, expressions `void 0` and statement runs a
+/// comment, each naming the platforms the stripped code was for.
+pub fn slice(source: &str, inventory: &PlatformInventory, platform: Platform) -> String {
+    let mut guards: Vec<&PlatformGuard> = inventory.guards.iter().filter(|g| !g.platforms.contains(&platform)).collect();
+    guards.sort_by_key(|g| (g.region.start, std::cmp::Reverse(g.region.end)));
+
+    let mut out = String::with_capacity(source.len());
+    let mut at = 0;
+    for guard in guards {
+        let (start, end) = (guard.region.start as usize, guard.region.end as usize);
+        if start < at {
+            continue;
+        }
+        let note = match guard.platforms.as_slice() {
+            [] => "unreachable".to_string(),
+            platforms => format!("{} only", names(platforms)),
+        };
+        out.push_str(&source[at..start]);
+        out.push_str(&match guard.kind {
+            GuardKind::If | GuardKind::Else => format!("{{ /* {} */ }}", note),
+            GuardKind::Ternary | GuardKind::Logical => format!("void 0 /* {} */", note),
+            GuardKind::EarlyReturn | GuardKind::Switch => format!("/* {} */", note),
+        });
+        at = end;
+    }
+    out.push_str(&source[at..]);
+    out
+}
+
+/// Parse source code and strip the regions that don't run on `platform`.
+pub fn slice_source(source: &str, platform: Platform) -> Result<String> {
+    let allocator = Allocator::default();
+    let parser = Parser::new(source.to_string());
+    let parse_result = parser.parse(&allocator)?;
+    let analyzer = Analyzer::new(parse_result.program());
+    let symbol_table = SymbolTable::new(parse_result.program());
+    let inventory = PlatformExtractor::new(&analyzer, &symbol_table).extract()?;
+    Ok(slice(source, &inventory, platform))
+}
+
+/// A guarded region before narrowing by the guards around it.
+struct Region {
+    kind: GuardKind,
+    condition: String,
+    span: Span,
+    platforms: PlatformSet,
+}
+
+/// Extractor for platform-specific code paths.
+pub struct PlatformExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+    functions: HashMap<&'a str, &'a Function<'a>>,
+    modules: ModuleAliases,
+    source_text: &'a str,
+}
+
+impl<'a> PlatformExtractor<'a> {
+    /// Create a new platform extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        let program = analyzer.program();
+        Self {
+            analyzer,
+            modules: ModuleAliases::collect(program, symbol_table),
+            symbol_table,
+            functions: top_level_functions(program),
+            source_text: program.source_text,
+        }
+    }
+
+    /// Collect every platform-specific region in source order.
+    pub fn extract(&self) -> Result<PlatformInventory> {
+        debug!("Extracting platform-specific code paths");
+
+        let mut regions = Vec::new();
+        let program = self.analyzer.program();
+        let functions = self.analyzer.find_functions();
+        for body in std::iter::once(program.body.as_slice()).chain(functions.iter().map(|f| f.statements)) {
+            walk_statements(body, &mut Vec::new(), &mut |stmts, _| self.visit_statements(stmts, &mut regions));
+        }
+        for expr in self.analyzer.find_expressions(|expr| matches!(expr, Expression::ConditionalExpression(_))) {
+            let Expression::ConditionalExpression(cond) = expr else {
+                continue;
+            };
+            let condition = self.label(cond.test.span());
+            self.push(&mut regions, GuardKind::Ternary, &condition, cond.consequent.span(), self.holds(&cond.test, true, 0));
+            self.push(&mut regions, GuardKind::Ternary, &condition, cond.alternate.span(), self.holds(&cond.test, false, 0));
+        }
+        regions.sort_by_key(|r| (r.span.start, std::cmp::Reverse(r.span.end)));
+
+        let mut calls: Vec<(Span, String)> = self
+            .analyzer
+            .find_expressions(|expr| matches!(expr, Expression::CallExpression(_)))
+            .into_iter()
+            .filter_map(|expr| match expr {
+                Expression::CallExpression(call) => Some((call.span, callee_name(&call.callee)?)),
+                _ => None,
+            })
+            .collect();
+        calls.sort_by_key(|(span, _)| span.start);
+
+        let mut inventory = PlatformInventory::default();
+        for region in &regions {
+            // Narrow by every region around this one
+            let platforms = regions
+                .iter()
+                .filter(|outer| !std::ptr::eq(*outer, region))
+                .filter(|outer| outer.span.start <= region.span.start && region.span.end <= outer.span.end)
+                .fold(region.platforms, |set, outer| PlatformSet(set.0 & outer.platforms.0))
+                .platforms();
+
+            let first = calls.partition_point(|(span, _)| span.start < region.span.start);
+            let mut callees: Vec<String> = Vec::new();
+            for (span, name) in calls[first..].iter().take_while(|(span, _)| span.start < region.span.end) {
+                if span.end <= region.span.end && !callees.contains(name) && callees.len() < MAX_CALLS {
+                    callees.push(name.clone());
+                }
+            }
+
+            let guard = PlatformGuard {
+                kind: region.kind,
+                condition: region.condition.clone(),
+                region: SourceSpan::locate(self.analyzer.line_index(), region.span),
+                end_line: self.analyzer.line_index().locate(region.span.end).0,
+                function: self.analyzer.context().function_at(region.span).map(str::to_string),
+                calls: callees,
+                platforms,
+            };
+            trace!("{:?} region at line {} runs on {:?}", guard.kind, guard.region.line, guard.platforms);
+            for platform in &guard.platforms {
+                *inventory.platforms.entry(*platform).or_default() += 1;
+            }
+            inventory.guards.push(guard);
+        }
+
+        debug!("Found {} platform-specific regions", inventory.guards.len());
+        Ok(inventory)
+    }
+
+    /// Record the guarded regions among a list of statements.
+    fn visit_statements(&self, stmts: &'a [Statement<'a>], regions: &mut Vec<Region>) {
+        for (i, stmt) in stmts.iter().enumerate() {
+            match stmt {
+                Statement::IfStatement(if_stmt) => {
+                    let condition = self.label(if_stmt.test.span());
+                    let then = self.holds(&if_stmt.test, true, 0);
+                    let otherwise = self.holds(&if_stmt.test, false, 0);
+                    self.push(regions, GuardKind::If, &condition, if_stmt.consequent.span(), then);
+                    match &if_stmt.alternate {
+                        Some(alt) => self.push(regions, GuardKind::Else, &condition, alt.span(), otherwise),
+                        None if exits(&if_stmt.consequent) => {
+                            if let (Some(next), Some(last)) = (stmts.get(i + 1), stmts.last()) {
+                                let rest = Span::new(next.span().start, last.span().end);
+                                self.push(regions, GuardKind::EarlyReturn, &condition, rest, otherwise);
+                            }
+                        }
+                        None => {}
+                    }
+                }
+                Statement::SwitchStatement(switch) => self.visit_switch(switch, regions),
+                Statement::ExpressionStatement(expr_stmt) => self.visit_logical(&expr_stmt.expression, regions),
+                _ => {}
+            }
+        }
+    }
+
+    /// Record `switch (process.platform)` case bodies, with fallthrough from empty cases.
+    fn visit_switch(&self, switch: &'a SwitchStatement<'a>, regions: &mut Vec<Region>) {
+        let Some(raw) = self.platform_value(&switch.discriminant, 0) else {
+            return;
+        };
+        let discriminant = self.label(switch.discriminant.span());
+        let case_set = |case: &'a SwitchCase<'a>| case.test.as_ref().and_then(string_value).map(|v| value_set(v, raw));
+        let named = switch.cases.iter().filter_map(case_set).fold(PlatformSet::NONE, |all, set| PlatformSet(all.0 | set.0));
+
+        let mut pending = Some(PlatformSet::NONE);
+        for case in &switch.cases {
+            let set = match &case.test {
+                Some(_) => case_set(case),
+                None => Some(named.complement()),
+            };
+            pending = join(pending, set);
+            let (Some(first), Some(last)) = (case.consequent.first(), case.consequent.last()) else {
+                continue;
+            };
+            let condition = match &case.test {
+                Some(test) => format!("{} === {}", discriminant, self.label(test.span())),
+                None => format!("{} (default)", discriminant),
+            };
+            let body = Span::new(first.span().start, last.span().end);
+            self.push(regions, GuardKind::Switch, &condition, body, pending);
+            pending = Some(PlatformSet::NONE);
+        }
+    }
+
+    /// Record the right side of `cond && expr` and `cond || expr` statements.
+    fn visit_logical(&self, expr: &'a Expression<'a>, regions: &mut Vec<Region>) {
+        match expr.without_parentheses() {
+            Expression::LogicalExpression(logical) if logical.operator != LogicalOperator::Coalesce => {
+                let when = logical.operator == LogicalOperator::And;
+                let condition = self.label(logical.left.span());
+                let platforms = self.holds(&logical.left, when, 0);
+                self.push(regions, GuardKind::Logical, &condition, logical.right.span(), platforms);
+            }
+            Expression::SequenceExpression(seq) => {
+                for expr in &seq.expressions {
+                    self.visit_logical(expr, regions);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push(&self, regions: &mut Vec<Region>, kind: GuardKind, condition: &str, span: Span, platforms: Option<PlatformSet>) {
+        if let Some(platforms) = platforms.filter(|p| *p != PlatformSet::ALL) {
+            regions.push(Region {
+                kind,
+                condition: condition.to_string(),
+                span,
+                platforms,
+            });
+        }
+    }
+
+    /// Platforms on which a test is truthy (or falsy); `None` when it isn't platform-specific.
+    fn holds(&self, test: &'a Expression<'a>, positive: bool, depth: usize) -> Option<PlatformSet> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return None;
+        }
+        match test.without_parentheses() {
+            Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::LogicalNot => {
+                self.holds(&unary.argument, !positive, depth + 1)
+            }
+            Expression::LogicalExpression(logical) if logical.operator != LogicalOperator::Coalesce => {
+                let left = self.holds(&logical.left, positive, depth + 1);
+                let right = self.holds(&logical.right, positive, depth + 1);
+                // `a && b` is truthy where both are and falsy where either is
+                if (logical.operator == LogicalOperator::And) == positive {
+                    meet(left, right)
+                } else {
+                    join(left, right)
+                }
+            }
+            Expression::BinaryExpression(binary) if binary.operator.is_equality() => {
+                let set = self.comparison(&binary.left, &binary.right, depth)?;
+                let equal = matches!(binary.operator, BinaryOperator::Equality | BinaryOperator::StrictEquality);
+                Some(if equal == positive { set } else { set.complement() })
+            }
+            other => {
+                let set = self.flag(other, depth)?;
+                Some(if positive { set } else { set.complement() })
+            }
+        }
+    }
+
+    /// Platforms on which a platform value equals a string.
+    fn comparison(&self, left: &'a Expression<'a>, right: &'a Expression<'a>, depth: usize) -> Option<PlatformSet> {
+        let (value, other) = match (string_value(left), string_value(right)) {
+            (Some(value), None) => (value, right),
+            (None, Some(value)) => (value, left),
+            _ => return None,
+        };
+        let raw = self.platform_value(other, depth + 1)?;
+        Some(value_set(value, raw))
+    }
+
+    /// Platforms on which a boolean-like atom is truthy.
+    fn flag(&self, expr: &'a Expression<'a>, depth: usize) -> Option<PlatformSet> {
+        let wsl = PlatformSet::of(&[Platform::Wsl]);
+        match expr {
+            Expression::Identifier(id) => {
+                if is_wsl_name(id.name.as_str()) {
+                    return Some(wsl);
+                }
+                let binding = self.symbol_table.get_binding(id.name.as_str())?;
+                if is_function_expr(binding) {
+                    return None;
+                }
+                self.holds(binding, true, depth + 1)
+            }
+            Expression::CallExpression(call) => {
+                if self.mentions_wsl(call) || callee_name(&call.callee).is_some_and(|name| is_wsl_name(&name)) {
+                    return Some(wsl);
+                }
+                let Expression::Identifier(id) = &call.callee else {
+                    return None;
+                };
+                // A helper is truthy wherever one of its returns is
+                let function = self.helper(id.name.as_str())?;
+                function.returns().into_iter().try_fold(PlatformSet::NONE, |set, returned| {
+                    let holds = match returned.without_parentheses() {
+                        Expression::BooleanLiteral(b) if !b.value => Some(PlatformSet::NONE),
+                        other => self.holds(other, true, depth + 1),
+                    };
+                    join(Some(set), holds)
+                })
+            }
+            Expression::StaticMemberExpression(member) if is_process_env(&member.object) => {
+                self.env_platform(member.property.name.as_str())
+            }
+            Expression::BinaryExpression(binary) if binary.operator == BinaryOperator::In && is_process_env(&binary.right) => {
+                self.env_platform(string_value(&binary.left)?)
+            }
+            _ => None,
+        }
+    }
+
+    fn env_platform(&self, name: &str) -> Option<PlatformSet> {
+        let (_, platform) = PLATFORM_ENV.iter().find(|(var, _)| *var == name)?;
+        Some(PlatformSet::of(&[*platform]))
+    }
+
+    /// `version.includes("microsoft")` and `/wsl/i.test(…)` style checks.
+    fn mentions_wsl(&self, call: &'a CallExpression<'a>) -> bool {
+        let Expression::StaticMemberExpression(member) = &call.callee else {
+            return false;
+        };
+        if !matches!(member.property.name.as_str(), "includes" | "test" | "match" | "startsWith" | "endsWith") {
+            return false;
+        }
+        let operands = std::iter::once(&member.object).chain(call.arguments.iter().filter_map(|a| a.as_expression()));
+        operands.into_iter().any(|operand| {
+            let text = match operand.without_parentheses() {
+                Expression::StringLiteral(s) => s.value.as_str(),
+                Expression::RegExpLiteral(re) => &self.source_text[re.span.start as usize..re.span.end as usize],
+                _ => return false,
+            };
+            let text = text.to_ascii_lowercase();
+            text.contains("microsoft") || text.contains("wsl")
+        })
+    }
+
+    /// Whether an expression is the platform: `Some(true)` for `process.platform`
+    /// or `os` values, `Some(false)` for helpers returning names like `"macos"`.
+    fn platform_value(&self, expr: &'a Expression<'a>, depth: usize) -> Option<bool> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return None;
+        }
+        match expr.without_parentheses() {
+            Expression::StaticMemberExpression(member) => {
+                let process = matches!(&member.object, Expression::Identifier(id) if id.name == "process");
+                (process && member.property.name == "platform").then_some(true)
+            }
+            Expression::Identifier(id) => {
+                let binding = self.symbol_table.get_binding(id.name.as_str())?;
+                self.platform_value(binding, depth + 1)
+            }
+            Expression::CallExpression(call) => {
+                if let Some((module, method)) = self.modules.module_method(self.symbol_table, &call.callee) {
+                    return (module == "os" && matches!(method.as_str(), "platform" | "type")).then_some(true);
+                }
+                let Expression::Identifier(id) = &call.callee else {
+                    return None;
+                };
+                let function = self.helper(id.name.as_str())?;
+                let returns = function.returns();
+                if returns.iter().any(|r| self.platform_value(r, depth + 1) == Some(true)) {
+                    return Some(true);
+                }
+                let names_platform = returns
+                    .iter()
+                    .filter_map(|r| string_value(r))
+                    .any(|v| PLATFORM_NAMES.contains(&v.to_ascii_lowercase().as_str()));
+                (names_platform || id.name.to_ascii_lowercase().contains("platform")).then_some(false)
+            }
+            _ => None,
+        }
+    }
+
+    /// A named helper, including memoized ones: `var X = memoize(() => …)`.
+    fn helper(&self, name: &str) -> Option<NamedFunction<'a>> {
+        if let Some(function) = find_function(&self.functions, self.symbol_table, name) {
+            return Some(function);
+        }
+        match self.symbol_table.get_binding(name)? {
+            Expression::CallExpression(call) if call.arguments.len() == 1 => {
+                NamedFunction::from_expression(call.arguments[0].as_expression()?)
+            }
+            _ => None,
+        }
+    }
+
+    /// Collapse whitespace and truncate source for use as a label.
+    fn label(&self, span: Span) -> String {
+        let source = &self.source_text[span.start as usize..span.end as usize];
+        let label = source.split_whitespace().collect::<Vec<_>>().join(" ");
+        if label.chars().count() <= MAX_LABEL_LEN {
+            return label;
+        }
+        let truncated: String = label.chars().take(MAX_LABEL_LEN).collect();
+        format!("{}…", truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    const CODE: &str = r#"
+        import { platform as Tq } from "os";
+        function getPlatform() {
+            if (process.platform === "darwin") return "macos";
+            if (process.platform === "win32") return "windows";
+            return "linux";
+        }
+        var isWsl = memoize(() => {
+            try {
+                return readFileSync("/proc/version", "utf8").toLowerCase().includes("microsoft");
+            } catch {
+                return false;
+            }
+        });
+        function openUrl(A) {
+            if (getPlatform() === "macos") {
+                spawn("open", [A]);
+            } else if (Tq() === "win32") {
+                spawn("cmd", ["/c", "start", A]);
+            } else {
+                if (isWsl()) spawn("wslview", [A]);
+                else spawn("xdg-open", [A]);
+            }
+        }
+        function keychain() {
+            if (process.platform !== "darwin") return null;
+            return readKeychain();
+        }
+        var shell = process.platform === "win32" ? "cmd.exe" : "/bin/sh";
+        switch (process.platform) {
+            case "linux":
+            case "freebsd":
+                useInotify();
+                break;
+            default:
+                useFsEvents();
+        }
+        process.env.WSL_DISTRO_NAME && fixPaths();
+    "#;
+
+    fn extract(code: &str) -> PlatformInventory {
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+        PlatformExtractor::new(&analyzer, &symbol_table).extract().unwrap()
+    }
+
+    /// Kind, first callee and platforms of every region.
+    fn regions(inventory: &PlatformInventory) -> Vec<(GuardKind, Option<&str>, Vec<Platform>)> {
+        inventory
+            .guards
+            .iter()
+            .map(|g| (g.kind, g.calls.first().map(String::as_str), g.platforms.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_extracts_platform_guards() {
+        let inventory = extract(CODE);
+        let regions: Vec<_> = inventory
+            .guards
+            .iter()
+            .filter(|g| g.function.as_deref() != Some("getPlatform"))
+            .map(|g| (g.kind, g.calls.first().map(String::as_str), g.platforms.clone()))
+            .collect();
+
+        use Platform::*;
+        assert_eq!(
+            regions,
+            [
+                (GuardKind::If, Some("spawn"), vec![Darwin]),
+                (GuardKind::Else, Some("Tq"), vec![Linux, Wsl, Win32, Other]),
+                (GuardKind::If, Some("spawn"), vec![Win32]),
+                (GuardKind::Else, Some("isWsl"), vec![Linux, Wsl, Other]),
+                (GuardKind::If, Some("spawn"), vec![Wsl]),
+                (GuardKind::Else, Some("spawn"), vec![Linux, Other]),
+                (GuardKind::If, None, vec![Linux, Wsl, Win32, Other]),
+                (GuardKind::EarlyReturn, Some("readKeychain"), vec![Darwin]),
+                (GuardKind::Ternary, None, vec![Win32]),
+                (GuardKind::Ternary, None, vec![Darwin, Linux, Wsl, Other]),
+                (GuardKind::Switch, Some("useInotify"), vec![Linux, Wsl, Other]),
+                (GuardKind::Switch, Some("useFsEvents"), vec![Darwin, Win32]),
+                (GuardKind::Logical, Some("fixPaths"), vec![Wsl]),
+            ]
+        );
+        assert_eq!(inventory.guards[0].function.as_deref(), Some("getPlatform"));
+        assert_eq!(inventory.platforms[&Darwin], 5);
+    }
+
+    #[test]
+    fn test_slices_other_platforms() {
+        let sliced = slice_source(CODE, Platform::Darwin).unwrap();
+
+        assert!(sliced.contains(r#"spawn("open", [A]);"#));
+        assert!(sliced.contains("else { /* linux, wsl, win32, other only */ }"));
+        assert!(!sliced.contains("xdg-open"));
+        assert!(sliced.contains(r#"process.platform === "win32" ? void 0 /* win32 only */ : "/bin/sh""#));
+        assert!(sliced.contains("/* linux, wsl, other only */"));
+        assert!(sliced.contains("process.env.WSL_DISTRO_NAME && void 0 /* wsl only */"));
+        assert!(sliced.contains("readKeychain()"));
+
+        let linux = slice_source(CODE, Platform::Linux).unwrap();
+        assert!(linux.contains("xdg-open") && !linux.contains("wslview") && !linux.contains("readKeychain"));
+    }
+
+    #[test]
+    fn test_narrows_nested_checks() {
+        let inventory = extract(
+            r#"
+            function notify(A) {
+                if (process.platform !== "win32") {
+                    if (process.platform === "darwin") osascript(A);
+                    else notifySend(A);
+                }
+            }
+        "#,
+        );
+
+        use Platform::*;
+        assert_eq!(
+            regions(&inventory),
+            [
+                (GuardKind::If, Some("osascript"), vec![Darwin, Linux, Wsl, Other]),
+                (GuardKind::If, Some("osascript"), vec![Darwin]),
+                (GuardKind::Else, Some("notifySend"), vec![Linux, Wsl, Other]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_negated_checks() {
+        let inventory = extract(
+            r#"
+            var isWindows = process.platform === "win32";
+            if (!(process.platform === "darwin")) noKeychain();
+            if (!isWindows) chmod();
+            !process.env.WSL_DISTRO_NAME && openBrowser();
+        "#,
+        );
+
+        use Platform::*;
+        assert_eq!(
+            regions(&inventory),
+            [
+                (GuardKind::If, Some("noKeychain"), vec![Linux, Wsl, Win32, Other]),
+                (GuardKind::If, Some("chmod"), vec![Darwin, Linux, Wsl, Other]),
+                (GuardKind::Logical, Some("openBrowser"), vec![Darwin, Linux, Win32, Other]),
+            ]
+        );
+    }
+}
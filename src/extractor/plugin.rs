@@ -0,0 +1,1083 @@
+//! Pluggable extractors.
+//!
+//! Prompts, tools and catalogs such as slash commands, the CLI, hooks and
+//! models are each an [`ExtractorPlugin`] registered in a [`PluginRegistry`]. The registry runs
+//! plugins in dependency order against one [`Extractor`], so they share its
+//! analyzer, context index and rules, and a plugin can read the outputs of
+//! the plugins it depends on. A downstream crate adds its own extractors by
+//! registering them next to the built-ins; [`OutputWriter`], the extraction
+//! summary and the dashboard pick up every output without further wiring.
+
+use super::{api_requests::ApiInventory, assembly::PromptAssembly, commander::{self, CliCommand}, commands::SlashCommand};
+use super::{errors::{self, ErrorCatalog}, filesystem::{self, FilesystemMap}, hooks::HookCatalog, models::ModelCatalog, network::{self, NetworkInventory}};
+use super::{platforms::{self, PlatformInventory}, processes::{self, ProcessInventory}, settings::SettingsSchema, templates::{self, PromptTemplate}};
+use super::{config::ConfigValue, examples, prompts::SystemPrompt, prompts_enhanced::EnhancedSystemPrompt, sections, strings::InterestingString};
+use super::{tools::ToolDefinition, Extractor};
+use crate::analyzer::SymbolTable;
+use crate::error::DecypherError;
+use crate::output::OutputWriter;
+use crate::tokens::{self, Tokenizer};
+use crate::Result;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+
+/// An extractor that can be registered and run by name.
+pub trait ExtractorPlugin {
+    /// What the plugin extracts.
+    type Output: Serialize + 'static;
+
+    /// Unique name; also the stem of the default output file.
+    fn name(&self) -> &str;
+
+    /// Plugins whose outputs this one reads; they run first, and run even
+    /// when not selected.
+    fn dependencies(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Plugins whose outputs this one reads when they run; they run first
+    /// when selected, but aren't run for this one.
+    fn optional_dependencies(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Extract from the shared context.
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output>;
+
+    /// Number of items in an output; empty outputs aren't written.
+    fn count(&self, output: &Self::Output) -> usize;
+
+    /// Files to write under `extracted/`; `<name>.json` by default.
+    fn files(&self, output: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![PluginFile::json(format!("{}.json", self.name()), output)?])
+    }
+}
+
+/// A file a plugin writes, relative to `extracted/`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginFile {
+    /// Path relative to `extracted/`.
+    pub path: String,
+
+    /// What to write.
+    pub contents: FileContents,
+}
+
+/// Contents of a [`PluginFile`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileContents {
+    /// Written as pretty-printed JSON.
+    Json(JsonValue),
+
+    /// Written as is, e.g. Markdown.
+    Text(String),
+}
+
+impl PluginFile {
+    /// A JSON file holding `value`.
+    pub fn json<T: Serialize + ?Sized>(path: impl Into<String>, value: &T) -> Result<Self> {
+        let value = serde_json::to_value(value).map_err(|e| DecypherError::Other(e.into()))?;
+        Ok(Self {
+            path: path.into(),
+            contents: FileContents::Json(value),
+        })
+    }
+
+    /// A text file.
+    pub fn text(path: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            contents: FileContents::Text(text.into()),
+        }
+    }
+}
+
+/// Object-safe view of a plugin, as the registry stores it.
+trait ErasedPlugin {
+    fn name(&self) -> &str;
+    fn dependencies(&self) -> &[&str];
+    fn optional_dependencies(&self) -> &[&str];
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<PluginOutput>;
+    fn files(&self, output: &PluginOutput) -> Result<Vec<PluginFile>>;
+}
+
+impl<P: ExtractorPlugin> ErasedPlugin for P {
+    fn name(&self) -> &str {
+        ExtractorPlugin::name(self)
+    }
+
+    fn dependencies(&self) -> &[&str] {
+        ExtractorPlugin::dependencies(self)
+    }
+
+    fn optional_dependencies(&self) -> &[&str] {
+        ExtractorPlugin::optional_dependencies(self)
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<PluginOutput> {
+        let value = ExtractorPlugin::run(self, cx)?;
+        let count = self.count(&value);
+        Ok(PluginOutput::new(value, count))
+    }
+
+    fn files(&self, output: &PluginOutput) -> Result<Vec<PluginFile>> {
+        match output.get::<P::Output>() {
+            Some(value) => ExtractorPlugin::files(self, value),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// What a plugin sees while it runs.
+pub struct PluginContext<'c, 'a> {
+    extractor: &'c Extractor<'a>,
+    options: &'c PluginOptions<'c>,
+    outputs: &'c PluginOutputs,
+}
+
+impl<'c, 'a> PluginContext<'c, 'a> {
+    /// The extractor, with its analyzer and rules.
+    pub fn extractor(&self) -> &'c Extractor<'a> {
+        self.extractor
+    }
+
+    /// Symbol table shared by every plugin.
+    pub fn symbol_table(&self) -> &'c SymbolTable<'a> {
+        self.extractor.symbol_table()
+    }
+
+    /// Tool definitions found structurally, shared by every plugin.
+    pub fn tools(&self) -> Result<&'c [ToolDefinition]> {
+        self.extractor.tools()
+    }
+
+    /// Variable renames applied to the beautified code, if any.
+    pub fn rename_map(&self) -> Option<&'c HashMap<String, String>> {
+        self.options.rename_map
+    }
+
+    /// Tokenizer for plugins that record token counts, if any.
+    pub fn tokenizer(&self) -> Option<&'c dyn Tokenizer> {
+        self.options.tokenizer
+    }
+
+    /// Output of a plugin that already ran.
+    pub fn output<T: 'static>(&self, name: &str) -> Option<&'c T> {
+        self.outputs.get_as(name)
+    }
+}
+
+/// Options for a registry run.
+#[derive(Default)]
+pub struct PluginOptions<'o> {
+    /// Variable renames, for plugins that name things after bindings.
+    pub rename_map: Option<&'o HashMap<String, String>>,
+
+    /// Tokenizer for prompt and tool token counts; none skips counting.
+    pub tokenizer: Option<&'o dyn Tokenizer>,
+
+    /// Run only these plugins and their dependencies; all when empty.
+    pub only: Vec<&'o str>,
+}
+
+/// Object-safe view of a plugin's typed result.
+trait ErasedOutput {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn to_json(&self) -> serde_json::Result<JsonValue>;
+}
+
+impl<T: Serialize + 'static> ErasedOutput for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn to_json(&self) -> serde_json::Result<JsonValue> {
+        serde_json::to_value(self)
+    }
+}
+
+/// A plugin's result and the number of items in it.
+pub struct PluginOutput {
+    value: Box<dyn ErasedOutput>,
+    count: usize,
+}
+
+impl PluginOutput {
+    /// Wrap a result holding `count` items.
+    fn new<T: Serialize + 'static>(value: T, count: usize) -> Self {
+        Self {
+            value: Box::new(value),
+            count,
+        }
+    }
+
+    /// Number of items extracted.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Whether nothing was extracted.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The typed result.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.value.as_any().downcast_ref()
+    }
+
+    /// The typed result, for post-processing before it's written.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.value.as_any_mut().downcast_mut()
+    }
+
+    /// The result as JSON.
+    pub fn to_json(&self) -> Result<JsonValue> {
+        self.value.to_json().map_err(|e| DecypherError::Other(e.into()))
+    }
+}
+
+/// Outputs of a registry run, in run order.
+#[derive(Default)]
+pub struct PluginOutputs {
+    entries: Vec<(String, PluginOutput)>,
+}
+
+impl PluginOutputs {
+    /// Output of the named plugin.
+    pub fn get(&self, name: &str) -> Option<&PluginOutput> {
+        self.entries.iter().find(|(n, _)| n == name).map(|(_, output)| output)
+    }
+
+    /// Typed output of the named plugin.
+    pub fn get_as<T: 'static>(&self, name: &str) -> Option<&T> {
+        self.get(name)?.get()
+    }
+
+    /// Mutable typed output of the named plugin.
+    pub fn get_mut_as<T: 'static>(&mut self, name: &str) -> Option<&mut T> {
+        self.entries.iter_mut().find(|(n, _)| n == name)?.1.get_mut()
+    }
+
+    /// Items of a plugin whose output is a `Vec<T>`; empty if it didn't run.
+    pub fn items<T: 'static>(&self, name: &str) -> &[T] {
+        self.get_as::<Vec<T>>(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Item count of the named plugin, zero if it didn't run.
+    pub fn count(&self, name: &str) -> usize {
+        self.get(name).map_or(0, PluginOutput::count)
+    }
+
+    /// Plugin names and outputs in run order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PluginOutput)> {
+        self.entries.iter().map(|(name, output)| (name.as_str(), output))
+    }
+
+    /// Number of outputs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no plugin ran.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Registered extractor plugins.
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Box<dyn ErasedPlugin>>,
+}
+
+impl PluginRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with every built-in extractor.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register_builtin(PromptsPlugin);
+        registry.register_builtin(ToolsPlugin);
+        registry.register_builtin(ExamplesPlugin);
+        registry.register_builtin(ConfigsPlugin);
+        registry.register_builtin(StringsPlugin);
+        registry.register_builtin(CommandsPlugin);
+        registry.register_builtin(CliPlugin);
+        registry.register_builtin(SettingsPlugin);
+        registry.register_builtin(HooksPlugin);
+        registry.register_builtin(ModelsPlugin);
+        registry.register_builtin(ApiRequestsPlugin);
+        registry.register_builtin(ErrorsPlugin);
+        registry.register_builtin(NetworkPlugin);
+        registry.register_builtin(FilesystemPlugin);
+        registry.register_builtin(ProcessesPlugin);
+        registry.register_builtin(PlatformsPlugin);
+        registry.register_builtin(PromptTemplatesPlugin);
+        registry.register_builtin(PromptAssemblyPlugin);
+        registry
+    }
+
+    fn register_builtin(&mut self, plugin: impl ExtractorPlugin + 'static) {
+        self.register(plugin).expect("built-in plugin names are unique");
+    }
+
+    /// Add a plugin; names must be unique.
+    pub fn register(&mut self, plugin: impl ExtractorPlugin + 'static) -> Result<()> {
+        if self.contains(plugin.name()) {
+            return Err(DecypherError::extraction(
+                format!("plugin '{}'", plugin.name()),
+                "a plugin with this name is already registered",
+            ));
+        }
+        self.plugins.push(Box::new(plugin));
+        Ok(())
+    }
+
+    /// Whether a plugin with this name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.plugins.iter().any(|p| p.name() == name)
+    }
+
+    /// Registered plugin names, in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.plugins.iter().map(|p| p.name())
+    }
+
+    /// Run plugins in dependency order.
+    pub fn run(&self, extractor: &Extractor<'_>, options: &PluginOptions<'_>) -> Result<PluginOutputs> {
+        let mut outputs = PluginOutputs::default();
+        for idx in self.schedule(&options.only)? {
+            let plugin = &self.plugins[idx];
+            info!("Running {} extractor...", plugin.name());
+            let output = plugin.run(&PluginContext {
+                extractor,
+                options,
+                outputs: &outputs,
+            })?;
+            outputs.entries.push((plugin.name().to_string(), output));
+        }
+        Ok(outputs)
+    }
+
+    /// Write the files of every non-empty output.
+    pub fn write(&self, outputs: &PluginOutputs, writer: &OutputWriter) -> Result<()> {
+        self.write_with(outputs, writer, |_| {})
+    }
+
+    /// Write the files of every non-empty output, passing JSON files through `edit` first.
+    pub fn write_with(&self, outputs: &PluginOutputs, writer: &OutputWriter, mut edit: impl FnMut(&mut JsonValue)) -> Result<()> {
+        for (name, output) in outputs.iter().filter(|(_, output)| !output.is_empty()) {
+            if let Some(plugin) = self.plugins.iter().find(|p| p.name() == name) {
+                let mut files = plugin.files(output)?;
+                for file in &mut files {
+                    if let FileContents::Json(value) = &mut file.contents {
+                        edit(value);
+                    }
+                }
+                writer.write_plugin_output(name, output.count(), &files)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Plugin indices with every dependency before its dependents, otherwise
+    /// in registration order.
+    fn schedule(&self, only: &[&str]) -> Result<Vec<usize>> {
+        let index_of = |name: &str, needed_by: Option<&str>| {
+            self.plugins.iter().position(|p| p.name() == name).ok_or_else(|| match needed_by {
+                Some(by) => DecypherError::extraction(format!("plugin '{}'", by), format!("unknown dependency '{}'", name)),
+                None => DecypherError::extraction(format!("plugin '{}'", name), "not registered"),
+            })
+        };
+        let mut roots: Vec<usize> = if only.is_empty() {
+            (0..self.plugins.len()).collect()
+        } else {
+            only.iter().map(|name| index_of(name, None)).collect::<Result<_>>()?
+        };
+        roots.sort_unstable();
+        roots.dedup();
+
+        // Everything that runs: the roots and their dependencies
+        let mut selected = HashSet::new();
+        let mut pending = roots.clone();
+        while let Some(idx) = pending.pop() {
+            if selected.insert(idx) {
+                let plugin = &self.plugins[idx];
+                for dep in plugin.dependencies() {
+                    pending.push(index_of(dep, Some(plugin.name()))?);
+                }
+            }
+        }
+        // Optional dependencies order the plugins that run without adding any
+        let edges = |idx: usize| -> Result<Vec<usize>> {
+            let plugin = &self.plugins[idx];
+            let mut deps: Vec<usize> =
+                plugin.dependencies().iter().map(|dep| index_of(dep, Some(plugin.name()))).collect::<Result<_>>()?;
+            deps.extend(
+                plugin
+                    .optional_dependencies()
+                    .iter()
+                    .filter_map(|dep| self.plugins.iter().position(|p| p.name() == *dep))
+                    .filter(|dep| selected.contains(dep)),
+            );
+            Ok(deps)
+        };
+
+        // Depth-first, with the current path to report cycles
+        let mut order = Vec::new();
+        let mut done = HashSet::new();
+        let mut path: Vec<usize> = Vec::new();
+        let mut stack: Vec<(usize, usize)> = roots.into_iter().rev().map(|root| (root, 0)).collect();
+        while let Some((idx, next_dep)) = stack.pop() {
+            if next_dep == 0 {
+                if done.contains(&idx) {
+                    continue;
+                }
+                if path.contains(&idx) {
+                    let cycle: Vec<&str> = path.iter().chain([&idx]).map(|&i| self.plugins[i].name()).collect();
+                    return Err(DecypherError::extraction(
+                        format!("plugin '{}'", self.plugins[idx].name()),
+                        format!("dependency cycle {}", cycle.join(" -> ")),
+                    ));
+                }
+                path.push(idx);
+            }
+
+            match edges(idx)?.get(next_dep).copied() {
+                Some(dep) => {
+                    stack.push((idx, next_dep + 1));
+                    if !done.contains(&dep) {
+                        stack.push((dep, 0));
+                    }
+                }
+                None => {
+                    path.pop();
+                    if done.insert(idx) {
+                        order.push(idx);
+                    }
+                }
+            }
+        }
+        Ok(order)
+    }
+}
+
+/// System prompts, with near-duplicates clustered and sections outlined.
+struct PromptsPlugin;
+
+impl ExtractorPlugin for PromptsPlugin {
+    type Output = Vec<EnhancedSystemPrompt>;
+
+    fn name(&self) -> &str {
+        "prompts"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        let mut prompts = cx.extractor().extract_prompts_enhanced()?;
+        if let Some(tokenizer) = cx.tokenizer() {
+            tokens::count_prompts(tokenizer, &mut prompts);
+        }
+        Ok(prompts)
+    }
+
+    fn count(&self, prompts: &Self::Output) -> usize {
+        prompts.len()
+    }
+
+    fn files(&self, enhanced: &Self::Output) -> Result<Vec<PluginFile>> {
+        let prompts: Vec<SystemPrompt> = enhanced.iter().map(SystemPrompt::from).collect();
+        let mut files = vec![PluginFile::json("system-prompts.json", &prompts)?];
+
+        let clustered: Vec<_> = enhanced.iter().filter(|p| !p.near_duplicates.is_empty()).collect();
+        if !clustered.is_empty() {
+            files.push(PluginFile::json("prompt-clusters.json", &clustered)?);
+        }
+
+        let outlines = sections::outline_prompts(&prompts);
+        if !outlines.is_empty() {
+            files.push(PluginFile::json("prompt-sections.json", &outlines)?);
+            for outline in &outlines {
+                let path = format!("prompt-sections/{}.md", outline.prompt_id);
+                files.push(PluginFile::text(path, outline.render_markdown()));
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// Tool definitions, enriched with the prompts plugin's output when it runs.
+struct ToolsPlugin;
+
+impl ExtractorPlugin for ToolsPlugin {
+    type Output = Vec<ToolDefinition>;
+
+    fn name(&self) -> &str {
+        "tools"
+    }
+
+    fn optional_dependencies(&self) -> &[&str] {
+        &["prompts"]
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        let prompts = cx.output::<Vec<EnhancedSystemPrompt>>("prompts").map_or(&[][..], Vec::as_slice);
+        let mut tools = cx.extractor().extract_tools_with_enhanced_prompts(prompts)?;
+        if let Some(tokenizer) = cx.tokenizer() {
+            tokens::count_tools(tokenizer, &mut tools);
+        }
+        Ok(tools)
+    }
+
+    fn count(&self, tools: &Self::Output) -> usize {
+        tools.len()
+    }
+
+    fn files(&self, tools: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![PluginFile::json("tool-definitions.json", tools)?])
+    }
+}
+
+/// Prompt examples as a JSONL dataset, from the prompts and tools that run.
+struct ExamplesPlugin;
+
+impl ExtractorPlugin for ExamplesPlugin {
+    type Output = Vec<examples::ExampleRecord>;
+
+    fn name(&self) -> &str {
+        "examples"
+    }
+
+    fn optional_dependencies(&self) -> &[&str] {
+        &["prompts", "tools"]
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        let prompts: Vec<SystemPrompt> = cx
+            .output::<Vec<EnhancedSystemPrompt>>("prompts")
+            .map_or_else(Vec::new, |p| p.iter().map(SystemPrompt::from).collect());
+        let tools = cx.output::<Vec<ToolDefinition>>("tools").map_or(&[][..], Vec::as_slice);
+        Ok(examples::collect_examples(&prompts, tools))
+    }
+
+    fn count(&self, examples: &Self::Output) -> usize {
+        examples.len()
+    }
+
+    fn files(&self, examples: &Self::Output) -> Result<Vec<PluginFile>> {
+        let mut jsonl = String::new();
+        for example in examples {
+            jsonl.push_str(&serde_json::to_string(example).map_err(|e| DecypherError::Other(e.into()))?);
+            jsonl.push('\n');
+        }
+        Ok(vec![PluginFile::text("examples.jsonl", jsonl)])
+    }
+}
+
+/// Configuration values.
+struct ConfigsPlugin;
+
+impl ExtractorPlugin for ConfigsPlugin {
+    type Output = Vec<ConfigValue>;
+
+    fn name(&self) -> &str {
+        "configs"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_configs()
+    }
+
+    fn count(&self, configs: &Self::Output) -> usize {
+        configs.len()
+    }
+
+    fn files(&self, configs: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![PluginFile::json("configurations.json", configs)?])
+    }
+}
+
+/// Interesting string literals.
+struct StringsPlugin;
+
+impl ExtractorPlugin for StringsPlugin {
+    type Output = Vec<InterestingString>;
+
+    fn name(&self) -> &str {
+        "strings"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_strings()
+    }
+
+    fn count(&self, strings: &Self::Output) -> usize {
+        strings.len()
+    }
+}
+
+/// Built-in slash commands.
+struct CommandsPlugin;
+
+impl ExtractorPlugin for CommandsPlugin {
+    type Output = Vec<SlashCommand>;
+
+    fn name(&self) -> &str {
+        "commands"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_commands()
+    }
+
+    fn count(&self, commands: &Self::Output) -> usize {
+        commands.len()
+    }
+}
+
+/// The bundle's own commander CLI, with its rendered `--help`.
+struct CliPlugin;
+
+impl ExtractorPlugin for CliPlugin {
+    type Output = Vec<CliCommand>;
+
+    fn name(&self) -> &str {
+        "cli"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_cli()
+    }
+
+    fn count(&self, cli: &Self::Output) -> usize {
+        cli.len()
+    }
+
+    fn files(&self, cli: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![
+            PluginFile::json("cli.json", cli)?,
+            PluginFile::text("cli-help.txt", commander::render_help(cli)),
+        ])
+    }
+}
+
+/// Settings schema and permission rule grammar, as JSON Schema and Markdown.
+struct SettingsPlugin;
+
+impl ExtractorPlugin for SettingsPlugin {
+    type Output = Option<SettingsSchema>;
+
+    fn name(&self) -> &str {
+        "settings-schema"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_settings_schema()
+    }
+
+    /// Top-level settings, or one for a schema without properties.
+    fn count(&self, settings: &Self::Output) -> usize {
+        settings.as_ref().map_or(0, |s| {
+            s.schema["properties"].as_object().map_or(1, |p| p.len().max(1))
+        })
+    }
+
+    fn files(&self, settings: &Self::Output) -> Result<Vec<PluginFile>> {
+        let Some(settings) = settings else {
+            return Ok(Vec::new());
+        };
+        Ok(vec![
+            PluginFile::json("settings-schema.json", &settings.to_json_schema())?,
+            PluginFile::text("settings-schema.md", settings.render_markdown()),
+        ])
+    }
+}
+
+/// Hook events and payloads.
+struct HooksPlugin;
+
+impl ExtractorPlugin for HooksPlugin {
+    type Output = HookCatalog;
+
+    fn name(&self) -> &str {
+        "hooks"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_hooks()
+    }
+
+    fn count(&self, hooks: &Self::Output) -> usize {
+        hooks.events.len()
+    }
+}
+
+/// Model catalog.
+struct ModelsPlugin;
+
+impl ExtractorPlugin for ModelsPlugin {
+    type Output = ModelCatalog;
+
+    fn name(&self) -> &str {
+        "models"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_models()
+    }
+
+    fn count(&self, models: &Self::Output) -> usize {
+        models.models.len()
+    }
+}
+
+/// Messages API call sites, beta flags and headers.
+struct ApiRequestsPlugin;
+
+impl ExtractorPlugin for ApiRequestsPlugin {
+    type Output = ApiInventory;
+
+    fn name(&self) -> &str {
+        "api-requests"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_api_requests()
+    }
+
+    fn count(&self, inventory: &Self::Output) -> usize {
+        inventory.call_sites.len() + inventory.betas.len()
+    }
+}
+
+/// Throw sites, rejections and error reports.
+struct ErrorsPlugin;
+
+impl ExtractorPlugin for ErrorsPlugin {
+    type Output = ErrorCatalog;
+
+    fn name(&self) -> &str {
+        "errors"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_errors()
+    }
+
+    fn count(&self, catalog: &Self::Output) -> usize {
+        catalog.sites.len()
+    }
+
+    fn files(&self, catalog: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![
+            PluginFile::json("errors.json", catalog)?,
+            PluginFile::text("errors.md", errors::render_markdown(catalog)),
+        ])
+    }
+}
+
+/// HTTP and WebSocket endpoints grouped by host.
+struct NetworkPlugin;
+
+impl ExtractorPlugin for NetworkPlugin {
+    type Output = NetworkInventory;
+
+    fn name(&self) -> &str {
+        "network"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_network()
+    }
+
+    fn count(&self, inventory: &Self::Output) -> usize {
+        inventory.endpoints.len()
+    }
+
+    fn files(&self, inventory: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![
+            PluginFile::json("network.json", inventory)?,
+            PluginFile::text("network.md", network::render_markdown(inventory)),
+        ])
+    }
+}
+
+/// Filesystem accesses grouped by path root.
+struct FilesystemPlugin;
+
+impl ExtractorPlugin for FilesystemPlugin {
+    type Output = FilesystemMap;
+
+    fn name(&self) -> &str {
+        "filesystem"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_filesystem()
+    }
+
+    fn count(&self, map: &Self::Output) -> usize {
+        map.accesses.len()
+    }
+
+    fn files(&self, map: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![
+            PluginFile::json("filesystem.json", map)?,
+            PluginFile::text("filesystem.md", filesystem::render_markdown(map)),
+        ])
+    }
+}
+
+/// External binaries with argument templates and sandboxing.
+struct ProcessesPlugin;
+
+impl ExtractorPlugin for ProcessesPlugin {
+    type Output = ProcessInventory;
+
+    fn name(&self) -> &str {
+        "processes"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_processes()
+    }
+
+    fn count(&self, inventory: &Self::Output) -> usize {
+        inventory.calls.len()
+    }
+
+    fn files(&self, inventory: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![
+            PluginFile::json("processes.json", inventory)?,
+            PluginFile::text("processes.md", processes::render_markdown(inventory)),
+        ])
+    }
+}
+
+/// Code regions that only run on some platforms.
+struct PlatformsPlugin;
+
+impl ExtractorPlugin for PlatformsPlugin {
+    type Output = PlatformInventory;
+
+    fn name(&self) -> &str {
+        "platforms"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_platforms()
+    }
+
+    fn count(&self, inventory: &Self::Output) -> usize {
+        inventory.guards.len()
+    }
+
+    fn files(&self, inventory: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![
+            PluginFile::json("platforms.json", inventory)?,
+            PluginFile::text("platforms.md", platforms::render_markdown(inventory)),
+        ])
+    }
+}
+
+/// Prompt templates, with placeholders named after renamed bindings.
+struct PromptTemplatesPlugin;
+
+impl ExtractorPlugin for PromptTemplatesPlugin {
+    type Output = Vec<PromptTemplate>;
+
+    fn name(&self) -> &str {
+        "prompt-templates"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_prompt_templates(cx.rename_map())
+    }
+
+    fn count(&self, templates: &Self::Output) -> usize {
+        templates.len()
+    }
+
+    fn files(&self, templates: &Self::Output) -> Result<Vec<PluginFile>> {
+        Ok(vec![
+            PluginFile::json("prompt-templates.json", templates)?,
+            PluginFile::text("prompt-templates.md", templates::render_markdown(templates)),
+        ])
+    }
+}
+
+/// Traced prompt assembly graphs.
+struct PromptAssemblyPlugin;
+
+impl ExtractorPlugin for PromptAssemblyPlugin {
+    type Output = Vec<PromptAssembly>;
+
+    fn name(&self) -> &str {
+        "prompt-assembly"
+    }
+
+    fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+        cx.extractor().extract_prompt_assemblies()
+    }
+
+    fn count(&self, assemblies: &Self::Output) -> usize {
+        assemblies.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::Analyzer;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    /// Counts string literals.
+    struct LiteralsPlugin;
+
+    impl ExtractorPlugin for LiteralsPlugin {
+        type Output = Vec<String>;
+
+        fn name(&self) -> &str {
+            "literals"
+        }
+
+        fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+            Ok(cx
+                .extractor()
+                .analyzer()
+                .find_string_literals()
+                .iter()
+                .map(|l| l.value.to_string())
+                .collect())
+        }
+
+        fn count(&self, literals: &Self::Output) -> usize {
+            literals.len()
+        }
+
+        fn files(&self, literals: &Self::Output) -> Result<Vec<PluginFile>> {
+            Ok(vec![
+                PluginFile::json("literals/all.json", literals)?,
+                PluginFile::text("literals/first.txt", literals[0].as_str()),
+            ])
+        }
+    }
+
+    /// Reads the literals and the slash commands.
+    struct SummaryPlugin(&'static [&'static str]);
+
+    impl ExtractorPlugin for SummaryPlugin {
+        type Output = (usize, usize);
+
+        fn name(&self) -> &str {
+            "summary"
+        }
+
+        fn dependencies(&self) -> &[&str] {
+            self.0
+        }
+
+        fn run(&self, cx: &PluginContext<'_, '_>) -> Result<Self::Output> {
+            let literals = cx.output::<Vec<String>>("literals").map_or(0, Vec::len);
+            let commands = cx.output::<Vec<SlashCommand>>("commands").map_or(0, Vec::len);
+            Ok((literals, commands))
+        }
+
+        fn count(&self, _: &Self::Output) -> usize {
+            1
+        }
+    }
+
+    #[test]
+    fn test_runs_plugins_in_dependency_order() {
+        let code = r#"var a = "one", b = "two";"#;
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let extractor = Extractor::new(Analyzer::new(parse_result.program()));
+
+        let mut registry = PluginRegistry::builtin();
+        // Registered before its dependency; still runs after it
+        registry.register(SummaryPlugin(&["literals", "commands"])).unwrap();
+        registry.register(LiteralsPlugin).unwrap();
+        assert!(registry.register(LiteralsPlugin).is_err());
+
+        let options = PluginOptions {
+            only: vec!["summary"],
+            ..Default::default()
+        };
+        let outputs = registry.run(&extractor, &options).unwrap();
+        let names: Vec<&str> = outputs.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["literals", "commands", "summary"]);
+        assert_eq!(outputs.get_as::<(usize, usize)>("summary"), Some(&(2, 0)));
+        assert_eq!(outputs.get("literals").unwrap().to_json().unwrap(), serde_json::json!(["one", "two"]));
+
+        let mut cyclic = PluginRegistry::new();
+        cyclic.register(SummaryPlugin(&["summary"])).unwrap();
+        let error = cyclic.run(&extractor, &PluginOptions::default()).err().unwrap();
+        assert!(error.to_string().contains("dependency cycle summary -> summary"));
+    }
+
+    #[test]
+    fn test_orders_optional_dependencies() {
+        let code = r#"var a = "one", b = "two";"#;
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let extractor = Extractor::new(Analyzer::new(parse_result.program()));
+
+        // Registered in reverse; every plugin still sees the outputs it reads
+        let mut registry = PluginRegistry::new();
+        registry.register(ExamplesPlugin).unwrap();
+        registry.register(ToolsPlugin).unwrap();
+        registry.register(PromptsPlugin).unwrap();
+
+        let run = |only: Vec<&str>| -> Vec<String> {
+            let outputs = registry.run(&extractor, &PluginOptions { only, ..Default::default() }).unwrap();
+            outputs.iter().map(|(name, _)| name.to_string()).collect()
+        };
+        assert_eq!(run(Vec::new()), ["prompts", "tools", "examples"]);
+        assert_eq!(run(vec!["examples", "prompts"]), ["prompts", "examples"]);
+        // Optional dependencies aren't pulled in
+        assert_eq!(run(vec!["tools"]), ["tools"]);
+    }
+
+    #[test]
+    fn test_writes_plugin_files() {
+        let code = r#"var a = "one", b = "two";"#;
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let extractor = Extractor::new(Analyzer::new(parse_result.program()));
+
+        let mut registry = PluginRegistry::new();
+        registry.register(LiteralsPlugin).unwrap();
+        registry.register(SummaryPlugin(&[])).unwrap();
+        let outputs = registry.run(&extractor, &PluginOptions::default()).unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let writer = OutputWriter::new(temp_dir.path());
+        writer.create_structure().unwrap();
+        registry.write(&outputs, &writer).unwrap();
+
+        let extracted = temp_dir.path().join("extracted");
+        let all = std::fs::read_to_string(extracted.join("literals/all.json")).unwrap();
+        assert_eq!(serde_json::from_str::<JsonValue>(&all).unwrap(), serde_json::json!(["one", "two"]));
+        assert_eq!(std::fs::read_to_string(extracted.join("literals/first.txt")).unwrap(), "one");
+        // Without its own files a plugin writes `<name>.json`
+        let summary = std::fs::read_to_string(extracted.join("summary.json")).unwrap();
+        assert_eq!(serde_json::from_str::<JsonValue>(&summary).unwrap(), serde_json::json!([2, 0]));
+    }
+}
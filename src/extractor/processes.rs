@@ -0,0 +1,776 @@
+//! External process inventory.
+//!
+//! `child_process` calls (and `execa`/`cross-spawn`) are resolved to the
+//! binary they start and an argument template. Argument arrays are followed
+//! through locals, including `push`/`unshift` calls between the declaration
+//! and the spawn (conditional ones shown as `[…]`), `.concat`, spreads and
+//! helper functions that build and return the array. Shell command strings
+//! are split into binary and arguments. A call runs in the sandbox when a
+//! launcher like `sandbox-exec` or `bwrap` appears in its command, or when a
+//! `…Sandbox…(` wrapper call produces one of its arguments.
+
+use crate::analyzer::{Analyzer, SymbolTable};
+use crate::extractor::api_requests::SourceSpan;
+use crate::extractor::members::{
+    callee_name, find_function, is_function_expr, top_level_functions, walk_statements, ObjectMembers,
+};
+use crate::extractor::modules::ModuleAliases;
+use crate::extractor::tools::ToolFlag;
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tracing::{debug, trace};
+
+/// Maximum depth when following variables and helpers into an argument.
+const MAX_RESOLVE_DEPTH: usize = 8;
+
+/// Maximum length of a dynamic part label.
+const MAX_LABEL_LEN: usize = 60;
+
+/// Program name for binaries chosen at runtime.
+const DYNAMIC_PROGRAM: &str = "(dynamic)";
+
+/// Programs that run their arguments as a script.
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "fish", "cmd", "cmd.exe", "powershell", "powershell.exe", "pwsh"];
+
+/// Programs that run their arguments inside a sandbox.
+const SANDBOX_LAUNCHERS: &[&str] = &["sandbox-exec", "bwrap", "firejail", "nsjail"];
+
+/// How a call starts a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnKind {
+    /// `spawn(binary, args)`, also `execa` and `cross-spawn`.
+    Spawn,
+    /// `execFile(binary, args)`.
+    ExecFile,
+    /// `exec(command)`, a command line run by the shell.
+    Exec,
+    /// `fork(module, args)`, a Node.js child.
+    Fork,
+}
+
+/// A call site that starts an external process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessCall {
+    /// How the process is started.
+    pub kind: SpawnKind,
+
+    /// Callee as written, e.g. `cp.execSync`.
+    pub callee: String,
+
+    /// Binary template, e.g. `git` or `${A}/rg`.
+    pub binary: String,
+
+    /// Program name the call is grouped under: the binary's file name.
+    pub program: String,
+
+    /// Argument templates; conditional ones are wrapped in `[…]`.
+    pub args: Vec<String>,
+
+    /// Whether the command line goes through a shell.
+    pub shell: bool,
+
+    /// Sandbox launcher or wrapper the command runs under.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<String>,
+
+    /// Innermost named function around the call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+
+    /// Location of the call.
+    pub span: SourceSpan,
+}
+
+impl ProcessCall {
+    /// The command line as a single template.
+    pub fn command(&self) -> String {
+        std::iter::once(self.binary.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Every process call site, and the number of call sites per program.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessInventory {
+    /// Call sites in source order.
+    pub calls: Vec<ProcessCall>,
+
+    /// Call sites per program name.
+    pub programs: BTreeMap<String, usize>,
+}
+
+/// Render the inventory as Markdown, one table per program.
+pub fn render_markdown(inventory: &ProcessInventory) -> String {
+    let mut out = String::from("# External Processes\n");
+
+    let mut groups: BTreeMap<&str, Vec<&ProcessCall>> = BTreeMap::new();
+    for call in &inventory.calls {
+        groups.entry(call.program.as_str()).or_default().push(call);
+    }
+
+    for (program, calls) in groups {
+        out.push_str(&format!("\n## {} ({})\n\n", program, calls.len()));
+        out.push_str("| Line | Command | Call | Shell | Sandbox | Function |\n");
+        out.push_str("|------|---------|------|-------|---------|----------|\n");
+        for call in calls {
+            out.push_str(&format!(
+                "| {} | `{}` | `{}` | {} | {} | {} |\n",
+                call.span.line,
+                call.command().replace('|', "\\|"),
+                call.callee,
+                if call.shell { "yes" } else { "" },
+                call.sandbox.as_deref().map(|s| format!("`{}`", s)).unwrap_or_default(),
+                call.function.as_deref().map(|f| format!("`{}`", f)).unwrap_or_default(),
+            ));
+        }
+    }
+
+    out
+}
+
+/// Where an expression is evaluated.
+struct Cx<'p, 'a> {
+    /// Enclosing function bodies, innermost first.
+    scopes: Vec<&'a [Statement<'a>]>,
+    /// Helper parameters bound to the caller's arguments.
+    params: HashMap<&'a str, &'a Expression<'a>>,
+    /// Context the caller's arguments are evaluated in.
+    parent: Option<&'p Cx<'p, 'a>>,
+    /// Only declarations and mutations before this offset apply.
+    before: u32,
+}
+
+impl Cx<'_, '_> {
+    /// Context for top-level bindings.
+    fn root() -> Self {
+        Self {
+            scopes: Vec::new(),
+            params: HashMap::new(),
+            parent: None,
+            before: u32::MAX,
+        }
+    }
+}
+
+/// What an identifier is bound to.
+enum Binding<'c, 'a> {
+    /// A helper parameter, evaluated in the caller's context.
+    Param(&'a Expression<'a>, &'c Cx<'c, 'a>),
+    /// A local declared in an enclosing function body.
+    Local {
+        init: &'a Expression<'a>,
+        scope: &'a [Statement<'a>],
+        declared: Span,
+    },
+    /// A top-level variable.
+    Global(&'a Expression<'a>),
+}
+
+/// Extractor for external process call sites.
+pub struct ProcessExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+    functions: HashMap<&'a str, &'a Function<'a>>,
+    modules: ModuleAliases,
+    source_text: &'a str,
+}
+
+impl<'a> ProcessExtractor<'a> {
+    /// Create a new process extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        let program = analyzer.program();
+        Self {
+            analyzer,
+            modules: ModuleAliases::collect(program, symbol_table),
+            symbol_table,
+            functions: top_level_functions(program),
+            source_text: program.source_text,
+        }
+    }
+
+    /// Collect every process call site in source order.
+    pub fn extract(&self) -> Result<ProcessInventory> {
+        debug!("Extracting external process calls");
+
+        let calls = self.analyzer.find_expressions(|expr| matches!(expr, Expression::CallExpression(_)));
+        let scopes = self.analyzer.find_functions();
+
+        let mut inventory = ProcessInventory::default();
+        for expr in calls {
+            let Expression::CallExpression(call) = expr else {
+                continue;
+            };
+            let Some(kind) = self.spawn_kind(call) else {
+                continue;
+            };
+            let cx = Cx {
+                scopes: scopes
+                    .iter()
+                    .rev()
+                    .filter(|f| f.span.start <= call.span.start && call.span.end <= f.span.end)
+                    .map(|f| f.statements)
+                    .collect(),
+                params: HashMap::new(),
+                parent: None,
+                before: call.span.start,
+            };
+            let process = self.process(call, kind, &cx);
+            trace!("{} at line {}", process.command(), process.span.line);
+            *inventory.programs.entry(process.program.clone()).or_default() += 1;
+            inventory.calls.push(process);
+        }
+
+        debug!(
+            "Found {} process calls starting {} programs",
+            inventory.calls.len(),
+            inventory.programs.len()
+        );
+        Ok(inventory)
+    }
+
+    /// How a call starts a process, if it does.
+    fn spawn_kind(&self, call: &'a CallExpression<'a>) -> Option<SpawnKind> {
+        if let Some((module, method)) = self.modules.module_method(self.symbol_table, &call.callee) {
+            return match (module.as_str(), method.as_str()) {
+                ("child_process", "spawn" | "spawnSync") => Some(SpawnKind::Spawn),
+                ("child_process", "execFile" | "execFileSync") => Some(SpawnKind::ExecFile),
+                ("child_process", "exec" | "execSync") => Some(SpawnKind::Exec),
+                ("child_process", "fork") => Some(SpawnKind::Fork),
+                ("execa", "default" | "execa" | "execaSync") => Some(SpawnKind::Spawn),
+                ("execa", "execaCommand" | "execaCommandSync") => Some(SpawnKind::Exec),
+                ("cross-spawn", "default" | "spawn" | "sync") | ("node-pty", "spawn") => Some(SpawnKind::Spawn),
+                _ => None,
+            };
+        }
+
+        // Minified bundles hide the module behind a short name
+        match call.callee.without_parentheses() {
+            Expression::Identifier(id) => match id.name.as_str() {
+                "execa" | "execaSync" => Some(SpawnKind::Spawn),
+                "execaCommand" | "execaCommandSync" => Some(SpawnKind::Exec),
+                _ => None,
+            },
+            Expression::StaticMemberExpression(member) => match member.property.name.as_str() {
+                "spawnSync" => Some(SpawnKind::Spawn),
+                "execFile" | "execFileSync" => Some(SpawnKind::ExecFile),
+                "execSync" => Some(SpawnKind::Exec),
+                // `spawn` is a common method name; require a literal binary
+                "spawn" => matches!(
+                    call.arguments.first().and_then(|a| a.as_expression()),
+                    Some(Expression::StringLiteral(_))
+                )
+                .then_some(SpawnKind::Spawn),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Resolve a call site's binary, arguments, shell and sandbox.
+    fn process(&self, call: &'a CallExpression<'a>, kind: SpawnKind, cx: &Cx<'_, 'a>) -> ProcessCall {
+        let args: Vec<&'a Expression<'a>> = call.arguments.iter().filter_map(|a| a.as_expression()).collect();
+        let first = args.first().map(|a| self.text(a, cx, 0)).unwrap_or_default();
+
+        // Arguments array and options object follow the binary
+        let (list, options) = match args.get(1).map(|a| a.without_parentheses()) {
+            Some(Expression::ObjectExpression(options)) => (None, Some(&**options)),
+            Some(list) => match args.get(2).map(|a| a.without_parentheses()) {
+                Some(Expression::ObjectExpression(options)) => (Some(list), Some(&**options)),
+                _ => (Some(list), None),
+            },
+            None => (None, None),
+        };
+        let mut list = list.map(|l| self.list(l, cx, 0)).unwrap_or_default();
+
+        let shell_option = options.is_some_and(|options| {
+            let members = ObjectMembers::collect(self.symbol_table, self.source_text, options);
+            !matches!(members.flag("shell", false), ToolFlag::Constant(false))
+        });
+
+        let (binary, args) = match kind {
+            SpawnKind::Fork => {
+                list.insert(0, first);
+                ("node".to_string(), list)
+            }
+            SpawnKind::Exec => split_first(&first),
+            SpawnKind::Spawn | SpawnKind::ExecFile if shell_option && list.is_empty() => split_first(&first),
+            SpawnKind::Spawn | SpawnKind::ExecFile => (first, list),
+        };
+        let program = program_name(&binary);
+        let shell = kind == SpawnKind::Exec || shell_option || SHELLS.contains(&program.as_str());
+
+        ProcessCall {
+            kind,
+            callee: callee_name(&call.callee).unwrap_or_else(|| self.label(call.callee.span())),
+            sandbox: sandbox_of(&binary, &args),
+            program,
+            binary,
+            args,
+            shell,
+            function: self.analyzer.context().function_at(call.span).map(str::to_string),
+            span: SourceSpan::locate(self.analyzer.line_index(), call.span),
+        }
+    }
+
+    /// Rebuild a string argument as a template.
+    fn text(&self, expr: &'a Expression<'a>, cx: &Cx<'_, 'a>, depth: usize) -> String {
+        if depth > MAX_RESOLVE_DEPTH {
+            return self.placeholder(expr.span());
+        }
+        match expr.without_parentheses() {
+            Expression::StringLiteral(s) => s.value.to_string(),
+            Expression::NumericLiteral(n) => n.value.to_string(),
+            Expression::TemplateLiteral(tmpl) => {
+                let mut out = String::new();
+                for (i, quasi) in tmpl.quasis.iter().enumerate() {
+                    out.push_str(quasi.value.raw.as_str());
+                    if let Some(expr) = tmpl.expressions.get(i) {
+                        out.push_str(&self.text(expr, cx, depth + 1));
+                    }
+                }
+                out
+            }
+            Expression::BinaryExpression(binary) if binary.operator == BinaryOperator::Addition => {
+                format!("{}{}", self.text(&binary.left, cx, depth + 1), self.text(&binary.right, cx, depth + 1))
+            }
+            Expression::LogicalExpression(logical) if logical.operator != LogicalOperator::And => {
+                self.text(&logical.right, cx, depth + 1)
+            }
+            Expression::AwaitExpression(await_expr) => self.text(&await_expr.argument, cx, depth),
+            Expression::Identifier(id) => match self.binding(id.name.as_str(), cx) {
+                Some(Binding::Param(arg, parent)) => self.text(arg, parent, depth + 1),
+                Some(Binding::Local { init, .. }) if !is_function_expr(init) => self.text(init, cx, depth + 1),
+                Some(Binding::Global(init)) => self.text(init, &Cx::root(), depth + 1),
+                _ => self.placeholder(expr.span()),
+            },
+            Expression::StaticMemberExpression(member) => match &member.object {
+                Expression::StaticMemberExpression(env)
+                    if env.property.name == "env" && matches!(&env.object, Expression::Identifier(p) if p.name == "process") =>
+                {
+                    format!("${}", member.property.name)
+                }
+                _ => self.placeholder(expr.span()),
+            },
+            Expression::CallExpression(call) => {
+                // `[...].join(" ")` joins the resolved list
+                if let Expression::StaticMemberExpression(member) = &call.callee {
+                    if member.property.name == "join" {
+                        let separator = match call.arguments.first().and_then(|a| a.as_expression()) {
+                            Some(sep) => self.text(sep, cx, depth + 1),
+                            None => ",".to_string(),
+                        };
+                        return self.list(&member.object, cx, depth + 1).join(&separator);
+                    }
+                }
+                match self.helper(call, cx) {
+                    Some((returned, inner)) => self.text(returned, &inner, depth + 1),
+                    None => self.placeholder(call.span),
+                }
+            }
+            other => self.placeholder(other.span()),
+        }
+    }
+
+    /// Rebuild an argument array as a list of templates.
+    fn list(&self, expr: &'a Expression<'a>, cx: &Cx<'_, 'a>, depth: usize) -> Vec<String> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return vec![self.placeholder(expr.span())];
+        }
+        match expr.without_parentheses() {
+            Expression::ArrayExpression(array) => {
+                let mut out = Vec::new();
+                for element in &array.elements {
+                    if let ArrayExpressionElement::SpreadElement(spread) = element {
+                        out.extend(self.list(&spread.argument, cx, depth + 1));
+                    } else if let Some(expr) = element.as_expression() {
+                        out.push(self.text(expr, cx, depth + 1));
+                    }
+                }
+                out
+            }
+            Expression::AwaitExpression(await_expr) => self.list(&await_expr.argument, cx, depth),
+            Expression::Identifier(id) => match self.binding(id.name.as_str(), cx) {
+                Some(Binding::Param(arg, parent)) => self.list(arg, parent, depth + 1),
+                Some(Binding::Local { init, scope, declared }) if !is_function_expr(init) => {
+                    let mut out = self.list(init, cx, depth + 1);
+                    for (call, conditional) in mutations(scope, id.name.as_str(), declared, cx.before) {
+                        let mut added = Vec::new();
+                        for arg in &call.arguments {
+                            if let Argument::SpreadElement(spread) = arg {
+                                added.extend(self.list(&spread.argument, cx, depth + 1));
+                            } else if let Some(arg) = arg.as_expression() {
+                                added.push(self.text(arg, cx, depth + 1));
+                            }
+                        }
+                        if conditional {
+                            added = vec![format!("[{}]", added.join(" "))];
+                        }
+                        match &call.callee {
+                            Expression::StaticMemberExpression(m) if m.property.name == "unshift" => {
+                                out.splice(0..0, added);
+                            }
+                            _ => out.extend(added),
+                        }
+                    }
+                    out
+                }
+                Some(Binding::Global(init)) => self.list(init, &Cx::root(), depth + 1),
+                _ => vec![self.placeholder(expr.span())],
+            },
+            Expression::CallExpression(call) => {
+                if let Expression::StaticMemberExpression(member) = &call.callee {
+                    match member.property.name.as_str() {
+                        "concat" => {
+                            let mut out = self.list(&member.object, cx, depth + 1);
+                            for arg in call.arguments.iter().filter_map(|a| a.as_expression()) {
+                                match arg.without_parentheses() {
+                                    Expression::ArrayExpression(_) | Expression::Identifier(_) => {
+                                        out.extend(self.list(arg, cx, depth + 1))
+                                    }
+                                    _ => out.push(self.text(arg, cx, depth + 1)),
+                                }
+                            }
+                            return out;
+                        }
+                        "filter" | "flat" => return self.list(&member.object, cx, depth + 1),
+                        _ => {}
+                    }
+                }
+                match self.helper(call, cx) {
+                    Some((returned, inner)) => self.list(returned, &inner, depth + 1),
+                    None => vec![self.placeholder(call.span)],
+                }
+            }
+            other => vec![self.placeholder(other.span())],
+        }
+    }
+
+    /// What an identifier is bound to in a context.
+    fn binding<'c>(&self, name: &str, cx: &'c Cx<'c, 'a>) -> Option<Binding<'c, 'a>> {
+        if let Some(arg) = cx.params.get(name) {
+            return Some(Binding::Param(arg, cx.parent?));
+        }
+        for scope in &cx.scopes {
+            if let Some((init, declared)) = declaration(scope, name, cx.before) {
+                return Some(Binding::Local {
+                    init,
+                    scope,
+                    declared,
+                });
+            }
+        }
+        self.symbol_table.get_binding(name).filter(|init| !is_function_expr(init)).map(Binding::Global)
+    }
+
+    /// A helper's returned expression, with its parameters bound to the call's arguments.
+    fn helper<'c>(&self, call: &'a CallExpression<'a>, cx: &'c Cx<'c, 'a>) -> Option<(&'a Expression<'a>, Cx<'c, 'a>)> {
+        let Expression::Identifier(id) = &call.callee else {
+            return None;
+        };
+        let function = find_function(&self.functions, self.symbol_table, id.name.as_str())?;
+        let returned = function.returned()?;
+        let params = function
+            .param_names()
+            .into_iter()
+            .zip(&call.arguments)
+            .filter_map(|(name, arg)| Some((name?, arg.as_expression()?)))
+            .collect();
+        Some((
+            returned,
+            Cx {
+                scopes: vec![function.statements],
+                params,
+                parent: Some(cx),
+                before: returned.span().start,
+            },
+        ))
+    }
+
+    fn placeholder(&self, span: Span) -> String {
+        format!("${{{}}}", self.label(span))
+    }
+
+    /// Collapse whitespace and truncate source for use as a label.
+    fn label(&self, span: Span) -> String {
+        let source = &self.source_text[span.start as usize..span.end as usize];
+        let label = source.split_whitespace().collect::<Vec<_>>().join(" ");
+        if label.chars().count() <= MAX_LABEL_LEN {
+            return label;
+        }
+        let truncated: String = label.chars().take(MAX_LABEL_LEN).collect();
+        format!("{}…", truncated)
+    }
+}
+
+/// The last declaration of `name` with an initializer before an offset.
+fn declaration<'a>(scope: &'a [Statement<'a>], name: &str, before: u32) -> Option<(&'a Expression<'a>, Span)> {
+    let mut found = None;
+    walk_statements(scope, &mut Vec::new(), &mut |stmts, _| {
+        for stmt in stmts {
+            let Statement::VariableDeclaration(decl) = stmt else {
+                continue;
+            };
+            for declarator in &decl.declarations {
+                let declared = matches!(&declarator.id.kind, BindingPatternKind::BindingIdentifier(id) if id.name == name);
+                if let (true, Some(init)) = (declared, &declarator.init) {
+                    if declarator.span.start < before {
+                        found = Some((init, declarator.span));
+                    }
+                }
+            }
+        }
+    });
+    found
+}
+
+/// `name.push(…)`/`name.unshift(…)` calls after a declaration and before an
+/// offset, with whether each is conditional relative to the declaration.
+fn mutations<'a>(
+    scope: &'a [Statement<'a>],
+    name: &str,
+    declared: Span,
+    before: u32,
+) -> Vec<(&'a CallExpression<'a>, bool)> {
+    fn collect<'a>(expr: &'a Expression<'a>, name: &str, conditional: bool, out: &mut Vec<(&'a CallExpression<'a>, bool)>) {
+        match expr.without_parentheses() {
+            Expression::CallExpression(call) => {
+                if let Expression::StaticMemberExpression(member) = &call.callee {
+                    let target = matches!(&member.object, Expression::Identifier(id) if id.name == name);
+                    if target && matches!(member.property.name.as_str(), "push" | "unshift") {
+                        out.push((call, conditional));
+                    }
+                }
+            }
+            Expression::LogicalExpression(logical) => {
+                collect(&logical.left, name, conditional, out);
+                collect(&logical.right, name, true, out);
+            }
+            Expression::ConditionalExpression(cond) => {
+                collect(&cond.consequent, name, true, out);
+                collect(&cond.alternate, name, true, out);
+            }
+            Expression::SequenceExpression(seq) => {
+                for expr in &seq.expressions {
+                    collect(expr, name, conditional, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut found = Vec::new();
+    walk_statements(scope, &mut Vec::new(), &mut |stmts, guards| {
+        let conditional = guards.iter().any(|g| !(g.start <= declared.start && declared.end <= g.end));
+        for stmt in stmts {
+            let Statement::ExpressionStatement(expr_stmt) = stmt else {
+                continue;
+            };
+            let mut calls = Vec::new();
+            collect(&expr_stmt.expression, name, conditional, &mut calls);
+            found.extend(
+                calls
+                    .into_iter()
+                    .filter(|(call, _)| call.span.start > declared.end && call.span.end <= before),
+            );
+        }
+    });
+    found.sort_by_key(|(call, _)| call.span.start);
+    found
+}
+
+/// Split a command line into its binary and argument templates.
+///
+/// Whitespace inside quotes and `${…}` placeholders doesn't split.
+fn split_first(command: &str) -> (String, Vec<String>) {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut braces = 0usize;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '$' if chars.peek() == Some(&'{') => {
+                braces += 1;
+                word.push(c);
+                word.push(chars.next().unwrap_or('{'));
+                continue;
+            }
+            '}' if braces > 0 => braces -= 1,
+            '"' | '\'' if braces == 0 => match quote {
+                None => quote = Some(c),
+                Some(q) if q == c => quote = None,
+                Some(_) => {}
+            },
+            c if c.is_whitespace() && braces == 0 && quote.is_none() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    let mut words = words.into_iter();
+    (words.next().unwrap_or_default(), words.collect())
+}
+
+/// File name of a binary template, or `(dynamic)` when it isn't static.
+fn program_name(binary: &str) -> String {
+    let name = binary.rsplit(['/', '\\']).next().unwrap_or(binary);
+    if name.is_empty() || name.contains("${") || name.starts_with('$') {
+        DYNAMIC_PROGRAM.to_string()
+    } else {
+        name.trim_matches(['"', '\'']).to_string()
+    }
+}
+
+/// Sandbox launcher in a command, or a `…Sandbox…(` call producing part of it.
+fn sandbox_of(binary: &str, args: &[String]) -> Option<String> {
+    let parts = || std::iter::once(binary).chain(args.iter().map(String::as_str));
+
+    let launcher = parts()
+        .flat_map(str::split_whitespace)
+        .map(|word| word.trim_matches(['"', '\'']).rsplit('/').next().unwrap_or(word))
+        .find(|name| SANDBOX_LAUNCHERS.contains(name));
+    if let Some(launcher) = launcher {
+        return Some(launcher.to_string());
+    }
+
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    parts().find_map(|part| {
+        let lower = part.to_ascii_lowercase();
+        lower.match_indices("sandbox").find_map(|(at, _)| {
+            let start = part[..at].rfind(|c: char| !is_name(c) && c != '.').map_or(0, |i| i + 1);
+            let end = part[at..].find(|c: char| !is_name(c)).map_or(part.len(), |i| at + i);
+            part[end..].starts_with('(').then(|| part[start..end].to_string())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    fn extract(code: &str) -> ProcessInventory {
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+        ProcessExtractor::new(&analyzer, &symbol_table).extract().unwrap()
+    }
+
+    #[test]
+    fn test_extracts_process_calls() {
+        let code = r#"
+            import { spawn, execFile } from "node:child_process";
+            import * as cp from "child_process";
+            var RG = "rg";
+            function gitArgs(A) {
+                const Q = ["log", "--oneline"];
+                if (A.limit) Q.push("-n", String(A.limit));
+                Q.push(A.ref);
+                return Q;
+            }
+            function search(B, G) {
+                const Z = ["--json", B];
+                G && Z.push("--glob", G);
+                Z.unshift("--no-config");
+                return spawn(RG, Z, { stdio: "pipe" });
+            }
+            function history(A) {
+                return execFile("git", gitArgs(A), { cwd: A.dir });
+            }
+            function run(A) {
+                return spawn(process.env.SHELL || "/bin/bash", ["-c", SandboxManager.wrapWithSandbox(A)]);
+            }
+            function status() {
+                return cp.execSync(`gh pr view --json ${["title", "body"].join(",")}`);
+            }
+            var re = /x/;
+            re.exec("x");
+        "#;
+
+        let inventory = extract(code);
+        let commands: Vec<_> = inventory.calls.iter().map(|c| (c.program.as_str(), c.command())).collect();
+        assert_eq!(
+            commands,
+            [
+                ("rg", "rg --no-config --json ${B} [--glob ${G}]".to_string()),
+                ("git", "git log --oneline [-n ${String(A.limit)}] ${A.ref}".to_string()),
+                ("bash", "/bin/bash -c ${SandboxManager.wrapWithSandbox(A)}".to_string()),
+                ("gh", "gh pr view --json title,body".to_string()),
+            ]
+        );
+
+        let calls = &inventory.calls;
+        assert_eq!(calls[0].function.as_deref(), Some("search"));
+        assert_eq!(calls[1].kind, SpawnKind::ExecFile);
+        assert!(!calls[0].shell && calls[2].shell && calls[3].shell);
+        assert_eq!(calls[2].sandbox.as_deref(), Some("SandboxManager.wrapWithSandbox"));
+        assert_eq!(calls[3].callee, "cp.execSync");
+        assert_eq!(inventory.programs["git"], 1);
+    }
+
+    #[test]
+    fn test_spawn_and_exec_file_templates() {
+        let inventory = extract(
+            r#"
+            const { spawn, execFile, execFileSync } = require("child_process");
+            function diff(file, base) {
+                spawn("git", ["diff", `--src-prefix=${base}/`, file]);
+                execFile("git", ["diff", `--src-prefix=${base}/`, file]);
+                execFileSync("git", ["rev-parse", "HEAD"], { encoding: "utf8" });
+            }
+        "#,
+        );
+        let calls: Vec<_> = inventory.calls.iter().map(|c| (c.kind, c.command(), c.shell)).collect();
+        assert_eq!(
+            calls,
+            [
+                (SpawnKind::Spawn, "git diff --src-prefix=${base}/ ${file}".to_string(), false),
+                (SpawnKind::ExecFile, "git diff --src-prefix=${base}/ ${file}".to_string(), false),
+                (SpawnKind::ExecFile, "git rev-parse HEAD".to_string(), false),
+            ]
+        );
+        assert_eq!(inventory.programs["git"], 3);
+    }
+
+    #[test]
+    fn test_shell_option() {
+        let inventory = extract(
+            r#"
+            const { spawn } = require("child_process");
+            function install(pkg) {
+                spawn("npm install " + pkg, { shell: true });
+                spawn("ls", ["-la"], { shell: true });
+                spawn("ls", ["-la"], { shell: false });
+                spawn("where", [pkg], { shell: process.platform === "win32" });
+            }
+        "#,
+        );
+        let calls: Vec<_> = inventory.calls.iter().map(|c| (c.binary.as_str(), c.args.clone(), c.shell)).collect();
+        assert_eq!(
+            calls,
+            [
+                ("npm", vec!["install".to_string(), "${pkg}".to_string()], true),
+                ("ls", vec!["-la".to_string()], true),
+                ("ls", vec!["-la".to_string()], false),
+                ("where", vec!["${pkg}".to_string()], true),
+            ]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! System prompt extraction.
 
-use crate::analyzer::{Analyzer, StringLiteralInfo};
+use crate::analyzer::{Analyzer, HasProvenance, Provenance, StringLiteralInfo};
+use crate::rules::{Rules, Subject};
 use crate::Result;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
@@ -19,6 +20,20 @@ pub struct SystemPrompt {
 
     /// Category/type of prompt.
     pub category: PromptCategory,
+
+    /// Estimated token count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<usize>,
+
+    /// Where the prompt was found in the bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for SystemPrompt {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
 }
 
 /// Category of system prompt.
@@ -41,12 +56,22 @@ pub enum PromptCategory {
 /// Extractor for system prompts.
 pub struct PromptExtractor<'a> {
     analyzer: &'a Analyzer<'a>,
+    rules: &'a Rules,
 }
 
 impl<'a> PromptExtractor<'a> {
     /// Create a new prompt extractor.
     pub fn new(analyzer: &'a Analyzer<'a>) -> Self {
-        Self { analyzer }
+        Self {
+            analyzer,
+            rules: Rules::bundled(),
+        }
+    }
+
+    /// Use these extraction rules instead of the bundled ones.
+    pub fn with_rules(mut self, rules: &'a Rules) -> Self {
+        self.rules = rules;
+        self
     }
 
     /// Extract all system prompts.
@@ -64,6 +89,8 @@ impl<'a> PromptExtractor<'a> {
                     content: literal.value.to_string(),
                     length: literal.length,
                     category,
+                    tokens: None,
+                    provenance: Some(self.analyzer.provenance(literal.span)),
                 };
                 prompts.push(prompt);
             }
@@ -74,73 +101,18 @@ impl<'a> PromptExtractor<'a> {
     }
 
     /// Check if a string literal is likely a system prompt.
-    fn is_likely_prompt(&self, literal: &StringLiteralInfo) -> bool {
-        // Heuristics for identifying prompts:
-        // 1. Length > 80 chars (lowered threshold to catch more)
-        // 2. Contains prompt-related keywords
-        // 3. Has instruction-like language
-        // 4. Multi-line or has markdown formatting
-
-        if literal.length < 80 {
+    fn is_likely_prompt(&self, literal: &StringLiteralInfo<'a>) -> bool {
+        // Long enough and with a prompt indicator from the rules
+        if literal.length < self.rules.prompts.min_length {
             return false;
         }
 
-        let value = literal.value;
-
-        // Check for key phrases (expanded list)
-        let prompt_indicators = [
-            "You are Claude",
-            "You are powered by",
-            "answer the user",
-            "tool_use",
-            "function_calls",
-            "system prompt",
-            "IMPORTANT:",
-            "Usage notes:",
-            "Usage:",
-            "# ",
-            "## ",
-            "When NOT to use",
-            "When to use",
-            "Example:",
-            "This tool",
-            "Use this",
-            "Available",
-            "allows you to",
-            "enables",
-            "Supports",
-            "Note:",
-            "WARNING:",
-            "Caution:",
-            "Description:",
-            "<example>",
-            "```",
-            "Parameters:",
-            "Returns:",
-            "Throws:",
-            "\n\n", // Multi-paragraph
-        ];
-
-        prompt_indicators.iter().any(|&indicator| value.contains(indicator))
+        self.rules.prompts.is_indicated(&Subject::literal(self.analyzer, literal))
     }
 
     /// Categorize the prompt based on content.
-    fn categorize_prompt(&self, literal: &StringLiteralInfo) -> PromptCategory {
-        let value = literal.value;
-
-        if value.contains("You are Claude") || value.contains("answer the user") {
-            PromptCategory::System
-        } else if value.contains("tool") || value.contains("function") {
-            PromptCategory::Tool
-        } else if value.contains("Example:") || value.contains("<example>") {
-            PromptCategory::Example
-        } else if value.contains("Error:") || value.contains("error") {
-            PromptCategory::Error
-        } else if value.contains("IMPORTANT:") || value.contains("Usage") {
-            PromptCategory::Instruction
-        } else {
-            PromptCategory::Other
-        }
+    fn categorize_prompt(&self, literal: &StringLiteralInfo<'a>) -> PromptCategory {
+        self.rules.prompts.categorize(&Subject::literal(self.analyzer, literal))
     }
 }
 
@@ -1,8 +1,11 @@
 //! Enhanced system prompt extraction with fragment merging and tool association.
 
-use crate::analyzer::{Analyzer, StringLiteralInfo, SymbolTable};
+use crate::analyzer::{Analyzer, HasProvenance, Provenance, StringLiteralInfo, SymbolTable};
+use crate::extractor::clustering::{self, TextChange};
 use crate::extractor::prompts::{PromptCategory, SystemPrompt};
+use crate::rules::{Rules, Subject};
 use crate::Result;
+use oxc_span::GetSpan;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::{debug, trace};
@@ -51,12 +54,62 @@ pub struct EnhancedSystemPrompt {
     /// Fragment IDs that were merged into this prompt.
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub merged_fragments: Vec<String>,
+
+    /// Estimated token count.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tokens: Option<usize>,
+
+    /// Near-duplicate copies folded into this canonical prompt.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub near_duplicates: Vec<NearDuplicate>,
+
+    /// Where the prompt (or its first fragment) was found in the bundle.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for EnhancedSystemPrompt {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+}
+
+impl From<&EnhancedSystemPrompt> for SystemPrompt {
+    fn from(prompt: &EnhancedSystemPrompt) -> Self {
+        Self {
+            id: prompt.id.clone(),
+            content: prompt.content.clone(),
+            length: prompt.length,
+            category: prompt.category.clone(),
+            tokens: prompt.tokens,
+            provenance: prompt.provenance.clone(),
+        }
+    }
+}
+
+/// A near-duplicate of a canonical prompt and how it differs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NearDuplicate {
+    /// Id of the duplicate prompt.
+    pub id: String,
+
+    /// Length of the duplicate.
+    pub length: usize,
+
+    /// Jaccard similarity of word shingles with the canonical prompt.
+    pub similarity: f64,
+
+    /// Word-level changes from the canonical prompt to the duplicate.
+    pub changes: Vec<TextChange>,
 }
 
 /// Enhanced extractor for system prompts.
 pub struct EnhancedPromptExtractor<'a> {
     analyzer: &'a Analyzer<'a>,
     symbol_table: &'a SymbolTable<'a>,
+    rules: &'a Rules,
+    /// Tool names that prompts may be associated with.
+    tool_names: Vec<String>,
 }
 
 impl<'a> EnhancedPromptExtractor<'a> {
@@ -65,9 +118,23 @@ impl<'a> EnhancedPromptExtractor<'a> {
         Self {
             analyzer,
             symbol_table,
+            rules: Rules::bundled(),
+            tool_names: Vec::new(),
         }
     }
 
+    /// Use these extraction rules instead of the bundled ones.
+    pub fn with_rules(mut self, rules: &'a Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Set the tool names used for prompt-to-tool association.
+    pub fn with_tool_names(mut self, tool_names: Vec<String>) -> Self {
+        self.tool_names = tool_names;
+        self
+    }
+
     /// Extract all system prompts with enhanced processing.
     pub fn extract(&self) -> Result<Vec<EnhancedSystemPrompt>> {
         debug!("Extracting system prompts with enhancement");
@@ -87,6 +154,9 @@ impl<'a> EnhancedPromptExtractor<'a> {
                     context: PromptContext::Standalone,
                     associated_tool: None,
                     merged_fragments: Vec::new(),
+                    tokens: None,
+                    near_duplicates: Vec::new(),
+                    provenance: Some(self.analyzer.provenance(literal.span)),
                 };
                 basic_prompts.push(prompt);
             }
@@ -102,7 +172,7 @@ impl<'a> EnhancedPromptExtractor<'a> {
         basic_prompts.append(&mut symbol_prompts);
 
         // Step 2: Detect and associate tool prompts
-        let mut prompts_with_tools = self.associate_tools(basic_prompts);
+        let prompts_with_tools = self.associate_tools(basic_prompts);
 
         // Step 3: Merge related fragments
         let merged_prompts = self.merge_fragments(prompts_with_tools);
@@ -112,6 +182,9 @@ impl<'a> EnhancedPromptExtractor<'a> {
         // Step 4: Deduplicate
         let deduplicated = self.deduplicate(merged_prompts);
 
+        // Step 5: Fold near-duplicates into one canonical prompt per cluster
+        let deduplicated = self.cluster_near_duplicates(deduplicated);
+
         debug!("Final count: {} enhanced prompts after deduplication", deduplicated.len());
 
         // Debug: verify Read prompt survived
@@ -129,97 +202,49 @@ impl<'a> EnhancedPromptExtractor<'a> {
         let mut idx = 0;
 
         for (name, value) in &self.symbol_table.symbols {
-            match value {
-                SymbolValue::String(s) | SymbolValue::TemplateLiteral(s) => {
-                    // Check if this is a prompt (lower threshold for symbol table)
-                    if s.len() >= 60 && !self.is_code_fragment_str(s) {
-                        if self.has_prompt_indicators(s) || self.looks_like_tool_description(s) {
-                            let category = self.categorize_prompt_str(s);
-                            prompts.push(EnhancedSystemPrompt {
-                                id: format!("prompt_sym_{}_{}", name, idx),
-                                content: s.clone(),
-                                length: s.len(),
-                                category,
-                                context: PromptContext::Standalone,
-                                associated_tool: None,
-                                merged_fragments: Vec::new(),
-                            });
-                            idx += 1;
-                        }
-                    }
-                }
-                _ => {}
+            let s = match value {
+                SymbolValue::String(s) | SymbolValue::TemplateLiteral(s) => s,
+                _ => continue,
+            };
+
+            // Check if this is a prompt (symbol values have their own indicators)
+            let subject = Subject::text(s);
+            if s.len() >= self.rules.prompts.enhanced_min_length
+                && !self.is_code_fragment_str(s)
+                && self.rules.prompts.is_symbol_indicated(&subject)
+            {
+                let category = self.rules.prompts.categorize(&subject);
+                prompts.push(EnhancedSystemPrompt {
+                    id: format!("prompt_sym_{}_{}", name, idx),
+                    content: s.clone(),
+                    length: s.len(),
+                    category,
+                    context: PromptContext::Standalone,
+                    associated_tool: None,
+                    merged_fragments: Vec::new(),
+                    tokens: None,
+                    near_duplicates: Vec::new(),
+                    provenance: self
+                        .symbol_table
+                        .get_binding(name)
+                        .map(|expr| self.analyzer.provenance(expr.span())),
+                });
+                idx += 1;
             }
         }
 
         prompts
     }
 
-    /// Check if content has prompt indicators.
-    fn has_prompt_indicators(&self, content: &str) -> bool {
-        let prompt_indicators = [
-            "You are Claude",
-            "IMPORTANT:",
-            "Usage notes:",
-            "Usage:",
-            "## ",
-            "When NOT to use",
-            "When to use",
-            "Example:",
-            "This tool",
-            "Use this",
-            "allows you to",
-            "Supports",
-            "Parameters:",
-            "\n\n",
-        ];
-
-        prompt_indicators.iter().any(|&indicator| content.contains(indicator))
-    }
-
-    /// Check if content looks like a tool description (even without standard indicators).
-    fn looks_like_tool_description(&self, content: &str) -> bool {
-        // Descriptions for NotebookEdit, BashOutput, KillShell, Skill patterns
-        let tool_desc_patterns = [
-            "Completely replaces",
-            "Retrieves output from",
-            "Kills a running",
-            "Language Server Protocol",
-            "Execute a skill",
-            "Execute a slash command",
-            "skills_instructions",
-            "available_skills",
-        ];
-
-        tool_desc_patterns.iter().any(|&pattern| content.contains(pattern))
-    }
-
-    /// Categorize prompt from string content.
-    fn categorize_prompt_str(&self, value: &str) -> PromptCategory {
-        if value.contains("You are Claude") || value.contains("answer the user") {
-            PromptCategory::System
-        } else if value.contains("tool") || value.contains("function") || value.contains("This tool") {
-            PromptCategory::Tool
-        } else if value.contains("Example:") || value.contains("<example>") {
-            PromptCategory::Example
-        } else if value.contains("Error:") || value.contains("error") {
-            PromptCategory::Error
-        } else if value.contains("IMPORTANT:") || value.contains("Usage") {
-            PromptCategory::Instruction
-        } else {
-            PromptCategory::Other
-        }
-    }
-
     /// Check if string content is code (for symbol table values).
     fn is_code_fragment_str(&self, content: &str) -> bool {
         self.is_code_fragment(content)
     }
 
     /// Check if a string literal is likely a system prompt.
-    fn is_likely_prompt(&self, literal: &StringLiteralInfo) -> bool {
-        // Lower threshold to 60 to catch shorter tool descriptions
-        if literal.length < 60 {
+    fn is_likely_prompt(&self, literal: &StringLiteralInfo<'a>) -> bool {
+        // Lower threshold to catch shorter tool descriptions
+        if literal.length < self.rules.prompts.enhanced_min_length {
             return false;
         }
 
@@ -235,39 +260,7 @@ impl<'a> EnhancedPromptExtractor<'a> {
             return false;
         }
 
-        let prompt_indicators = [
-            "You are Claude",
-            "You are powered by",
-            "answer the user",
-            "tool_use",
-            "function_calls",
-            "system prompt",
-            "IMPORTANT:",
-            "Usage notes:",
-            "Usage:",
-            "## ",
-            "When NOT to use",
-            "When to use",
-            "Example:",
-            "This tool",
-            "Use this",
-            "Available",
-            "allows you to",
-            "enables",
-            "Supports",
-            "Note:",
-            "WARNING:",
-            "Caution:",
-            "Description:",
-            "<example>",
-            "```",
-            "Parameters:",
-            "Returns:",
-            "Throws:",
-            "\n\n",
-        ];
-
-        prompt_indicators.iter().any(|&indicator| value.contains(indicator))
+        self.rules.prompts.is_indicated(&Subject::literal(self.analyzer, literal))
     }
 
     /// Check if content is a list of keywords/function names (not prose).
@@ -289,10 +282,9 @@ impl<'a> EnhancedPromptExtractor<'a> {
         // Count how many look like identifiers vs prose words
         let identifier_count = words.iter().filter(|w| {
             // camelCase, snake_case, or all lowercase identifier
-            let is_identifier = w.chars().all(|c| c.is_alphanumeric() || c == '_')
+            w.chars().all(|c| c.is_alphanumeric() || c == '_')
                 && !w.contains(' ')
-                && w.len() > 2;
-            is_identifier
+                && w.len() > 2
         }).count();
 
         // If >80% are identifiers and no sentences, it's a keyword list
@@ -361,48 +353,32 @@ impl<'a> EnhancedPromptExtractor<'a> {
     }
 
     /// Categorize the prompt based on content.
-    fn categorize_prompt(&self, literal: &StringLiteralInfo) -> PromptCategory {
-        let value = literal.value;
-
-        if value.contains("You are Claude") || value.contains("answer the user") {
-            PromptCategory::System
-        } else if value.contains("tool") || value.contains("function") || value.contains("This tool") {
-            PromptCategory::Tool
-        } else if value.contains("Example:") || value.contains("<example>") {
-            PromptCategory::Example
-        } else if value.contains("Error:") || value.contains("error") {
-            PromptCategory::Error
-        } else if value.contains("IMPORTANT:") || value.contains("Usage") {
-            PromptCategory::Instruction
-        } else {
-            PromptCategory::Other
-        }
+    fn categorize_prompt(&self, literal: &StringLiteralInfo<'a>) -> PromptCategory {
+        self.rules.prompts.categorize(&Subject::literal(self.analyzer, literal))
     }
 
     /// Associate prompts with tools based on content analysis.
     fn associate_tools(&self, prompts: Vec<EnhancedSystemPrompt>) -> Vec<EnhancedSystemPrompt> {
-        let known_tools = [
-            "Bash", "Read", "Write", "Edit", "Grep", "Glob", "Task",
-            "TodoWrite", "NotebookEdit", "WebFetch", "WebSearch",
-            "Skill", "SlashCommand", "AskUserQuestion", "ExitPlanMode",
-            "BashOutput", "KillShell", "LSP",
-        ];
-
         prompts
             .into_iter()
             .map(|mut prompt| {
                 // Only process tool-category prompts
                 if prompt.category == PromptCategory::Tool {
-                    // Find which tool this prompt is about
-                    for tool_name in &known_tools {
-                        if self.is_tool_prompt_for(&prompt.content, tool_name) {
-                            prompt.associated_tool = Some(tool_name.to_string());
-                            prompt.context = PromptContext::ToolDocumentation {
-                                tool_name: tool_name.to_string(),
-                            };
-                            trace!("Associated prompt {} with tool {}", prompt.id, tool_name);
-                            break;
-                        }
+                    // The tool mentioned earliest is the one being documented
+                    let best = self
+                        .tool_names
+                        .iter()
+                        .filter_map(|name| {
+                            Self::tool_mention_position(&prompt.content, name).map(|pos| (pos, name))
+                        })
+                        .min_by_key(|(pos, _)| *pos);
+
+                    if let Some((_, tool_name)) = best {
+                        prompt.associated_tool = Some(tool_name.clone());
+                        prompt.context = PromptContext::ToolDocumentation {
+                            tool_name: tool_name.clone(),
+                        };
+                        trace!("Associated prompt {} with tool {}", prompt.id, tool_name);
                     }
                 }
                 prompt
@@ -410,42 +386,29 @@ impl<'a> EnhancedPromptExtractor<'a> {
             .collect()
     }
 
-    /// Check if a prompt is describing a specific tool.
-    fn is_tool_prompt_for(&self, content: &str, tool_name: &str) -> bool {
-        let first_100 = content.chars().take(100).collect::<String>();
-
-        // Use specific patterns for each tool (matching find_tool_specific_prompt logic)
-        match tool_name {
-            "Read" => content.starts_with("Reads a file from the local filesystem"),
-            "Write" => content.starts_with("Writes a file to the local filesystem"),
-            "Edit" => content.starts_with("Performs exact string replacements") ||
-                     first_100.contains("exact string replacements in files"),
-            "Bash" => content.starts_with("Executes a given bash command") ||
-                     first_100.contains("Executes a given bash command"),
-            "Grep" => first_100.contains("powerful search tool") &&
-                     first_100.contains("ripgrep") &&
-                     !first_100.contains("glob patterns like"),
-            "Glob" => first_100.contains("Fast file pattern matching") ||
-                     (first_100.contains("glob patterns") && first_100.contains("**/*.js")),
-            "Task" => first_100.contains("launches specialized agents") ||
-                     first_100.contains("Launch a new agent"),
-            "TodoWrite" => content.starts_with("Use this tool to create and manage a structured task list"),
-            "WebFetch" => first_100.contains("Fetches content from a specified URL"),
-            "WebSearch" => first_100.contains("search the web"),
-            "NotebookEdit" => first_100.contains("Jupyter notebook") && first_100.contains("cell"),
-            "AskUserQuestion" => content.starts_with("Use this tool when you need to ask the user"),
-            "Skill" => first_100.contains("Execute a skill within the main conversation"),
-            "SlashCommand" => first_100.contains("Execute a slash command"),
-            "ExitPlanMode" => first_100.contains("plan mode") && first_100.contains("ready to code"),
-            "BashOutput" => first_100.contains("Retrieves output from a running"),
-            "KillShell" => first_100.contains("Kills a running background bash"),
-            "LSP" => content.starts_with("Interact with Language Server Protocol"),
-            _ => {
-                // Fallback: tool name mentioned early
-                let first_200 = content.chars().take(200).collect::<String>();
-                first_200.contains(tool_name) && content.len() > 200
-            }
+    /// Find where a tool is named in the opening of a prompt.
+    ///
+    /// Matches the name case-insensitively as a whole word, allowing a verb
+    /// suffix ("Reads", "Executes bash").
+    fn tool_mention_position(content: &str, tool_name: &str) -> Option<usize> {
+        let opening = content.chars().take(100).collect::<String>().to_lowercase();
+        let name = tool_name.to_lowercase();
+        if name.is_empty() {
+            return None;
         }
+
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+
+        opening.match_indices(&name).map(|(pos, _)| pos).find(|&pos| {
+            let before_ok = opening[..pos].chars().next_back().is_none_or(|c| !is_word_char(c));
+            let rest = &opening[pos + name.len()..];
+            let rest = rest
+                .strip_prefix("es")
+                .or_else(|| rest.strip_prefix('s'))
+                .filter(|r| r.chars().next().is_none_or(|c| !is_word_char(c)))
+                .unwrap_or(rest);
+            before_ok && rest.chars().next().is_none_or(|c| !is_word_char(c))
+        })
     }
 
     /// Merge related fragments into complete prompts.
@@ -498,6 +461,9 @@ impl<'a> EnhancedPromptExtractor<'a> {
                 } else {
                     Vec::new()
                 },
+                tokens: prompt.tokens,
+                near_duplicates: Vec::new(),
+                provenance: prompt.provenance.clone(),
             });
         }
 
@@ -586,6 +552,84 @@ impl<'a> EnhancedPromptExtractor<'a> {
         debug!("Deduplicated to {} unique prompts", result.len());
         result
     }
+
+    /// Keep one canonical prompt per near-duplicate cluster.
+    ///
+    /// The canonical member is the one most similar to the rest of its
+    /// cluster (longest on ties); the others are listed on it with their
+    /// word-level changes.
+    fn cluster_near_duplicates(&self, prompts: Vec<EnhancedSystemPrompt>) -> Vec<EnhancedSystemPrompt> {
+        let texts: Vec<&str> = prompts.iter().map(|p| p.content.as_str()).collect();
+        let clusters = clustering::cluster_texts(&texts);
+        if clusters.is_empty() {
+            return prompts;
+        }
+
+        let mut folded = vec![false; prompts.len()];
+        let mut duplicates: HashMap<usize, Vec<NearDuplicate>> = HashMap::new();
+
+        for cluster in &clusters {
+            let shingles: Vec<_> = cluster.iter().map(|&i| clustering::shingles(texts[i])).collect();
+            let similarities: Vec<Vec<f64>> = shingles
+                .iter()
+                .map(|a| shingles.iter().map(|b| clustering::jaccard(a, b)).collect())
+                .collect();
+            let (canonical_pos, _) = similarities
+                .iter()
+                .enumerate()
+                .max_by(|(i, x), (j, y)| {
+                    let (sx, sy): (f64, f64) = (x.iter().sum(), y.iter().sum());
+                    sx.total_cmp(&sy)
+                        .then_with(|| texts[cluster[*i]].len().cmp(&texts[cluster[*j]].len()))
+                        .then_with(|| j.cmp(i))
+                })
+                .unwrap_or((0, &Vec::new()));
+            let canonical = cluster[canonical_pos];
+
+            let members = cluster.iter().enumerate().filter(|(pos, _)| *pos != canonical_pos);
+            for (pos, &member) in members {
+                folded[member] = true;
+                duplicates.entry(canonical).or_default().push(NearDuplicate {
+                    id: prompts[member].id.clone(),
+                    length: prompts[member].length,
+                    similarity: similarities[canonical_pos][pos],
+                    changes: clustering::diff_words(texts[canonical], texts[member]),
+                });
+                trace!("Folded {} into canonical {}", prompts[member].id, prompts[canonical].id);
+            }
+        }
+
+        // Tool associations survive on the canonical member
+        let tools: HashMap<usize, String> = clusters
+            .iter()
+            .filter_map(|cluster| {
+                let tool = cluster.iter().find_map(|&i| prompts[i].associated_tool.clone())?;
+                let canonical = cluster.iter().copied().find(|&i| !folded[i])?;
+                Some((canonical, tool))
+            })
+            .collect();
+
+        let result: Vec<EnhancedSystemPrompt> = prompts
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| !folded[*idx])
+            .map(|(idx, mut prompt)| {
+                if let Some(near) = duplicates.remove(&idx) {
+                    prompt.near_duplicates = near;
+                }
+                if prompt.associated_tool.is_none() {
+                    if let Some(tool) = tools.get(&idx) {
+                        prompt.associated_tool = Some(tool.clone());
+                        prompt.context = PromptContext::ToolDocumentation { tool_name: tool.clone() };
+                    }
+                }
+                prompt
+            })
+            .collect();
+
+        debug!("Clustered near-duplicates into {} canonical prompts", result.len());
+        result
+    }
 }
 
 #[cfg(test)]
@@ -617,7 +661,7 @@ mod tests {
     fn test_tool_association() {
         let code = r#"
             const bashPrompt = "Executes bash commands in a persistent shell session. Use this tool for terminal operations.";
-            const readPrompt = "Reads a file from the local filesystem. You can access any file directly.";
+            const readPrompt = "Reads a file from the local filesystem. You can access any file directly with this tool.";
         "#;
 
         let allocator = Allocator::default();
@@ -626,9 +670,43 @@ mod tests {
 
         let analyzer = Analyzer::new(parse_result.program());
         let symbol_table = SymbolTable::new(parse_result.program());
-        let extractor = EnhancedPromptExtractor::new(&analyzer, &symbol_table);
+        let extractor = EnhancedPromptExtractor::new(&analyzer, &symbol_table)
+            .with_tool_names(vec!["Bash".to_string(), "Read".to_string()]);
+
+        let prompts = extractor.extract().unwrap();
+        let tool_for = |prefix: &str| {
+            prompts
+                .iter()
+                .find(|p| p.content.starts_with(prefix))
+                .and_then(|p| p.associated_tool.clone())
+        };
+
+        assert_eq!(tool_for("Executes bash"), Some("Bash".to_string()));
+        assert!(EnhancedPromptExtractor::tool_mention_position("Reads a file from the local", "Read").is_some());
+        assert!(EnhancedPromptExtractor::tool_mention_position("Readable output only", "Read").is_none());
+    }
 
-        assert!(extractor.is_tool_prompt_for("Executes bash commands", "Bash"));
-        assert!(extractor.is_tool_prompt_for("Reads a file from the local", "Read"));
+    #[test]
+    fn test_near_duplicates_fold_into_canonical() {
+        let code = r#"
+            const a = "Use this tool to search file contents with regular expressions. Supports full regex syntax and filters files by glob pattern. Returns matching file paths sorted by modification time.";
+            const b = "Use this tool to search the file contents with regular expressions. Supports full regex syntax and filters files by glob pattern. Returns matching file paths sorted by modification time.";
+            const c = "Executes a given bash command in a persistent shell session. Use this tool for terminal operations like git, npm and docker.";
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+        let prompts = EnhancedPromptExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+
+        let search: Vec<_> = prompts.iter().filter(|p| p.content.contains("regular expressions")).collect();
+        assert_eq!(search.len(), 1);
+        let near = &search[0].near_duplicates;
+        assert!(!near.is_empty());
+        assert!(near[0].changes.iter().any(|c| c.added == "the " || c.removed == "the "));
+        assert!(prompts.iter().any(|p| p.content.starts_with("Executes") && p.near_duplicates.is_empty()));
     }
 }
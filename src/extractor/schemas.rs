@@ -1,10 +1,70 @@
-//! Schema extraction from builder patterns (k.object(), k.strictObject(), etc.).
+//! Schema extraction from zod builder chains (`k.object()`, `k.string().optional()`, ...).
+//!
+//! A chain is a constructor such as `k.object({...})` followed by modifiers
+//! (`.optional()`, `.describe()`, `.default()`, `.array()`, ...). The zod
+//! namespace is renamed in minified bundles, so any identifier is accepted as
+//! long as the constructor name is one zod provides.
 
 use crate::analyzer::SymbolTable;
+use crate::extractor::members::{first_return, property_key, unwind_chain};
 use oxc_ast::ast::*;
-use serde_json::{Map, Value as JsonValue};
+use serde_json::{json, Map, Value as JsonValue};
 use tracing::trace;
 
+/// Constructors that may start a schema chain.
+const ZOD_CONSTRUCTORS: &[&str] = &[
+    "string",
+    "number",
+    "boolean",
+    "bigint",
+    "date",
+    "null",
+    "undefined",
+    "void",
+    "any",
+    "unknown",
+    "never",
+    "object",
+    "strictObject",
+    "looseObject",
+    "array",
+    "enum",
+    "nativeEnum",
+    "literal",
+    "union",
+    "discriminatedUnion",
+    "intersection",
+    "record",
+    "tuple",
+    "lazy",
+    "optional",
+    "nullable",
+    "custom",
+    "instanceof",
+    "function",
+    "map",
+    "set",
+    "promise",
+];
+
+/// Maximum nesting of sub-schemas and identifier hops followed.
+const MAX_SCHEMA_DEPTH: usize = 24;
+
+/// A parsed schema and whether its property may be omitted.
+struct Parsed {
+    schema: JsonValue,
+    optional: bool,
+}
+
+impl Parsed {
+    fn required(schema: JsonValue) -> Self {
+        Self {
+            schema,
+            optional: false,
+        }
+    }
+}
+
 /// Extractor for schema objects built with builder patterns.
 pub struct SchemaExtractor<'a> {
     symbol_table: &'a SymbolTable<'a>,
@@ -16,203 +76,414 @@ impl<'a> SchemaExtractor<'a> {
         Self { symbol_table }
     }
 
-    /// Parse a schema builder call like k.object(...) or k.strictObject(...).
-    pub fn parse_schema_builder_call(&self, call: &CallExpression) -> Option<JsonValue> {
-        // Check if this is k.object() or k.strictObject()
-        if !self.is_schema_builder_call(call) {
-            return None;
+    /// Parse any schema expression: a builder chain, a JSON Schema object
+    /// literal, or an identifier bound to either.
+    pub fn parse_schema_expr(&self, expr: &Expression) -> Option<JsonValue> {
+        match expr {
+            Expression::ObjectExpression(_) => literal_to_json(expr),
+            Expression::Identifier(id) => match self.symbol_table.get_binding(id.name.as_str()) {
+                Some(bound @ Expression::ObjectExpression(_)) => literal_to_json(bound),
+                _ => self.parse(expr, 0).map(|p| p.schema),
+            },
+            _ => self.parse(expr, 0).map(|p| p.schema),
         }
+    }
 
-        trace!("Parsing schema builder call");
-
-        // Get the argument (should be object expression)
-        let arg = call.arguments.first()?;
-
-        if let Argument::ObjectExpression(obj_expr) = arg {
-            self.parse_object_schema(obj_expr)
-        } else {
-            None
-        }
+    /// Whether an expression is a zod builder chain.
+    pub fn is_schema_chain(&self, expr: &Expression) -> bool {
+        let (base, calls) = unwind_chain(expr);
+        matches!(base, Expression::Identifier(_))
+            && calls
+                .first()
+                .is_some_and(|(method, _)| ZOD_CONSTRUCTORS.contains(method))
     }
 
-    /// Check if a call expression is a schema builder call.
-    fn is_schema_builder_call(&self, call: &CallExpression) -> bool {
-        // Check for: k.object(...) or k.strictObject(...)
-        if let Expression::StaticMemberExpression(member) = &call.callee {
-            if let Expression::Identifier(obj) = &member.object {
-                if obj.name.as_str() == "k" {
-                    let prop_name = member.property.name.as_str();
-                    return matches!(prop_name, "object" | "strictObject" | "array" | "string" | "number" | "boolean" | "enum");
-                }
-            }
-        }
-        false
+    /// Parse the shape passed to `k.object({...})` as an object schema.
+    ///
+    /// Members that can't be parsed are kept as unconstrained (`{}`) and
+    /// treated as optional.
+    pub fn parse_object_shape(&self, shape: &ObjectExpression) -> JsonValue {
+        self.object_shape(shape, 0)
     }
 
-    /// Parse an object expression as a schema.
-    fn parse_object_schema(&self, obj_expr: &ObjectExpression) -> Option<JsonValue> {
+    fn object_shape(&self, shape: &ObjectExpression, depth: usize) -> JsonValue {
         let mut properties = Map::new();
         let mut required = Vec::new();
 
-        for prop in &obj_expr.properties {
-            if let ObjectPropertyKind::ObjectProperty(p) = prop {
-                let key = self.extract_key(&p.key)?;
-
-                // Value might be:
-                // 1. Another schema builder call (nested)
-                // 2. Variable reference
-                // 3. Call to .describe()
-
-                let value = self.parse_schema_value(&p.value)?;
-                properties.insert(key.clone(), value);
-
-                // Assume all properties are required (common pattern)
-                required.push(key);
+        for prop in &shape.properties {
+            let ObjectPropertyKind::ObjectProperty(p) = prop else {
+                continue;
+            };
+            let Some(key) = property_key(&p.key) else {
+                continue;
+            };
+
+            match self.parse(&p.value, depth + 1) {
+                Some(parsed) => {
+                    if !parsed.optional {
+                        required.push(JsonValue::String(key.to_string()));
+                    }
+                    properties.insert(key.to_string(), parsed.schema);
+                }
+                None => {
+                    trace!("Unparsed schema member: {}", key);
+                    properties.insert(key.to_string(), json!({}));
+                }
             }
         }
 
         let mut schema = Map::new();
-        schema.insert("type".to_string(), JsonValue::String("object".to_string()));
+        schema.insert("type".to_string(), json!("object"));
         schema.insert("properties".to_string(), JsonValue::Object(properties));
-
         if !required.is_empty() {
-            schema.insert(
-                "required".to_string(),
-                JsonValue::Array(required.into_iter().map(JsonValue::String).collect()),
-            );
+            schema.insert("required".to_string(), JsonValue::Array(required));
         }
-
-        Some(JsonValue::Object(schema))
+        JsonValue::Object(schema)
     }
 
-    /// Extract property key from PropertyKey.
-    fn extract_key(&self, key: &PropertyKey) -> Option<String> {
-        match key {
-            PropertyKey::StaticIdentifier(id) => Some(id.name.as_str().to_string()),
-            PropertyKey::StringLiteral(s) => Some(s.value.as_str().to_string()),
-            _ => None,
+    /// Parse a chain: a constructor or schema reference, then its modifiers.
+    fn parse(&self, expr: &Expression, depth: usize) -> Option<Parsed> {
+        if depth > MAX_SCHEMA_DEPTH {
+            return None;
         }
-    }
 
-    /// Parse schema value (might have .describe() call).
-    fn parse_schema_value(&self, expr: &Expression) -> Option<JsonValue> {
-        match expr {
-            // Handle: k.string().describe("...")
-            Expression::CallExpression(call) => {
-                // Check if this is a .describe() call
-                if self.is_describe_call(call) {
-                    // Extract the description
-                    let description = self.extract_describe_arg(call)?;
-
-                    // Get the base type from callee
-                    let mut base_type = self.extract_base_schema_type(&call.callee)?;
-
-                    if let JsonValue::Object(ref mut map) = base_type {
-                        map.insert("description".to_string(), JsonValue::String(description));
+        let (base, calls) = unwind_chain(expr);
+        let (mut parsed, modifiers) = match base {
+            Expression::Identifier(id) => match self.resolve_reference(id.name.as_str(), depth) {
+                Some(parsed) => (parsed, &calls[..]),
+                None => {
+                    let (method, call) = calls.first()?;
+                    if !ZOD_CONSTRUCTORS.contains(method) {
+                        return None;
                     }
-
-                    Some(base_type)
-                } else {
-                    // Might be k.string(), k.number(), etc.
-                    self.parse_type_call(call)
+                    (self.parse_constructor(method, call, depth)?, &calls[1..])
                 }
+            },
+            Expression::ParenthesizedExpression(paren) if calls.is_empty() => {
+                return self.parse(&paren.expression, depth);
             }
-            // Handle: variable reference
-            Expression::Identifier(id) => {
-                self.symbol_table.get_schema(id.name.as_str())
-            }
-            _ => None,
-        }
-    }
+            _ => return None,
+        };
 
-    /// Check if call is to .describe().
-    fn is_describe_call(&self, call: &CallExpression) -> bool {
-        if let Expression::StaticMemberExpression(member) = &call.callee {
-            member.property.name.as_str() == "describe"
-        } else {
-            false
+        for (method, call) in modifiers {
+            self.apply_modifier(&mut parsed, method, call, depth);
         }
+        Some(parsed)
     }
 
-    /// Extract description argument from .describe() call.
-    fn extract_describe_arg(&self, call: &CallExpression) -> Option<String> {
-        let arg = call.arguments.first()?;
-        match arg {
-            Argument::StringLiteral(s) => Some(s.value.as_str().to_string()),
+    /// Resolve an identifier bound to another schema chain.
+    fn resolve_reference(&self, name: &str, depth: usize) -> Option<Parsed> {
+        if let Some(schema) = self.symbol_table.get_schema(name) {
+            return Some(Parsed::required(schema));
+        }
+        match self.symbol_table.get_binding(name)? {
+            bound @ (Expression::CallExpression(_) | Expression::Identifier(_)) => self.parse(bound, depth + 1),
             _ => None,
         }
     }
 
-    /// Extract base schema type from chained calls.
-    fn extract_base_schema_type(&self, expr: &Expression) -> Option<JsonValue> {
-        match expr {
-            Expression::StaticMemberExpression(member) => {
-                // This is the k.string() part
-                self.parse_type_from_member(member)
+    /// Parse `k.<constructor>(args)`.
+    fn parse_constructor(&self, method: &str, call: &CallExpression, depth: usize) -> Option<Parsed> {
+        let arg = |i: usize| call.arguments.get(i).and_then(|a| a.as_expression());
+        let sub = |expr: Option<&Expression>| {
+            expr.and_then(|e| self.parse(e, depth + 1))
+                .map(|p| p.schema)
+                .unwrap_or_else(|| json!({}))
+        };
+
+        let schema = match method {
+            "string" => json!({ "type": "string" }),
+            "number" => json!({ "type": "number" }),
+            "bigint" => json!({ "type": "integer" }),
+            "boolean" => json!({ "type": "boolean" }),
+            "null" => json!({ "type": "null" }),
+            "date" => json!({ "type": "string", "format": "date-time" }),
+            "object" | "strictObject" | "looseObject" => {
+                let mut schema = match arg(0) {
+                    Some(Expression::ObjectExpression(shape)) => self.object_shape(shape, depth),
+                    _ => json!({ "type": "object" }),
+                };
+                if method == "strictObject" {
+                    schema["additionalProperties"] = json!(false);
+                }
+                schema
             }
-            Expression::CallExpression(call) => {
-                // Nested call
-                self.parse_type_call(call)
+            "array" => json!({ "type": "array", "items": sub(arg(0)) }),
+            "enum" => {
+                let values = arg(0).and_then(|e| self.literal_values(e))?;
+                json!({ "type": "string", "enum": values })
             }
-            _ => None,
-        }
+            "nativeEnum" => {
+                let values: Vec<JsonValue> = match arg(0).and_then(|e| self.literal_values(e))? {
+                    JsonValue::Object(map) => map.into_iter().map(|(_, v)| v).collect(),
+                    other => vec![other],
+                };
+                json!({ "enum": values })
+            }
+            "literal" => {
+                let value = arg(0).and_then(literal_to_json)?;
+                match json_type(&value) {
+                    Some(ty) => json!({ "type": ty, "const": value }),
+                    None => json!({ "const": value }),
+                }
+            }
+            "union" | "discriminatedUnion" => {
+                let options = if method == "union" { arg(0) } else { arg(1) };
+                let variants: Vec<JsonValue> = match options {
+                    Some(Expression::ArrayExpression(arr)) => arr
+                        .elements
+                        .iter()
+                        .map(|e| sub(e.as_expression()))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                let keyword = if method == "union" { "anyOf" } else { "oneOf" };
+                json!({ keyword: variants })
+            }
+            "intersection" => json!({ "allOf": [sub(arg(0)), sub(arg(1))] }),
+            "record" => {
+                let values = if call.arguments.len() > 1 { arg(1) } else { arg(0) };
+                json!({ "type": "object", "additionalProperties": sub(values) })
+            }
+            "tuple" => {
+                let items: Vec<JsonValue> = match arg(0) {
+                    Some(Expression::ArrayExpression(arr)) => arr
+                        .elements
+                        .iter()
+                        .map(|e| sub(e.as_expression()))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                json!({ "type": "array", "prefixItems": items })
+            }
+            "lazy" => {
+                let inner = match arg(0)? {
+                    Expression::ArrowFunctionExpression(arrow) if arrow.expression => {
+                        match arrow.body.statements.first()? {
+                            Statement::ExpressionStatement(stmt) => &stmt.expression,
+                            _ => return None,
+                        }
+                    }
+                    Expression::ArrowFunctionExpression(arrow) => first_return(&arrow.body.statements)?,
+                    Expression::FunctionExpression(func) => first_return(&func.body.as_ref()?.statements)?,
+                    _ => return None,
+                };
+                return self.parse(inner, depth + 1);
+            }
+            "optional" | "nullable" => {
+                let mut parsed = Parsed::required(sub(arg(0)));
+                self.apply_modifier(&mut parsed, method, call, depth);
+                return Some(parsed);
+            }
+            _ => json!({}),
+        };
+
+        Some(Parsed::required(schema))
     }
 
-    /// Parse type from member expression (k.string, k.number, etc.).
-    fn parse_type_from_member(&self, member: &StaticMemberExpression) -> Option<JsonValue> {
-        if let Expression::Identifier(obj) = &member.object {
-            if obj.name.as_str() == "k" {
-                let type_name = member.property.name.as_str();
-                return Some(self.type_name_to_schema(type_name));
+    /// Apply a chained modifier such as `.optional()` or `.describe("...")`.
+    fn apply_modifier(&self, parsed: &mut Parsed, method: &str, call: &CallExpression, depth: usize) {
+        let arg = call.arguments.first().and_then(|a| a.as_expression());
+        let schema = &mut parsed.schema;
+
+        match method {
+            "optional" => parsed.optional = true,
+            "nullable" => make_nullable(schema),
+            "nullish" => {
+                parsed.optional = true;
+                make_nullable(schema);
+            }
+            "describe" => {
+                if let Some(description) = arg.and_then(|e| self.symbol_table.resolve_template_expr(e)) {
+                    schema["description"] = JsonValue::String(description);
+                }
+            }
+            "default" => {
+                if let Some(value) = arg.and_then(literal_to_json) {
+                    schema["default"] = value;
+                }
+                parsed.optional = true;
             }
+            "int" => schema["type"] = json!("integer"),
+            "positive" => schema["exclusiveMinimum"] = json!(0),
+            "nonnegative" => schema["minimum"] = json!(0),
+            "min" | "max" | "gte" | "lte" | "length" => {
+                if let Some(n) = arg.and_then(literal_to_json).filter(JsonValue::is_number) {
+                    let bound = if matches!(method, "min" | "gte") { "min" } else { "max" };
+                    let keyword = match (schema.get("type").and_then(JsonValue::as_str), bound) {
+                        (Some("string"), "min") => "minLength",
+                        (Some("string"), _) => "maxLength",
+                        (Some("array"), "min") => "minItems",
+                        (Some("array"), _) => "maxItems",
+                        (_, "min") => "minimum",
+                        _ => "maximum",
+                    };
+                    schema[keyword] = n.clone();
+                    if method == "length" {
+                        schema[keyword.replace("max", "min")] = n;
+                    }
+                }
+            }
+            "url" => schema["format"] = json!("uri"),
+            "email" => schema["format"] = json!("email"),
+            "uuid" => schema["format"] = json!("uuid"),
+            "datetime" => schema["format"] = json!("date-time"),
+            "regex" => {
+                if let Some(Expression::RegExpLiteral(re)) = arg {
+                    schema["pattern"] = json!(re.regex.pattern.to_string());
+                }
+            }
+            "array" => {
+                let items = std::mem::take(schema);
+                *schema = json!({ "type": "array", "items": items });
+            }
+            "or" => {
+                let other = arg.and_then(|e| self.parse(e, depth + 1)).map(|p| p.schema);
+                let first = std::mem::take(schema);
+                *schema = json!({ "anyOf": [first, other.unwrap_or_else(|| json!({}))] });
+            }
+            "and" => {
+                let other = arg.and_then(|e| self.parse(e, depth + 1)).map(|p| p.schema);
+                let first = std::mem::take(schema);
+                *schema = json!({ "allOf": [first, other.unwrap_or_else(|| json!({}))] });
+            }
+            "passthrough" => schema["additionalProperties"] = json!(true),
+            "strict" => schema["additionalProperties"] = json!(false),
+            "partial" => {
+                if let Some(map) = schema.as_object_mut() {
+                    map.remove("required");
+                }
+            }
+            "extend" | "merge" => {
+                let extra = match arg {
+                    Some(Expression::ObjectExpression(shape)) => Some(self.object_shape(shape, depth + 1)),
+                    Some(other) => self.parse(other, depth + 1).map(|p| p.schema),
+                    None => None,
+                };
+                if let Some(extra) = extra {
+                    merge_object_schemas(schema, extra);
+                }
+            }
+            // Refinements, transforms and brands don't change the JSON shape
+            _ => {}
         }
-        None
     }
 
-    /// Extract base schema type from k.string(), k.number(), etc.
-    fn parse_type_call(&self, call: &CallExpression) -> Option<JsonValue> {
-        // Check if this is k.string(), k.number(), k.boolean(), etc.
-        if let Expression::StaticMemberExpression(member) = &call.callee {
-            if let Expression::Identifier(obj) = &member.object {
-                if obj.name.as_str() == "k" {
-                    let type_name = member.property.name.as_str();
-                    return Some(self.type_name_to_schema(type_name));
-                }
-            }
+    /// Resolve an array or object literal, following one identifier hop.
+    fn literal_values(&self, expr: &Expression) -> Option<JsonValue> {
+        match expr {
+            Expression::Identifier(id) => self
+                .symbol_table
+                .get_binding(id.name.as_str())
+                .and_then(literal_to_json),
+            other => literal_to_json(other),
         }
+    }
+}
 
-        // Or it might be a nested builder call
-        if self.is_schema_builder_call(call) {
-            return self.parse_schema_builder_call(call);
+/// Allow `null` in addition to the schema's type.
+fn make_nullable(schema: &mut JsonValue) {
+    match schema.get("type").cloned() {
+        Some(JsonValue::String(ty)) => schema["type"] = json!([ty, "null"]),
+        Some(JsonValue::Array(mut types)) if !types.contains(&json!("null")) => {
+            types.push(json!("null"));
+            schema["type"] = JsonValue::Array(types);
+        }
+        Some(_) => {}
+        None => {
+            let inner = std::mem::take(schema);
+            *schema = json!({ "anyOf": [inner, { "type": "null" }] });
         }
+    }
+}
 
-        None
+/// Merge the properties and required list of `extra` into `schema`.
+fn merge_object_schemas(schema: &mut JsonValue, extra: JsonValue) {
+    if let Some(props) = extra.get("properties").and_then(JsonValue::as_object) {
+        let target = schema
+            .as_object_mut()
+            .map(|m| m.entry("properties").or_insert_with(|| json!({})));
+        if let Some(JsonValue::Object(target)) = target {
+            target.extend(props.clone());
+        }
     }
+    if let Some(JsonValue::Array(extra_required)) = extra.get("required") {
+        let required = schema
+            .as_object_mut()
+            .map(|m| m.entry("required").or_insert_with(|| json!([])));
+        if let Some(JsonValue::Array(required)) = required {
+            required.extend(extra_required.iter().cloned());
+        }
+    }
+}
 
-    /// Convert type name to JSON schema.
-    fn type_name_to_schema(&self, type_name: &str) -> JsonValue {
-        let mut schema = Map::new();
+fn json_type(value: &JsonValue) -> Option<&'static str> {
+    match value {
+        JsonValue::String(_) => Some("string"),
+        JsonValue::Number(_) => Some("number"),
+        JsonValue::Bool(_) => Some("boolean"),
+        JsonValue::Null => Some("null"),
+        _ => None,
+    }
+}
 
-        match type_name {
-            "string" => {
-                schema.insert("type".to_string(), JsonValue::String("string".to_string()));
-            }
-            "number" => {
-                schema.insert("type".to_string(), JsonValue::String("number".to_string()));
+/// Convert a literal expression (object, array, string, number, boolean,
+/// null) into JSON. Minified booleans (`!0`, `!1`) are recognised.
+pub fn literal_to_json(expr: &Expression) -> Option<JsonValue> {
+    match expr {
+        Expression::StringLiteral(s) => Some(JsonValue::String(s.value.as_str().to_string())),
+        Expression::NumericLiteral(n) => serde_json::Number::from_f64(n.value).map(|num| {
+            if n.value.fract() == 0.0 && n.value.abs() < i64::MAX as f64 {
+                JsonValue::from(n.value as i64)
+            } else {
+                JsonValue::Number(num)
             }
-            "boolean" => {
-                schema.insert("type".to_string(), JsonValue::String("boolean".to_string()));
+        }),
+        Expression::BooleanLiteral(b) => Some(JsonValue::Bool(b.value)),
+        Expression::NullLiteral(_) => Some(JsonValue::Null),
+        Expression::TemplateLiteral(tmpl) if tmpl.expressions.is_empty() => tmpl
+            .quasis
+            .first()
+            .map(|q| JsonValue::String(q.value.raw.as_str().to_string())),
+        Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::LogicalNot => {
+            match &unary.argument {
+                Expression::NumericLiteral(n) => Some(JsonValue::Bool(n.value == 0.0)),
+                _ => None,
             }
-            "object" | "strictObject" => {
-                schema.insert("type".to_string(), JsonValue::String("object".to_string()));
+        }
+        Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::UnaryNegation => {
+            match literal_to_json(&unary.argument)? {
+                JsonValue::Number(n) => n
+                    .as_i64()
+                    .map(|i| JsonValue::from(-i))
+                    .or_else(|| n.as_f64().map(|f| JsonValue::from(-f))),
+                _ => None,
             }
-            "array" => {
-                schema.insert("type".to_string(), JsonValue::String("array".to_string()));
+        }
+        Expression::ArrayExpression(arr) => Some(JsonValue::Array(
+            arr.elements
+                .iter()
+                .filter_map(|elem| elem.as_expression().and_then(literal_to_json))
+                .collect(),
+        )),
+        Expression::ObjectExpression(obj) => {
+            let mut map = Map::new();
+            for prop in &obj.properties {
+                if let ObjectPropertyKind::ObjectProperty(p) = prop {
+                    let key = match &p.key {
+                        PropertyKey::StaticIdentifier(id) => id.name.as_str(),
+                        PropertyKey::StringLiteral(s) => s.value.as_str(),
+                        _ => continue,
+                    };
+                    if let Some(value) = literal_to_json(&p.value) {
+                        map.insert(key.to_string(), value);
+                    }
+                }
             }
-            _ => {}
+            Some(JsonValue::Object(map))
         }
-
-        JsonValue::Object(schema)
+        Expression::ParenthesizedExpression(paren) => literal_to_json(&paren.expression),
+        _ => None,
     }
 }
 
@@ -238,7 +509,49 @@ mod tests {
         let symbol_table = SymbolTable::new(parse_result.program());
         let extractor = SchemaExtractor::new(&symbol_table);
 
-        // Find the k.object() call
-        // This test is simplified - in real use, we'd traverse to find the call
+        let schema = symbol_table
+            .get_binding("schema")
+            .and_then(|expr| extractor.parse_schema_expr(expr))
+            .unwrap();
+
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["properties"]["name"]["type"], "string");
+        assert_eq!(schema["properties"]["age"]["description"], "The age");
+    }
+
+    #[test]
+    fn test_parse_zod_chains() {
+        let code = r#"
+            var Mode = y.enum(["default", "plan"]);
+            var Rule = y.string().describe("Permission rule");
+            var schema = y.object({
+                model: y.string().optional().describe("Model override"),
+                cleanupPeriodDays: y.number().int().nonnegative().default(30),
+                permissions: y.object({ allow: Rule.array().optional(), defaultMode: Mode.optional() }).strict(),
+                env: y.record(y.string(), y.string()).nullish(),
+                verbose: y.union([y.boolean(), y.literal("auto")])
+            }).passthrough();
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+
+        let symbol_table = SymbolTable::new(parse_result.program());
+        let extractor = SchemaExtractor::new(&symbol_table);
+        let schema = extractor
+            .parse_schema_expr(symbol_table.get_binding("schema").unwrap())
+            .unwrap();
+        let props = &schema["properties"];
+
+        assert_eq!(schema["required"], serde_json::json!(["permissions", "verbose"]));
+        assert_eq!(schema["additionalProperties"], true);
+        assert_eq!(props["model"]["description"], "Model override");
+        assert_eq!(props["cleanupPeriodDays"]["type"], "integer");
+        assert_eq!(props["cleanupPeriodDays"]["default"], 30);
+        assert_eq!(props["permissions"]["properties"]["allow"]["items"]["description"], "Permission rule");
+        assert_eq!(props["permissions"]["properties"]["defaultMode"]["enum"][1], "plan");
+        assert_eq!(props["env"]["type"], serde_json::json!(["object", "null"]));
+        assert_eq!(props["verbose"]["anyOf"][1]["const"], "auto");
     }
 }
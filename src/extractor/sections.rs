@@ -0,0 +1,501 @@
+//! Section trees for long prompts.
+//!
+//! Long prompts are parsed into a tree from their Markdown structure: `#`
+//! headings nest by level, while `IMPORTANT:` paragraphs, `<example>` blocks
+//! and bullet lists become leaf sections of the heading they appear under.
+//! Every section gets a slug path id that stays stable across versions as long
+//! as its heading does, so two versions can be compared section by section.
+
+use crate::analyzer::{HasProvenance, Provenance};
+use crate::extractor::prompts::SystemPrompt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Minimum prompt length to be outlined.
+const MIN_OUTLINE_LENGTH: usize = 1000;
+
+/// Maximum length of a title derived from a list lead-in.
+const MAX_TITLE_LENGTH: usize = 60;
+
+/// Structural role of a prompt section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionKind {
+    /// Text before the first heading.
+    Preamble,
+    /// A Markdown `#` heading.
+    Heading,
+    /// A paragraph starting with `IMPORTANT:`.
+    Important,
+    /// An `<example>` block.
+    Example,
+    /// A run of bullet or numbered list items.
+    List,
+}
+
+/// A section of a prompt and its nested sections.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptSection {
+    /// Slug path, e.g. `doing-tasks/git-commits`.
+    pub id: String,
+
+    /// Heading text, or a descriptive title for blocks.
+    pub title: String,
+
+    /// Structural role.
+    pub kind: SectionKind,
+
+    /// Heading level (1-6); 0 for other kinds.
+    #[serde(skip_serializing_if = "is_zero", default)]
+    pub level: usize,
+
+    /// Text directly in this section, excluding nested sections.
+    pub content: String,
+
+    /// Nested sections in document order.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub children: Vec<PromptSection>,
+}
+
+/// The section tree of one prompt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptOutline {
+    /// Id of the outlined prompt.
+    pub prompt_id: String,
+
+    /// Length of the prompt.
+    pub length: usize,
+
+    /// Top-level sections.
+    pub sections: Vec<PromptSection>,
+
+    /// Where the outlined prompt is defined.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for PromptOutline {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+}
+
+/// How a section differs between two outlines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A section-level difference between two outlines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionChange {
+    /// Section id.
+    pub id: String,
+
+    /// Section title.
+    pub title: String,
+
+    /// Kind of change.
+    pub change: SectionChangeKind,
+}
+
+impl PromptOutline {
+    /// Outline a prompt, if it is long and has structure beyond plain text.
+    pub fn from_prompt(prompt: &SystemPrompt) -> Option<Self> {
+        if prompt.content.len() < MIN_OUTLINE_LENGTH {
+            return None;
+        }
+
+        let sections = parse_sections(&prompt.content);
+        if sections.iter().all(|s| s.kind == SectionKind::Preamble) {
+            return None;
+        }
+
+        Some(Self {
+            prompt_id: prompt.id.clone(),
+            length: prompt.content.len(),
+            sections,
+            provenance: prompt.provenance.clone(),
+        })
+    }
+
+    /// Render the outline as a Markdown document.
+    pub fn render_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n{} chars\n", self.prompt_id, self.length);
+        for section in &self.sections {
+            render_section(section, 2, &mut out);
+        }
+        out
+    }
+}
+
+/// Outline every long, structured prompt.
+pub fn outline_prompts(prompts: &[SystemPrompt]) -> Vec<PromptOutline> {
+    prompts.iter().filter_map(PromptOutline::from_prompt).collect()
+}
+
+/// Parse prompt text into a section tree.
+pub fn parse_sections(text: &str) -> Vec<PromptSection> {
+    let mut builder = TreeBuilder::default();
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if let Some((level, title)) = heading(trimmed) {
+            builder.open_heading(level, title);
+        } else if trimmed.starts_with("<example") {
+            let mut body = vec![trimmed.trim_start_matches(|c| c != '>').trim_start_matches('>')];
+            let mut closed = trimmed.contains("</example>");
+            while !closed {
+                let Some(next) = lines.next() else { break };
+                closed = next.contains("</example>");
+                body.push(next);
+            }
+            let content = body.join("\n");
+            let content = content.split("</example>").next().unwrap_or_default().trim();
+            builder.leaf(SectionKind::Example, "Example".to_string(), content.to_string());
+        } else if trimmed.starts_with("IMPORTANT:") {
+            let mut body = vec![trimmed];
+            while let Some(next) = lines.next_if(|l| !l.trim().is_empty() && heading(l.trim()).is_none()) {
+                body.push(next.trim());
+            }
+            builder.leaf(SectionKind::Important, "IMPORTANT".to_string(), body.join("\n"));
+        } else if is_list_item(trimmed) {
+            let mut body = vec![line.trim_end()];
+            while let Some(next) = lines.next_if(|l| is_list_item(l.trim()) || is_continuation(l)) {
+                body.push(next.trim_end());
+            }
+            let title = builder.take_lead_in().unwrap_or_else(|| "List".to_string());
+            builder.leaf(SectionKind::List, title, body.join("\n"));
+        } else {
+            builder.text(line.trim_end());
+        }
+    }
+
+    builder.finish()
+}
+
+/// Compare two section trees by section id.
+pub fn diff_sections(old: &[PromptSection], new: &[PromptSection]) -> Vec<SectionChange> {
+    let old_flat = flatten(old);
+    let new_flat = flatten(new);
+    let old_map: HashMap<&str, &PromptSection> = old_flat.iter().map(|s| (s.id.as_str(), *s)).collect();
+    let new_ids: HashSet<&str> = new_flat.iter().map(|s| s.id.as_str()).collect();
+
+    let mut changes = Vec::new();
+    for section in &new_flat {
+        let change = match old_map.get(section.id.as_str()) {
+            None => Some(SectionChangeKind::Added),
+            Some(old) if old.content != section.content => Some(SectionChangeKind::Changed),
+            Some(_) => None,
+        };
+        if let Some(change) = change {
+            changes.push(SectionChange {
+                id: section.id.clone(),
+                title: section.title.clone(),
+                change,
+            });
+        }
+    }
+    for section in old_flat.iter().filter(|s| !new_ids.contains(s.id.as_str())) {
+        changes.push(SectionChange {
+            id: section.id.clone(),
+            title: section.title.clone(),
+            change: SectionChangeKind::Removed,
+        });
+    }
+
+    changes
+}
+
+/// Builds the tree with a stack of open headings.
+#[derive(Default)]
+struct TreeBuilder {
+    /// Finished top-level sections.
+    roots: Vec<PromptSection>,
+    /// Open headings, outermost first.
+    stack: Vec<PromptSection>,
+    /// Text lines not yet attached to a section.
+    pending: Vec<String>,
+}
+
+impl TreeBuilder {
+    fn open_heading(&mut self, level: usize, title: &str) {
+        self.flush_text();
+        while self.stack.last().is_some_and(|s| s.level >= level) {
+            self.close();
+        }
+        self.stack.push(section(SectionKind::Heading, title.to_string(), level, String::new()));
+    }
+
+    fn leaf(&mut self, kind: SectionKind, title: String, content: String) {
+        self.flush_text();
+        self.attach(section(kind, title, 0, content));
+    }
+
+    fn text(&mut self, line: &str) {
+        self.pending.push(line.to_string());
+    }
+
+    /// Take a trailing `…:` line as the title of the list that follows.
+    fn take_lead_in(&mut self) -> Option<String> {
+        let last = self.pending.last()?.trim();
+        let title = last.strip_suffix(':')?.trim();
+        if title.is_empty() || title.len() > MAX_TITLE_LENGTH {
+            return None;
+        }
+        let title = title.to_string();
+        self.pending.pop();
+        Some(title)
+    }
+
+    /// Move pending text into the innermost heading, or a preamble.
+    fn flush_text(&mut self) {
+        let text = self.pending.join("\n").trim().to_string();
+        self.pending.clear();
+        if text.is_empty() {
+            return;
+        }
+
+        match self.stack.last_mut() {
+            Some(open) if open.content.is_empty() => open.content = text,
+            Some(open) => {
+                open.content.push_str("\n\n");
+                open.content.push_str(&text);
+            }
+            None => self.roots.push(section(SectionKind::Preamble, "Preamble".to_string(), 0, text)),
+        }
+    }
+
+    fn attach(&mut self, child: PromptSection) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(child),
+            None => self.roots.push(child),
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(done) = self.stack.pop() {
+            self.attach(done);
+        }
+    }
+
+    fn finish(mut self) -> Vec<PromptSection> {
+        self.flush_text();
+        while !self.stack.is_empty() {
+            self.close();
+        }
+        assign_ids(&mut self.roots, "");
+        self.roots
+    }
+}
+
+fn section(kind: SectionKind, title: String, level: usize, content: String) -> PromptSection {
+    PromptSection {
+        id: String::new(),
+        title,
+        kind,
+        level,
+        content,
+        children: Vec::new(),
+    }
+}
+
+/// Give each section a slug path, numbering repeated sibling slugs.
+fn assign_ids(sections: &mut [PromptSection], prefix: &str) {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for section in sections {
+        let base = slugify(&section.title);
+        let count = seen.entry(base.clone()).or_insert(0);
+        *count += 1;
+        let slug = if *count == 1 { base } else { format!("{}-{}", base, count) };
+
+        section.id = if prefix.is_empty() { slug } else { format!("{}/{}", prefix, slug) };
+        let id = section.id.clone();
+        assign_ids(&mut section.children, &id);
+    }
+}
+
+/// Lowercase alphanumeric words joined by `-`.
+fn slugify(title: &str) -> String {
+    let slug = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() { "section".to_string() } else { slug }
+}
+
+/// A Markdown ATX heading: level and title.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let title = line[level..].strip_prefix(' ')?.trim().trim_end_matches('#').trim();
+    (!title.is_empty()).then_some((level, title))
+}
+
+/// `- item`, `* item`, `+ item` or `1. item`.
+fn is_list_item(line: &str) -> bool {
+    if line.starts_with("- ") || line.starts_with("* ") || line.starts_with("+ ") {
+        return true;
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    digits > 0 && line[digits..].starts_with(". ")
+}
+
+/// Indented line continuing a list item.
+fn is_continuation(line: &str) -> bool {
+    line.starts_with("  ") && !line.trim().is_empty()
+}
+
+fn flatten(sections: &[PromptSection]) -> Vec<&PromptSection> {
+    let mut out = Vec::new();
+    for section in sections {
+        out.push(section);
+        out.extend(flatten(&section.children));
+    }
+    out
+}
+
+fn render_section(section: &PromptSection, depth: usize, out: &mut String) {
+    let hashes = "#".repeat(depth.min(6));
+    out.push_str(&format!("\n{} {}\n\n", hashes, section.title));
+    out.push_str(&format!("`{}` ({:?})\n", section.id, section.kind));
+
+    if !section.content.is_empty() {
+        out.push('\n');
+        match section.kind {
+            SectionKind::Example | SectionKind::Important => {
+                for line in section.content.lines() {
+                    out.push_str(&format!("> {}\n", line).replace("> \n", ">\n"));
+                }
+            }
+            _ => {
+                out.push_str(&section.content);
+                out.push('\n');
+            }
+        }
+    }
+
+    for child in &section.children {
+        render_section(child, depth + 1, out);
+    }
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::prompts::PromptCategory;
+    use crate::parser::SourceSpan;
+    use oxc_span::Span;
+
+    const PROMPT: &str = "You are an interactive CLI tool.
+IMPORTANT: Refuse to write malicious code.
+
+# Tone and style
+Be concise.
+
+<example>
+user: 2 + 2
+assistant: 4
+</example>
+
+## Examples
+<example>
+user: is 11 prime?
+assistant: Yes
+</example>
+
+# Doing tasks
+Follow these steps:
+- Search the codebase
+- Implement the solution
+  using all tools available
+1. Verify with tests
+
+# Doing tasks
+Duplicate heading.";
+
+    #[test]
+    fn test_parses_section_tree() {
+        let sections = parse_sections(PROMPT);
+        let ids: Vec<_> = flatten(&sections).iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "preamble",
+                "important",
+                "tone-and-style",
+                "tone-and-style/example",
+                "tone-and-style/examples",
+                "tone-and-style/examples/example",
+                "doing-tasks",
+                "doing-tasks/follow-these-steps",
+                "doing-tasks-2",
+            ]
+        );
+
+        assert_eq!(sections[2].content, "Be concise.");
+        assert_eq!(sections[2].children[0].content, "user: 2 + 2\nassistant: 4");
+        assert_eq!(sections[2].children[1].level, 2);
+
+        let list = &sections[3].children[0];
+        assert_eq!(list.kind, SectionKind::List);
+        assert_eq!(list.title, "Follow these steps");
+        assert_eq!(list.content.lines().count(), 4);
+        assert!(sections[3].content.is_empty());
+    }
+
+    #[test]
+    fn test_diffs_sections_by_id() {
+        let old = parse_sections(PROMPT);
+        let changed = PROMPT
+            .replace("Be concise.", "Be very concise.")
+            .replace("# Doing tasks\nDuplicate heading.", "# Git commits\nCommit carefully.");
+        let new = parse_sections(&changed);
+
+        let changes = diff_sections(&old, &new);
+        let summary: Vec<_> = changes.iter().map(|c| (c.id.as_str(), c.change)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("tone-and-style", SectionChangeKind::Changed),
+                ("git-commits", SectionChangeKind::Added),
+                ("doing-tasks-2", SectionChangeKind::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_keeps_prompt_provenance() {
+        let content = PROMPT.repeat(3);
+        let provenance = Provenance {
+            span: SourceSpan::new("var p = `x`;", Span::new(8, 11)),
+            beautified: None,
+            function: None,
+            binding: Some("p".to_string()),
+        };
+        let prompt = SystemPrompt {
+            id: "prompt_lit_0".to_string(),
+            length: content.len(),
+            content,
+            category: PromptCategory::System,
+            tokens: None,
+            provenance: Some(provenance.clone()),
+        };
+
+        let outline = PromptOutline::from_prompt(&prompt).unwrap();
+        assert_eq!(outline.provenance, Some(provenance));
+    }
+}
@@ -0,0 +1,432 @@
+//! Settings schema extraction.
+//!
+//! The settings file is validated against a zod object whose shape carries a
+//! `permissions` member with `allow`/`deny` rule lists. Rules follow a small
+//! grammar, `Tool` or `Tool(specifier)`, e.g. `Bash(npm run test:*)` or
+//! `WebFetch(domain:example.com)`; its examples and parsing regexes are
+//! recovered from literals elsewhere in the bundle.
+
+use crate::analyzer::{Analyzer, HasProvenance, Provenance, SymbolTable};
+use crate::extractor::members::property_key;
+use crate::extractor::schemas::SchemaExtractor;
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{debug, trace};
+
+/// Grammar of a permission rule as a JSON Schema pattern.
+const RULE_PATTERN: &str = r"^[A-Za-z][\w-]*(\(.*\))?$";
+
+/// Permission lists whose items are rules.
+const RULE_LISTS: &[&str] = &["allow", "deny", "ask"];
+
+/// Maximum number of examples kept per tool.
+const MAX_EXAMPLES_PER_TOOL: usize = 8;
+
+/// The settings schema and the permission rule grammar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsSchema {
+    /// Object schema of the settings file.
+    pub schema: JsonValue,
+
+    /// Grammar of `permissions.allow`/`deny`/`ask` entries.
+    pub permission_rules: PermissionRuleGrammar,
+
+    /// Where the settings shape was found in the bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for SettingsSchema {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+}
+
+/// Grammar of permission rules such as `Bash(git diff:*)`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PermissionRuleGrammar {
+    /// Regex every rule matches.
+    pub pattern: String,
+
+    /// Regex literals in the bundle that parse rules into tool and specifier.
+    pub parser_patterns: Vec<String>,
+
+    /// Specifier markers the bundle tests for, e.g. `:*` or `domain:`.
+    pub specifier_markers: Vec<String>,
+
+    /// Example specifiers found in the bundle, keyed by tool.
+    pub examples: BTreeMap<String, Vec<String>>,
+}
+
+impl SettingsSchema {
+    /// Render as a standalone JSON Schema document.
+    pub fn to_json_schema(&self) -> JsonValue {
+        let mut doc = Map::new();
+        doc.insert(
+            "$schema".to_string(),
+            json!("https://json-schema.org/draft/2020-12/schema"),
+        );
+        doc.insert("title".to_string(), json!("Claude Code settings"));
+
+        let mut schema = self.schema.clone();
+        if let Some(permissions) = schema.pointer_mut("/properties/permissions/properties") {
+            for list in RULE_LISTS {
+                if let Some(items) = permissions.pointer_mut(&format!("/{}/items", list)) {
+                    *items = json!({ "$ref": "#/$defs/permissionRule" });
+                }
+            }
+        }
+        if let JsonValue::Object(map) = schema {
+            doc.extend(map);
+        }
+
+        let rules = &self.permission_rules;
+        let examples: Vec<String> = rules
+            .examples
+            .iter()
+            .flat_map(|(tool, specs)| specs.iter().map(move |s| format!("{}({})", tool, s)))
+            .collect();
+        let mut rule = json!({
+            "type": "string",
+            "description": "A tool name, optionally followed by a parenthesised specifier.",
+            "pattern": rules.pattern,
+        });
+        if !examples.is_empty() {
+            rule["examples"] = json!(examples);
+        }
+        if !rules.parser_patterns.is_empty() {
+            rule["x-parser-patterns"] = json!(rules.parser_patterns);
+        }
+        doc.insert("$defs".to_string(), json!({ "permissionRule": rule }));
+        if let Some(provenance) = &self.provenance {
+            doc.insert("x-provenance".to_string(), json!(provenance));
+        }
+
+        JsonValue::Object(doc)
+    }
+
+    /// Render a Markdown reference of settings keys and permission rules.
+    pub fn render_markdown(&self) -> String {
+        let mut out = String::from("# Settings Reference\n\n");
+        out.push_str("| Key | Type | Default | Description |\n");
+        out.push_str("|-----|------|---------|-------------|\n");
+        render_rows(&self.schema, "", &mut out);
+
+        let rules = &self.permission_rules;
+        out.push_str("\n## Permission Rules\n\n");
+        out.push_str("Entries in `permissions.allow`, `permissions.deny` and `permissions.ask` ");
+        out.push_str("are a tool name, optionally followed by a specifier in parentheses.\n\n");
+        out.push_str(&format!("Pattern: `{}`\n", rules.pattern));
+
+        if !rules.parser_patterns.is_empty() {
+            out.push_str("\nParsed in the bundle by:\n\n");
+            for pattern in &rules.parser_patterns {
+                out.push_str(&format!("- `{}`\n", pattern));
+            }
+        }
+        if !rules.specifier_markers.is_empty() {
+            out.push_str("\nSpecifier markers:\n\n");
+            for marker in &rules.specifier_markers {
+                out.push_str(&format!("- `{}`\n", marker));
+            }
+        }
+        if !rules.examples.is_empty() {
+            out.push_str("\n### Examples\n\n");
+            for (tool, specs) in &rules.examples {
+                for spec in specs {
+                    out.push_str(&format!("- `{}({})`\n", tool, spec));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Emit one table row per property, recursing into nested objects.
+fn render_rows(schema: &JsonValue, prefix: &str, out: &mut String) {
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return;
+    };
+
+    for (key, prop) in properties {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        let default = prop
+            .get("default")
+            .map(|d| format!("`{}`", d))
+            .unwrap_or_default();
+        let description = prop
+            .get("description")
+            .and_then(|d| d.as_str())
+            .unwrap_or_default()
+            .replace('\n', " ");
+
+        out.push_str(&format!(
+            "| `{}` | {} | {} | {} |\n",
+            path,
+            escape_cell(&type_label(prop)),
+            escape_cell(&default),
+            escape_cell(&description)
+        ));
+        render_rows(prop, &path, out);
+    }
+}
+
+/// Short human-readable type of a schema, e.g. `array<string>` or `"a" | "b"`.
+fn type_label(schema: &JsonValue) -> String {
+    if let Some(values) = schema.get("enum").and_then(|e| e.as_array()) {
+        return values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" | ");
+    }
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+    for combinator in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(combinator).and_then(|v| v.as_array()) {
+            return variants.iter().map(type_label).collect::<Vec<_>>().join(" | ");
+        }
+    }
+
+    let label = match schema.get("type") {
+        Some(JsonValue::String(t)) => t.clone(),
+        Some(JsonValue::Array(types)) => types
+            .iter()
+            .filter_map(|t| t.as_str())
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => return "any".to_string(),
+    };
+    match (label.as_str(), schema.get("items")) {
+        ("array", Some(items)) => format!("array<{}>", type_label(items)),
+        _ => label,
+    }
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Extractor for the settings schema.
+pub struct SettingsExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+    tool_names: Vec<String>,
+}
+
+impl<'a> SettingsExtractor<'a> {
+    /// Create a new settings extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        Self {
+            analyzer,
+            symbol_table,
+            tool_names: Vec::new(),
+        }
+    }
+
+    /// Restrict permission rule examples to known tool names.
+    pub fn with_tool_names(mut self, tool_names: Vec<String>) -> Self {
+        self.tool_names = tool_names;
+        self
+    }
+
+    /// Extract the settings schema, if the bundle defines one.
+    pub fn extract(&self) -> Result<Option<SettingsSchema>> {
+        debug!("Extracting settings schema");
+
+        let Some((schema, span)) = self.find_settings_shape() else {
+            debug!("No settings schema found");
+            return Ok(None);
+        };
+
+        Ok(Some(SettingsSchema {
+            schema,
+            permission_rules: self.permission_rules(),
+            provenance: Some(self.analyzer.provenance(span)),
+        }))
+    }
+
+    /// Find the largest object shape with a `permissions` member holding rule
+    /// lists, and the object's span.
+    fn find_settings_shape(&self) -> Option<(JsonValue, Span)> {
+        let schemas = SchemaExtractor::new(self.symbol_table);
+        let candidates = self.analyzer.find_expressions(|expr| match expr {
+            Expression::ObjectExpression(obj) => obj.properties.iter().any(|p| {
+                matches!(p, ObjectPropertyKind::ObjectProperty(p) if property_key(&p.key) == Some("permissions"))
+            }),
+            _ => false,
+        });
+
+        candidates
+            .into_iter()
+            .filter_map(|expr| match expr {
+                Expression::ObjectExpression(obj) => Some((schemas.parse_object_shape(obj), obj.span)),
+                _ => None,
+            })
+            .filter(|(schema, _)| {
+                let permissions = &schema["properties"]["permissions"]["properties"];
+                RULE_LISTS[..2].iter().any(|list| permissions.get(list).is_some())
+            })
+            .max_by_key(|(schema, _)| {
+                schema["properties"].as_object().map_or(0, |p| p.len())
+            })
+    }
+
+    /// Recover the rule grammar from literals in the bundle.
+    fn permission_rules(&self) -> PermissionRuleGrammar {
+        let source_text = self.analyzer.program().source_text;
+        let mut examples: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut parser_patterns = BTreeSet::new();
+        let mut markers = BTreeSet::new();
+
+        for expr in self.analyzer.find_expressions(|expr| {
+            matches!(
+                expr,
+                Expression::StringLiteral(_)
+                    | Expression::RegExpLiteral(_)
+                    | Expression::CallExpression(_)
+            )
+        }) {
+            match expr {
+                Expression::StringLiteral(s) => {
+                    if let Some((tool, spec)) = self.parse_rule(s.value.as_str()) {
+                        trace!("Permission rule example: {}({})", tool, spec);
+                        examples.entry(tool.to_string()).or_default().insert(spec.to_string());
+                    }
+                }
+                Expression::RegExpLiteral(re) => {
+                    let literal = &source_text[re.span().start as usize..re.span().end as usize];
+                    if literal.contains(r"\(") && literal.contains(r"\)") {
+                        parser_patterns.insert(literal.to_string());
+                    }
+                }
+                Expression::CallExpression(call) => {
+                    if let Some(marker) = marker_argument(call) {
+                        markers.insert(marker);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Only keep markers that real rule examples use
+        let specifier_markers = markers
+            .into_iter()
+            .filter(|m| {
+                examples
+                    .values()
+                    .flatten()
+                    .any(|spec| spec.starts_with(m.as_str()) || spec.ends_with(m.as_str()))
+            })
+            .collect();
+
+        PermissionRuleGrammar {
+            pattern: RULE_PATTERN.to_string(),
+            parser_patterns: parser_patterns.into_iter().collect(),
+            specifier_markers,
+            examples: examples
+                .into_iter()
+                .map(|(tool, specs)| (tool, specs.into_iter().take(MAX_EXAMPLES_PER_TOOL).collect()))
+                .collect(),
+        }
+    }
+
+    /// Split `Tool(specifier)` into its parts, if the tool is known.
+    fn parse_rule<'s>(&self, text: &'s str) -> Option<(&'s str, &'s str)> {
+        if text.len() > 256 || text.contains('\n') {
+            return None;
+        }
+        let (tool, rest) = text.split_once('(')?;
+        let spec = rest.strip_suffix(')')?;
+        if spec.trim().is_empty() || spec.contains('(') || spec.contains(')') {
+            return None;
+        }
+
+        let known = if self.tool_names.is_empty() {
+            tool.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+                && tool.chars().all(|c| c.is_ascii_alphanumeric())
+        } else {
+            self.tool_names.iter().any(|t| t == tool)
+        };
+        known.then_some((tool, spec))
+    }
+}
+
+/// The string argument of `x.startsWith("domain:")` or `x.endsWith(":*")`.
+fn marker_argument(call: &CallExpression) -> Option<String> {
+    let Expression::StaticMemberExpression(member) = &call.callee else {
+        return None;
+    };
+    if !matches!(member.property.name.as_str(), "startsWith" | "endsWith") {
+        return None;
+    }
+    match call.arguments.first()?.as_expression()? {
+        Expression::StringLiteral(s) if s.value.contains(':') && s.value.len() <= 16 => {
+            Some(s.value.to_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_extracts_settings_schema() {
+        let code = r#"
+            var rule = k.string().describe("Permission rule");
+            var settings = k.object({
+                apiKeyHelper: k.string().optional().describe("Script that prints an API key"),
+                cleanupPeriodDays: k.number().nonnegative().int().optional(),
+                permissions: k.object({
+                    allow: k.array(rule).optional(),
+                    deny: k.array(rule).optional(),
+                    defaultMode: k.enum(["default", "plan"]).optional()
+                }).optional()
+            }).passthrough();
+            function parse(A) { return A.match(/^([^(]+)\(([^)]+)\)$/) }
+            function prefix(A) { return A.endsWith(":*") }
+            var hints = ["Bash(npm run test:*)", "WebFetch(domain:example.com)", "Read(x)"];
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let settings = SettingsExtractor::new(&analyzer, &symbol_table)
+            .with_tool_names(vec!["Bash".to_string(), "WebFetch".to_string()])
+            .extract()
+            .unwrap()
+            .unwrap();
+
+        let rules = &settings.permission_rules;
+        assert_eq!(rules.examples["Bash"], ["npm run test:*"]);
+        assert!(!rules.examples.contains_key("Read"));
+        assert_eq!(rules.specifier_markers, [":*"]);
+        assert_eq!(rules.parser_patterns, [r"/^([^(]+)\(([^)]+)\)$/"]);
+
+        let doc = settings.to_json_schema();
+        assert_eq!(
+            doc["properties"]["permissions"]["properties"]["allow"]["items"]["$ref"],
+            "#/$defs/permissionRule"
+        );
+        assert_eq!(doc["$defs"]["permissionRule"]["pattern"], RULE_PATTERN);
+        let line = settings.provenance.as_ref().unwrap().span.line;
+        assert_eq!(doc["x-provenance"]["span"]["line"], line);
+
+        let markdown = settings.render_markdown();
+        assert!(markdown.contains("| `apiKeyHelper` | string |  | Script that prints an API key |"));
+        assert!(markdown.contains("| `permissions.defaultMode` | \"default\" \\| \"plan\" |"));
+    }
+}
@@ -1,6 +1,7 @@
 //! Interesting string literal extraction.
 
-use crate::analyzer::{Analyzer, StringLiteralInfo};
+use crate::analyzer::{Analyzer, HasProvenance, Provenance, StringLiteralInfo};
+use crate::rules::{Rules, Subject};
 use crate::Result;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
@@ -19,6 +20,16 @@ pub struct InterestingString {
 
     /// Relevance score (0.0-1.0).
     pub relevance: f32,
+
+    /// Where the string was found in the bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for InterestingString {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
 }
 
 /// Category of interesting string.
@@ -43,12 +54,22 @@ pub enum StringCategory {
 /// Extractor for interesting strings.
 pub struct StringExtractor<'a> {
     analyzer: &'a Analyzer<'a>,
+    rules: &'a Rules,
 }
 
 impl<'a> StringExtractor<'a> {
     /// Create a new string extractor.
     pub fn new(analyzer: &'a Analyzer<'a>) -> Self {
-        Self { analyzer }
+        Self {
+            analyzer,
+            rules: Rules::bundled(),
+        }
+    }
+
+    /// Use these extraction rules instead of the bundled ones.
+    pub fn with_rules(mut self, rules: &'a Rules) -> Self {
+        self.rules = rules;
+        self
     }
 
     /// Extract all interesting strings.
@@ -67,27 +88,28 @@ impl<'a> StringExtractor<'a> {
         // Sort by relevance
         strings.sort_by(|a, b| b.relevance.partial_cmp(&a.relevance).unwrap());
 
-        // Limit to top 1000 most relevant strings
-        strings.truncate(1000);
+        // Keep only the most relevant strings
+        strings.truncate(self.rules.strings.limit);
 
         debug!("Extracted {} interesting strings", strings.len());
         Ok(strings)
     }
 
     /// Classify a string and determine if it's interesting.
-    fn classify_string(&self, literal: &StringLiteralInfo) -> Option<InterestingString> {
+    fn classify_string(&self, literal: &StringLiteralInfo<'a>) -> Option<InterestingString> {
         let value = literal.value;
         let length = literal.length;
+        let rules = &self.rules.strings;
 
         // Ignore very short strings
-        if length < 5 {
+        if length < rules.min_length {
             return None;
         }
 
-        let (category, relevance) = self.categorize_and_score(value, length);
+        let (category, relevance) = rules.classify(&Subject::literal(self.analyzer, literal));
 
         // Only include if relevance is above threshold
-        if relevance < 0.3 {
+        if relevance < rules.min_relevance {
             return None;
         }
 
@@ -96,54 +118,9 @@ impl<'a> StringExtractor<'a> {
             length,
             category,
             relevance,
+            provenance: Some(self.analyzer.provenance(literal.span)),
         })
     }
-
-    /// Categorize and score a string.
-    fn categorize_and_score(&self, value: &str, length: usize) -> (StringCategory, f32) {
-        let mut score = 0.0;
-        let mut category = StringCategory::Other;
-
-        // URL detection
-        if value.starts_with("http://") || value.starts_with("https://") {
-            category = StringCategory::Url;
-            score = 0.9;
-        }
-        // Path detection
-        else if value.starts_with('/') || value.contains("\\") || value.contains("./") {
-            category = StringCategory::Path;
-            score = 0.7;
-        }
-        // Error message
-        else if value.starts_with("Error:") || value.starts_with("Failed") {
-            category = StringCategory::ErrorMessage;
-            score = 0.8;
-        }
-        // Log message
-        else if value.contains("[INFO]")
-            || value.contains("[ERROR]")
-            || value.contains("[DEBUG]")
-        {
-            category = StringCategory::LogMessage;
-            score = 0.6;
-        }
-        // Documentation
-        else if length > 50 && (value.contains("/**") || value.contains("///")) {
-            category = StringCategory::Documentation;
-            score = 0.5;
-        }
-        // Code snippet
-        else if value.contains("function") || value.contains("const ") || value.contains("=>") {
-            category = StringCategory::CodeSnippet;
-            score = 0.4;
-        }
-        // Other interesting patterns
-        else if length > 20 && length < 200 {
-            score = 0.3;
-        }
-
-        (category, score)
-    }
 }
 
 #[cfg(test)]
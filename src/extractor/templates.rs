@@ -0,0 +1,356 @@
+//! Prompt template reconstruction.
+//!
+//! `SymbolTable` flattens every unresolved interpolation to `${...}`. Here
+//! prompt-like template literals are emitted with named placeholders instead:
+//! the renamed binding when a rename map is supplied, `TOOL_NAME_<TOOL>` for
+//! constants holding a tool name, and the expression source otherwise. Each
+//! placeholder is described in a table alongside the template.
+
+use crate::analyzer::symbols::SymbolValue;
+use crate::analyzer::{Analyzer, SymbolTable};
+use crate::extractor::api_requests::SourceSpan;
+use crate::extractor::members::callee_name;
+use crate::Result;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{debug, trace};
+
+/// Minimum static text for a template to be treated as a prompt.
+const MIN_TEMPLATE_TEXT: usize = 80;
+
+/// Minimum number of words in the static text.
+const MIN_TEMPLATE_WORDS: usize = 12;
+
+/// Maximum length of a placeholder name derived from source.
+const MAX_PLACEHOLDER_NAME: usize = 60;
+
+/// What kind of expression fills a placeholder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaceholderKind {
+    /// Resolves to a known constant.
+    Constant,
+    /// A variable without a known constant value.
+    Variable,
+    /// The result of a function call.
+    Call,
+    /// A property access, e.g. `options.cwd`.
+    Member,
+    /// A conditional or logical expression.
+    Conditional,
+    /// Anything else.
+    Expression,
+}
+
+/// A named placeholder in a prompt template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Placeholder {
+    /// Name used inside `${…}` in the template.
+    pub name: String,
+
+    /// Source of the interpolated expression.
+    pub expression: String,
+
+    /// Kind of expression.
+    pub kind: PlaceholderKind,
+
+    /// Function producing the value, for calls and variables bound to calls.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+
+    /// Known constant value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// A prompt with named placeholders.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    /// Binding the template is assigned to, or a location-based id.
+    pub id: String,
+
+    /// Template text with named `${…}` placeholders.
+    pub template: String,
+
+    /// Template text with constant placeholders substituted.
+    pub rendered: String,
+
+    /// Placeholders in order of first appearance.
+    pub placeholders: Vec<Placeholder>,
+
+    /// Location of the template literal.
+    pub span: SourceSpan,
+}
+
+impl PromptTemplate {
+    /// Whether every placeholder is constant.
+    pub fn is_static(&self) -> bool {
+        self.placeholders.iter().all(|p| p.kind == PlaceholderKind::Constant)
+    }
+}
+
+/// Extractor for prompt templates.
+pub struct TemplateExtractor<'a> {
+    analyzer: &'a Analyzer<'a>,
+    symbol_table: &'a SymbolTable<'a>,
+    tool_names: Vec<String>,
+    rename_map: HashMap<String, String>,
+}
+
+impl<'a> TemplateExtractor<'a> {
+    /// Create a new template extractor.
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
+        Self {
+            analyzer,
+            symbol_table,
+            tool_names: Vec::new(),
+            rename_map: HashMap::new(),
+        }
+    }
+
+    /// Set the tool names used to name tool-name constants.
+    pub fn with_tool_names(mut self, tool_names: Vec<String>) -> Self {
+        self.tool_names = tool_names;
+        self
+    }
+
+    /// Name placeholders after renamed bindings where available.
+    pub fn with_rename_map(mut self, rename_map: HashMap<String, String>) -> Self {
+        self.rename_map = rename_map;
+        self
+    }
+
+    /// Extract prompt templates in source order.
+    pub fn extract(&self) -> Result<Vec<PromptTemplate>> {
+        debug!("Extracting prompt templates");
+
+        // Name templates after the variable they initialise
+        let bound: HashMap<u32, &str> = self
+            .symbol_table
+            .symbols
+            .keys()
+            .filter_map(|name| {
+                let binding = self.symbol_table.get_binding(name)?;
+                Some((binding.span().start, name.as_str()))
+            })
+            .collect();
+
+        let line_index = self.analyzer.line_index();
+        let templates: Vec<PromptTemplate> = self
+            .analyzer
+            .find_expressions(|expr| matches!(expr, Expression::TemplateLiteral(t) if !t.expressions.is_empty()))
+            .into_iter()
+            .filter_map(|expr| match expr {
+                Expression::TemplateLiteral(tmpl) if is_prompt_like(tmpl) => {
+                    let span = SourceSpan::locate(line_index, tmpl.span);
+                    let id = match bound.get(&tmpl.span.start) {
+                        Some(name) => name.to_string(),
+                        None => format!("template_L{}", span.line),
+                    };
+                    trace!("Prompt template {} at line {}", id, span.line);
+                    Some(self.template(id, tmpl, span))
+                }
+                _ => None,
+            })
+            .collect();
+
+        debug!("Extracted {} prompt templates", templates.len());
+        Ok(templates)
+    }
+
+    /// Build a template from a literal.
+    fn template(&self, id: String, tmpl: &TemplateLiteral<'a>, span: SourceSpan) -> PromptTemplate {
+        let mut template = String::new();
+        let mut rendered = String::new();
+        let mut placeholders: Vec<Placeholder> = Vec::new();
+
+        for (i, quasi) in tmpl.quasis.iter().enumerate() {
+            template.push_str(quasi.value.raw.as_str());
+            rendered.push_str(quasi.value.raw.as_str());
+            let Some(expr) = tmpl.expressions.get(i) else {
+                continue;
+            };
+
+            let placeholder = self.placeholder(expr);
+            template.push_str(&format!("${{{}}}", placeholder.name));
+            match (&placeholder.kind, &placeholder.value) {
+                (PlaceholderKind::Constant, Some(value)) => rendered.push_str(value),
+                _ => rendered.push_str(&format!("${{{}}}", placeholder.name)),
+            }
+            if !placeholders.iter().any(|p| p.name == placeholder.name) {
+                placeholders.push(placeholder);
+            }
+        }
+
+        PromptTemplate {
+            id,
+            template,
+            rendered,
+            placeholders,
+            span,
+        }
+    }
+
+    /// Describe an interpolated expression.
+    fn placeholder(&self, expr: &Expression<'a>) -> Placeholder {
+        let expression = self.source_of(expr.span());
+        let value = self.constant(expr);
+
+        let (kind, function) = match expr {
+            _ if value.is_some() => (PlaceholderKind::Constant, None),
+            Expression::Identifier(id) => {
+                let function = match self.symbol_table.get_binding(id.name.as_str()) {
+                    Some(Expression::CallExpression(call)) => callee_name(&call.callee),
+                    Some(Expression::AwaitExpression(await_expr)) => match &await_expr.argument {
+                        Expression::CallExpression(call) => callee_name(&call.callee),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                (PlaceholderKind::Variable, function)
+            }
+            Expression::CallExpression(call) => (PlaceholderKind::Call, callee_name(&call.callee)),
+            Expression::StaticMemberExpression(_) | Expression::ComputedMemberExpression(_) => {
+                (PlaceholderKind::Member, None)
+            }
+            Expression::ConditionalExpression(_) | Expression::LogicalExpression(_) => {
+                (PlaceholderKind::Conditional, None)
+            }
+            _ => (PlaceholderKind::Expression, None),
+        };
+
+        Placeholder {
+            name: self.placeholder_name(expr, &expression, value.as_deref()),
+            expression,
+            kind,
+            function,
+            value,
+        }
+    }
+
+    /// Pick a readable name for a placeholder.
+    fn placeholder_name(&self, expr: &Expression, source: &str, value: Option<&str>) -> String {
+        if let Expression::Identifier(id) = expr {
+            if let Some(renamed) = self.rename_map.get(id.name.as_str()) {
+                return renamed.clone();
+            }
+        }
+        if let Some(tool) = value.filter(|v| self.tool_names.iter().any(|t| t == v)) {
+            return format!("TOOL_NAME_{}", tool.to_ascii_uppercase());
+        }
+
+        let name = source.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.chars().count() <= MAX_PLACEHOLDER_NAME {
+            return name;
+        }
+        let truncated: String = name.chars().take(MAX_PLACEHOLDER_NAME).collect();
+        format!("{}…", truncated)
+    }
+
+    /// Resolve an expression to a fully constant string.
+    fn constant(&self, expr: &Expression) -> Option<String> {
+        let value = match expr {
+            Expression::NumericLiteral(n) => return Some(n.value.to_string()),
+            Expression::BooleanLiteral(b) => return Some(b.value.to_string()),
+            Expression::Identifier(id) => match self.symbol_table.get_value(id.name.as_str())? {
+                SymbolValue::Number(n) => return Some(n.to_string()),
+                SymbolValue::Boolean(b) => return Some(b.to_string()),
+                _ => self.symbol_table.get_string_value(id.name.as_str())?,
+            },
+            Expression::StringLiteral(s) => s.value.to_string(),
+            _ => return None,
+        };
+        (!value.contains("${...}")).then_some(value)
+    }
+
+    fn source_of(&self, span: Span) -> String {
+        self.analyzer.program().source_text[span.start as usize..span.end as usize].to_string()
+    }
+}
+
+/// Render templates as Markdown, each followed by its placeholder table.
+pub fn render_markdown(templates: &[PromptTemplate]) -> String {
+    let mut out = String::from("# Prompt Templates\n");
+
+    for template in templates {
+        out.push_str(&format!("\n## {}\n\n", template.id));
+        out.push_str(&format!("Line {}\n\n", template.span.line));
+        out.push_str("```text\n");
+        out.push_str(&template.template);
+        out.push_str("\n```\n\n");
+
+        out.push_str("| Placeholder | Kind | Function | Value |\n");
+        out.push_str("|-------------|------|----------|-------|\n");
+        for p in &template.placeholders {
+            out.push_str(&format!(
+                "| `{}` | {:?} | {} | {} |\n",
+                p.name.replace('|', "\\|"),
+                p.kind,
+                p.function.as_deref().map(|f| format!("`{}`", f)).unwrap_or_default(),
+                p.value.as_deref().map(|v| format!("`{}`", v.replace('|', "\\|"))).unwrap_or_default(),
+            ));
+        }
+    }
+
+    out
+}
+
+/// Prose-like templates: enough static text and words.
+fn is_prompt_like(tmpl: &TemplateLiteral) -> bool {
+    let text: String = tmpl.quasis.iter().map(|q| q.value.raw.as_str()).collect();
+    text.trim().len() >= MIN_TEMPLATE_TEXT && text.split_whitespace().count() >= MIN_TEMPLATE_WORDS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_extracts_named_placeholders() {
+        let code = r#"
+            var x4 = "Bash";
+            var dir = getCwd();
+            var PROMPT = `You are an interactive CLI tool that helps users with software engineering tasks.
+Use the ${x4} tool to run commands. Working directory: ${dir}. Platform: ${process.platform}.
+Today's date is ${new Date().toISOString()} and the model is ${isOpus() ? "opus" : "sonnet"}. Use ${x4} sparingly.`;
+            var short = `${a}-${b}`;
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let templates = TemplateExtractor::new(&analyzer, &symbol_table)
+            .with_tool_names(vec!["Bash".to_string()])
+            .extract()
+            .unwrap();
+        assert_eq!(templates.len(), 1);
+
+        let template = &templates[0];
+        assert_eq!(template.id, "PROMPT");
+        assert!(template.template.contains("Use the ${TOOL_NAME_BASH} tool"));
+        assert!(template.template.contains("Working directory: ${dir}."));
+        assert!(template.rendered.contains("Use the Bash tool"));
+        assert!(!template.is_static());
+
+        let kinds: Vec<_> = template.placeholders.iter().map(|p| (p.name.as_str(), p.kind)).collect();
+        assert_eq!(
+            kinds,
+            [
+                ("TOOL_NAME_BASH", PlaceholderKind::Constant),
+                ("dir", PlaceholderKind::Variable),
+                ("process.platform", PlaceholderKind::Member),
+                ("new Date().toISOString()", PlaceholderKind::Call),
+                ("isOpus() ? \"opus\" : \"sonnet\"", PlaceholderKind::Conditional),
+            ]
+        );
+        assert_eq!(template.placeholders[1].function.as_deref(), Some("getCwd"));
+        assert_eq!(template.placeholders[0].value.as_deref(), Some("Bash"));
+    }
+}
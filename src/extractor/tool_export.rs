@@ -0,0 +1,334 @@
+//! Export tool definitions as Anthropic Messages API `tools` and MCP `tools/list`.
+
+use crate::error::DecypherError;
+use crate::extractor::tools::ToolDefinition;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tracing::{debug, warn};
+
+/// Target format for exported tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolExportFormat {
+    /// Messages API `tools` array.
+    AnthropicApi,
+    /// MCP `tools/list` result.
+    Mcp,
+}
+
+impl ToolExportFormat {
+    /// File name used when writing this format under `extracted/`.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ToolExportFormat::AnthropicApi => "tools-anthropic-api.json",
+            ToolExportFormat::Mcp => "tools-mcp.json",
+        }
+    }
+}
+
+/// Which text to use as a tool's description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DescriptionMode {
+    /// One-line description, falling back to the full prompt.
+    #[default]
+    Short,
+    /// Full prompt, falling back to the short description.
+    Full,
+}
+
+/// A tool in Messages API format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTool {
+    /// Tool name.
+    pub name: String,
+
+    /// Tool description shown to the model.
+    pub description: String,
+
+    /// JSON Schema for the tool input.
+    pub input_schema: JsonValue,
+}
+
+/// A tool in MCP `tools/list` format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpTool {
+    /// Tool name.
+    pub name: String,
+
+    /// Human-readable title.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Tool description.
+    pub description: String,
+
+    /// JSON Schema for the tool input.
+    #[serde(rename = "inputSchema")]
+    pub input_schema: JsonValue,
+
+    /// JSON Schema for structured tool output.
+    #[serde(rename = "outputSchema", skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<JsonValue>,
+
+    /// Behavioral hints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<McpToolAnnotations>,
+}
+
+/// MCP tool annotations we can derive from tool properties.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpToolAnnotations {
+    /// Tool does not modify its environment.
+    #[serde(rename = "readOnlyHint")]
+    pub read_only_hint: bool,
+}
+
+/// Result of an MCP `tools/list` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpToolsList {
+    /// Listed tools.
+    pub tools: Vec<McpTool>,
+}
+
+/// Convert tools to the Messages API `tools` array.
+pub fn to_api_tools(tools: &[ToolDefinition], mode: DescriptionMode) -> Vec<ApiTool> {
+    tools
+        .iter()
+        .map(|tool| ApiTool {
+            name: tool.name.clone(),
+            description: description_for(tool, mode),
+            input_schema: object_schema(tool.input_schema.as_ref(), &tool.name),
+        })
+        .collect()
+}
+
+/// Convert tools to an MCP `tools/list` result.
+pub fn to_mcp_tools_list(tools: &[ToolDefinition], mode: DescriptionMode) -> McpToolsList {
+    let tools = tools
+        .iter()
+        .map(|tool| McpTool {
+            name: tool.name.clone(),
+            title: tool
+                .properties
+                .user_facing_name
+                .clone()
+                .filter(|title| *title != tool.name),
+            description: description_for(tool, mode),
+            input_schema: object_schema(tool.input_schema.as_ref(), &tool.name),
+            output_schema: tool.output_schema.clone().filter(is_object_schema),
+            annotations: tool
+                .properties
+                .is_read_only
+                .as_bool()
+                .map(|read_only_hint| McpToolAnnotations { read_only_hint }),
+        })
+        .collect();
+
+    McpToolsList { tools }
+}
+
+/// Convert tools to the given format and validate the result.
+pub fn export_tools(
+    tools: &[ToolDefinition],
+    format: ToolExportFormat,
+    mode: DescriptionMode,
+) -> Result<JsonValue> {
+    let value = match format {
+        ToolExportFormat::AnthropicApi => serde_json::to_value(to_api_tools(tools, mode)),
+        ToolExportFormat::Mcp => serde_json::to_value(to_mcp_tools_list(tools, mode)),
+    }
+    .map_err(|e| DecypherError::Other(e.into()))?;
+
+    match format {
+        ToolExportFormat::AnthropicApi => validate_api_tools(&value)?,
+        ToolExportFormat::Mcp => validate_mcp_tools_list(&value)?,
+    }
+
+    debug!("Exported {} tools as {:?}", tools.len(), format);
+    Ok(value)
+}
+
+/// Validate a Messages API `tools` array.
+pub fn validate_api_tools(value: &JsonValue) -> Result<()> {
+    let tools = value
+        .as_array()
+        .ok_or_else(|| invalid("Messages API tools", "expected an array"))?;
+
+    for (i, tool) in tools.iter().enumerate() {
+        let item = format!("Messages API tool #{}", i);
+        let obj = tool
+            .as_object()
+            .ok_or_else(|| invalid(&item, "expected an object"))?;
+
+        if let Some(key) = obj
+            .keys()
+            .find(|k| !matches!(k.as_str(), "name" | "description" | "input_schema"))
+        {
+            return Err(invalid(&item, &format!("unexpected field '{}'", key)));
+        }
+        check_name(&item, obj.get("name"))?;
+        check_optional_string(&item, obj.get("description"), "description")?;
+        check_input_schema(&item, obj.get("input_schema"), "input_schema")?;
+    }
+
+    Ok(())
+}
+
+/// Validate an MCP `tools/list` result.
+pub fn validate_mcp_tools_list(value: &JsonValue) -> Result<()> {
+    let tools = value
+        .get("tools")
+        .and_then(JsonValue::as_array)
+        .ok_or_else(|| invalid("MCP tools/list", "expected an object with a 'tools' array"))?;
+
+    for (i, tool) in tools.iter().enumerate() {
+        let item = format!("MCP tool #{}", i);
+        let obj = tool
+            .as_object()
+            .ok_or_else(|| invalid(&item, "expected an object"))?;
+
+        check_name(&item, obj.get("name"))?;
+        check_optional_string(&item, obj.get("title"), "title")?;
+        check_optional_string(&item, obj.get("description"), "description")?;
+        check_input_schema(&item, obj.get("inputSchema"), "inputSchema")?;
+        if let Some(output) = obj.get("outputSchema") {
+            check_input_schema(&item, Some(output), "outputSchema")?;
+        }
+        if let Some(annotations) = obj.get("annotations") {
+            if !annotations.is_object() {
+                return Err(invalid(&item, "'annotations' must be an object"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Pick the description text according to the mode.
+fn description_for(tool: &ToolDefinition, mode: DescriptionMode) -> String {
+    let (preferred, fallback) = match mode {
+        DescriptionMode::Short => (&tool.short_description, &tool.full_prompt),
+        DescriptionMode::Full => (&tool.full_prompt, &tool.short_description),
+    };
+
+    if preferred.trim().is_empty() {
+        fallback.clone()
+    } else {
+        preferred.clone()
+    }
+}
+
+/// Both formats require an object schema; substitute an empty one otherwise.
+fn object_schema(schema: Option<&JsonValue>, tool_name: &str) -> JsonValue {
+    match schema {
+        Some(schema) if is_object_schema(schema) => schema.clone(),
+        Some(_) => {
+            warn!("Input schema for {} is not an object schema; exporting an empty one", tool_name);
+            json!({ "type": "object", "properties": {} })
+        }
+        None => json!({ "type": "object", "properties": {} }),
+    }
+}
+
+fn is_object_schema(schema: &JsonValue) -> bool {
+    schema.get("type").and_then(JsonValue::as_str) == Some("object")
+}
+
+fn check_name(item: &str, name: Option<&JsonValue>) -> Result<()> {
+    let name = name
+        .and_then(JsonValue::as_str)
+        .ok_or_else(|| invalid(item, "'name' must be a string"))?;
+
+    let valid = (1..=64).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(invalid(
+            item,
+            &format!("name '{}' must match ^[a-zA-Z0-9_-]{{1,64}}$", name),
+        ));
+    }
+    Ok(())
+}
+
+fn check_optional_string(item: &str, value: Option<&JsonValue>, field: &str) -> Result<()> {
+    match value {
+        None | Some(JsonValue::String(_)) => Ok(()),
+        Some(_) => Err(invalid(item, &format!("'{}' must be a string", field))),
+    }
+}
+
+fn check_input_schema(item: &str, schema: Option<&JsonValue>, field: &str) -> Result<()> {
+    let schema = schema.ok_or_else(|| invalid(item, &format!("missing '{}'", field)))?;
+    if !is_object_schema(schema) {
+        return Err(invalid(item, &format!("'{}' must be a JSON Schema with type \"object\"", field)));
+    }
+    if let Some(properties) = schema.get("properties") {
+        if !properties.is_object() {
+            return Err(invalid(item, &format!("'{}.properties' must be an object", field)));
+        }
+    }
+    Ok(())
+}
+
+fn invalid(item: &str, message: &str) -> DecypherError {
+    DecypherError::extraction(item, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::tools::{ToolFlag, ToolProperties};
+
+    fn sample_tool() -> ToolDefinition {
+        ToolDefinition {
+            name: "Read".to_string(),
+            short_description: "Read a file".to_string(),
+            full_prompt: "Reads a file from the local filesystem.\n\nUsage: ...".to_string(),
+            input_schema: Some(json!({
+                "type": "object",
+                "properties": { "file_path": { "type": "string" } },
+                "required": ["file_path"]
+            })),
+            output_schema: None,
+            properties: ToolProperties {
+                is_read_only: ToolFlag::Constant(true),
+                ..ToolProperties::default()
+            },
+            confidence: 1.0,
+            tokens: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_export_api_tools() {
+        let value = export_tools(&[sample_tool()], ToolExportFormat::AnthropicApi, DescriptionMode::Short).unwrap();
+
+        assert_eq!(value[0]["name"], "Read");
+        assert_eq!(value[0]["description"], "Read a file");
+        assert_eq!(value[0]["input_schema"]["required"][0], "file_path");
+
+        let full = export_tools(&[sample_tool()], ToolExportFormat::AnthropicApi, DescriptionMode::Full).unwrap();
+        assert!(full[0]["description"].as_str().unwrap().starts_with("Reads a file"));
+    }
+
+    #[test]
+    fn test_export_mcp_tools_list() {
+        let mut tool = sample_tool();
+        tool.input_schema = None;
+
+        let value = export_tools(&[tool], ToolExportFormat::Mcp, DescriptionMode::Short).unwrap();
+
+        assert_eq!(value["tools"][0]["inputSchema"]["type"], "object");
+        assert_eq!(value["tools"][0]["annotations"]["readOnlyHint"], true);
+    }
+
+    #[test]
+    fn test_validation_rejects_bad_shapes() {
+        assert!(validate_api_tools(&json!([{ "name": "has space", "input_schema": { "type": "object" } }])).is_err());
+        assert!(validate_api_tools(&json!([{ "name": "Ok", "input_schema": { "type": "string" } }])).is_err());
+        assert!(validate_mcp_tools_list(&json!([{ "name": "Ok" }])).is_err());
+    }
+}
@@ -1,11 +1,14 @@
 //! Enhanced tool definition extraction using symbol table and schema extractor.
 
-use crate::analyzer::{Analyzer, ObjectExpressionInfo, SymbolTable};
+use crate::analyzer::{Analyzer, HasProvenance, ObjectExpressionInfo, Provenance, SymbolTable};
+use crate::extractor::commands::COMMAND_TYPES;
+use crate::extractor::members::{callee_name, MemberBody, ObjectMembers};
 use crate::extractor::prompts::SystemPrompt;
 use crate::extractor::schemas::SchemaExtractor;
 use crate::Result;
 use oxc_ast::ast::*;
 use serde::{Deserialize, Serialize};
+use oxc_span::GetSpan;
 use serde_json::Value as JsonValue;
 use tracing::{debug, trace};
 
@@ -34,50 +37,136 @@ pub struct ToolDefinition {
 
     /// Confidence score (0.0-1.0).
     pub confidence: f32,
+
+    /// Estimated tokens for the prompt plus serialized input schema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<usize>,
+
+    /// Where the tool object (or its prompt) was found in the bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl HasProvenance for ToolDefinition {
+    fn provenance_mut(&mut self) -> Option<&mut Provenance> {
+        self.provenance.as_mut()
+    }
+}
+
+/// A behavioral flag derived from a tool member.
+///
+/// Serializes as a plain boolean when the member is constant, or as
+/// `{"conditional": "<expression source>"}` when it depends on its input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolFlag {
+    /// Member always evaluates to this value.
+    Constant(bool),
+    /// Member evaluates an expression that isn't statically known.
+    Conditional {
+        /// Source of the expression (or whole body) that decides the flag.
+        #[serde(rename = "conditional")]
+        expression: String,
+    },
+}
+
+impl ToolFlag {
+    /// The constant value, if the flag isn't conditional.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ToolFlag::Constant(b) => Some(*b),
+            ToolFlag::Conditional { .. } => None,
+        }
+    }
+}
+
+/// The member a tool uses to decide whether a call needs permission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionCheck {
+    /// Member name (`checkPermissions` or `needsPermissions`).
+    pub member: String,
+
+    /// Function the member delegates to, when it just returns a call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegate: Option<String>,
+
+    /// Source of the member.
+    pub source: String,
 }
 
 /// Tool properties and behavioral flags.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolProperties {
     /// Whether the tool uses strict validation.
-    pub is_strict: bool,
+    pub is_strict: ToolFlag,
 
     /// Whether the tool is enabled.
-    pub is_enabled: bool,
+    pub is_enabled: ToolFlag,
 
     /// Whether the tool only reads data (no mutations).
-    pub is_read_only: bool,
+    pub is_read_only: ToolFlag,
 
     /// Whether the tool can be called concurrently.
-    pub is_concurrency_safe: bool,
+    pub is_concurrency_safe: ToolFlag,
 
     /// User-facing display name.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_facing_name: Option<String>,
+
+    /// Source of `userFacingName` when it isn't a constant string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_facing_name_logic: Option<String>,
+
+    /// Maximum size of a tool result before it is truncated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_result_size_chars: Option<u64>,
+
+    /// Permission-check member, if the tool defines one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission_check: Option<PermissionCheck>,
 }
 
 impl Default for ToolProperties {
     fn default() -> Self {
         Self {
-            is_strict: false,
-            is_enabled: true,
-            is_read_only: false,
-            is_concurrency_safe: false,
+            is_strict: ToolFlag::Constant(false),
+            is_enabled: ToolFlag::Constant(true),
+            is_read_only: ToolFlag::Constant(false),
+            is_concurrency_safe: ToolFlag::Constant(false),
             user_facing_name: None,
+            user_facing_name_logic: None,
+            max_result_size_chars: None,
+            permission_check: None,
         }
     }
 }
 
+/// Members that characterise a tool object in the bundle.
+const TOOL_MEMBERS: &[&str] = &[
+    "description",
+    "prompt",
+    "inputSchema",
+    "call",
+    "isReadOnly",
+    "isConcurrencySafe",
+    "userFacingName",
+];
+
+/// Member names that may hold a tool's input schema.
+pub(crate) const INPUT_SCHEMA_MEMBERS: &[&str] = &["inputSchema", "input_schema", "parameters"];
+
+/// Members only slash command objects have.
+const COMMAND_ONLY_MEMBERS: &[&str] = &["aliases", "argumentHint", "getPromptForCommand"];
+
 /// Extractor for tool definitions.
 pub struct ToolExtractor<'a> {
     analyzer: &'a Analyzer<'a>,
-    symbol_table: SymbolTable<'a>,
+    symbol_table: &'a SymbolTable<'a>,
 }
 
 impl<'a> ToolExtractor<'a> {
     /// Create a new tool extractor.
-    pub fn new(analyzer: &'a Analyzer<'a>) -> Self {
-        let symbol_table = SymbolTable::new(analyzer.program());
+    pub fn new(analyzer: &'a Analyzer<'a>, symbol_table: &'a SymbolTable<'a>) -> Self {
         Self {
             analyzer,
             symbol_table,
@@ -86,20 +175,12 @@ impl<'a> ToolExtractor<'a> {
 
     /// Extract all tool definitions.
     pub fn extract(&self) -> Result<Vec<ToolDefinition>> {
-        debug!("Extracting tool definitions with enhanced AST analysis");
+        debug!("Extracting tool definitions with structural AST analysis");
 
         let objects = self.analyzer.find_object_expressions();
         debug!("Found {} total objects to analyze", objects.len());
 
-        // Debug: check first few objects
-        for (i, obj) in objects.iter().take(10).enumerate() {
-            let props: Vec<_> = obj.properties.iter()
-                .filter_map(|p| p.key.map(|k| (k, p.is_method)))
-                .collect();
-            trace!("Object {}: {} properties: {:?}", i, obj.property_count, props);
-        }
-
-        let mut tools = Vec::new();
+        let mut tools: Vec<ToolDefinition> = Vec::new();
 
         for obj in &objects {
             if let Some(tool) = self.extract_tool_from_object(obj) {
@@ -107,7 +188,14 @@ impl<'a> ToolExtractor<'a> {
                     "Extracted tool: {} (confidence: {:.2})",
                     tool.name, tool.confidence
                 );
-                tools.push(tool);
+
+                // The same tool may be visible through several objects (e.g. a
+                // base and its spread); keep the most complete one.
+                match tools.iter_mut().find(|t| t.name == tool.name) {
+                    Some(existing) if existing.confidence < tool.confidence => *existing = tool,
+                    Some(_) => {}
+                    None => tools.push(tool),
+                }
             }
         }
 
@@ -116,43 +204,26 @@ impl<'a> ToolExtractor<'a> {
     }
 
     /// Extract tools from system prompts (alternative method).
-    pub fn extract_from_prompts(prompts: &[SystemPrompt]) -> Result<Vec<ToolDefinition>> {
+    pub fn extract_from_prompts(
+        prompts: &[SystemPrompt],
+        tool_names: &[String],
+    ) -> Result<Vec<ToolDefinition>> {
         debug!("Extracting tool definitions from system prompts");
 
         let mut tools = Vec::new();
 
-        // Known tool names
-        let tool_names = [
-            "Bash",
-            "Read",
-            "Write",
-            "Edit",
-            "Grep",
-            "Glob",
-            "Task",
-            "TodoWrite",
-            "NotebookEdit",
-            "WebFetch",
-            "WebSearch",
-            "Skill",
-            "SlashCommand",
-            "AskUserQuestion",
-            "ExitPlanMode",
-            "BashOutput",
-            "KillShell",
-            "LSP",
-        ];
-
-        for tool_name in &tool_names {
+        for tool_name in tool_names {
             if let Some(tool_prompt) = Self::find_tool_prompt(prompts, tool_name) {
                 let tool = ToolDefinition {
-                    name: tool_name.to_string(),
+                    name: tool_name.clone(),
                     short_description: String::new(),
                     full_prompt: tool_prompt.content.clone(),
                     input_schema: None,
                     output_schema: None,
                     properties: ToolProperties::default(),
                     confidence: 1.0,
+                    tokens: None,
+                    provenance: tool_prompt.provenance.clone(),
                 };
 
                 debug!(
@@ -185,117 +256,105 @@ impl<'a> ToolExtractor<'a> {
             .max_by_key(|p| p.length)
     }
 
-    /// Check if an object matches tool definition pattern.
-    fn is_tool_object(&self, obj: &ObjectExpressionInfo) -> bool {
-        let has_name = obj.properties.iter().any(|p| p.key == Some("name"));
-
-        // Check for description/prompt as method OR as function expression
-        let has_description_func = obj.properties.iter().any(|p| {
-            if p.key == Some("description") {
-                // Method shorthand OR function expression
-                let result = p.is_method || self.property_is_function(obj.ast_object, "description");
-                if result {
-                    trace!("Found description function in object");
-                }
-                result
-            } else {
-                false
-            }
-        });
+    /// Check if a set of members matches the tool definition pattern.
+    ///
+    /// A tool needs a `name` plus either a strong signal (an input schema, or
+    /// `description`/`prompt`/`call` implemented as functions), a description
+    /// alongside a schema, or at least three of the characteristic members.
+    /// Objects without a schema that look like slash commands are excluded.
+    fn is_tool_object(&self, members: &ObjectMembers<'_, 'a>) -> bool {
+        if !members.contains("name") {
+            return false;
+        }
 
-        let has_prompt_func = obj.properties.iter().any(|p| {
-            if p.key == Some("prompt") {
-                let result = p.is_method || self.property_is_function(obj.ast_object, "prompt");
-                if result {
-                    trace!("Found prompt function in object");
-                }
-                result
-            } else {
-                false
-            }
-        });
+        let has_schema = INPUT_SCHEMA_MEMBERS.iter().any(|k| members.contains(k));
+        if !has_schema && Self::is_command_like(members) {
+            return false;
+        }
 
-        let has_input_schema = obj
-            .properties
-            .iter()
-            .any(|p| p.key == Some("inputSchema"));
+        let has_description = members.contains("description") || members.contains("prompt");
+        let strong = members.contains("inputSchema")
+            || members.is_function("description")
+            || members.is_function("prompt")
+            || members.is_function("call");
+        let characteristic = TOOL_MEMBERS.iter().filter(|k| members.contains(k)).count();
 
-        let is_match = has_name && (has_description_func || has_prompt_func || has_input_schema);
+        let is_match = strong || (has_description && has_schema) || characteristic >= 3;
 
         if is_match {
-            let name_val = obj.properties.iter()
-                .find(|p| p.key == Some("name"))
-                .and_then(|p| p.string_value.or(p.identifier_value))
-                .unwrap_or("unknown");
-            trace!("MATCHED tool object: name={}, has_desc_func={}, has_prompt_func={}, has_schema={}",
-                   name_val, has_description_func, has_prompt_func, has_input_schema);
+            trace!(
+                "MATCHED tool object: strong={}, has_desc={}, has_schema={}, characteristic={}",
+                strong, has_description, has_schema, characteristic
+            );
         }
 
-        // Must have name + (description OR prompt OR schema)
         is_match
     }
 
-    /// Check if a property is a function expression.
-    fn property_is_function(&self, obj: &ObjectExpression, prop_name: &str) -> bool {
-        for prop in &obj.properties {
-            if let ObjectPropertyKind::ObjectProperty(p) = prop {
-                let key_matches = match &p.key {
-                    PropertyKey::StaticIdentifier(id) => id.name.as_str() == prop_name,
-                    PropertyKey::StringLiteral(s) => s.value.as_str() == prop_name,
-                    _ => false,
-                };
-
-                if key_matches {
-                    return matches!(
-                        &p.value,
-                        Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_)
-                    );
-                }
-            }
-        }
-        false
+    /// Whether members mark a slash command: a command `type` or command-only members.
+    fn is_command_like(members: &ObjectMembers<'_, 'a>) -> bool {
+        members
+            .string("type")
+            .is_some_and(|t| COMMAND_TYPES.contains(&t.as_str()))
+            || COMMAND_ONLY_MEMBERS.iter().any(|k| members.contains(k))
     }
 
     /// Extract complete tool definition from object.
-    fn extract_tool_from_object(&self, obj: &ObjectExpressionInfo) -> Option<ToolDefinition> {
-        // Check if this matches tool pattern
-        if !self.is_tool_object(obj) {
+    fn extract_tool_from_object(&self, obj: &ObjectExpressionInfo<'a>) -> Option<ToolDefinition> {
+        // Cheap pre-filter before resolving spreads
+        let has_spread = obj
+            .ast_object
+            .properties
+            .iter()
+            .any(|p| matches!(p, ObjectPropertyKind::SpreadProperty(_)));
+        let has_tool_member = obj.properties.iter().any(|p| {
+            p.key
+                .is_some_and(|k| k == "name" || TOOL_MEMBERS.contains(&k) || INPUT_SCHEMA_MEMBERS.contains(&k))
+        });
+        if !has_spread && !has_tool_member {
+            return None;
+        }
+
+        let source_text = self.analyzer.program().source_text;
+        let members = ObjectMembers::collect(self.symbol_table, source_text, obj.ast_object);
+        if !self.is_tool_object(&members) {
             return None;
         }
 
         // Extract name (resolving variable reference)
-        let name = self.extract_property_value(obj, "name")?;
-        let name_str = self.resolve_to_string(&name)?;
+        let name_str = members.string("name")?;
+        if !is_plausible_tool_name(&name_str) {
+            trace!("Rejecting tool candidate with implausible name: {:?}", name_str);
+            return None;
+        }
 
         trace!("Extracting tool: {}", name_str);
 
         // Extract descriptions - prioritize prompt() for full documentation
-        let full_prompt = self
-            .extract_method_return_value(obj, "prompt")
-            .and_then(|v| self.resolve_to_string(v))
-            .unwrap_or_default();
-
-        let short_desc = self
-            .extract_method_return_value(obj, "description")
-            .and_then(|v| self.resolve_to_string(v))
+        let full_prompt = members.string("prompt").unwrap_or_default();
+
+        let short_desc = members
+            .string("description")
             .unwrap_or_else(|| {
                 // If no separate description, use first 200 chars of prompt
                 full_prompt.chars().take(200).collect()
             });
 
         // Extract schemas
-        let schema_extractor = SchemaExtractor::new(&self.symbol_table);
+        let schema_extractor = SchemaExtractor::new(self.symbol_table);
 
-        let input_schema = self
-            .extract_property_value(obj, "inputSchema")
-            .and_then(|v| self.resolve_to_schema(&schema_extractor, v));
+        let input_schema = INPUT_SCHEMA_MEMBERS.iter().find_map(|key| {
+            members
+                .value(key)
+                .and_then(|v| schema_extractor.parse_schema_expr(v))
+        });
 
-        let output_schema = self
-            .extract_property_value(obj, "outputSchema")
-            .and_then(|v| self.resolve_to_schema(&schema_extractor, v));
+        let output_schema = members
+            .value("outputSchema")
+            .and_then(|v| schema_extractor.parse_schema_expr(v));
 
         // Extract properties
-        let properties = self.extract_tool_properties(obj);
+        let properties = self.extract_tool_properties(&members);
 
         // Calculate confidence
         let confidence = self.calculate_confidence(&name_str, &short_desc, &full_prompt, &input_schema);
@@ -311,117 +370,54 @@ impl<'a> ToolExtractor<'a> {
             output_schema,
             properties,
             confidence,
+            tokens: None,
+            provenance: Some(self.analyzer.provenance(obj.ast_object.span)),
         })
     }
 
-    /// Extract property value from object.
-    fn extract_property_value<'b>(
-        &self,
-        obj: &'b ObjectExpressionInfo,
-        prop_name: &str,
-    ) -> Option<&'b Expression<'b>> {
-        for prop in &obj.ast_object.properties {
-            if let ObjectPropertyKind::ObjectProperty(p) = prop {
-                let key_matches = match &p.key {
-                    PropertyKey::StaticIdentifier(id) => id.name.as_str() == prop_name,
-                    PropertyKey::StringLiteral(s) => s.value.as_str() == prop_name,
-                    _ => false,
-                };
-
-                if key_matches {
-                    return Some(&p.value);
-                }
-            }
-        }
-        None
-    }
-
-    /// Extract return value from async method.
-    fn extract_method_return_value<'b>(
-        &self,
-        obj: &'b ObjectExpressionInfo,
-        method_name: &str,
-    ) -> Option<&'b Expression<'b>> {
-        for prop in &obj.ast_object.properties {
-            if let ObjectPropertyKind::ObjectProperty(p) = prop {
-                // Check if this is the right method
-                let key_matches = match &p.key {
-                    PropertyKey::StaticIdentifier(id) => id.name.as_str() == method_name,
-                    PropertyKey::StringLiteral(s) => s.value.as_str() == method_name,
-                    _ => false,
-                };
-
-                if !key_matches || !p.method {
-                    continue;
-                }
-
-                // Extract from function body
-                if let Expression::FunctionExpression(func) = &p.value {
-                    if let Some(body) = &func.body {
-                        for stmt in &body.statements {
-                            if let Statement::ReturnStatement(ret) = stmt {
-                                if let Some(arg) = &ret.argument {
-                                    return Some(arg);
-                                }
-                            }
-                        }
-                    }
-                }
-                // Also handle arrow functions
-                else if let Expression::ArrowFunctionExpression(arrow) = &p.value {
-                    // For arrow functions, check if body is a direct expression or block
-                    for stmt in &arrow.body.statements {
-                        if let Statement::ReturnStatement(ret) = stmt {
-                            if let Some(arg) = &ret.argument {
-                                return Some(arg);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        None
-    }
-
-    /// Resolve expression to string using symbol table.
-    fn resolve_to_string(&self, expr: &Expression) -> Option<String> {
-        self.symbol_table.resolve_template_expr(expr)
-    }
-
-    /// Resolve schema reference to JSON.
-    fn resolve_to_schema(
-        &self,
-        schema_extractor: &SchemaExtractor,
-        expr: &Expression,
-    ) -> Option<JsonValue> {
-        match expr {
-            Expression::Identifier(id) => {
-                // Look up schema in symbol table
-                self.symbol_table.get_schema(id.name.as_str())
-            }
-            Expression::CallExpression(call) => {
-                // Parse k.object() call
-                schema_extractor.parse_schema_builder_call(call)
-            }
+    /// Describe the tool's permission-check member.
+    fn permission_check(&self, members: &ObjectMembers<'_, 'a>) -> Option<PermissionCheck> {
+        let (member, prop) = ["checkPermissions", "needsPermissions"]
+            .iter()
+            .find_map(|key| members.get(key).map(|p| (*key, p)))?;
+
+        let delegate = match members.body(member) {
+            Some(MemberBody::Value(Expression::CallExpression(call))) => callee_name(&call.callee),
+            Some(MemberBody::Value(Expression::AwaitExpression(await_expr))) => match &await_expr.argument {
+                Expression::CallExpression(call) => callee_name(&call.callee),
+                _ => None,
+            },
             _ => None,
-        }
+        };
+
+        Some(PermissionCheck {
+            member: member.to_string(),
+            delegate,
+            source: members.source_of(prop.span),
+        })
     }
 
     /// Extract tool properties from object.
-    fn extract_tool_properties(&self, obj: &ObjectExpressionInfo) -> ToolProperties {
-        let mut props = ToolProperties::default();
-
-        // Extract strict flag
-        if let Some(strict_expr) = self.extract_property_value(obj, "strict") {
-            if let Expression::BooleanLiteral(b) = strict_expr {
-                props.is_strict = b.value;
-            }
+    fn extract_tool_properties(&self, members: &ObjectMembers<'_, 'a>) -> ToolProperties {
+        let (user_facing_name, user_facing_name_logic) = match members.body("userFacingName") {
+            Some(MemberBody::Value(expr)) => match members.resolve_string(expr) {
+                Some(name) => (Some(name), None),
+                None => (None, Some(members.source_of(expr.span()))),
+            },
+            Some(MemberBody::Complex(span)) => (None, Some(members.source_of(span))),
+            None => (None, None),
+        };
+
+        ToolProperties {
+            is_strict: members.flag("strict", false),
+            is_enabled: members.flag("isEnabled", true),
+            is_read_only: members.flag("isReadOnly", false),
+            is_concurrency_safe: members.flag("isConcurrencySafe", false),
+            user_facing_name,
+            user_facing_name_logic,
+            max_result_size_chars: members.number("maxResultSizeChars"),
+            permission_check: self.permission_check(members),
         }
-
-        // Extract other boolean properties similarly
-        // (isEnabled, isReadOnly, isConcurrencySafe)
-
-        props
     }
 
     /// Calculate confidence score.
@@ -450,3 +446,151 @@ impl<'a> ToolExtractor<'a> {
         score.min(1.0)
     }
 }
+
+/// Tool names are identifiers like `Bash`, `TodoWrite` or `mcp__server__tool`.
+fn is_plausible_tool_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_detects_unknown_tool_structurally() {
+        let code = r#"
+            var base = { isReadOnly() { return !0 }, isConcurrencySafe() { return !0 } };
+            var nm = "FrobnicateWidgets";
+            var tool = {
+                ...base,
+                name: nm,
+                async description() { return "Frobnicate the widgets in a project" },
+                inputSchema: k.strictObject({ path: k.string().describe("Widget path") }),
+                async *call(input) { yield input; }
+            };
+            var notATool = { name: "config", value: 3 };
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let tools = ToolExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "FrobnicateWidgets");
+        assert_eq!(tools[0].short_description, "Frobnicate the widgets in a project");
+        assert!(tools[0].input_schema.is_some());
+    }
+
+    #[test]
+    fn test_command_objects_are_not_tools() {
+        let code = r#"
+            var tool = {
+                name: "Frobnicate",
+                async description() { return "Frobnicate a widget" },
+                inputSchema: k.strictObject({ path: k.string() }),
+                async *call(input) { yield input; }
+            };
+            var clear = {
+                type: "local",
+                name: "clear",
+                description: "Clear conversation history",
+                aliases: ["reset"],
+                async call(A, B) { return B.clear() },
+                userFacingName() { return "clear" }
+            };
+            var review = {
+                name: "review",
+                description: "Review a pull request",
+                argumentHint: "[pr]",
+                async call() {},
+                async getPromptForCommand(A) { return [{ type: "text", text: A }] }
+            };
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let tools = ToolExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+
+        let names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Frobnicate"]);
+    }
+
+    #[test]
+    fn test_spread_base_supplies_members() {
+        let code = r#"
+            var common = { name: "Sleep", userFacingName() { return "Sleep" }, isReadOnly() { return !0 } };
+            var tool = { ...common, prompt: () => "Waits for a while before continuing." };
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let tools = ToolExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+
+        let sleep = tools.iter().find(|t| t.name == "Sleep").unwrap();
+        assert_eq!(sleep.full_prompt, "Waits for a while before continuing.");
+    }
+
+    #[test]
+    fn test_properties_from_method_bodies() {
+        let code = r#"
+            var LIMIT = 30000;
+            var tool = {
+                name: "Bash",
+                strict: !0,
+                maxResultSizeChars: LIMIT,
+                async description() { return "Run a shell command" },
+                inputSchema: k.strictObject({ command: k.string() }),
+                isEnabled() { return !0 },
+                isReadOnly(A) { return checkReadOnly(A.command) },
+                isConcurrencySafe(A) { return this.isReadOnly(A) },
+                userFacingName(A) { if (A?.sandbox) return "SandboxedBash"; return "Bash" },
+                async checkPermissions(A, B) { return bashPermissions(A, B) }
+            };
+        "#;
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let symbol_table = SymbolTable::new(parse_result.program());
+
+        let tools = ToolExtractor::new(&analyzer, &symbol_table).extract().unwrap();
+        let props = &tools[0].properties;
+
+        assert_eq!(props.is_strict, ToolFlag::Constant(true));
+        assert_eq!(props.is_enabled, ToolFlag::Constant(true));
+        assert_eq!(
+            props.is_read_only,
+            ToolFlag::Conditional { expression: "checkReadOnly(A.command)".to_string() }
+        );
+        assert!(props.is_concurrency_safe.as_bool().is_none());
+        assert_eq!(props.max_result_size_chars, Some(30000));
+        assert!(props.user_facing_name_logic.as_deref().unwrap().contains("SandboxedBash"));
+
+        let check = props.permission_check.as_ref().unwrap();
+        assert_eq!(check.member, "checkPermissions");
+        assert_eq!(check.delegate.as_deref(), Some("bashPermissions"));
+
+        let json = serde_json::to_value(props).unwrap();
+        assert_eq!(json["is_enabled"], true);
+        assert_eq!(json["is_read_only"]["conditional"], "checkReadOnly(A.command)");
+    }
+}
@@ -6,7 +6,10 @@
 //! - Parse JavaScript code using the Oxc parser
 //! - Analyze and traverse the AST
 //! - Extract system prompts, tool definitions, and configuration
+//! - Match prompts, config values and strings with declarative rules
 //! - Transform and organize code into readable modules
+//! - Generate TypeScript and Rust types from tool schemas
+//! - Estimate token counts offline for prompts and tool definitions
 //! - Generate documentation and analysis reports
 
 pub mod analysis;
@@ -17,7 +20,11 @@ pub mod error;
 pub mod extractor;
 pub mod output;
 pub mod parser;
+pub mod rules;
+pub mod tokens;
 pub mod transformer;
+pub mod typegen;
+pub mod validation;
 pub mod visualization;
 
 pub use error::{DecypherError, Result};
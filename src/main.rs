@@ -3,16 +3,31 @@
 use claude_code_decypher::{
     analysis::AdvancedAnalyzer,
     analyzer::Analyzer,
-    cli::{Cli, Commands, OutputFormat, SplitStrategy},
+    cli::{Cli, CodegenLanguage, Commands, OutputFormat, SplitStrategy, TargetPlatform, ToolFormat},
     dashboard::Dashboard,
-    extractor::Extractor,
+    extractor::{
+        config::ConfigValue,
+        errors::ErrorCatalog,
+        platforms::{self, Platform, PlatformInventory},
+        plugin::{PluginOptions, PluginRegistry},
+        prompts::SystemPrompt,
+        prompts_enhanced::EnhancedSystemPrompt,
+        strings::InterestingString,
+        tool_export::{export_tools, DescriptionMode, ToolExportFormat},
+        tools::ToolDefinition,
+        Extractor,
+    },
     output::{ExtractionSummary, OutputWriter},
     parser::{visitor::StatsVisitor, Parser},
+    rules::Rules,
+    tokens::{TokenBudget, Tokenizer},
     transformer::{
-        codegen::beautify_code,
+        codegen::{beautify_code, BeautifiedMap},
         rename::apply_rename_map,
         Transformer,
     },
+    typegen::{self, TypeModel},
+    validation::CallValidator,
     visualization::Visualizer,
     Result,
 };
@@ -45,6 +60,8 @@ fn run(cli: Cli) -> Result<()> {
 
     // Store input path for later use
     let input_path = cli.input.clone();
+    let tokenizer = cli.tokenizer()?;
+    let rules = cli.rules()?;
 
     // Create allocator for AST
     let allocator = Allocator::default();
@@ -82,6 +99,8 @@ fn run(cli: Cli) -> Result<()> {
                 rename,
                 split,
                 detailed,
+                tokenizer.as_ref(),
+                &rules,
             )?;
         }
         Some(Commands::Parse { detailed, format }) => {
@@ -90,14 +109,21 @@ fn run(cli: Cli) -> Result<()> {
         Some(Commands::Extract {
             prompts_only,
             tools_only,
+            tool_formats,
+            full_descriptions,
             format,
         }) => {
             handle_extract_command(
                 &parse_result,
+                &allocator,
                 &cli.output,
                 prompts_only,
                 tools_only,
+                &tool_formats,
+                full_descriptions,
                 format,
+                tokenizer.as_ref(),
+                &rules,
             )?;
         }
         Some(Commands::Transform {
@@ -127,6 +153,36 @@ fn run(cli: Cli) -> Result<()> {
                 format,
             )?;
         }
+        Some(Commands::Codegen { languages }) => {
+            handle_codegen_command(&parse_result, &cli.output, &languages)?;
+        }
+        Some(Commands::Assemble {
+            function,
+            conditions,
+            max_variants,
+            list,
+            format,
+        }) => {
+            handle_assemble_command(
+                &parse_result,
+                &cli.output,
+                function.as_deref(),
+                &conditions,
+                max_variants,
+                list,
+                format,
+                tokenizer.as_ref(),
+            )?;
+        }
+        Some(Commands::Errors { query, class, format }) => {
+            handle_errors_command(&parse_result, &cli.output, query.as_deref(), class.as_deref(), format)?;
+        }
+        Some(Commands::Platforms { platform, slice, format }) => {
+            handle_platforms_command(&parse_result, &allocator, &cli.output, platform, slice, format)?;
+        }
+        Some(Commands::ValidateCalls { calls, format }) => {
+            handle_validate_calls_command(&parse_result, &cli.output, &calls, format)?;
+        }
         Some(Commands::Dashboard { diagrams, format }) => {
             handle_dashboard_command(
                 &parse_result,
@@ -134,6 +190,8 @@ fn run(cli: Cli) -> Result<()> {
                 &cli.output,
                 diagrams,
                 format,
+                tokenizer.as_ref(),
+                &rules,
             )?;
         }
         None => {
@@ -148,6 +206,8 @@ fn run(cli: Cli) -> Result<()> {
                 true,  // rename
                 true,  // split
                 false, // detailed
+                tokenizer.as_ref(),
+                &rules,
             )?;
         }
     }
@@ -221,90 +281,100 @@ fn handle_parse_command(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_extract_command(
     parse_result: &claude_code_decypher::parser::ParseResult,
+    allocator: &Allocator,
     output_dir: &std::path::Path,
     prompts_only: bool,
     tools_only: bool,
+    tool_formats: &[ToolFormat],
+    full_descriptions: bool,
     _format: OutputFormat,
+    tokenizer: &dyn Tokenizer,
+    rules: &Rules,
 ) -> Result<()> {
     info!("Running extract command");
 
     // Create analyzer and extractor
-    let analyzer = Analyzer::new(parse_result.program());
-    let extractor = Extractor::new(analyzer);
+    let analyzer = Analyzer::new(parse_result.program())
+        .with_line_index(parse_result.line_index());
+    let extractor = Extractor::new(analyzer).with_rules(rules);
 
     // Create output writer and directory structure
     let writer = OutputWriter::new(output_dir);
     writer.create_structure()?;
 
-    // Extract enhanced prompts with fragment merging and tool association
-    let enhanced_prompts = if !tools_only {
-        info!("Extracting enhanced system prompts...");
-        extractor.extract_prompts_enhanced()?
+    // Run the extractors; prompts-only and tools-only keep their own plugins
+    let only = if prompts_only {
+        vec!["prompts", "examples", "prompt-templates", "prompt-assembly"]
+    } else if tools_only {
+        vec!["tools", "examples"]
     } else {
         Vec::new()
     };
+    let registry = PluginRegistry::builtin();
+    let plugins = registry.run(&extractor, &PluginOptions { only, tokenizer: Some(tokenizer), ..Default::default() })?;
+    let prompts: Vec<SystemPrompt> = plugins.items::<EnhancedSystemPrompt>("prompts").iter().map(SystemPrompt::from).collect();
+    let tools = plugins.items::<ToolDefinition>("tools");
 
-    // Convert enhanced prompts to legacy format for backward compatibility
-    let prompts: Vec<_> = enhanced_prompts
-        .iter()
-        .map(|ep| claude_code_decypher::extractor::prompts::SystemPrompt {
-            id: ep.id.clone(),
-            content: ep.content.clone(),
-            length: ep.length,
-            category: ep.category.clone(),
-        })
-        .collect();
-
-    // Extract tools with enhanced prompt matching
-    let tools = if !prompts_only {
-        info!("Extracting tool definitions with enhanced prompts...");
-        extractor.extract_tools_with_enhanced_prompts(&enhanced_prompts)?
-    } else {
-        Vec::new()
-    };
-
-    let configs = if !prompts_only && !tools_only {
-        info!("Extracting configurations...");
-        extractor.extract_configs()?
-    } else {
-        Vec::new()
-    };
-
-    let strings = if !prompts_only && !tools_only {
-        info!("Extracting interesting strings...");
-        extractor.extract_strings()?
-    } else {
-        Vec::new()
-    };
+    // Point artifacts at the beautified.js `transform` writes; keep columns only if it wasn't renamed
+    let generated = Transformer::new(parse_result.program()).beautify_mapped(allocator)?;
+    let beautified = beautify_code(&generated.code);
+    let beautified_map = BeautifiedMap::new(&generated, &beautified);
+    let columns = std::fs::read_to_string(output_dir.join("beautified.js")).map_or(true, |code| code == beautified);
 
     // Write results
-    if !prompts.is_empty() {
-        writer.write_prompts(&prompts)?;
-    }
+    let line_index = parse_result.line_index();
+    registry.write_with(&plugins, &writer, |json| beautified_map.annotate_json(line_index, json, columns))?;
     if !tools.is_empty() {
-        writer.write_tools(&tools)?;
-    }
-    if !configs.is_empty() {
-        writer.write_configs(&configs)?;
-    }
-    if !strings.is_empty() {
-        writer.write_strings(&strings)?;
+        let mode = if full_descriptions {
+            DescriptionMode::Full
+        } else {
+            DescriptionMode::Short
+        };
+        for format in tool_formats {
+            let format = match format {
+                ToolFormat::Api => ToolExportFormat::AnthropicApi,
+                ToolFormat::Mcp => ToolExportFormat::Mcp,
+            };
+            let export = export_tools(tools, format, mode)?;
+            writer.write_tool_export(format, &export)?;
+        }
     }
 
     // Write summary
-    let summary = ExtractionSummary::new(&prompts, &tools, &configs, &strings);
+    let summary = ExtractionSummary::new(
+        &prompts,
+        tools,
+        plugins.items::<ConfigValue>("configs"),
+        plugins.items::<InterestingString>("strings"),
+    )
+    .with_plugins(&plugins);
     writer.write_summary(&summary)?;
 
     // Print summary
     summary.print();
+    print_token_budget(&TokenBudget::new(tokenizer, &prompts, tools));
 
     println!("\nExtraction complete! Results written to: {}", output_dir.display());
 
     Ok(())
 }
 
+/// Print token totals and the most expensive tools.
+fn print_token_budget(budget: &TokenBudget) {
+    println!("\nToken Estimate ({}):", budget.tokenizer);
+    println!("  Prompts: {} tokens", budget.prompt_tokens);
+    println!("  Tools:   {} tokens", budget.tool_tokens);
+    for tool in budget.tools.iter().take(5) {
+        println!(
+            "    {:<20} {:>7} ({} prompt + {} schema)",
+            tool.name, tool.total, tool.prompt_tokens, tool.schema_tokens
+        );
+    }
+}
+
 fn handle_transform_command(
     parse_result: &claude_code_decypher::parser::ParseResult,
     allocator: &Allocator,
@@ -416,34 +486,6 @@ fn handle_transform_command(
     Ok(())
 }
 
-fn handle_default_action(
-    parse_result: &claude_code_decypher::parser::ParseResult,
-) -> Result<()> {
-    info!("Running default action (basic statistics)");
-
-    // Collect and display statistics
-    let mut visitor = StatsVisitor::new();
-    let stats = visitor.visit_program(parse_result.program());
-
-    println!("\n=== Claude Code Analysis ===\n");
-    stats.print_summary();
-
-    if parse_result.error_count() > 0 {
-        println!("\nWarning: {} parse errors detected", parse_result.error_count());
-        println!("Use --verbose for more details");
-    }
-
-    println!("\nPhase 1 (Parsing) Complete!");
-    println!("Phase 2 (Extraction) Available:");
-    println!("  Use 'extract' command to extract prompts, tools, and configs");
-    println!("Phase 3 (Transformation) Available:");
-    println!("  Use 'transform' command to beautify and organize code");
-    println!("Phase 4 (Analysis) Available:");
-    println!("  Use 'analyze' command for call graphs and complexity metrics");
-
-    Ok(())
-}
-
 fn handle_analyze_command(
     parse_result: &claude_code_decypher::parser::ParseResult,
     output_dir: &std::path::Path,
@@ -482,7 +524,7 @@ fn handle_analyze_command(
                 if !report.call_graph.functions.is_empty() {
                     println!("\nTop Called Functions:");
                     let mut funcs = report.call_graph.functions.clone();
-                    funcs.sort_by(|a, b| b.calls_out.cmp(&a.calls_out));
+                    funcs.sort_by_key(|f| std::cmp::Reverse(f.calls_out));
 
                     for func in funcs.iter().take(10) {
                         println!("  {} - {} calls out", func.name, func.calls_out);
@@ -497,7 +539,7 @@ fn handle_analyze_command(
                     println!("Most Complex Functions:");
 
                     let mut funcs = report.complexity.function_complexity.clone();
-                    funcs.sort_by(|a, b| b.cyclomatic.cmp(&a.cyclomatic));
+                    funcs.sort_by_key(|f| std::cmp::Reverse(f.cyclomatic));
 
                     for func in funcs.iter().take(10) {
                         println!(
@@ -526,12 +568,345 @@ fn handle_analyze_command(
     Ok(())
 }
 
+fn handle_codegen_command(
+    parse_result: &claude_code_decypher::parser::ParseResult,
+    output_dir: &std::path::Path,
+    languages: &[CodegenLanguage],
+) -> Result<()> {
+    info!("Running codegen command");
+
+    let analyzer = Analyzer::new(parse_result.program())
+        .with_line_index(parse_result.line_index());
+    let extractor = Extractor::new(analyzer);
+    let tools = extractor.extract_tools()?;
+
+    let model = TypeModel::from_tools(&tools);
+    info!("Built {} type declarations from {} tools", model.decls.len(), tools.len());
+
+    let codegen_dir = output_dir.join("codegen");
+    std::fs::create_dir_all(&codegen_dir)
+        .map_err(|e| claude_code_decypher::error::DecypherError::io(&codegen_dir, e))?;
+
+    for language in languages {
+        let (file, source) = match language {
+            CodegenLanguage::Typescript => (typegen::TYPESCRIPT_FILE, typegen::typescript::render(&model)),
+            CodegenLanguage::Rust => (typegen::RUST_FILE, typegen::rust::render(&model)),
+        };
+        let path = codegen_dir.join(file);
+        std::fs::write(&path, source)
+            .map_err(|e| claude_code_decypher::error::DecypherError::io(&path, e))?;
+        println!("✓ Wrote {}", path.display());
+    }
+
+    println!("\nGenerated types for {} tools ({} declarations)", tools.len(), model.decls.len());
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_assemble_command(
+    parse_result: &claude_code_decypher::parser::ParseResult,
+    output_dir: &std::path::Path,
+    function: Option<&str>,
+    conditions: &[(String, bool)],
+    max_variants: usize,
+    list: bool,
+    format: OutputFormat,
+    tokenizer: &dyn Tokenizer,
+) -> Result<()> {
+    info!("Running assemble command");
+
+    let analyzer = Analyzer::new(parse_result.program())
+        .with_line_index(parse_result.line_index());
+    let extractor = Extractor::new(analyzer);
+    let assemblies = extractor.extract_prompt_assemblies()?;
+
+    if list {
+        match format {
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&assemblies)
+                    .map_err(|e| claude_code_decypher::error::DecypherError::Other(e.into()))?;
+                println!("{}", json);
+            }
+            _ => {
+                for assembly in &assemblies {
+                    println!(
+                        "{} (line {}, {} chars, {} conditions)",
+                        assembly.name,
+                        assembly.span.line,
+                        assembly.static_chars,
+                        assembly.conditions.len()
+                    );
+                    for condition in &assembly.conditions {
+                        println!("  {:<4} {}", condition.id, condition.source);
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    let assembly = match function {
+        Some(name) => assemblies.iter().find(|a| a.name == name),
+        None => assemblies.first(),
+    }
+    .ok_or_else(|| {
+        claude_code_decypher::error::DecypherError::InvalidArguments(match function {
+            Some(name) => format!("no prompt assembly function named '{}'", name),
+            None => "no prompt assembly functions found".to_string(),
+        })
+    })?;
+
+    let fixed = assembly.resolve_conditions(conditions)?;
+    let mut variants = assembly.variants(&fixed, max_variants);
+    for variant in &mut variants {
+        variant.tokens = Some(tokenizer.count(&variant.text));
+    }
+
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| claude_code_decypher::error::DecypherError::io(output_dir, e))?;
+    let variants_path = output_dir.join("prompt-variants.json");
+    let json = serde_json::to_string_pretty(&serde_json::json!({
+        "assembly": assembly.name,
+        "conditions": assembly.conditions,
+        "variants": variants,
+    }))
+    .map_err(|e| claude_code_decypher::error::DecypherError::Other(e.into()))?;
+    std::fs::write(&variants_path, &json)
+        .map_err(|e| claude_code_decypher::error::DecypherError::io(&variants_path, e))?;
+
+    match format {
+        OutputFormat::Json => println!("{}", json),
+        _ => {
+            for (i, variant) in variants.iter().enumerate() {
+                let values: Vec<String> = variant
+                    .conditions
+                    .iter()
+                    .map(|(id, value)| format!("{}={}", id, value))
+                    .collect();
+                println!(
+                    "=== {} variant {} [{}] ({} tokens) ===\n",
+                    assembly.name,
+                    i + 1,
+                    values.join(", "),
+                    variant.tokens.unwrap_or_default()
+                );
+                println!("{}\n", variant.text);
+            }
+            println!("{} variants written to: {}", variants.len(), variants_path.display());
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_errors_command(
+    parse_result: &claude_code_decypher::parser::ParseResult,
+    output_dir: &std::path::Path,
+    query: Option<&str>,
+    class: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Running errors command");
+
+    let analyzer = Analyzer::new(parse_result.program())
+        .with_line_index(parse_result.line_index());
+    let extractor = Extractor::new(analyzer);
+    let registry = PluginRegistry::builtin();
+    let plugins = registry.run(&extractor, &PluginOptions { only: vec!["errors"], ..Default::default() })?;
+    let catalog: &ErrorCatalog = plugins.get_as("errors").expect("the errors plugin ran");
+
+    let writer = OutputWriter::new(output_dir);
+    writer.create_structure()?;
+    registry.write(&plugins, &writer)?;
+
+    let sites: Vec<_> = match query {
+        Some(query) => catalog.search(query),
+        None => catalog.sites.iter().collect(),
+    }
+    .into_iter()
+    .filter(|site| class.is_none() || site.class.as_deref() == class)
+    .collect();
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&sites)
+                .map_err(|e| claude_code_decypher::error::DecypherError::Other(e.into()))?;
+            println!("{}", json);
+        }
+        OutputFormat::Debug => println!("{:#?}", sites),
+        OutputFormat::Text => {
+            for site in &sites {
+                println!(
+                    "line {:<6} {:?} {} in {}",
+                    site.span.line,
+                    site.kind,
+                    site.class.as_deref().or(site.callee.as_deref()).unwrap_or("-"),
+                    site.function.as_deref().unwrap_or("<top level>")
+                );
+                if let Some(message) = &site.message {
+                    println!("  {}", message);
+                }
+                for condition in &site.conditions {
+                    println!("  when {}", condition);
+                }
+            }
+            println!(
+                "\n{} of {} error sites; catalog written to: {}",
+                sites.len(),
+                catalog.sites.len(),
+                output_dir.join("extracted/errors.json").display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_platforms_command(
+    parse_result: &claude_code_decypher::parser::ParseResult,
+    allocator: &Allocator,
+    output_dir: &std::path::Path,
+    platform: Option<TargetPlatform>,
+    slice: Option<TargetPlatform>,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Running platforms command");
+
+    let target = |platform: TargetPlatform| match platform {
+        TargetPlatform::Darwin => Platform::Darwin,
+        TargetPlatform::Linux => Platform::Linux,
+        TargetPlatform::Wsl => Platform::Wsl,
+        TargetPlatform::Win32 => Platform::Win32,
+    };
+
+    let analyzer = Analyzer::new(parse_result.program())
+        .with_line_index(parse_result.line_index());
+    let extractor = Extractor::new(analyzer);
+    let registry = PluginRegistry::builtin();
+    let plugins = registry.run(&extractor, &PluginOptions { only: vec!["platforms"], ..Default::default() })?;
+    let inventory: &PlatformInventory = plugins.get_as("platforms").expect("the platforms plugin ran");
+
+    let writer = OutputWriter::new(output_dir);
+    writer.create_structure()?;
+    registry.write(&plugins, &writer)?;
+
+    let guards: Vec<_> = match platform.map(target) {
+        Some(platform) => inventory.on(platform).collect(),
+        None => inventory.guards.iter().collect(),
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&guards)
+                .map_err(|e| claude_code_decypher::error::DecypherError::Other(e.into()))?;
+            println!("{}", json);
+        }
+        OutputFormat::Debug => println!("{:#?}", guards),
+        OutputFormat::Text => {
+            for guard in &guards {
+                let platforms: Vec<_> = guard.platforms.iter().map(|p| p.as_str()).collect();
+                println!(
+                    "lines {:>6}-{:<6} {:?} on {} in {}",
+                    guard.region.line,
+                    guard.end_line,
+                    guard.kind,
+                    platforms.join(", "),
+                    guard.function.as_deref().unwrap_or("<top level>")
+                );
+                println!("  when {}", guard.condition);
+            }
+            println!(
+                "\n{} of {} platform-specific regions; written to: {}",
+                guards.len(),
+                inventory.guards.len(),
+                output_dir.join("extracted/platforms.json").display()
+            );
+        }
+    }
+
+    if let Some(slice) = slice.map(target) {
+        // Slice the beautified.js the user reads, renamed or not; generate it if missing
+        let beautified_path = output_dir.join("beautified.js");
+        let code = match std::fs::read_to_string(&beautified_path) {
+            Ok(code) => code,
+            Err(_) => {
+                info!("Generating beautified code...");
+                let transformer = Transformer::new(parse_result.program());
+                beautify_code(&transformer.beautify(allocator)?)
+            }
+        };
+        let sliced = platforms::slice_source(&code, slice)?;
+        let slice_path = output_dir.join(format!("beautified.{}.js", slice));
+        std::fs::write(&slice_path, &sliced)
+            .map_err(|e| claude_code_decypher::error::DecypherError::io(&slice_path, e))?;
+        println!(
+            "Platform slice ({}): {} of {} lines kept, written to: {}",
+            slice,
+            sliced.lines().count(),
+            code.lines().count(),
+            slice_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+fn handle_validate_calls_command(
+    parse_result: &claude_code_decypher::parser::ParseResult,
+    output_dir: &std::path::Path,
+    calls_path: &std::path::Path,
+    format: OutputFormat,
+) -> Result<()> {
+    info!("Running validate-calls command");
+
+    let calls = std::fs::read_to_string(calls_path)
+        .map_err(|e| claude_code_decypher::error::DecypherError::io(calls_path, e))?;
+
+    let analyzer = Analyzer::new(parse_result.program())
+        .with_line_index(parse_result.line_index());
+    let extractor = Extractor::new(analyzer);
+    let tools = extractor.extract_tools()?;
+    info!("Validating against {} extracted tools", tools.len());
+
+    let report = CallValidator::new(&tools).validate_jsonl(&calls);
+
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| claude_code_decypher::error::DecypherError::io(output_dir, e))?;
+    let report_path = output_dir.join("validation-report.json");
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| claude_code_decypher::error::DecypherError::Other(e.into()))?;
+    std::fs::write(&report_path, &json)
+        .map_err(|e| claude_code_decypher::error::DecypherError::io(&report_path, e))?;
+
+    match format {
+        OutputFormat::Text => {
+            report.print();
+            println!("\nReport written to: {}", report_path.display());
+        }
+        OutputFormat::Json => println!("{}", json),
+        OutputFormat::Debug => println!("{:#?}", report),
+    }
+
+    if report.is_valid() {
+        Ok(())
+    } else {
+        Err(claude_code_decypher::error::DecypherError::ValidationError(format!(
+            "{} of {} tool calls have violations",
+            report.total_calls - report.valid_calls,
+            report.total_calls
+        )))
+    }
+}
+
 fn handle_dashboard_command(
     parse_result: &claude_code_decypher::parser::ParseResult,
     allocator: &Allocator,
     output_dir: &std::path::Path,
     diagrams: bool,
     format: OutputFormat,
+    tokenizer: &dyn Tokenizer,
+    rules: &Rules,
 ) -> Result<()> {
     info!("Running dashboard command - executing all phases");
 
@@ -541,15 +916,22 @@ fn handle_dashboard_command(
 
     // Phase 2: Extract
     info!("Extracting data...");
-    let analyzer = Analyzer::new(parse_result.program());
-    let extractor = Extractor::new(analyzer);
-
-    let prompts = extractor.extract_prompts()?;
-    let tools = extractor.extract_tools()?;
-    let configs = extractor.extract_configs()?;
-    let strings = extractor.extract_strings()?;
-
-    let extraction_summary = ExtractionSummary::new(&prompts, &tools, &configs, &strings);
+    let analyzer = Analyzer::new(parse_result.program())
+        .with_line_index(parse_result.line_index());
+    let extractor = Extractor::new(analyzer).with_rules(rules);
+
+    let plugins = PluginRegistry::builtin().run(&extractor, &PluginOptions::default())?;
+    let prompts: Vec<SystemPrompt> = plugins.items::<EnhancedSystemPrompt>("prompts").iter().map(SystemPrompt::from).collect();
+    let tools = plugins.items::<ToolDefinition>("tools");
+
+    let extraction_summary = ExtractionSummary::new(
+        &prompts,
+        tools,
+        plugins.items::<ConfigValue>("configs"),
+        plugins.items::<InterestingString>("strings"),
+    )
+    .with_plugins(&plugins);
+    let token_budget = TokenBudget::new(tokenizer, &prompts, tools);
 
     // Phase 3: Transform
     info!("Transforming code...");
@@ -610,7 +992,9 @@ fn handle_dashboard_command(
         input_size,
         4094, // Known from vendors/claude
         beautified.lines().count(),
-    );
+    )
+    .with_plugins(&plugins)
+    .with_tokens(token_budget);
 
     dashboard.write_json(output_dir)?;
     dashboard.generate_markdown(output_dir)?;
@@ -643,6 +1027,7 @@ fn handle_dashboard_command(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_all_command(
     parse_result: &claude_code_decypher::parser::ParseResult,
     allocator: &Allocator,
@@ -652,6 +1037,8 @@ fn handle_all_command(
     enable_rename: bool,
     enable_split: bool,
     detailed: bool,
+    tokenizer: &dyn Tokenizer,
+    rules: &Rules,
 ) -> Result<()> {
     println!("\n🚀 Running complete analysis pipeline...\n");
 
@@ -666,40 +1053,13 @@ fn handle_all_command(
     println!("   ✓ Analyzed {} AST nodes, {} functions, {} variables",
              ast_stats.total_nodes, ast_stats.function_count, ast_stats.variable_count);
 
-    // Phase 2: Extract
-    println!("\n🔍 Phase 2: Extraction");
-    let analyzer = Analyzer::new(parse_result.program());
-    let extractor = Extractor::new(analyzer);
-
-    let prompts = extractor.extract_prompts()?;
-    let configs = extractor.extract_configs()?;
-    let strings = extractor.extract_strings()?;
-
-    // Phase 3: Transformation (do this BEFORE tool extraction)
-    println!("\n✨ Phase 3: Transformation");
+    // Phase 2: Transform first; extraction reads the renames
+    println!("\n✨ Phase 2: Transformation");
     let transformer = Transformer::new(parse_result.program());
 
     info!("Generating beautified code...");
-    let beautified = transformer.beautify(allocator)?;
-    let beautified = beautify_code(&beautified);
-
-    // NOW extract tools from beautified code (was working - got 19 tools!)
-    let tools = extractor.extract_tools_from_beautified(&beautified)?;
-
-    println!("   ✓ Extracted {} prompts, {} tools, {} configs, {} strings",
-             prompts.len(), tools.len(), configs.len(), strings.len());
-
-    let writer = OutputWriter::new(output_dir);
-    writer.create_structure()?;
-    writer.write_prompts(&prompts)?;
-    writer.write_tools(&tools)?;
-    writer.write_configs(&configs)?;
-    writer.write_strings(&strings)?;
-
-    let extraction_summary = ExtractionSummary::new(&prompts, &tools, &configs, &strings);
-    writer.write_summary(&extraction_summary)?;
-
-    // (Phase 3 already done above for tool extraction)
+    let generated = transformer.beautify_mapped(allocator)?;
+    let beautified = beautify_code(&generated.code);
     println!("   ✓ Beautified code: {} lines", beautified.lines().count());
 
     let mut code = beautified.clone();
@@ -719,6 +1079,38 @@ fn handle_all_command(
         std::collections::HashMap::new()
     };
 
+    // Phase 3: Run every extractor; templates name placeholders after renamed bindings
+    println!("\n🔍 Phase 3: Extraction");
+    let analyzer = Analyzer::new(parse_result.program())
+        .with_line_index(parse_result.line_index());
+    let extractor = Extractor::new(analyzer).with_rules(rules);
+    let registry = PluginRegistry::builtin();
+    let options = PluginOptions {
+        rename_map: Some(&rename_map),
+        tokenizer: Some(tokenizer),
+        ..Default::default()
+    };
+    let plugins = registry.run(&extractor, &options)?;
+
+    let prompts: Vec<SystemPrompt> = plugins.items::<EnhancedSystemPrompt>("prompts").iter().map(SystemPrompt::from).collect();
+    let tools = plugins.items::<ToolDefinition>("tools");
+    let configs = plugins.items::<ConfigValue>("configs");
+    let strings = plugins.items::<InterestingString>("strings");
+    println!("   ✓ Extracted {} prompts, {} tools, {} configs, {} strings",
+             prompts.len(), tools.len(), configs.len(), strings.len());
+    println!("   ✓ Ran {} extractors", plugins.len());
+
+    let writer = OutputWriter::new(output_dir);
+    writer.create_structure()?;
+
+    // Point every artifact at beautified.js; renaming rewrites lines, so keep columns only without it
+    let beautified_map = BeautifiedMap::new(&generated, &beautified);
+    let line_index = parse_result.line_index();
+    registry.write_with(&plugins, &writer, |json| beautified_map.annotate_json(line_index, json, !enable_rename))?;
+
+    let extraction_summary = ExtractionSummary::new(&prompts, tools, configs, strings).with_plugins(&plugins);
+    writer.write_summary(&extraction_summary)?;
+
     let modules = if enable_split {
         info!("Splitting code into modules...");
         let mods = transformer.split_into_modules(
@@ -807,14 +1199,16 @@ fn handle_all_command(
         input_size,
         4094,
         code.lines().count(),
-    );
+    )
+    .with_plugins(&plugins)
+    .with_tokens(TokenBudget::new(tokenizer, &prompts, tools));
 
     dashboard.write_json(output_dir)?;
     dashboard.generate_markdown(output_dir)?;
 
     // Print summary
     println!("\n{}", "=".repeat(60));
-    println!("{}", "COMPLETE ANALYSIS SUMMARY".to_string());
+    println!("COMPLETE ANALYSIS SUMMARY");
     println!("{}", "=".repeat(60));
 
     dashboard.print();
@@ -1,8 +1,9 @@
 //! Output module for writing extraction results.
 
-use crate::extractor::{config::ConfigValue, prompts::SystemPrompt, strings::InterestingString, tools::ToolDefinition};
+use crate::extractor::{config::ConfigValue, plugin::{FileContents, PluginFile, PluginOutputs}, prompts::SystemPrompt, strings::InterestingString, tool_export::ToolExportFormat, tools::ToolDefinition};
 use crate::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info};
@@ -61,6 +62,35 @@ impl OutputWriter {
         Ok(())
     }
 
+    /// Write tools exported in an external format (Messages API, MCP).
+    pub fn write_tool_export(&self, format: ToolExportFormat, export: &serde_json::Value) -> Result<()> {
+        let path = self.output_dir.join("extracted").join(format.file_name());
+        self.write_json(&path, export)?;
+        info!("Wrote {:?} tool export to {}", format, path.display());
+        Ok(())
+    }
+
+    /// Write a plugin's files under `extracted/`.
+    pub fn write_plugin_output(&self, name: &str, count: usize, files: &[PluginFile]) -> Result<()> {
+        let extracted_dir = self.output_dir.join("extracted");
+        for file in files {
+            let path = extracted_dir.join(&file.path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| crate::error::DecypherError::io(parent, e))?;
+            }
+            match &file.contents {
+                FileContents::Json(value) => self.write_json(&path, value)?,
+                FileContents::Text(text) => fs::write(&path, text)
+                    .map_err(|e| crate::error::DecypherError::io(&path, e))?,
+            }
+        }
+        if let Some(file) = files.first() {
+            info!("Wrote {} {} to {}", count, name, extracted_dir.join(&file.path).display());
+        }
+        Ok(())
+    }
+
     /// Write a summary of all extractions.
     pub fn write_summary(&self, summary: &ExtractionSummary) -> Result<()> {
         let path = self.output_dir.join("extracted/summary.json");
@@ -89,6 +119,10 @@ pub struct ExtractionSummary {
     /// Number of tools extracted.
     pub tool_count: usize,
 
+    /// Number of slash commands extracted.
+    #[serde(default)]
+    pub command_count: usize,
+
     /// Number of configs extracted.
     pub config_count: usize,
 
@@ -103,6 +137,10 @@ pub struct ExtractionSummary {
 
     /// Categories breakdown for configs.
     pub config_categories: std::collections::HashMap<String, usize>,
+
+    /// Items found by each extractor plugin.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extractors: BTreeMap<String, usize>,
 }
 
 impl ExtractionSummary {
@@ -132,19 +170,29 @@ impl ExtractionSummary {
         Self {
             prompt_count: prompts.len(),
             tool_count: tools.len(),
+            command_count: 0,
             config_count: configs.len(),
             string_count: strings.len(),
             longest_prompt,
             prompt_categories,
             config_categories,
+            extractors: BTreeMap::new(),
         }
     }
 
+    /// Record what each extractor plugin found.
+    pub fn with_plugins(mut self, outputs: &PluginOutputs) -> Self {
+        self.command_count = outputs.count("commands");
+        self.extractors = outputs.iter().map(|(name, output)| (name.to_string(), output.count())).collect();
+        self
+    }
+
     /// Print a summary to stdout.
     pub fn print(&self) {
         println!("\n=== Extraction Summary ===\n");
         println!("System Prompts:     {}", self.prompt_count);
         println!("Tool Definitions:   {}", self.tool_count);
+        println!("Slash Commands:     {}", self.command_count);
         println!("Configuration:      {}", self.config_count);
         println!("Interesting Strings: {}", self.string_count);
         println!("Longest Prompt:     {} chars", self.longest_prompt);
@@ -162,5 +210,12 @@ impl ExtractionSummary {
                 println!("  {:<15} {}", category, count);
             }
         }
+
+        if !self.extractors.is_empty() {
+            println!("\nExtractors:");
+            for (name, count) in &self.extractors {
+                println!("  {:<18} {}", name, count);
+            }
+        }
     }
 }
@@ -0,0 +1,167 @@
+//! Byte offset to line and column translation for parsed source.
+//!
+//! Minified bundles keep megabytes on a single line, so columns can't be
+//! found by scanning back to the line start. [`LineIndex`] records which
+//! bytes start a character in 64-byte blocks, making any column lookup a
+//! binary search plus a popcount.
+
+use oxc_span::Span;
+use serde::{Deserialize, Serialize};
+
+/// Bytes per block of character-start bits.
+const BLOCK_BYTES: usize = 64;
+
+/// Line starts and character positions of a source text.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// Byte offset of every line start.
+    line_starts: Vec<u32>,
+
+    /// Characters before each block.
+    block_chars: Vec<u32>,
+
+    /// Bit `i` is set when byte `i` of the block starts a character.
+    block_starts: Vec<u64>,
+
+    /// Byte offsets of characters outside the BMP (two UTF-16 units).
+    astral: Vec<u32>,
+}
+
+impl LineIndex {
+    /// Index a source text.
+    pub fn new(text: &str) -> Self {
+        let bytes = text.as_bytes();
+        let mut line_starts = vec![0];
+        let mut block_chars = Vec::with_capacity(bytes.len() / BLOCK_BYTES + 1);
+        let mut block_starts = Vec::with_capacity(bytes.len() / BLOCK_BYTES + 1);
+        let mut astral = Vec::new();
+        let mut chars = 0u32;
+
+        for (block, chunk) in bytes.chunks(BLOCK_BYTES).enumerate() {
+            block_chars.push(chars);
+            let mut starts = 0u64;
+            for (i, &b) in chunk.iter().enumerate() {
+                let offset = (block * BLOCK_BYTES + i) as u32;
+                if b & 0xC0 != 0x80 {
+                    starts |= 1 << i;
+                    chars += 1;
+                }
+                if b >= 0xF0 {
+                    astral.push(offset);
+                }
+                if b == b'\n' {
+                    line_starts.push(offset + 1);
+                }
+            }
+            block_starts.push(starts);
+        }
+        // Sentinel so the end of the text resolves like any other offset
+        if bytes.len().is_multiple_of(BLOCK_BYTES) {
+            block_chars.push(chars);
+            block_starts.push(0);
+        }
+
+        Self {
+            line_starts,
+            block_chars,
+            block_starts,
+            astral,
+        }
+    }
+
+    /// Number of lines.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// 1-based line and character column of a byte offset.
+    pub fn locate(&self, offset: u32) -> (usize, usize) {
+        let line = self.line_of(offset);
+        let column = self.chars_before(offset) - self.chars_before(self.line_starts[line]);
+        (line + 1, column + 1)
+    }
+
+    /// 0-based line and UTF-16 column of a byte offset, as in source maps.
+    pub fn locate_utf16(&self, offset: u32) -> (u32, u32) {
+        let line = self.line_of(offset);
+        let start = self.line_starts[line];
+        let chars = self.chars_before(offset) - self.chars_before(start);
+        let astral = self.astral_before(offset) - self.astral_before(start);
+        (line as u32, (chars + astral) as u32)
+    }
+
+    fn line_of(&self, offset: u32) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    fn chars_before(&self, offset: u32) -> usize {
+        let offset = offset as usize;
+        let block = (offset / BLOCK_BYTES).min(self.block_chars.len() - 1);
+        let within = offset - block * BLOCK_BYTES;
+        let mask = if within >= BLOCK_BYTES { u64::MAX } else { (1u64 << within) - 1 };
+        self.block_chars[block] as usize + (self.block_starts[block] & mask).count_ones() as usize
+    }
+
+    fn astral_before(&self, offset: u32) -> usize {
+        self.astral.partition_point(|&o| o < offset)
+    }
+}
+
+/// A span in the parsed source, with its 1-based line and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSpan {
+    pub start: u32,
+    pub end: u32,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceSpan {
+    /// Locate a span in the source text.
+    pub fn new(source_text: &str, span: Span) -> Self {
+        let before = &source_text[..span.start as usize];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            start: span.start,
+            end: span.end,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Locate a span through a line index.
+    pub fn locate(index: &LineIndex, span: Span) -> Self {
+        let (line, column) = index.locate(span.start);
+        Self {
+            start: span.start,
+            end: span.end,
+            line,
+            column,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_matches_scan() {
+        let line = format!("var a=\"{}é🎉\";\n", "x".repeat(70));
+        let text = format!("{}{}b=\"ü\"", line, line);
+        let index = LineIndex::new(&text);
+        assert_eq!(index.line_count(), 3);
+
+        for (offset, _) in text.char_indices().chain([(text.len(), ' ')]) {
+            let span = Span::new(offset as u32, offset as u32);
+            let expected = SourceSpan::new(&text, span);
+            assert_eq!(SourceSpan::locate(&index, span), expected, "offset {}", offset);
+        }
+
+        // The emoji takes two UTF-16 units
+        let quote = line.rfind('"').unwrap() as u32;
+        assert_eq!(index.locate(quote), (1, 80));
+        assert_eq!(index.locate_utf16(quote), (0, 80));
+        assert_eq!(index.locate_utf16(line.len() as u32 + quote), (1, 80));
+    }
+}
@@ -3,13 +3,17 @@
 //! This module provides functionality to parse JavaScript code and work with
 //! the resulting Abstract Syntax Tree (AST).
 
+pub mod location;
 pub mod visitor;
 
+pub use location::{LineIndex, SourceSpan};
+
 use oxc_allocator::Allocator;
 use oxc_ast::ast::Program;
 use oxc_parser::{Parser as OxcParser, ParserReturn};
 use oxc_span::SourceType;
 use std::path::Path;
+use std::sync::OnceLock;
 use tracing::{debug, info, warn};
 
 use crate::error::{DecypherError, Result};
@@ -18,6 +22,7 @@ use crate::error::{DecypherError, Result};
 pub struct Parser {
     source_text: String,
     source_path: Option<String>,
+    line_index: OnceLock<LineIndex>,
 }
 
 /// Result of parsing JavaScript code.
@@ -30,6 +35,9 @@ pub struct ParseResult<'a> {
 
     /// Whether parsing was successful (no fatal errors).
     pub is_success: bool,
+
+    /// Line index of the parsed source.
+    pub line_index: &'a LineIndex,
 }
 
 impl Parser {
@@ -38,6 +46,7 @@ impl Parser {
         Self {
             source_text,
             source_path: None,
+            line_index: OnceLock::new(),
         }
     }
 
@@ -52,6 +61,7 @@ impl Parser {
         Ok(Self {
             source_text,
             source_path: Some(path.display().to_string()),
+            line_index: OnceLock::new(),
         })
     }
 
@@ -100,6 +110,7 @@ impl Parser {
             program,
             error_count,
             is_success,
+            line_index: self.line_index(),
         })
     }
 
@@ -112,6 +123,11 @@ impl Parser {
     pub fn source_path(&self) -> Option<&str> {
         self.source_path.as_deref()
     }
+
+    /// Line index of the source text, built on first use.
+    pub fn line_index(&self) -> &LineIndex {
+        self.line_index.get_or_init(|| LineIndex::new(&self.source_text))
+    }
 }
 
 impl<'a> ParseResult<'a> {
@@ -130,6 +146,11 @@ impl<'a> ParseResult<'a> {
         self.error_count
     }
 
+    /// Get the line index of the parsed source.
+    pub fn line_index(&self) -> &'a LineIndex {
+        self.line_index
+    }
+
     /// Print error count to stderr.
     pub fn print_errors(&self) {
         if self.error_count > 0 {
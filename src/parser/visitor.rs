@@ -110,15 +110,9 @@ impl StatsVisitor {
                 }
             }
             Statement::ForStatement(for_stmt) => {
-                if let Some(ref init) = for_stmt.init {
-                    match init {
-                        ForStatementInit::VariableDeclaration(decl) => {
-                            self.stats.variable_count += decl.declarations.len();
-                        }
-                        _ => {
-                            // Other expression types in for loop init
-                        }
-                    }
+                // Other expression types in for loop init are not counted
+                if let Some(ForStatementInit::VariableDeclaration(decl)) = &for_stmt.init {
+                    self.stats.variable_count += decl.declarations.len();
                 }
                 self.visit_statement(&for_stmt.body);
             }
@@ -0,0 +1,603 @@
+//! Declarative extraction rules.
+//!
+//! Which literals count as prompts, config values and interesting strings,
+//! how they are categorized, and which names look like tools all come from a
+//! rules file. The default set is compiled in (`assets/rules/default.toml`);
+//! `--rules` loads a TOML or YAML file that extends it, or replaces a section
+//! outright with `replace = true`. Regexes and categories are checked when
+//! the file is loaded, so a bad rules file fails before extraction starts.
+
+use crate::analyzer::{Analyzer, StringLiteralInfo};
+use crate::error::DecypherError;
+use crate::extractor::config::ConfigCategory;
+use crate::extractor::prompts::PromptCategory;
+use crate::extractor::strings::StringCategory;
+use crate::Result;
+use oxc_span::Span;
+use regex::Regex;
+use serde::de::{DeserializeOwned, IntoDeserializer};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Rules compiled into the binary.
+const BUNDLED_RULES: &str = include_str!("../../assets/rules/default.toml");
+
+/// A string being matched and, for literals, where it sits in the AST.
+pub struct Subject<'s> {
+    text: &'s str,
+    length: usize,
+    site: Option<(&'s Analyzer<'s>, Span)>,
+}
+
+impl<'s> Subject<'s> {
+    /// A bare string with no AST context.
+    pub fn text(text: &'s str) -> Self {
+        Self {
+            text,
+            length: text.len(),
+            site: None,
+        }
+    }
+
+    /// A string literal, whose binding and callee can be looked up.
+    pub fn literal(analyzer: &'s Analyzer<'s>, literal: &StringLiteralInfo<'s>) -> Self {
+        Self {
+            text: literal.value,
+            length: literal.length,
+            site: Some((analyzer, literal.span)),
+        }
+    }
+
+    /// Last segment of the variable or property the string is assigned to.
+    fn property_key(&self) -> Option<&str> {
+        let (analyzer, span) = self.site?;
+        let binding = analyzer.context().binding_at(span)?;
+        binding.rsplit('.').next()
+    }
+
+    /// Function or method the string is passed to.
+    fn callee(&self) -> Option<&str> {
+        let (analyzer, span) = self.site?;
+        analyzer.context().callee_at(span)
+    }
+}
+
+/// A compiled matcher: every condition it sets must hold.
+#[derive(Debug, Clone, Default)]
+pub struct Matcher {
+    contains: Vec<String>,
+    starts_with: Vec<String>,
+    regex: Option<Regex>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    property_key: Option<Regex>,
+    callee: Option<Regex>,
+}
+
+impl Matcher {
+    /// Check a string against the matcher.
+    pub fn matches(&self, subject: &Subject<'_>) -> bool {
+        let text = subject.text;
+        if self.min_length.is_some_and(|min| subject.length < min)
+            || self.max_length.is_some_and(|max| subject.length > max)
+        {
+            return false;
+        }
+        if !self.contains.is_empty() && !self.contains.iter().any(|s| text.contains(s.as_str())) {
+            return false;
+        }
+        if !self.starts_with.is_empty() && !self.starts_with.iter().any(|s| text.starts_with(s.as_str())) {
+            return false;
+        }
+        if self.regex.as_ref().is_some_and(|re| !re.is_match(text)) {
+            return false;
+        }
+        if let Some(ref pattern) = self.property_key {
+            if !subject.property_key().is_some_and(|key| pattern.is_match(key)) {
+                return false;
+            }
+        }
+        if let Some(ref pattern) = self.callee {
+            if !subject.callee().is_some_and(|callee| pattern.is_match(callee)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A category assigned to strings matching a matcher.
+#[derive(Debug, Clone)]
+pub struct CategoryRule<C> {
+    pub category: C,
+
+    /// Relevance score (0.0-1.0); only used for interesting strings.
+    pub relevance: f32,
+
+    pub matcher: Matcher,
+}
+
+/// First category whose matcher holds.
+fn first_category<'r, C>(rules: &'r [CategoryRule<C>], subject: &Subject<'_>) -> Option<&'r CategoryRule<C>> {
+    rules.iter().find(|rule| rule.matcher.matches(subject))
+}
+
+/// Rules for system prompts.
+#[derive(Debug, Clone)]
+pub struct PromptRules {
+    /// Shortest literal the prompt extractor considers.
+    pub min_length: usize,
+
+    /// Shortest literal or symbol value the enhanced extractor considers.
+    pub enhanced_min_length: usize,
+
+    pub indicators: Vec<Matcher>,
+    pub symbol_indicators: Vec<Matcher>,
+    pub categories: Vec<CategoryRule<PromptCategory>>,
+}
+
+impl PromptRules {
+    /// Whether a literal has any prompt indicator.
+    pub fn is_indicated(&self, subject: &Subject<'_>) -> bool {
+        self.indicators.iter().any(|m| m.matches(subject))
+    }
+
+    /// Whether a resolved symbol value has any prompt indicator.
+    pub fn is_symbol_indicated(&self, subject: &Subject<'_>) -> bool {
+        self.symbol_indicators.iter().any(|m| m.matches(subject))
+    }
+
+    /// Category of a prompt, `Other` when no rule matches.
+    pub fn categorize(&self, subject: &Subject<'_>) -> PromptCategory {
+        first_category(&self.categories, subject)
+            .map(|rule| rule.category.clone())
+            .unwrap_or(PromptCategory::Other)
+    }
+}
+
+/// Rules for configuration values.
+#[derive(Debug, Clone)]
+pub struct ConfigRules {
+    /// Longest literal considered a config value.
+    pub max_length: usize,
+
+    pub patterns: Vec<Matcher>,
+    pub categories: Vec<CategoryRule<ConfigCategory>>,
+}
+
+impl ConfigRules {
+    /// Whether a literal matches any config pattern.
+    pub fn is_config(&self, subject: &Subject<'_>) -> bool {
+        subject.length <= self.max_length && self.patterns.iter().any(|m| m.matches(subject))
+    }
+
+    /// Category of a config value, `Other` when no rule matches.
+    pub fn categorize(&self, subject: &Subject<'_>) -> ConfigCategory {
+        first_category(&self.categories, subject)
+            .map(|rule| rule.category.clone())
+            .unwrap_or(ConfigCategory::Other)
+    }
+}
+
+/// Rules for interesting strings.
+#[derive(Debug, Clone)]
+pub struct StringRules {
+    /// Shortest literal considered.
+    pub min_length: usize,
+
+    /// Lowest relevance kept.
+    pub min_relevance: f32,
+
+    /// Most strings kept, by relevance.
+    pub limit: usize,
+
+    pub categories: Vec<CategoryRule<StringCategory>>,
+}
+
+impl StringRules {
+    /// Category and relevance of a string; zero relevance when no rule matches.
+    pub fn classify(&self, subject: &Subject<'_>) -> (StringCategory, f32) {
+        first_category(&self.categories, subject)
+            .map(|rule| (rule.category.clone(), rule.relevance))
+            .unwrap_or((StringCategory::Other, 0.0))
+    }
+}
+
+/// Rules for tool names found without structural discovery.
+#[derive(Debug, Clone)]
+pub struct ToolRules {
+    pub names: Vec<Matcher>,
+    pub exclude: Vec<Matcher>,
+}
+
+impl ToolRules {
+    /// Whether a name matches a name rule and no exclusion.
+    pub fn is_tool_name(&self, name: &str) -> bool {
+        let subject = Subject::text(name);
+        self.names.iter().any(|m| m.matches(&subject)) && !self.exclude.iter().any(|m| m.matches(&subject))
+    }
+}
+
+/// Compiled extraction rules.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    pub prompts: PromptRules,
+    pub config: ConfigRules,
+    pub strings: StringRules,
+    pub tools: ToolRules,
+}
+
+impl Rules {
+    /// The rules compiled into the binary.
+    pub fn bundled() -> &'static Rules {
+        static BUNDLED: OnceLock<Rules> = OnceLock::new();
+        BUNDLED.get_or_init(|| {
+            let file = parse_toml(BUNDLED_RULES, "bundled rules").expect("bundled rules parse");
+            let mut rules = Rules::empty();
+            rules.apply(file, "bundled rules").expect("bundled rules are valid");
+            rules
+        })
+    }
+
+    /// Load a TOML or YAML rules file on top of the bundled rules.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path).map_err(|e| DecypherError::io(path, e))?;
+        let origin = path.display().to_string();
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        let overlay = match extension.to_ascii_lowercase().as_str() {
+            "toml" => parse_toml(&data, &origin)?,
+            "yaml" | "yml" => parse_yaml(&data, &origin)?,
+            _ => {
+                return Err(DecypherError::RulesError(format!(
+                    "{}: unsupported rules format, expected .toml, .yaml or .yml",
+                    origin
+                )));
+            }
+        };
+        Self::extend(overlay, &origin)
+    }
+
+    /// Parse TOML rules text on top of the bundled rules.
+    pub fn from_toml(data: &str) -> Result<Self> {
+        Self::extend(parse_toml(data, "rules")?, "rules")
+    }
+
+    /// Parse YAML rules text on top of the bundled rules.
+    pub fn from_yaml(data: &str) -> Result<Self> {
+        Self::extend(parse_yaml(data, "rules")?, "rules")
+    }
+
+    /// Load the rules file at `path`, or the bundled rules.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::from_file(path),
+            None => Ok(Self::bundled().clone()),
+        }
+    }
+
+    fn extend(overlay: RulesFile, origin: &str) -> Result<Self> {
+        let mut rules = Self::bundled().clone();
+        rules.apply(overlay, origin)?;
+        Ok(rules)
+    }
+
+    /// Rules that match nothing.
+    fn empty() -> Self {
+        Self {
+            prompts: PromptRules {
+                min_length: 0,
+                enhanced_min_length: 0,
+                indicators: Vec::new(),
+                symbol_indicators: Vec::new(),
+                categories: Vec::new(),
+            },
+            config: ConfigRules {
+                max_length: 0,
+                patterns: Vec::new(),
+                categories: Vec::new(),
+            },
+            strings: StringRules {
+                min_length: 0,
+                min_relevance: 0.0,
+                limit: 0,
+                categories: Vec::new(),
+            },
+            tools: ToolRules {
+                names: Vec::new(),
+                exclude: Vec::new(),
+            },
+        }
+    }
+
+    /// Validate a rules file and extend (or replace) these rules with it.
+    fn apply(&mut self, file: RulesFile, origin: &str) -> Result<()> {
+        let cx = Compiler { origin };
+
+        let (rules, section) = (&mut self.prompts, file.prompts);
+        let replace = section.replace;
+        set(&mut rules.min_length, section.min_length);
+        set(&mut rules.enhanced_min_length, section.enhanced_min_length);
+        merge(&mut rules.indicators, cx.matchers(section.indicators, "prompts.indicators")?, replace, false);
+        merge(
+            &mut rules.symbol_indicators,
+            cx.matchers(section.symbol_indicators, "prompts.symbol_indicators")?,
+            replace,
+            false,
+        );
+        merge(&mut rules.categories, cx.categories(section.categories, "prompts.categories", false)?, replace, true);
+
+        let (rules, section) = (&mut self.config, file.config);
+        let replace = section.replace;
+        set(&mut rules.max_length, section.max_length);
+        merge(&mut rules.patterns, cx.matchers(section.patterns, "config.patterns")?, replace, false);
+        merge(&mut rules.categories, cx.categories(section.categories, "config.categories", false)?, replace, true);
+
+        let (rules, section) = (&mut self.strings, file.strings);
+        let replace = section.replace;
+        if section.min_relevance.is_some_and(|r| !(0.0..=1.0).contains(&r)) {
+            return Err(cx.error("strings.min_relevance", "must be between 0 and 1"));
+        }
+        set(&mut rules.min_length, section.min_length);
+        set(&mut rules.min_relevance, section.min_relevance);
+        set(&mut rules.limit, section.limit);
+        merge(&mut rules.categories, cx.categories(section.categories, "strings.categories", true)?, replace, true);
+
+        let (rules, section) = (&mut self.tools, file.tools);
+        let replace = section.replace;
+        merge(&mut rules.names, cx.matchers(section.names, "tools.names")?, replace, false);
+        merge(&mut rules.exclude, cx.matchers(section.exclude, "tools.exclude")?, replace, false);
+
+        Ok(())
+    }
+}
+
+fn set<T>(value: &mut T, overlay: Option<T>) {
+    if let Some(overlay) = overlay {
+        *value = overlay;
+    }
+}
+
+/// Replace or extend a list. Extra categories go first so they win.
+fn merge<T>(base: &mut Vec<T>, overlay: Vec<T>, replace: bool, prepend: bool) {
+    if replace {
+        *base = overlay;
+    } else if prepend {
+        base.splice(0..0, overlay);
+    } else {
+        base.extend(overlay);
+    }
+}
+
+fn parse_toml(data: &str, origin: &str) -> Result<RulesFile> {
+    toml::from_str(data).map_err(|e| DecypherError::RulesError(format!("{}: {}", origin, e.to_string().trim_end())))
+}
+
+fn parse_yaml(data: &str, origin: &str) -> Result<RulesFile> {
+    if data.trim().is_empty() {
+        return Ok(RulesFile::default());
+    }
+    serde_yaml::from_str(data).map_err(|e| DecypherError::RulesError(format!("{}: {}", origin, e)))
+}
+
+/// A rules file as written.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RulesFile {
+    prompts: PromptsSection,
+    config: ConfigSection,
+    strings: StringsSection,
+    tools: ToolsSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PromptsSection {
+    replace: bool,
+    min_length: Option<usize>,
+    enhanced_min_length: Option<usize>,
+    indicators: Vec<MatcherSpec>,
+    symbol_indicators: Vec<MatcherSpec>,
+    categories: Vec<MatcherSpec>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigSection {
+    replace: bool,
+    max_length: Option<usize>,
+    patterns: Vec<MatcherSpec>,
+    categories: Vec<MatcherSpec>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StringsSection {
+    replace: bool,
+    min_length: Option<usize>,
+    min_relevance: Option<f32>,
+    limit: Option<usize>,
+    categories: Vec<MatcherSpec>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ToolsSection {
+    replace: bool,
+    names: Vec<MatcherSpec>,
+    exclude: Vec<MatcherSpec>,
+}
+
+/// A matcher as written; `category` and `relevance` only apply in category lists.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct MatcherSpec {
+    category: Option<String>,
+    relevance: Option<f32>,
+    contains: Vec<String>,
+    starts_with: Vec<String>,
+    regex: Option<String>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    property_key: Option<String>,
+    callee: Option<String>,
+}
+
+/// Validates specs, naming the file and field in every error.
+struct Compiler<'o> {
+    origin: &'o str,
+}
+
+impl Compiler<'_> {
+    fn error(&self, field: &str, message: impl std::fmt::Display) -> DecypherError {
+        DecypherError::RulesError(format!("{}: {}: {}", self.origin, field, message))
+    }
+
+    fn regex(&self, pattern: Option<String>, field: &str) -> Result<Option<Regex>> {
+        pattern
+            .map(|p| Regex::new(&p).map_err(|e| self.error(field, e)))
+            .transpose()
+    }
+
+    fn matchers(&self, specs: Vec<MatcherSpec>, field: &str) -> Result<Vec<Matcher>> {
+        specs
+            .into_iter()
+            .enumerate()
+            .map(|(idx, spec)| {
+                let field = format!("{}[{}]", field, idx);
+                if spec.category.is_some() {
+                    return Err(self.error(&field, "`category` is only allowed in category lists"));
+                }
+                if spec.relevance.is_some() {
+                    return Err(self.error(&field, "`relevance` is only allowed in strings.categories"));
+                }
+                self.matcher(spec, &field)
+            })
+            .collect()
+    }
+
+    fn categories<C: DeserializeOwned>(
+        &self,
+        specs: Vec<MatcherSpec>,
+        field: &str,
+        scored: bool,
+    ) -> Result<Vec<CategoryRule<C>>> {
+        specs
+            .into_iter()
+            .enumerate()
+            .map(|(idx, mut spec)| {
+                let field = format!("{}[{}]", field, idx);
+                let name = spec
+                    .category
+                    .take()
+                    .ok_or_else(|| self.error(&field, "missing `category`"))?;
+                let category = C::deserialize(name.as_str().into_deserializer())
+                    .map_err(|e: serde::de::value::Error| self.error(&format!("{}.category", field), e))?;
+
+                let relevance = match (spec.relevance.take(), scored) {
+                    (Some(r), true) if (0.0..=1.0).contains(&r) => r,
+                    (Some(_), true) => return Err(self.error(&field, "`relevance` must be between 0 and 1")),
+                    (None, true) => return Err(self.error(&field, "missing `relevance`")),
+                    (Some(_), false) => {
+                        return Err(self.error(&field, "`relevance` is only allowed in strings.categories"));
+                    }
+                    (None, false) => 0.0,
+                };
+
+                Ok(CategoryRule {
+                    category,
+                    relevance,
+                    matcher: self.matcher(spec, &field)?,
+                })
+            })
+            .collect()
+    }
+
+    fn matcher(&self, spec: MatcherSpec, field: &str) -> Result<Matcher> {
+        let has_condition = !spec.contains.is_empty()
+            || !spec.starts_with.is_empty()
+            || spec.regex.is_some()
+            || spec.min_length.is_some()
+            || spec.max_length.is_some()
+            || spec.property_key.is_some()
+            || spec.callee.is_some();
+        if !has_condition {
+            return Err(self.error(field, "matcher has no conditions"));
+        }
+        if let (Some(min), Some(max)) = (spec.min_length, spec.max_length) {
+            if min > max {
+                return Err(self.error(field, format!("min_length {} exceeds max_length {}", min, max)));
+            }
+        }
+        if let Some(empty) = spec
+            .contains
+            .iter()
+            .chain(&spec.starts_with)
+            .find(|s| s.is_empty())
+        {
+            return Err(self.error(field, format!("empty string {:?} matches everything", empty)));
+        }
+
+        Ok(Matcher {
+            regex: self.regex(spec.regex, &format!("{}.regex", field))?,
+            property_key: self.regex(spec.property_key, &format!("{}.property_key", field))?,
+            callee: self.regex(spec.callee, &format!("{}.callee", field))?,
+            contains: spec.contains,
+            starts_with: spec.starts_with,
+            min_length: spec.min_length,
+            max_length: spec.max_length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use oxc_allocator::Allocator;
+
+    #[test]
+    fn test_extend_with_ast_matchers() {
+        let rules = Rules::from_yaml(
+            "strings:\n  categories:\n    - category: ErrorMessage\n      relevance: 0.95\n      callee: '^(console\\.error|Error)$'\n\
+             config:\n  patterns:\n    - property_key: '^(model|baseURL)$'\n",
+        )
+        .unwrap();
+        // Bundled rules are still there, behind the new category
+        assert_eq!(rules.strings.categories.len(), Rules::bundled().strings.categories.len() + 1);
+
+        let code = r#"console.error("disk quota exhausted"); const cfg = { model: "custom-model-1" };"#;
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let analyzer = Analyzer::new(parse_result.program());
+        let literals = analyzer.find_string_literals();
+
+        let quota = Subject::literal(&analyzer, &literals[0]);
+        assert_eq!(rules.strings.classify(&quota), (StringCategory::ErrorMessage, 0.95));
+        assert!(!rules.config.is_config(&quota));
+
+        let model = Subject::literal(&analyzer, &literals[1]);
+        assert!(rules.config.is_config(&model));
+        assert!(!Rules::bundled().config.is_config(&model));
+    }
+
+    #[test]
+    fn test_invalid_rules_are_reported() {
+        let error = |data: &str| Rules::from_toml(data).unwrap_err().to_string();
+
+        assert!(error("[[strings.categories]]\ncategory = \"Url\"\nrelevance = 0.5\nregex = \"(\"\n")
+            .contains("strings.categories[0].regex"));
+        assert!(error("[[prompts.categories]]\ncategory = \"Prompt\"\ncontains = [\"x\"]\n")
+            .contains("unknown variant `Prompt`"));
+        assert!(error("[[config.patterns]]\nmin_length = 9\nmax_length = 2\n").contains("config.patterns[0]"));
+        assert!(error("[tools]\nnames = [{}]\n").contains("matcher has no conditions"));
+        assert!(error("[prompts]\nmin_lenght = 3\n").contains("unknown field `min_lenght`"));
+
+        let replaced = Rules::from_toml("[tools]\nreplace = true\nnames = [{ regex = '^mcp__' }]\n").unwrap();
+        assert!(replaced.tools.is_tool_name("mcp__github"));
+        assert!(!replaced.tools.is_tool_name("Bash"));
+        assert!(Rules::bundled().tools.is_tool_name("Bash"));
+        assert!(!Rules::bundled().tools.is_tool_name("SIGHUP"));
+    }
+}
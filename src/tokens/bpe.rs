@@ -0,0 +1,125 @@
+//! Byte-level BPE over a tiktoken-format vocabulary.
+
+use super::Tokenizer;
+use crate::error::DecypherError;
+use crate::Result;
+use base64::Engine;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Splits text into pieces that are merged independently.
+///
+/// Matches `scripts/train_bpe_vocab.py`, which trained the bundled vocabulary.
+const PIECE_PATTERN: &str = r"'(?:s|t|re|ve|m|ll|d)| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+";
+
+/// Pieces longer than this are merged in chunks to bound the quadratic merge.
+const MAX_PIECE_BYTES: usize = 256;
+
+/// Tokenizer merging byte pairs by vocabulary rank.
+pub struct BpeTokenizer {
+    name: String,
+    ranks: HashMap<Vec<u8>, u32>,
+    pattern: Regex,
+}
+
+impl BpeTokenizer {
+    /// Load a vocabulary of `<base64 token> <rank>` lines.
+    pub fn from_tiktoken(name: impl Into<String>, data: &str) -> Result<Self> {
+        let mut ranks = HashMap::new();
+        for (idx, line) in data.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let bad_line = |message: &str| DecypherError::TokenizerError(format!("line {}: {}", idx + 1, message));
+            let (token, rank) = line.split_once(' ').ok_or_else(|| bad_line("expected '<token> <rank>'"))?;
+            let token = base64::engine::general_purpose::STANDARD
+                .decode(token)
+                .map_err(|e| bad_line(&e.to_string()))?;
+            let rank = rank.trim().parse().map_err(|_| bad_line("invalid rank"))?;
+            ranks.insert(token, rank);
+        }
+
+        if ranks.is_empty() {
+            return Err(DecypherError::TokenizerError("empty vocabulary".to_string()));
+        }
+
+        Ok(Self {
+            name: name.into(),
+            ranks,
+            pattern: Regex::new(PIECE_PATTERN).expect("valid piece pattern"),
+        })
+    }
+
+    /// Load a vocabulary file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path).map_err(|e| DecypherError::io(path, e))?;
+        let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        Self::from_tiktoken(format!("bpe:{}", name), &data)
+    }
+
+    /// Number of tokens in the vocabulary.
+    pub fn vocab_size(&self) -> usize {
+        self.ranks.len()
+    }
+
+    /// Number of tokens a piece merges into.
+    fn count_piece(&self, piece: &[u8]) -> usize {
+        if self.ranks.contains_key(piece) {
+            return 1;
+        }
+
+        // Boundaries of the current parts; merge the lowest-ranked adjacent pair
+        let mut bounds: Vec<usize> = (0..=piece.len()).collect();
+        loop {
+            let best = (0..bounds.len().saturating_sub(2))
+                .filter_map(|i| self.ranks.get(&piece[bounds[i]..bounds[i + 2]]).map(|rank| (*rank, i)))
+                .min();
+            match best {
+                Some((_, i)) => {
+                    bounds.remove(i + 1);
+                }
+                None => return bounds.len() - 1,
+            }
+        }
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn count(&self, text: &str) -> usize {
+        self.pattern
+            .find_iter(text)
+            .flat_map(|m| m.as_str().as_bytes().chunks(MAX_PIECE_BYTES))
+            .map(|piece| self.count_piece(piece))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::general_purpose::STANDARD;
+
+    #[test]
+    fn test_merges_by_rank() {
+        let mut vocab: Vec<Vec<u8>> = (0..=255u8).map(|b| vec![b]).collect();
+        for token in ["ll", "he", "hell", "hello", " w", "or", " wor"] {
+            vocab.push(token.as_bytes().to_vec());
+        }
+        let data: String = vocab
+            .iter()
+            .enumerate()
+            .map(|(rank, token)| format!("{} {}\n", STANDARD.encode(token), rank))
+            .collect();
+
+        let tokenizer = BpeTokenizer::from_tiktoken("test", &data).unwrap();
+        assert_eq!(tokenizer.vocab_size(), 263);
+        assert_eq!(tokenizer.count("hello"), 1);
+        // "hello" + " wor" + "l" + "d" + "!"
+        assert_eq!(tokenizer.count("hello world!"), 5);
+        assert_eq!(tokenizer.count(""), 0);
+
+        assert!(BpeTokenizer::from_tiktoken("bad", "not-a-line").is_err());
+    }
+}
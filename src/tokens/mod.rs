@@ -0,0 +1,179 @@
+//! Offline token estimation for prompts and tool definitions.
+//!
+//! Counts come from a byte-level BPE vocabulary bundled with the crate
+//! (`assets/tokenizer/bpe-8k.tiktoken`, trained with
+//! `scripts/train_bpe_vocab.py`) or, as a fallback, from a fixed
+//! characters-per-token ratio. Both implement [`Tokenizer`], so a more
+//! accurate tiktoken-format vocabulary can be dropped in with `--vocab`.
+
+pub mod bpe;
+
+use crate::extractor::prompts::SystemPrompt;
+use crate::extractor::prompts_enhanced::EnhancedSystemPrompt;
+use crate::extractor::tools::ToolDefinition;
+use crate::Result;
+use bpe::BpeTokenizer;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::warn;
+
+/// Vocabulary compiled into the binary.
+const BUNDLED_VOCAB: &str = include_str!("../../assets/tokenizer/bpe-8k.tiktoken");
+
+/// Characters per token for English prose and code.
+pub const DEFAULT_CHARS_PER_TOKEN: f64 = 3.5;
+
+/// Counts tokens in text.
+pub trait Tokenizer: Send + Sync {
+    /// Name recorded alongside counts.
+    fn name(&self) -> &str;
+
+    /// Number of tokens in `text`.
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Estimates tokens from character count.
+pub struct CharRatioTokenizer {
+    chars_per_token: f64,
+}
+
+impl CharRatioTokenizer {
+    /// Create an estimator with a characters-per-token ratio.
+    pub fn new(chars_per_token: f64) -> Self {
+        Self { chars_per_token }
+    }
+}
+
+impl Default for CharRatioTokenizer {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHARS_PER_TOKEN)
+    }
+}
+
+impl Tokenizer for CharRatioTokenizer {
+    fn name(&self) -> &str {
+        "char-ratio"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        (text.chars().count() as f64 / self.chars_per_token).ceil() as usize
+    }
+}
+
+/// The bundled BPE tokenizer, or the character ratio if it fails to load.
+pub fn bundled() -> Box<dyn Tokenizer> {
+    match BpeTokenizer::from_tiktoken("bpe:bpe-8k", BUNDLED_VOCAB) {
+        Ok(tokenizer) => Box::new(tokenizer),
+        Err(e) => {
+            warn!("Bundled vocabulary unusable ({}), estimating from character count", e);
+            Box::new(CharRatioTokenizer::default())
+        }
+    }
+}
+
+/// Load a tokenizer from a vocabulary file, or the bundled one.
+pub fn load(vocab: Option<&Path>) -> Result<Box<dyn Tokenizer>> {
+    match vocab {
+        Some(path) => Ok(Box::new(BpeTokenizer::from_file(path)?)),
+        None => Ok(bundled()),
+    }
+}
+
+/// Token cost of one tool definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolTokens {
+    /// Tool name.
+    pub name: String,
+
+    /// Tokens in the tool prompt.
+    pub prompt_tokens: usize,
+
+    /// Tokens in the serialized input schema.
+    pub schema_tokens: usize,
+
+    /// Prompt plus schema.
+    pub total: usize,
+}
+
+impl ToolTokens {
+    /// Count a tool's prompt (or short description) and input schema.
+    pub fn count(tokenizer: &dyn Tokenizer, tool: &ToolDefinition) -> Self {
+        let prompt = if tool.full_prompt.is_empty() { &tool.short_description } else { &tool.full_prompt };
+        let prompt_tokens = tokenizer.count(prompt);
+        let schema_tokens = tool
+            .input_schema
+            .as_ref()
+            .map(|schema| tokenizer.count(&schema.to_string()))
+            .unwrap_or(0);
+
+        Self {
+            name: tool.name.clone(),
+            prompt_tokens,
+            schema_tokens,
+            total: prompt_tokens + schema_tokens,
+        }
+    }
+}
+
+/// Token totals for prompts and tools, with tools ranked by cost.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenBudget {
+    /// Tokenizer that produced the counts.
+    pub tokenizer: String,
+
+    /// Tokens across all system prompts.
+    pub prompt_tokens: usize,
+
+    /// Tokens across all tool definitions.
+    pub tool_tokens: usize,
+
+    /// Tools, most expensive first.
+    pub tools: Vec<ToolTokens>,
+}
+
+impl TokenBudget {
+    /// Count every prompt and tool.
+    pub fn new(tokenizer: &dyn Tokenizer, prompts: &[SystemPrompt], tools: &[ToolDefinition]) -> Self {
+        let mut ranked: Vec<ToolTokens> = tools.iter().map(|t| ToolTokens::count(tokenizer, t)).collect();
+        ranked.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+
+        Self {
+            tokenizer: tokenizer.name().to_string(),
+            prompt_tokens: prompts.iter().map(|p| tokenizer.count(&p.content)).sum(),
+            tool_tokens: ranked.iter().map(|t| t.total).sum(),
+            tools: ranked,
+        }
+    }
+}
+
+/// Record token counts on enhanced prompts.
+pub fn count_prompts(tokenizer: &dyn Tokenizer, prompts: &mut [EnhancedSystemPrompt]) {
+    for prompt in prompts {
+        prompt.tokens = Some(tokenizer.count(&prompt.content));
+    }
+}
+
+/// Record token counts (prompt plus schema) on tool definitions.
+pub fn count_tools(tokenizer: &dyn Tokenizer, tools: &mut [ToolDefinition]) {
+    for tool in tools {
+        tool.tokens = Some(ToolTokens::count(tokenizer, tool).total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_vocabulary_estimates() {
+        let tokenizer = bundled();
+        assert!(tokenizer.name().starts_with("bpe"));
+
+        let text = "You are an interactive CLI tool that helps users with software engineering tasks.";
+        let count = tokenizer.count(text);
+        assert!((12..=30).contains(&count), "unexpected count {}", count);
+
+        let ratio = CharRatioTokenizer::default();
+        assert_eq!(ratio.count("abcdefg"), 2);
+    }
+}
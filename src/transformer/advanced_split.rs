@@ -1,6 +1,5 @@
 //! Advanced AST-aware module splitting.
 
-use crate::analyzer::Analyzer;
 use crate::Result;
 use oxc_ast::ast::*;
 use serde::{Deserialize, Serialize};
@@ -35,13 +34,16 @@ pub struct AdvancedModule {
 /// Advanced splitter that actually divides code into modules.
 pub struct AdvancedSplitter<'a> {
     program: &'a Program<'a>,
-    source_code: &'a str,
+    _source_code: &'a str,
 }
 
 impl<'a> AdvancedSplitter<'a> {
     /// Create a new advanced splitter.
     pub fn new(program: &'a Program<'a>, source_code: &'a str) -> Self {
-        Self { program, source_code }
+        Self {
+            program,
+            _source_code: source_code,
+        }
     }
 
     /// Split code into modules with actual content.
@@ -37,7 +37,7 @@ pub struct ModuleCode {
 
 /// Assembles module code from components.
 pub struct CodeAssembler<'a> {
-    allocator: &'a Allocator,
+    _allocator: &'a Allocator,
     program: &'a Program<'a>,
     source_code: &'a str,
 }
@@ -46,7 +46,7 @@ impl<'a> CodeAssembler<'a> {
     /// Create a new code assembler.
     pub fn new(allocator: &'a Allocator, program: &'a Program<'a>, source_code: &'a str) -> Self {
         Self {
-            allocator,
+            _allocator: allocator,
             program,
             source_code,
         }
@@ -99,7 +99,7 @@ impl<'a> CodeAssembler<'a> {
         let mut code = String::new();
 
         // Add header comment
-        code.push_str(&format!("// Module: auto-generated\n\n"));
+        code.push_str("// Module: auto-generated\n\n");
 
         // Add imports
         if !imports.is_empty() {
@@ -185,7 +185,7 @@ mod tests {
     use super::*;
     use crate::transformer::{
         function_extractor::SpanInfo,
-        import_generator::{ExportInfo, ImportStatement},
+        import_generator::ExportInfo,
     };
 
     #[test]
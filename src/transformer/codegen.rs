@@ -1,21 +1,33 @@
 //! Code generation and beautification using oxc_codegen.
 
+use crate::analyzer::{BeautifiedLocation, HasProvenance};
+use crate::parser::LineIndex;
 use crate::Result;
 use oxc_allocator::Allocator;
 use oxc_ast::ast::Program;
 use oxc_codegen::{Codegen, CodegenOptions};
+use serde_json::Value as JsonValue;
+use std::path::PathBuf;
 use tracing::debug;
 
+/// Largest gap (in UTF-16 units) between a position and the next mapping.
+///
+/// Template literals are mapped at their first quasi, just past the backtick.
+const MAX_MAPPING_GAP: u32 = 1;
+
 /// Code generator that produces beautified JavaScript.
 pub struct CodeGenerator<'a> {
-    allocator: &'a Allocator,
+    _allocator: &'a Allocator,
     program: &'a Program<'a>,
 }
 
 impl<'a> CodeGenerator<'a> {
     /// Create a new code generator.
     pub fn new(allocator: &'a Allocator, program: &'a Program<'a>) -> Self {
-        Self { allocator, program }
+        Self {
+            _allocator: allocator,
+            program,
+        }
     }
 
     /// Generate beautified JavaScript code.
@@ -30,6 +42,30 @@ impl<'a> CodeGenerator<'a> {
         Ok(generated.code)
     }
 
+    /// Generate code with the source map positions of every node.
+    pub fn generate_mapped(&self) -> Result<GeneratedCode> {
+        debug!("Generating beautified code with source map");
+
+        let options = CodegenOptions {
+            source_map_path: Some(PathBuf::from("input.js")),
+            ..CodegenOptions::default()
+        };
+        let generated = Codegen::new().with_options(options).build(self.program);
+        let mappings = generated
+            .map
+            .map(|map| {
+                map.get_tokens()
+                    .map(|t| (t.get_src_line(), t.get_src_col(), t.get_dst_line(), t.get_dst_col()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(GeneratedCode {
+            code: generated.code,
+            mappings,
+        })
+    }
+
     /// Generate code with custom options.
     pub fn generate_with_options(&self, _options: CodegenOptions) -> Result<String> {
         debug!("Generating code with custom options");
@@ -41,6 +77,176 @@ impl<'a> CodeGenerator<'a> {
     }
 }
 
+/// Generated code with its source map.
+pub struct GeneratedCode {
+    /// The generated code.
+    pub code: String,
+
+    /// 0-based original line and UTF-16 column, then generated line and column.
+    mappings: Vec<(u32, u32, u32, u32)>,
+}
+
+/// Maps original source positions to lines and columns in beautified code.
+///
+/// [`beautify_code`] only inserts spaces and newlines, so a position in the
+/// generated code carries over by counting the other characters before it.
+pub struct BeautifiedMap {
+    /// 0-based original line and UTF-16 column, then 1-based beautified line
+    /// and character column, sorted.
+    positions: Vec<(u32, u32, u32, u32)>,
+}
+
+impl BeautifiedMap {
+    /// Align generated code's mappings with its beautified form.
+    pub fn new(generated: &GeneratedCode, beautified: &str) -> Self {
+        // Characters other than inserted whitespace before each mapping
+        let mut mappings = generated.mappings.clone();
+        mappings.sort_by_key(|m| (m.2, m.3));
+        let mut kept = Vec::with_capacity(mappings.len());
+        let mut chars = generated.code.chars().peekable();
+        let (mut line, mut column, mut count) = (0u32, 0u32, 0u32);
+        for &(src_line, src_col, dst_line, dst_col) in &mappings {
+            while (line, column) < (dst_line, dst_col) {
+                let Some(c) = chars.next() else { break };
+                if c == '\n' {
+                    line += 1;
+                    column = 0;
+                } else {
+                    column += c.len_utf16() as u32;
+                }
+                if !is_inserted_whitespace(c) {
+                    count += 1;
+                }
+            }
+            kept.push((count, src_line, src_col));
+        }
+
+        // Find the same character in the beautified code
+        kept.sort_unstable();
+        let mut positions = Vec::with_capacity(kept.len());
+        let mut chars = beautified.chars();
+        let (mut line, mut column, mut count) = (1u32, 1u32, 0u32);
+        let mut current = chars.next();
+        for (target, src_line, src_col) in kept {
+            while let Some(c) = current {
+                if count == target && !is_inserted_whitespace(c) {
+                    break;
+                }
+                if c == '\n' {
+                    line += 1;
+                    column = 1;
+                } else {
+                    column += 1;
+                }
+                if !is_inserted_whitespace(c) {
+                    count += 1;
+                }
+                current = chars.next();
+            }
+            positions.push((src_line, src_col, line, column));
+        }
+        positions.sort_unstable();
+        positions.dedup_by_key(|p| (p.0, p.1));
+
+        Self { positions }
+    }
+
+    /// 1-based beautified line and column of a 0-based original line and UTF-16 column.
+    pub fn locate(&self, line: u32, column: u32) -> Option<(usize, usize)> {
+        let idx = self.positions.partition_point(|p| (p.0, p.1) < (line, column));
+        let &(src_line, src_col, dst_line, dst_col) = self.positions.get(idx)?;
+        if src_line != line {
+            return None;
+        }
+        let gap = src_col - column;
+        if gap > MAX_MAPPING_GAP || dst_col <= gap {
+            return None;
+        }
+        Some((dst_line as usize, (dst_col - gap) as usize))
+    }
+
+    /// Record beautified locations on extracted artifacts.
+    ///
+    /// Without `columns` only lines are recorded, for code whose lines were
+    /// rewritten afterwards (e.g. by variable renaming).
+    pub fn annotate<T: HasProvenance>(&self, line_index: &LineIndex, items: &mut [T], columns: bool) {
+        for item in items {
+            if let Some(provenance) = item.provenance_mut() {
+                provenance.beautified = self.location(line_index, provenance.span.start, columns);
+            }
+            self.annotate(line_index, item.children_mut(), columns);
+        }
+    }
+
+    /// Record beautified locations on every span in serialized artifacts.
+    ///
+    /// An object's `span` gets a sibling `beautified`, as in [`Provenance`](crate::analyzer::Provenance);
+    /// spans under other keys or in lists get it inside them.
+    pub fn annotate_json(&self, line_index: &LineIndex, value: &mut JsonValue, columns: bool) {
+        match value {
+            JsonValue::Array(items) => {
+                for item in items {
+                    if !self.annotate_span(line_index, item, columns) {
+                        self.annotate_json(line_index, item, columns);
+                    }
+                }
+            }
+            JsonValue::Object(object) => {
+                let start = object.get("span").and_then(span_start);
+                if let Some(start) = start.filter(|_| !object.contains_key("beautified")) {
+                    if let Some(location) = self.location(line_index, start, columns) {
+                        object.insert("beautified".to_string(), location_json(&location));
+                    }
+                }
+                for (key, member) in object.iter_mut() {
+                    if key == "span" && start.is_some() {
+                        continue;
+                    }
+                    if !self.annotate_span(line_index, member, columns) {
+                        self.annotate_json(line_index, member, columns);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the beautified location inside a span; `false` when the value isn't one.
+    fn annotate_span(&self, line_index: &LineIndex, value: &mut JsonValue, columns: bool) -> bool {
+        let Some(start) = span_start(value) else {
+            return false;
+        };
+        if let (Some(location), JsonValue::Object(span)) = (self.location(line_index, start, columns), value) {
+            span.insert("beautified".to_string(), location_json(&location));
+        }
+        true
+    }
+
+    /// Beautified location of a byte offset in the original source.
+    fn location(&self, line_index: &LineIndex, offset: u32, columns: bool) -> Option<BeautifiedLocation> {
+        let (line, column) = line_index.locate_utf16(offset);
+        self.locate(line, column).map(|(line, column)| BeautifiedLocation {
+            line,
+            column: columns.then_some(column),
+        })
+    }
+}
+
+/// Start offset of a serialized [`SourceSpan`](crate::parser::SourceSpan).
+fn span_start(value: &JsonValue) -> Option<u32> {
+    let span = value.as_object()?;
+    let is_span = span.len() == 4 && ["start", "end", "line", "column"].iter().all(|k| span.get(*k).is_some_and(JsonValue::is_u64));
+    is_span.then(|| span["start"].as_u64()).flatten().and_then(|start| u32::try_from(start).ok())
+}
+
+fn location_json(location: &BeautifiedLocation) -> JsonValue {
+    serde_json::to_value(location).unwrap_or(JsonValue::Null)
+}
+
+fn is_inserted_whitespace(c: char) -> bool {
+    c == ' ' || c == '\n'
+}
+
 /// Post-process generated code for better readability.
 pub fn beautify_code(code: &str) -> String {
     let mut result = code.to_string();
@@ -97,6 +303,65 @@ mod tests {
         assert!(beautified.contains(";\nvar y"));
     }
 
+    #[test]
+    fn test_beautified_map_locates_literals() {
+        let code = "var a=1;var b={name:\"Read\",run:()=>`hi ${a}`};function f(){return \"🎉 done\"}";
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+
+        let generated = CodeGenerator::new(&allocator, parse_result.program()).generate_mapped().unwrap();
+        let beautified = beautify_code(&generated.code);
+        let map = BeautifiedMap::new(&generated, &beautified);
+
+        for needle in ["\"Read\"", "`hi", "\"🎉 done\""] {
+            let offset = code.find(needle).unwrap() as u32;
+            let (line, column) = parse_result.line_index().locate_utf16(offset);
+            let (line, column) = map.locate(line, column).unwrap();
+            let text: String = beautified.lines().nth(line - 1).unwrap().chars().skip(column - 1).collect();
+            assert!(text.starts_with(needle), "{} not at {}:{} in\n{}", needle, line, column, beautified);
+        }
+    }
+
+    #[test]
+    fn test_annotates_spans_in_json() {
+        let code = "var a=1;var b=2;function f(){throw new Error(\"boom\")}";
+
+        let allocator = Allocator::default();
+        let parser = Parser::new(code.to_string());
+        let parse_result = parser.parse(&allocator).unwrap();
+        let line_index = parse_result.line_index();
+
+        let generated = CodeGenerator::new(&allocator, parse_result.program()).generate_mapped().unwrap();
+        let beautified = beautify_code(&generated.code);
+        let map = BeautifiedMap::new(&generated, &beautified);
+
+        let start = code.find("\"boom\"").unwrap() as u32;
+        let span = crate::parser::SourceSpan::locate(line_index, oxc_span::Span::new(start, start + 6));
+        let span = serde_json::to_value(span).unwrap();
+        let mut value = serde_json::json!({
+            "sites": [{ "message": "boom", "span": span }],
+            "region": span,
+            "betas": { "x": [span] },
+        });
+        map.annotate_json(line_index, &mut value, true);
+
+        let (line, column) = map.locate(0, start).unwrap();
+        let beautified_line = beautified.lines().nth(line - 1).unwrap();
+        assert!(beautified_line.chars().skip(column - 1).collect::<String>().starts_with("\"boom\""));
+        let expected = serde_json::json!({ "line": line, "column": column });
+        assert_eq!(value["sites"][0]["beautified"], expected);
+        assert_eq!(value["sites"][0]["span"]["line"], 1);
+        assert!(value["sites"][0]["span"].get("beautified").is_none());
+        assert_eq!(value["region"]["beautified"], expected);
+        assert_eq!(value["betas"]["x"][0]["beautified"], expected);
+        assert!(line > 1, "beautified line {} should differ from the bundle's", line);
+
+        map.annotate_json(line_index, &mut value, false);
+        assert_eq!(value["sites"][0]["beautified"], expected, "existing locations are kept");
+    }
+
     #[test]
     fn test_generate_complex_code() {
         let code = r#"
@@ -60,7 +60,7 @@ impl DocsGenerator {
                 for func in &module.functions {
                     content.push_str(&format!("- `{}`\n", func));
                 }
-                content.push_str("\n");
+                content.push('\n');
             }
 
             if !module.keywords.is_empty() {
@@ -68,7 +68,7 @@ impl DocsGenerator {
                 for keyword in &module.keywords {
                     content.push_str(&format!("- {}\n", keyword));
                 }
-                content.push_str("\n");
+                content.push('\n');
             }
 
             content.push_str("---\n\n");
@@ -118,7 +118,7 @@ impl DocsGenerator {
             for module in mods {
                 content.push_str(&format!("- **{}**: {} estimated lines\n", module.name, module.estimated_lines));
             }
-            content.push_str("\n");
+            content.push('\n');
         }
 
         content.push_str("## Statistics\n\n");
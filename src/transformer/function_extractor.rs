@@ -215,11 +215,8 @@ impl<'a> FunctionExtractor<'a> {
 
                 // Visit arguments to find nested calls
                 for arg in &call.arguments {
-                    match arg {
-                        Argument::SpreadElement(spread) => {
-                            self.visit_expression(&spread.argument);
-                        }
-                        _ => {}
+                    if let Argument::SpreadElement(spread) = arg {
+                        self.visit_expression(&spread.argument);
                     }
                 }
             }
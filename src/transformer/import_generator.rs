@@ -68,7 +68,7 @@ impl ImportGenerator {
             // Convert to ImportStatement structures
             for (dep_module, imported_functions) in &imports_by_module {
                 let module_path = format!("./{}.js", dep_module);
-                let mut import_set: HashSet<String> = imported_functions.iter().cloned().collect();
+                let import_set: HashSet<String> = imported_functions.iter().cloned().collect();
                 let import_list: Vec<String> = import_set.into_iter().collect();
 
                 module_imports.push(ImportStatement {
@@ -177,7 +177,6 @@ impl ImportGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::transformer::function_extractor::SpanInfo;
 
     #[test]
     fn test_generate_imports() {
@@ -206,7 +205,7 @@ mod tests {
         let mut module_functions: HashMap<String, Vec<String>> = HashMap::new();
         module_functions.insert("core".to_string(), vec!["foo".to_string(), "bar".to_string()]);
 
-        let exports = ImportGenerator::generate_exports(&module_functions, &vec!["core".to_string()]);
+        let exports = ImportGenerator::generate_exports(&module_functions, &["core".to_string()]);
 
         assert!(exports.contains_key("core"));
         assert_eq!(exports.get("core").unwrap().exports.len(), 2);
@@ -46,6 +46,11 @@ impl<'a> Transformer<'a> {
         codegen::CodeGenerator::new(allocator, self.program).generate()
     }
 
+    /// Generate beautified code with its source map.
+    pub fn beautify_mapped(&self, allocator: &'a Allocator) -> Result<codegen::GeneratedCode> {
+        codegen::CodeGenerator::new(allocator, self.program).generate_mapped()
+    }
+
     /// Get the program reference.
     pub fn program(&self) -> &'a Program<'a> {
         self.program
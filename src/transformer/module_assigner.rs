@@ -70,7 +70,7 @@ impl ModuleAssigner {
                 let best_module = Self::find_best_module(
                     func,
                     modules,
-                    &dep_graph,
+                    dep_graph,
                     &module_keywords,
                     &function_to_module,
                 );
@@ -101,7 +101,7 @@ impl ModuleAssigner {
                 let best = Self::find_best_module(
                     func,
                     modules,
-                    &dep_graph,
+                    dep_graph,
                     &module_keywords,
                     &function_to_module,
                 );
@@ -7,7 +7,7 @@ use tracing::debug;
 
 /// Variable renamer that suggests meaningful names for minified variables.
 pub struct VariableRenamer<'a> {
-    analyzer: &'a Analyzer<'a>,
+    _analyzer: &'a Analyzer<'a>,
     rename_map: HashMap<String, String>,
     used_names: HashMap<String, usize>,
 }
@@ -16,7 +16,7 @@ impl<'a> VariableRenamer<'a> {
     /// Create a new variable renamer.
     pub fn new(analyzer: &'a Analyzer<'a>) -> Self {
         Self {
-            analyzer,
+            _analyzer: analyzer,
             rename_map: HashMap::new(),
             used_names: HashMap::new(),
         }
@@ -141,7 +141,7 @@ pub fn apply_rename_map(source: &str, rename_map: &HashMap<String, String>) -> S
 
     // Sort by length (longest first) to avoid partial replacements
     let mut entries: Vec<_> = rename_map.iter().collect();
-    entries.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+    entries.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
 
     for (old_name, new_name) in entries {
         // Use word boundaries to avoid partial replacements
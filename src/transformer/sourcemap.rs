@@ -3,12 +3,12 @@
 use crate::Result;
 use oxc_allocator::Allocator;
 use oxc_ast::ast::Program;
-use oxc_codegen::{Codegen, CodegenOptions};
+use oxc_codegen::Codegen;
 use tracing::debug;
 
 /// Source map generator.
 pub struct SourceMapGenerator<'a> {
-    allocator: &'a Allocator,
+    _allocator: &'a Allocator,
     program: &'a Program<'a>,
     source_name: String,
 }
@@ -17,7 +17,7 @@ impl<'a> SourceMapGenerator<'a> {
     /// Create a new source map generator.
     pub fn new(allocator: &'a Allocator, program: &'a Program<'a>, source_name: String) -> Self {
         Self {
-            allocator,
+            _allocator: allocator,
             program,
             source_name,
         }
@@ -107,18 +107,16 @@ impl<'a> ModuleSplitter<'a> {
         let _strings = self.analyzer.find_string_literals();
 
         // Analyze string patterns to infer modules
-        let mut modules = Vec::new();
-
-        // Core module
-        modules.push(Module {
-            name: "core".to_string(),
-            category: ModuleCategory::Core,
-            estimated_lines: 500,
-            functions: vec!["main_loop".to_string(), "process_message".to_string()],
-            keywords: vec!["loop".to_string(), "process".to_string()],
-        });
-
-        modules
+        vec![
+            // Core module
+            Module {
+                name: "core".to_string(),
+                category: ModuleCategory::Core,
+                estimated_lines: 500,
+                functions: vec!["main_loop".to_string(), "process_message".to_string()],
+                keywords: vec!["loop".to_string(), "process".to_string()],
+            },
+        ]
     }
 
     /// Split by feature/functionality.
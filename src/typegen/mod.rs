@@ -0,0 +1,279 @@
+//! Type generation from extracted tool schemas (TypeScript and Rust).
+//!
+//! Tool schemas are first lowered into a small language-neutral model, which
+//! each renderer then prints. Tools, properties and nested types are always
+//! visited in sorted order so regenerated files diff cleanly.
+
+pub mod rust;
+pub mod typescript;
+
+use crate::extractor::tools::ToolDefinition;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeSet;
+
+/// File name of the generated TypeScript module.
+pub const TYPESCRIPT_FILE: &str = "tool-types.ts";
+
+/// File name of the generated Rust module.
+pub const RUST_FILE: &str = "tool_types.rs";
+
+/// Reference to a type from a field or array.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeRef {
+    /// Text.
+    String,
+    /// Floating-point number.
+    Number,
+    /// Integer.
+    Integer,
+    /// Boolean.
+    Boolean,
+    /// Homogeneous array.
+    Array(Box<TypeRef>),
+    /// A declared struct or enum.
+    Named(String),
+    /// Object with arbitrary keys.
+    Map,
+    /// Anything (unions, untyped values).
+    Any,
+}
+
+/// A field of a generated struct.
+#[derive(Debug, Clone)]
+pub struct Field {
+    /// Property name as it appears in JSON.
+    pub name: String,
+    /// Field type.
+    pub ty: TypeRef,
+    /// Whether the property is required.
+    pub required: bool,
+    /// Description from `.describe()` text.
+    pub description: Option<String>,
+}
+
+/// A generated type declaration.
+#[derive(Debug, Clone)]
+pub enum TypeDecl {
+    /// Object type with named fields.
+    Struct {
+        name: String,
+        description: Option<String>,
+        fields: Vec<Field>,
+    },
+    /// String enumeration.
+    Enum {
+        name: String,
+        description: Option<String>,
+        variants: Vec<String>,
+    },
+}
+
+impl TypeDecl {
+    /// Declared type name.
+    pub fn name(&self) -> &str {
+        match self {
+            TypeDecl::Struct { name, .. } | TypeDecl::Enum { name, .. } => name,
+        }
+    }
+}
+
+/// All declarations generated for a set of tools.
+#[derive(Debug, Clone, Default)]
+pub struct TypeModel {
+    /// Declarations in output order.
+    pub decls: Vec<TypeDecl>,
+    used_names: BTreeSet<String>,
+}
+
+impl TypeModel {
+    /// Build declarations for every tool's input and output schema.
+    pub fn from_tools(tools: &[ToolDefinition]) -> Self {
+        let mut sorted: Vec<&ToolDefinition> = tools.iter().collect();
+        sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut model = Self::default();
+        for tool in sorted {
+            let base = pascal_case(&tool.name);
+            let summary = first_line(&tool.short_description);
+
+            if let Some(schema) = &tool.input_schema {
+                let doc = summary.map(|s| format!("Input for the {} tool: {}", tool.name, s));
+                model.add_root(&format!("{}Input", base), schema, doc);
+            }
+            if let Some(schema) = &tool.output_schema {
+                let doc = Some(format!("Output of the {} tool.", tool.name));
+                model.add_root(&format!("{}Output", base), schema, doc);
+            }
+        }
+        model
+    }
+
+    /// Add a top-level object type; non-object schemas are skipped.
+    fn add_root(&mut self, name: &str, schema: &JsonValue, doc: Option<String>) {
+        if schema_type(schema) == Some("object") {
+            let description = description_of(schema).or(doc);
+            self.add_struct(name, schema, description);
+        }
+    }
+
+    fn add_struct(&mut self, name: &str, schema: &JsonValue, description: Option<String>) -> String {
+        let name = self.reserve(name);
+
+        let required: BTreeSet<&str> = schema
+            .get("required")
+            .and_then(JsonValue::as_array)
+            .map(|r| r.iter().filter_map(JsonValue::as_str).collect())
+            .unwrap_or_default();
+
+        // serde_json maps are sorted, which keeps field order deterministic
+        let mut fields = Vec::new();
+        if let Some(properties) = schema.get("properties").and_then(JsonValue::as_object) {
+            for (prop, prop_schema) in properties {
+                let nested = format!("{}{}", name, pascal_case(prop));
+                fields.push(Field {
+                    name: prop.clone(),
+                    ty: self.type_ref(&nested, prop_schema),
+                    required: required.contains(prop.as_str()),
+                    description: description_of(prop_schema),
+                });
+            }
+        }
+
+        self.decls.push(TypeDecl::Struct {
+            name: name.clone(),
+            description,
+            fields,
+        });
+        name
+    }
+
+    /// Map a property schema to a type, declaring nested types as needed.
+    fn type_ref(&mut self, name_hint: &str, schema: &JsonValue) -> TypeRef {
+        if let Some(values) = schema.get("enum").and_then(JsonValue::as_array) {
+            let variants: Option<Vec<String>> = values
+                .iter()
+                .map(|v| v.as_str().map(str::to_string))
+                .collect();
+            return match variants {
+                Some(variants) if !variants.is_empty() => {
+                    let name = self.reserve(name_hint);
+                    self.decls.push(TypeDecl::Enum {
+                        name: name.clone(),
+                        description: None,
+                        variants,
+                    });
+                    TypeRef::Named(name)
+                }
+                _ => TypeRef::Any,
+            };
+        }
+
+        match schema_type(schema) {
+            Some("string") => TypeRef::String,
+            Some("number") => TypeRef::Number,
+            Some("integer") => TypeRef::Integer,
+            Some("boolean") => TypeRef::Boolean,
+            Some("array") => {
+                let item = schema
+                    .get("items")
+                    .map(|items| self.type_ref(&format!("{}Item", name_hint), items))
+                    .unwrap_or(TypeRef::Any);
+                TypeRef::Array(Box::new(item))
+            }
+            Some("object") if schema.get("properties").is_some_and(|p| p.is_object()) => {
+                TypeRef::Named(self.add_struct(name_hint, schema, None))
+            }
+            Some("object") => TypeRef::Map,
+            _ => TypeRef::Any,
+        }
+    }
+
+    /// Claim a unique type name.
+    fn reserve(&mut self, name: &str) -> String {
+        let mut candidate = name.to_string();
+        let mut n = 2;
+        while !self.used_names.insert(candidate.clone()) {
+            candidate = format!("{}{}", name, n);
+            n += 1;
+        }
+        candidate
+    }
+}
+
+fn schema_type(schema: &JsonValue) -> Option<&str> {
+    schema.get("type").and_then(JsonValue::as_str)
+}
+
+fn description_of(schema: &JsonValue) -> Option<String> {
+    schema
+        .get("description")
+        .and_then(JsonValue::as_str)
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+}
+
+fn first_line(text: &str) -> Option<&str> {
+    text.lines().map(str::trim).find(|l| !l.is_empty())
+}
+
+/// Convert `mcp__server__tool`, `file_path` or `todoWrite` to PascalCase.
+pub fn pascal_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper_next = true;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if upper_next {
+                out.push(c.to_ascii_uppercase());
+                upper_next = false;
+            } else {
+                out.push(c);
+            }
+        } else {
+            upper_next = true;
+        }
+    }
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, 'T');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::tools::ToolProperties;
+    use serde_json::json;
+
+    #[test]
+    fn test_model_from_tool() {
+        let tool = ToolDefinition {
+            name: "Edit".to_string(),
+            short_description: "Edit a file".to_string(),
+            full_prompt: String::new(),
+            input_schema: Some(json!({
+                "type": "object",
+                "properties": {
+                    "file_path": { "type": "string", "description": "Path to edit" },
+                    "mode": { "type": "string", "enum": ["replace", "append"] },
+                    "edits": { "type": "array", "items": {
+                        "type": "object",
+                        "properties": { "old": { "type": "string" } }
+                    } }
+                },
+                "required": ["file_path"]
+            })),
+            output_schema: None,
+            properties: ToolProperties::default(),
+            confidence: 1.0,
+            tokens: None,
+            provenance: None,
+        };
+
+        let model = TypeModel::from_tools(&[tool]);
+        let names: Vec<_> = model.decls.iter().map(TypeDecl::name).collect();
+
+        assert_eq!(names, ["EditInputEditsItem", "EditInputMode", "EditInput"]);
+        assert_eq!(pascal_case("mcp__ide__getDiagnostics"), "McpIdeGetDiagnostics");
+    }
+}
@@ -0,0 +1,217 @@
+//! Rust `serde` type rendering.
+
+use super::{pascal_case, Field, TypeDecl, TypeModel, TypeRef};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Rust keywords that must be written as raw identifiers.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override",
+    "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Render the model as a Rust module of `serde` types.
+pub fn render(model: &TypeModel) -> String {
+    let mut out = String::new();
+    out.push_str("//! Generated by claude-code-decypher from extracted tool schemas. Do not edit.\n\n");
+    out.push_str("use serde::{Deserialize, Serialize};\n");
+
+    for decl in &model.decls {
+        out.push('\n');
+        match decl {
+            TypeDecl::Struct { name, description, fields } => {
+                write_doc(&mut out, description.as_deref(), "");
+                out.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+                let _ = writeln!(out, "pub struct {} {{", name);
+                let mut used = BTreeSet::new();
+                for field in fields {
+                    write_field(&mut out, field, &mut used);
+                }
+                out.push_str("}\n");
+            }
+            TypeDecl::Enum { name, description, variants } => {
+                write_doc(&mut out, description.as_deref(), "");
+                out.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\n");
+                let _ = writeln!(out, "pub enum {} {{", name);
+                let mut used = BTreeSet::new();
+                for variant in variants {
+                    let ident = unique(variant_name(variant), &mut used);
+                    let _ = writeln!(out, "    #[serde(rename = {:?})]", variant);
+                    let _ = writeln!(out, "    {},", ident);
+                }
+                out.push_str("}\n");
+            }
+        }
+    }
+
+    out
+}
+
+fn write_field(out: &mut String, field: &Field, used: &mut BTreeSet<String>) {
+    write_doc(out, field.description.as_deref(), "    ");
+
+    let ident = unique(snake_case(&field.name), used);
+    let bare = ident.trim_start_matches("r#");
+    if bare != field.name {
+        let _ = writeln!(out, "    #[serde(rename = {:?})]", field.name);
+    }
+
+    let ty = type_name(&field.ty);
+    if field.required {
+        let _ = writeln!(out, "    pub {}: {},", ident, ty);
+    } else {
+        out.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+        let _ = writeln!(out, "    pub {}: Option<{}>,", ident, ty);
+    }
+}
+
+fn type_name(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String => "String".to_string(),
+        TypeRef::Number => "f64".to_string(),
+        TypeRef::Integer => "i64".to_string(),
+        TypeRef::Boolean => "bool".to_string(),
+        TypeRef::Array(item) => format!("Vec<{}>", type_name(item)),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Map => "serde_json::Map<String, serde_json::Value>".to_string(),
+        TypeRef::Any => "serde_json::Value".to_string(),
+    }
+}
+
+/// Convert a JSON property name to a Rust field identifier.
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            if !out.is_empty() && !out.ends_with('_') {
+                out.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+
+    let out = out.trim_matches('_').to_string();
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{}", out)
+    } else if matches!(out.as_str(), "crate" | "self" | "super") {
+        // These can't be raw identifiers
+        format!("{}_", out)
+    } else if KEYWORDS.contains(&out.as_str()) {
+        format!("r#{}", out)
+    } else {
+        out
+    }
+}
+
+/// PascalCase enum variant for a value.
+fn variant_name(value: &str) -> String {
+    let out = pascal_case(value);
+    // `Self` can't be a raw identifier either
+    if out == "Self" {
+        format!("{}_", out)
+    } else {
+        out
+    }
+}
+
+/// Disambiguate identifiers that collide after case conversion.
+fn unique(ident: String, used: &mut BTreeSet<String>) -> String {
+    let mut candidate = ident.clone();
+    let mut n = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = format!("{}{}", ident, n);
+        n += 1;
+    }
+    candidate
+}
+
+fn write_doc(out: &mut String, doc: Option<&str>, indent: &str) {
+    let Some(doc) = doc else { return };
+    for line in doc.lines() {
+        if line.trim().is_empty() {
+            let _ = writeln!(out, "{}///", indent);
+        } else {
+            let _ = writeln!(out, "{}/// {}", indent, line.trim_end());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snake_case_identifiers() {
+        assert_eq!(snake_case("file_path"), "file_path");
+        assert_eq!(snake_case("replaceAll"), "replace_all");
+        assert_eq!(snake_case("-i"), "i");
+        assert_eq!(snake_case("type"), "r#type");
+        assert_eq!(snake_case("0"), "field_0");
+        assert_eq!(snake_case("self"), "self_");
+    }
+
+    #[test]
+    fn test_render_struct_with_renames() {
+        let model = TypeModel {
+            decls: vec![TypeDecl::Struct {
+                name: "EditInput".to_string(),
+                description: Some("Input for the Edit tool".to_string()),
+                fields: vec![
+                    Field {
+                        name: "replaceAll".to_string(),
+                        ty: TypeRef::Boolean,
+                        required: false,
+                        description: Some("Replace every match".to_string()),
+                    },
+                    Field {
+                        name: "type".to_string(),
+                        ty: TypeRef::Array(Box::new(TypeRef::String)),
+                        required: true,
+                        description: None,
+                    },
+                ],
+            }],
+            ..TypeModel::default()
+        };
+
+        let rs = render(&model);
+
+        assert!(rs.contains("/// Input for the Edit tool\n#[derive("));
+        assert!(rs.contains("    /// Replace every match\n    #[serde(rename = \"replaceAll\")]\n"));
+        assert!(rs.contains("    pub replace_all: Option<bool>,"));
+        assert!(rs.contains("    pub r#type: Vec<String>,"));
+        assert!(!rs.contains("rename = \"type\""));
+    }
+
+    #[test]
+    fn test_render_enum_escapes_self() {
+        let model = TypeModel {
+            decls: vec![TypeDecl::Enum {
+                name: "Scope".to_string(),
+                description: None,
+                variants: vec!["self".to_string(), "Self".to_string(), "team".to_string()],
+            }],
+            ..TypeModel::default()
+        };
+
+        let rs = render(&model);
+
+        assert!(rs.contains("    #[serde(rename = \"self\")]\n    Self_,\n"));
+        assert!(rs.contains("    #[serde(rename = \"Self\")]\n    Self_2,\n"));
+        assert!(rs.contains("    Team,\n"));
+        assert!(!rs.contains("    Self,"));
+    }
+}
@@ -0,0 +1,146 @@
+//! TypeScript interface rendering.
+
+use super::{Field, TypeDecl, TypeModel, TypeRef};
+use std::fmt::Write;
+
+/// Render the model as a TypeScript module of exported types.
+pub fn render(model: &TypeModel) -> String {
+    let mut out = String::new();
+    out.push_str("// Generated by claude-code-decypher from extracted tool schemas. Do not edit.\n");
+
+    for decl in &model.decls {
+        out.push('\n');
+        match decl {
+            TypeDecl::Struct { name, description, fields } => {
+                write_doc(&mut out, description.as_deref(), "");
+                let _ = writeln!(out, "export interface {} {{", name);
+                for field in fields {
+                    write_field(&mut out, field);
+                }
+                out.push_str("}\n");
+            }
+            TypeDecl::Enum { name, description, variants } => {
+                write_doc(&mut out, description.as_deref(), "");
+                let union = variants
+                    .iter()
+                    .map(|v| serde_json::to_string(v).unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                let _ = writeln!(out, "export type {} = {};", name, union);
+            }
+        }
+    }
+
+    out
+}
+
+fn write_field(out: &mut String, field: &Field) {
+    write_doc(out, field.description.as_deref(), "  ");
+    let optional = if field.required { "" } else { "?" };
+    let _ = writeln!(
+        out,
+        "  {}{}: {};",
+        property_name(&field.name),
+        optional,
+        type_name(&field.ty)
+    );
+}
+
+fn type_name(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String => "string".to_string(),
+        TypeRef::Number | TypeRef::Integer => "number".to_string(),
+        TypeRef::Boolean => "boolean".to_string(),
+        TypeRef::Array(item) => match item.as_ref() {
+            TypeRef::Array(_) => format!("Array<{}>", type_name(item)),
+            _ => format!("{}[]", type_name(item)),
+        },
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Map => "Record<string, unknown>".to_string(),
+        TypeRef::Any => "unknown".to_string(),
+    }
+}
+
+/// Quote property names that aren't valid identifiers.
+fn property_name(name: &str) -> String {
+    let is_ident = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_ident {
+        name.to_string()
+    } else {
+        serde_json::to_string(name).unwrap_or_default()
+    }
+}
+
+fn write_doc(out: &mut String, doc: Option<&str>, indent: &str) {
+    let Some(doc) = doc else { return };
+    let doc = doc.replace("*/", "*\\/");
+    let lines: Vec<&str> = doc.lines().collect();
+    if lines.len() == 1 {
+        let _ = writeln!(out, "{}/** {} */", indent, lines[0]);
+    } else {
+        let _ = writeln!(out, "{}/**", indent);
+        for line in lines {
+            if line.trim().is_empty() {
+                let _ = writeln!(out, "{} *", indent);
+            } else {
+                let _ = writeln!(out, "{} * {}", indent, line);
+            }
+        }
+        let _ = writeln!(out, "{} */", indent);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typegen::Field;
+
+    #[test]
+    fn test_render_interface() {
+        let model = TypeModel {
+            decls: vec![
+                TypeDecl::Enum {
+                    name: "GrepInputMode".to_string(),
+                    description: None,
+                    variants: vec!["content".to_string(), "count".to_string()],
+                },
+                TypeDecl::Struct {
+                    name: "GrepInput".to_string(),
+                    description: Some("Input for the Grep tool".to_string()),
+                    fields: vec![
+                        Field {
+                            name: "pattern".to_string(),
+                            ty: TypeRef::String,
+                            required: true,
+                            description: Some("Regex to search for".to_string()),
+                        },
+                        Field {
+                            name: "-i".to_string(),
+                            ty: TypeRef::Boolean,
+                            required: false,
+                            description: None,
+                        },
+                        Field {
+                            name: "output_mode".to_string(),
+                            ty: TypeRef::Named("GrepInputMode".to_string()),
+                            required: false,
+                            description: None,
+                        },
+                    ],
+                },
+            ],
+            ..TypeModel::default()
+        };
+
+        let ts = render(&model);
+
+        assert!(ts.contains("export type GrepInputMode = \"content\" | \"count\";"));
+        assert!(ts.contains("  /** Regex to search for */\n  pattern: string;"));
+        assert!(ts.contains("  \"-i\"?: boolean;"));
+        assert!(ts.contains("  output_mode?: GrepInputMode;"));
+    }
+}
@@ -0,0 +1,481 @@
+//! Validation of recorded tool calls against extracted input schemas.
+
+use crate::extractor::tools::ToolDefinition;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use tracing::debug;
+
+/// A recorded tool call: one line of the calls JSONL file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallRecord {
+    /// Tool name.
+    #[serde(alias = "name")]
+    pub tool: String,
+
+    /// Tool input.
+    #[serde(default)]
+    pub input: JsonValue,
+}
+
+/// Kind of validation failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    /// Line isn't a valid `{tool, input}` record.
+    InvalidRecord,
+    /// No extracted tool has this name.
+    UnknownTool,
+    /// A required property is absent.
+    MissingRequired,
+    /// A value has the wrong JSON type.
+    TypeMismatch,
+    /// A value is not one of the allowed enum values.
+    EnumViolation,
+}
+
+/// A single validation failure.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Violation {
+    /// 1-based line number in the calls file.
+    pub line: usize,
+
+    /// Tool name, when the record could be parsed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+
+    /// Path to the offending value, e.g. `input.edits[0].old_string`.
+    pub path: String,
+
+    /// Failure kind.
+    pub kind: ViolationKind,
+
+    /// Human-readable explanation.
+    pub message: String,
+}
+
+/// Result of validating a calls file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Number of records read.
+    pub total_calls: usize,
+
+    /// Records with no violations.
+    pub valid_calls: usize,
+
+    /// All violations found.
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// Whether every call passed.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Violation counts by kind.
+    pub fn counts_by_kind(&self) -> HashMap<ViolationKind, usize> {
+        let mut counts = HashMap::new();
+        for violation in &self.violations {
+            *counts.entry(violation.kind).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Print a summary to stdout.
+    pub fn print(&self) {
+        println!("\n=== Tool Call Validation ===\n");
+        println!("Calls checked:  {}", self.total_calls);
+        println!("Valid calls:    {}", self.valid_calls);
+        println!("Violations:     {}", self.violations.len());
+
+        let mut counts: Vec<_> = self.counts_by_kind().into_iter().collect();
+        counts.sort_by_key(|(kind, _)| format!("{:?}", kind));
+        for (kind, count) in counts {
+            println!("  {:?}: {}", kind, count);
+        }
+
+        for violation in self.violations.iter().take(20) {
+            println!(
+                "  line {}: [{}] {}: {}",
+                violation.line,
+                violation.tool.as_deref().unwrap_or("?"),
+                violation.path,
+                violation.message
+            );
+        }
+        if self.violations.len() > 20 {
+            println!("  ... and {} more", self.violations.len() - 20);
+        }
+    }
+}
+
+/// Validates tool calls against the input schemas of extracted tools.
+pub struct CallValidator<'a> {
+    tools: HashMap<&'a str, &'a ToolDefinition>,
+}
+
+impl<'a> CallValidator<'a> {
+    /// Create a validator for the given tools.
+    pub fn new(tools: &'a [ToolDefinition]) -> Self {
+        Self {
+            tools: tools.iter().map(|t| (t.name.as_str(), t)).collect(),
+        }
+    }
+
+    /// Validate every record of a JSONL document.
+    pub fn validate_jsonl(&self, jsonl: &str) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        for (idx, line) in jsonl.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            report.total_calls += 1;
+
+            let violations = match serde_json::from_str::<CallRecord>(line) {
+                Ok(record) => self.validate_call(&record, idx + 1),
+                Err(e) => vec![Violation {
+                    line: idx + 1,
+                    tool: None,
+                    path: String::new(),
+                    kind: ViolationKind::InvalidRecord,
+                    message: e.to_string(),
+                }],
+            };
+
+            if violations.is_empty() {
+                report.valid_calls += 1;
+            }
+            report.violations.extend(violations);
+        }
+
+        debug!(
+            "Validated {} calls: {} valid, {} violations",
+            report.total_calls,
+            report.valid_calls,
+            report.violations.len()
+        );
+        report
+    }
+
+    /// Validate a single call.
+    pub fn validate_call(&self, record: &CallRecord, line: usize) -> Vec<Violation> {
+        let mut errors = Vec::new();
+
+        match self.tools.get(record.tool.as_str()) {
+            None => errors.push((
+                String::new(),
+                ViolationKind::UnknownTool,
+                format!("unknown tool '{}'", record.tool),
+            )),
+            // Tools without a recovered schema accept anything
+            Some(tool) => {
+                if let Some(schema) = &tool.input_schema {
+                    check_value(schema, &record.input, "input", &mut errors);
+                }
+            }
+        }
+
+        errors
+            .into_iter()
+            .map(|(path, kind, message)| Violation {
+                line,
+                tool: Some(record.tool.clone()),
+                path,
+                kind,
+                message,
+            })
+            .collect()
+    }
+}
+
+/// Check a value against a JSON Schema, collecting `(path, kind, message)`.
+fn check_value(
+    schema: &JsonValue,
+    value: &JsonValue,
+    path: &str,
+    errors: &mut Vec<(String, ViolationKind, String)>,
+) {
+    if value.is_null() && allows_null(schema) {
+        return;
+    }
+
+    for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+        if let Some(branches) = schema.get(keyword).and_then(JsonValue::as_array) {
+            check_union(branches, exactly_one, value, path, errors);
+        }
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(JsonValue::String(t)) => vec![t.as_str()],
+        Some(JsonValue::Array(ts)) => ts.iter().filter_map(JsonValue::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| matches_type(t, value)) {
+        errors.push((
+            path.to_string(),
+            ViolationKind::TypeMismatch,
+            format!("expected {}, found {}", types.join(" | "), json_type(value)),
+        ));
+        return;
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push((
+                path.to_string(),
+                ViolationKind::EnumViolation,
+                format!("{} is not {}", value, expected),
+            ));
+        }
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(JsonValue::as_array) {
+        if !allowed.contains(value) {
+            errors.push((
+                path.to_string(),
+                ViolationKind::EnumViolation,
+                format!("{} is not one of {}", value, JsonValue::Array(allowed.clone())),
+            ));
+        }
+        return;
+    }
+
+    match value {
+        JsonValue::Object(obj) => {
+            if let Some(required) = schema.get("required").and_then(JsonValue::as_array) {
+                for key in required.iter().filter_map(JsonValue::as_str) {
+                    if !obj.contains_key(key) {
+                        errors.push((
+                            format!("{}.{}", path, key),
+                            ViolationKind::MissingRequired,
+                            format!("missing required property '{}'", key),
+                        ));
+                    }
+                }
+            }
+            if let Some(properties) = schema.get("properties").and_then(JsonValue::as_object) {
+                for (key, prop_schema) in properties {
+                    if let Some(prop_value) = obj.get(key) {
+                        check_value(prop_schema, prop_value, &format!("{}.{}", path, key), errors);
+                    }
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check_value(item_schema, item, &format!("{}[{}]", path, i), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check a value against `anyOf` (some branch matches) or `oneOf` (exactly one does).
+///
+/// When exactly one branch accepts the value's type, its violations are
+/// reported as they are; otherwise a single type mismatch is.
+fn check_union(
+    branches: &[JsonValue],
+    exactly_one: bool,
+    value: &JsonValue,
+    path: &str,
+    errors: &mut Vec<(String, ViolationKind, String)>,
+) {
+    let results: Vec<_> = branches
+        .iter()
+        .map(|branch| {
+            let mut branch_errors = Vec::new();
+            check_value(branch, value, path, &mut branch_errors);
+            branch_errors
+        })
+        .collect();
+    let matching = results.iter().filter(|r| r.is_empty()).count();
+
+    if exactly_one && matching > 1 {
+        errors.push((
+            path.to_string(),
+            ViolationKind::TypeMismatch,
+            format!("{} matches {} of the oneOf schemas, expected exactly one", value, matching),
+        ));
+        return;
+    }
+    if matching > 0 {
+        return;
+    }
+
+    let mut typed = results
+        .into_iter()
+        .filter(|r| !r.iter().any(|(p, kind, _)| p == path && *kind == ViolationKind::TypeMismatch));
+    match (typed.next(), typed.next()) {
+        (Some(branch_errors), None) => errors.extend(branch_errors),
+        _ => errors.push((
+            path.to_string(),
+            ViolationKind::TypeMismatch,
+            format!("{} matches none of the {} alternatives", json_type(value), branches.len()),
+        )),
+    }
+}
+
+/// Whether a schema accepts `null` through its type, `nullable` or enum.
+fn allows_null(schema: &JsonValue) -> bool {
+    let type_allows = match schema.get("type") {
+        Some(JsonValue::String(t)) => t == "null",
+        Some(JsonValue::Array(ts)) => ts.iter().any(|t| t == "null"),
+        _ => false,
+    };
+    type_allows
+        || schema.get("nullable").and_then(JsonValue::as_bool) == Some(true)
+        || schema
+            .get("enum")
+            .and_then(JsonValue::as_array)
+            .is_some_and(|allowed| allowed.contains(&JsonValue::Null))
+}
+
+fn matches_type(expected: &str, value: &JsonValue) -> bool {
+    match expected {
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => value.is_number(),
+        other => json_type(value) == other,
+    }
+}
+
+fn json_type(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::tools::ToolProperties;
+    use serde_json::json;
+
+    fn grep_tool() -> ToolDefinition {
+        ToolDefinition {
+            name: "Grep".to_string(),
+            short_description: String::new(),
+            full_prompt: String::new(),
+            input_schema: Some(json!({
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string" },
+                    "output_mode": { "type": "string", "enum": ["content", "count"] },
+                    "head_limit": { "type": "integer" }
+                },
+                "required": ["pattern"]
+            })),
+            output_schema: None,
+            properties: ToolProperties::default(),
+            confidence: 1.0,
+            tokens: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_calls() {
+        let tools = [grep_tool()];
+        let validator = CallValidator::new(&tools);
+
+        let jsonl = r#"{"tool":"Grep","input":{"pattern":"fn main"}}
+{"tool":"Grep","input":{"output_mode":"lines","head_limit":2.5}}
+{"tool":"Frob","input":{}}
+
+not json
+"#;
+        let report = validator.validate_jsonl(jsonl);
+
+        assert_eq!(report.total_calls, 4);
+        assert_eq!(report.valid_calls, 1);
+
+        let counts = report.counts_by_kind();
+        assert_eq!(counts[&ViolationKind::MissingRequired], 1);
+        assert_eq!(counts[&ViolationKind::EnumViolation], 1);
+        assert_eq!(counts[&ViolationKind::TypeMismatch], 1);
+        assert_eq!(counts[&ViolationKind::UnknownTool], 1);
+        assert_eq!(counts[&ViolationKind::InvalidRecord], 1);
+
+        let mismatch = report
+            .violations
+            .iter()
+            .find(|v| v.kind == ViolationKind::TypeMismatch)
+            .unwrap();
+        assert_eq!(mismatch.line, 2);
+        assert_eq!(mismatch.path, "input.head_limit");
+    }
+
+    #[test]
+    fn test_union_schemas() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "timeout": { "anyOf": [{ "type": "integer" }, { "type": "string", "enum": ["auto"] }] },
+                "target": {
+                    "oneOf": [
+                        { "type": "object", "properties": { "file": { "type": "string" } }, "required": ["file"] },
+                        { "type": "string" }
+                    ]
+                },
+                "id": { "oneOf": [{ "type": "number" }, { "type": "integer" }] }
+            }
+        });
+        let check = |input: JsonValue| {
+            let mut errors = Vec::new();
+            check_value(&schema, &input, "input", &mut errors);
+            errors
+        };
+
+        assert!(check(json!({ "timeout": 30, "target": "src" })).is_empty());
+        assert!(check(json!({ "timeout": "auto", "target": { "file": "a.rs" } })).is_empty());
+
+        let errors = check(json!({ "timeout": true }));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].1, ViolationKind::TypeMismatch);
+
+        // Only the string branch accepts a string, so its enum error surfaces
+        let errors = check(json!({ "timeout": "never" }));
+        assert_eq!(errors[0].1, ViolationKind::EnumViolation);
+
+        let errors = check(json!({ "target": {} }));
+        assert_eq!(errors[0].0, "input.target.file");
+        assert_eq!(errors[0].1, ViolationKind::MissingRequired);
+
+        // 3 is both a number and an integer
+        let errors = check(json!({ "id": 3 }));
+        assert_eq!(errors.len(), 1);
+        assert!(check(json!({ "id": 3.5 })).is_empty());
+    }
+
+    #[test]
+    fn test_nullable_schemas() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "mode": { "type": ["string", "null"], "enum": ["plan", "edit"] },
+                "level": { "enum": ["low", "high", null] },
+                "limit": { "anyOf": [{ "type": "integer" }, { "type": "null" }] },
+                "name": { "type": "string" }
+            }
+        });
+        let check = |input: JsonValue| {
+            let mut errors = Vec::new();
+            check_value(&schema, &input, "input", &mut errors);
+            errors
+        };
+
+        assert!(check(json!({ "mode": null, "level": null, "limit": null })).is_empty());
+        assert_eq!(check(json!({ "mode": "other" }))[0].1, ViolationKind::EnumViolation);
+        assert_eq!(check(json!({ "name": null }))[0].1, ViolationKind::TypeMismatch);
+    }
+}
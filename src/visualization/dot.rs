@@ -16,10 +16,10 @@ impl DotGenerator {
 
         // Add top functions
         let mut functions = call_graph.functions.clone();
-        functions.sort_by(|a, b| b.calls_out.cmp(&a.calls_out));
+        functions.sort_by_key(|f| std::cmp::Reverse(f.calls_out));
 
         for func in functions.iter().take(limit) {
-            let node_name = func.name.replace('-', "_").replace('.', "_");
+            let node_name = func.name.replace(['-', '.'], "_");
 
             // Color by complexity (if we could infer it)
             let color = if func.calls_out > 5 {
@@ -38,7 +38,7 @@ impl DotGenerator {
             // Add call relationships
             if let Some(callees) = call_graph.calls.get(&func.name) {
                 for callee in callees.iter().take(3) {
-                    let callee_name = callee.replace('-', "_").replace('.', "_");
+                    let callee_name = callee.replace(['-', '.'], "_");
                     diagram.push_str(&format!("    {} -> {};\n", node_name, callee_name));
                 }
             }
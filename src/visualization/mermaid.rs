@@ -14,7 +14,7 @@ impl MermaidGenerator {
 
         // Add top functions by call count
         let mut functions = call_graph.functions.clone();
-        functions.sort_by(|a, b| b.calls_out.cmp(&a.calls_out));
+        functions.sort_by_key(|f| std::cmp::Reverse(f.calls_out));
 
         for (idx, func) in functions.iter().take(limit).enumerate() {
             let node_id = format!("F{}", idx);
@@ -4,8 +4,8 @@ use claude_code_decypher::{
     parser::Parser,
     transformer::{
         codegen::{beautify_code, CodeGenerator},
-        rename::{apply_rename_map, VariableRenamer},
-        split::{ModuleSplitter, SplitStrategy},
+        rename::apply_rename_map,
+        split::SplitStrategy,
         Transformer,
     },
     Result,